@@ -0,0 +1,313 @@
+//! Local HTTP/JSON API for pipeline tools and editor scripts.
+//!
+//! The server only listens on `127.0.0.1` and every `/api/v1` route needs the token from the
+//! `api_token` setting as `Authorization: Bearer <token>`. It answers from the same
+//! `DatabaseManager` queries as the app's commands. `openapi.json` describes every route.
+
+use std::net::{Ipv4Addr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use axum::extract::{Path as UrlPath, Query, Request, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Redirect, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use base64::Engine;
+use serde::Deserialize;
+use tokio::net::TcpListener;
+use tokio::sync::watch;
+use tracing::{error, info};
+
+use crate::database::DatabaseManager;
+use crate::models::asset_card::{AssetCardData, SortOption};
+use crate::models::asset_details::AssetForFrontend;
+use crate::models::{AppSettings, Collection};
+use crate::unreal::import::{self, ImportMode, ProjectImportReport};
+
+/// OpenAPI 3 description of the routes below, served at `/api/openapi.json`.
+pub const OPENAPI_SPEC: &str = include_str!("openapi.json");
+
+const DEFAULT_PAGE_SIZE: u32 = 50;
+const MAX_PAGE_SIZE: u32 = 500;
+
+#[derive(Clone)]
+struct ApiState {
+    db_manager: DatabaseManager,
+    token: Arc<str>,
+}
+
+/// Where and with which token the API is served; `None` while it is disabled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ApiConfig {
+    port: u16,
+    token: String,
+}
+
+impl ApiConfig {
+    fn from_settings(settings: &AppSettings) -> Option<Self> {
+        if !settings.api_enabled {
+            return None;
+        }
+        Some(Self {
+            port: u16::try_from(settings.api_port).ok()?,
+            token: settings.api_token.clone()?,
+        })
+    }
+}
+
+/// A random token for `api_token`.
+pub fn generate_token() -> String {
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(rand::random::<[u8; 32]>())
+}
+
+/// All routes of the API, answering requests that carry `token`.
+pub fn router(db_manager: DatabaseManager, token: &str) -> Router {
+    let state = ApiState { db_manager, token: Arc::from(token) };
+
+    let v1 = Router::new()
+        .route("/assets/search", get(search_assets))
+        .route("/assets/{id}", get(get_asset))
+        .route("/assets/{id}/thumbnail", get(get_thumbnail))
+        .route("/assets/{id}/import", post(import_asset))
+        .route("/collections", get(get_collections))
+        .route("/collections/{id}/assets", get(get_collection_assets))
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_token));
+
+    Router::new()
+        .nest("/api/v1", v1)
+        .route("/api/openapi.json", get(openapi_spec))
+        .with_state(state)
+}
+
+/// Serve the API while it is enabled, restarting it when its port or token changes.
+pub async fn run_api_server(db_manager: DatabaseManager, mut settings_rx: watch::Receiver<AppSettings>) {
+    loop {
+        let config = ApiConfig::from_settings(&settings_rx.borrow_and_update());
+        match config {
+            Some(config) => serve_until_changed(&db_manager, &config, settings_rx.clone()).await,
+            None => {
+                if settings_rx.changed().await.is_err() {
+                    break;
+                }
+            }
+        }
+        if settings_rx.has_changed().is_err() {
+            break;
+        }
+    }
+}
+
+async fn serve_until_changed(db_manager: &DatabaseManager, config: &ApiConfig, settings_rx: watch::Receiver<AppSettings>) {
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, config.port));
+    let listener = match TcpListener::bind(addr).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            error!("Failed to start the local API on {}: {}", addr, e);
+            config_changed(settings_rx, config.clone()).await;
+            return;
+        }
+    };
+
+    info!("Local API listening on http://{}", addr);
+    let app = router(db_manager.clone(), &config.token);
+    if let Err(e) = axum::serve(listener, app)
+        .with_graceful_shutdown(config_changed(settings_rx, config.clone()))
+        .await
+    {
+        error!("Local API stopped: {}", e);
+    }
+    info!("Local API on {} stopped", addr);
+}
+
+/// Resolves once the API settings differ from `config`, or the settings are gone.
+async fn config_changed(mut settings_rx: watch::Receiver<AppSettings>, config: ApiConfig) {
+    while settings_rx.changed().await.is_ok() {
+        if ApiConfig::from_settings(&settings_rx.borrow_and_update()).as_ref() != Some(&config) {
+            return;
+        }
+    }
+}
+
+async fn require_token(State(state): State<ApiState>, headers: HeaderMap, request: Request, next: Next) -> Response {
+    let given = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    match given {
+        Some(given) if tokens_match(given.trim().as_bytes(), state.token.as_bytes()) => next.run(request).await,
+        _ => ApiError::new(StatusCode::UNAUTHORIZED, "Missing or invalid API token").into_response(),
+    }
+}
+
+/// Compares in constant time, so the token cannot be recovered from response timings.
+fn tokens_match(given: &[u8], expected: &[u8]) -> bool {
+    given.len() == expected.len() && given.iter().zip(expected).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+/// An error response, sent as `{"error": "..."}`.
+#[derive(Debug)]
+struct ApiError {
+    status: StatusCode,
+    message: String,
+}
+
+impl ApiError {
+    fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self { status, message: message.into() }
+    }
+
+    fn not_found(what: &str, id: i64) -> Self {
+        Self::new(StatusCode::NOT_FOUND, format!("No {} with id {}", what, id))
+    }
+}
+
+impl From<anyhow::Error> for ApiError {
+    fn from(e: anyhow::Error) -> Self {
+        error!("Local API request failed: {}", e);
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(serde_json::json!({ "error": self.message }))).into_response()
+    }
+}
+
+type ApiResult<T> = Result<T, ApiError>;
+
+/// `limit` and `offset` query parameters, with the limit capped at `MAX_PAGE_SIZE`.
+fn page(limit: Option<u32>, offset: Option<u32>) -> (u32, u32) {
+    (limit.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE), offset.unwrap_or(0))
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchQuery {
+    q: String,
+    limit: Option<u32>,
+    offset: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CollectionAssetsQuery {
+    sort: Option<SortOption>,
+    limit: Option<u32>,
+    offset: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ImportRequest {
+    uproject_path: PathBuf,
+    mode: Option<ImportMode>,
+    #[serde(default)]
+    dry_run: bool,
+    #[serde(default)]
+    overwrite: bool,
+}
+
+async fn openapi_spec() -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, "application/json")], OPENAPI_SPEC)
+}
+
+async fn search_assets(State(state): State<ApiState>, Query(query): Query<SearchQuery>) -> ApiResult<Json<Vec<AssetCardData>>> {
+    let (limit, offset) = page(query.limit, query.offset);
+    let assets = state.db_manager.search_assets(&query.q, limit, offset).await?;
+    Ok(Json(assets))
+}
+
+async fn get_asset(State(state): State<ApiState>, UrlPath(id): UrlPath<i64>) -> ApiResult<Json<AssetForFrontend>> {
+    state
+        .db_manager
+        .get_asset_details(id)
+        .await?
+        .map(Json)
+        .ok_or_else(|| ApiError::not_found("asset", id))
+}
+
+/// The cached thumbnail file, or a redirect to the Orbital Market image when there is none.
+async fn get_thumbnail(State(state): State<ApiState>, UrlPath(id): UrlPath<i64>) -> ApiResult<Response> {
+    let asset = state
+        .db_manager
+        .get_asset_details(id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("asset", id))?;
+
+    if let Some(path) = asset.thumbnail_path.as_deref().map(Path::new) {
+        if let Ok(bytes) = tokio::fs::read(path).await {
+            return Ok(([(header::CONTENT_TYPE, image_content_type(path))], bytes).into_response());
+        }
+    }
+    match asset.orbital_thumbnail_url {
+        Some(url) => Ok(Redirect::temporary(&url).into_response()),
+        None => Err(ApiError::new(StatusCode::NOT_FOUND, format!("Asset {} has no thumbnail", id))),
+    }
+}
+
+fn image_content_type(path: &Path) -> &'static str {
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or_default().to_ascii_lowercase();
+    match extension.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "webp" => "image/webp",
+        "gif" => "image/gif",
+        _ => "application/octet-stream",
+    }
+}
+
+async fn get_collections(State(state): State<ApiState>) -> ApiResult<Json<Vec<Collection>>> {
+    Ok(Json(state.db_manager.get_collections().await?))
+}
+
+async fn get_collection_assets(
+    State(state): State<ApiState>,
+    UrlPath(id): UrlPath<i64>,
+    Query(query): Query<CollectionAssetsQuery>,
+) -> ApiResult<Json<Vec<AssetCardData>>> {
+    let (limit, offset) = page(query.limit, query.offset);
+    let sort_by = query.sort.unwrap_or(SortOption::NameAsc);
+    let assets = state.db_manager.get_collection_assets(id, sort_by, limit, offset).await?;
+    Ok(Json(assets))
+}
+
+/// Same as the `import_asset_into_project` command: refuses to overwrite unless asked to.
+async fn import_asset(
+    State(state): State<ApiState>,
+    UrlPath(id): UrlPath<i64>,
+    Json(request): Json<ImportRequest>,
+) -> ApiResult<Json<ProjectImportReport>> {
+    if state.db_manager.get_asset_details(id).await?.is_none() {
+        return Err(ApiError::not_found("asset", id));
+    }
+    import::import_asset(
+        &state.db_manager,
+        id,
+        &request.uproject_path,
+        request.mode.unwrap_or(ImportMode::Copy),
+        request.dry_run,
+        request.overwrite,
+    )
+    .await
+    .map(Json)
+    .map_err(|e| ApiError::new(StatusCode::UNPROCESSABLE_ENTITY, e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokens_only_match_exactly() {
+        assert!(tokens_match(b"0123456789abcdef", b"0123456789abcdef"));
+        assert!(!tokens_match(b"0123456789abcdeF", b"0123456789abcdef"));
+        assert!(!tokens_match(b"0123456789abcde", b"0123456789abcdef"));
+        assert!(!tokens_match(b"", b"0123456789abcdef"));
+    }
+
+    #[test]
+    fn test_openapi_spec_is_valid_json() {
+        let spec: serde_json::Value = serde_json::from_str(OPENAPI_SPEC).unwrap();
+        assert!(spec["paths"]["/api/v1/assets/search"].is_object());
+    }
+}
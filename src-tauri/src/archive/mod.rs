@@ -0,0 +1,293 @@
+//! Reading downloaded archives (.zip, .7z, .rar) without unpacking them, and unpacking them on request.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::{Component, Path, PathBuf};
+
+use crate::scanner::inventory::FolderInventory;
+use crate::unreal::is_package_file;
+
+mod rar;
+
+/// Largest metadata file read out of an archive for type detection.
+const MAX_METADATA_JSON_BYTES: u64 = 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArchiveFormat {
+    Zip,
+    #[serde(rename = "7z")]
+    SevenZ,
+    Rar,
+}
+
+impl ArchiveFormat {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "zip" => Some(Self::Zip),
+            "7z" => Some(Self::SevenZ),
+            "rar" => Some(Self::Rar),
+            _ => None,
+        }
+    }
+
+    /// Whether [`extract_archive`] can unpack this format. RAR decompression is proprietary,
+    /// so RAR archives are indexed but have to be unpacked with another tool.
+    pub fn can_extract(self) -> bool {
+        !matches!(self, Self::Rar)
+    }
+}
+
+pub fn is_archive(path: &Path) -> bool {
+    ArchiveFormat::from_path(path).is_some()
+}
+
+/// One entry of an archive's directory. `path` uses `/` separators.
+#[derive(Debug, Clone)]
+pub struct ArchiveEntry {
+    pub path: String,
+    pub size: u64,
+    pub is_dir: bool,
+}
+
+/// What an archive contains, stored in the asset's `metadata` under `"archive"`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveSummary {
+    pub format: ArchiveFormat,
+    pub compressed_size: u64,
+    pub uncompressed_size: u64,
+    pub file_count: usize,
+    /// Folder every entry sits in, when the archive wraps its contents in one.
+    pub wrapper_folder: Option<String>,
+    pub has_uproject: bool,
+    pub has_uplugin: bool,
+    pub package_count: usize,
+}
+
+/// List an archive's entries from its directory, without decompressing anything.
+pub fn list_entries(path: &Path) -> Result<Vec<ArchiveEntry>> {
+    let format = ArchiveFormat::from_path(path).ok_or_else(|| anyhow!("Not an archive: {}", path.display()))?;
+    match format {
+        ArchiveFormat::Zip => {
+            let mut zip = zip::ZipArchive::new(BufReader::new(File::open(path)?))?;
+            (0..zip.len())
+                .map(|index| {
+                    let entry = zip.by_index_raw(index)?;
+                    Ok(ArchiveEntry {
+                        path: entry.name().replace('\\', "/"),
+                        size: entry.size(),
+                        is_dir: entry.is_dir(),
+                    })
+                })
+                .collect()
+        }
+        ArchiveFormat::SevenZ => {
+            let mut file = File::open(path)?;
+            let len = file.metadata()?.len();
+            let archive = sevenz_rust::Archive::read(&mut file, len, &[])?;
+            Ok(archive
+                .files
+                .iter()
+                .map(|entry| ArchiveEntry {
+                    path: entry.name().replace('\\', "/"),
+                    size: entry.size(),
+                    is_dir: entry.is_directory(),
+                })
+                .collect())
+        }
+        ArchiveFormat::Rar => rar::list_entries(path),
+    }
+}
+
+/// The single top-level folder all files sit in, if there is one (`Pack/Content/...`).
+pub fn wrapper_folder(entries: &[ArchiveEntry]) -> Option<String> {
+    let mut wrapper: Option<&str> = None;
+    for entry in entries.iter().filter(|e| !e.is_dir) {
+        let (first, rest) = entry.path.trim_start_matches('/').split_once('/')?;
+        if rest.is_empty() || wrapper.is_some_and(|w| w != first) {
+            return None;
+        }
+        wrapper = Some(first);
+    }
+    wrapper.map(str::to_string)
+}
+
+/// Entry path relative to the wrapper folder, if any.
+fn path_within<'a>(entry: &'a ArchiveEntry, wrapper: Option<&str>) -> &'a str {
+    let path = entry.path.trim_start_matches('/');
+    wrapper
+        .and_then(|w| path.strip_prefix(w))
+        .map(|rest| rest.trim_start_matches('/'))
+        .unwrap_or(path)
+}
+
+pub fn summarize(format: ArchiveFormat, compressed_size: u64, entries: &[ArchiveEntry]) -> ArchiveSummary {
+    let has_extension = |entry: &ArchiveEntry, extension: &str| {
+        Path::new(&entry.path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| e.eq_ignore_ascii_case(extension))
+    };
+    let files: Vec<_> = entries.iter().filter(|e| !e.is_dir).collect();
+    ArchiveSummary {
+        format,
+        compressed_size,
+        uncompressed_size: files.iter().map(|e| e.size).sum(),
+        file_count: files.len(),
+        wrapper_folder: wrapper_folder(entries),
+        has_uproject: files.iter().any(|e| has_extension(e, "uproject")),
+        has_uplugin: files.iter().any(|e| has_extension(e, "uplugin")),
+        package_count: files.iter().filter(|e| is_package_file(Path::new(&e.path))).count(),
+    }
+}
+
+/// File inventory of an archive's contents, with paths taken relative to its wrapper folder.
+/// Entries are listed without modification times, so the fingerprint covers paths and sizes.
+pub fn inventory(entries: &[ArchiveEntry]) -> FolderInventory {
+    let wrapper = wrapper_folder(entries);
+    let mut inventory = FolderInventory::default();
+    for entry in entries.iter().filter(|e| !e.is_dir) {
+        inventory.add_file(Path::new(path_within(entry, wrapper.as_deref())), entry.size as i64, None);
+    }
+    inventory
+}
+
+/// Contents of the first `.json` file at the top of the archive (or of its wrapper folder),
+/// which is where packs ship the manifest used for type detection. Only zip archives are read;
+/// decompressing a single 7z entry means decompressing its whole solid block.
+pub fn read_metadata_json(path: &Path, entries: &[ArchiveEntry]) -> Option<String> {
+    if ArchiveFormat::from_path(path) != Some(ArchiveFormat::Zip) {
+        return None;
+    }
+    let wrapper = wrapper_folder(entries);
+    let name = entries.iter().find_map(|entry| {
+        let within = path_within(entry, wrapper.as_deref());
+        let is_json = !entry.is_dir && !within.contains('/') && within.to_ascii_lowercase().ends_with(".json");
+        (is_json && entry.size <= MAX_METADATA_JSON_BYTES).then_some(entry.path.as_str())
+    })?;
+
+    let mut zip = zip::ZipArchive::new(BufReader::new(File::open(path).ok()?)).ok()?;
+    let mut file = zip.by_name(name).ok()?;
+    let mut content = String::new();
+    file.read_to_string(&mut content).ok()?;
+    Some(content)
+}
+
+/// `name` as a path below the extraction folder, or `None` if it would escape it.
+fn safe_relative_path(name: &str) -> Option<PathBuf> {
+    let path = Path::new(name);
+    let mut relative = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => relative.push(part),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    (!relative.as_os_str().is_empty()).then_some(relative)
+}
+
+/// Unpack `archive` into `destination`, which is created if needed.
+/// Entries that would land outside `destination` are skipped. Returns the number of files written.
+pub fn extract_archive(archive: &Path, destination: &Path) -> Result<usize> {
+    let format = ArchiveFormat::from_path(archive).ok_or_else(|| anyhow!("Not an archive: {}", archive.display()))?;
+    std::fs::create_dir_all(destination)?;
+
+    match format {
+        ArchiveFormat::Zip => {
+            let mut zip = zip::ZipArchive::new(BufReader::new(File::open(archive)?))?;
+            let mut written = 0;
+            for index in 0..zip.len() {
+                let mut entry = zip.by_index(index)?;
+                let Some(relative) = entry.enclosed_name().as_deref().and_then(|p| safe_relative_path(&p.to_string_lossy())) else {
+                    tracing::warn!("Skipping unsafe entry {} in {}", entry.name(), archive.display());
+                    continue;
+                };
+                let target = destination.join(relative);
+                if entry.is_dir() {
+                    std::fs::create_dir_all(&target)?;
+                    continue;
+                }
+                if let Some(parent) = target.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::io::copy(&mut entry, &mut File::create(&target)?)?;
+                written += 1;
+            }
+            Ok(written)
+        }
+        ArchiveFormat::SevenZ => {
+            let mut written = 0;
+            sevenz_rust::decompress_file_with_extract_fn(archive, destination, |entry, reader, _| {
+                let Some(relative) = safe_relative_path(entry.name()) else {
+                    tracing::warn!("Skipping unsafe entry {} in {}", entry.name(), archive.display());
+                    return Ok(true);
+                };
+                let target = destination.join(relative);
+                if entry.is_directory() {
+                    std::fs::create_dir_all(&target)?;
+                    return Ok(true);
+                }
+                if let Some(parent) = target.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::io::copy(reader, &mut File::create(&target)?)?;
+                written += 1;
+                Ok(true)
+            })?;
+            Ok(written)
+        }
+        ArchiveFormat::Rar => Err(anyhow!(
+            "Extracting RAR archives is not supported; unpack {} with another tool",
+            archive.display()
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_zip_is_listed_and_extracted_inside_destination() {
+        let dir = std::env::temp_dir().join(format!("omnidex-archive-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let archive = dir.join("ForestPack.zip");
+        {
+            let mut zip = zip::ZipWriter::new(File::create(&archive).unwrap());
+            let options = zip::write::SimpleFileOptions::default();
+            zip.add_directory("ForestPack/", options).unwrap();
+            zip.start_file("ForestPack/ForestPack.uplugin", options).unwrap();
+            zip.write_all(b"{}").unwrap();
+            zip.start_file("ForestPack/manifest.json", options).unwrap();
+            zip.write_all(br#"{"category": "Environments"}"#).unwrap();
+            zip.start_file("ForestPack/Content/SM_Tree.uasset", options).unwrap();
+            zip.write_all(&[0u8; 100]).unwrap();
+            zip.finish().unwrap();
+        }
+
+        let entries = list_entries(&archive).unwrap();
+        let summary = summarize(ArchiveFormat::Zip, 0, &entries);
+        let inventory = inventory(&entries);
+        let json = read_metadata_json(&archive, &entries);
+        let written = extract_archive(&archive, &dir.join("out")).unwrap();
+        let extracted = dir.join("out").join("ForestPack").join("Content").join("SM_Tree.uasset").is_file();
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(summary.file_count, 3);
+        assert_eq!(summary.uncompressed_size, 130);
+        assert_eq!(summary.wrapper_folder.as_deref(), Some("ForestPack"));
+        assert!(summary.has_uplugin && !summary.has_uproject);
+        assert_eq!(summary.package_count, 1);
+        assert_eq!(inventory.by_folder["Content"].total_bytes, 100);
+        assert!(json.unwrap().contains("Environments"));
+        assert_eq!(written, 3);
+        assert!(extracted);
+        assert_eq!(safe_relative_path("../evil.txt"), None);
+        assert_eq!(safe_relative_path("/etc/passwd"), None);
+    }
+}
@@ -0,0 +1,258 @@
+//! Listing the contents of RAR archives from their block headers.
+//!
+//! Only headers are read, so this works for both RAR 4 and RAR 5 without a decompressor.
+//! Archives with encrypted headers cannot be listed.
+
+use super::ArchiveEntry;
+use anyhow::{anyhow, Result};
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+const RAR4_SIGNATURE: &[u8] = b"Rar!\x1a\x07\x00";
+const RAR5_SIGNATURE: &[u8] = b"Rar!\x1a\x07\x01\x00";
+
+/// Upper bound for a single header; anything larger means we are misreading the file.
+const MAX_HEADER_SIZE: u64 = 2 * 1024 * 1024;
+
+pub fn list_entries(path: &Path) -> Result<Vec<ArchiveEntry>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut signature = [0u8; 8];
+    reader.read_exact(&mut signature[..7])?;
+
+    if &signature[..7] == RAR4_SIGNATURE {
+        return list_rar4(&mut reader);
+    }
+    reader.read_exact(&mut signature[7..])?;
+    if signature == RAR5_SIGNATURE {
+        return list_rar5(&mut reader);
+    }
+    Err(anyhow!("{} is not a RAR archive", path.display()))
+}
+
+fn read_u8<R: Read>(reader: &mut R) -> Result<u8> {
+    let mut buf = [0u8; 1];
+    reader.read_exact(&mut buf)?;
+    Ok(buf[0])
+}
+
+fn read_u16<R: Read>(reader: &mut R) -> Result<u16> {
+    let mut buf = [0u8; 2];
+    reader.read_exact(&mut buf)?;
+    Ok(u16::from_le_bytes(buf))
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> Result<u32> {
+    let mut buf = [0u8; 4];
+    reader.read_exact(&mut buf)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+fn list_rar4<R: Read + Seek>(reader: &mut R) -> Result<Vec<ArchiveEntry>> {
+    const MAIN_HEADER: u8 = 0x73;
+    const FILE_HEADER: u8 = 0x74;
+    const END_OF_ARCHIVE: u8 = 0x7b;
+    const MAIN_ENCRYPTED_HEADERS: u16 = 0x0080;
+    const LONG_BLOCK: u16 = 0x8000;
+    const FILE_LARGE: u16 = 0x0100;
+    const FILE_UNICODE: u16 = 0x0200;
+    const FILE_DIRECTORY: u16 = 0x00e0;
+
+    let mut entries = Vec::new();
+    loop {
+        let block_start = reader.stream_position()?;
+        let Ok(_crc) = read_u16(reader) else {
+            break;
+        };
+        let block_type = read_u8(reader)?;
+        let flags = read_u16(reader)?;
+        let header_size = read_u16(reader)? as u64;
+        if header_size < 7 {
+            return Err(anyhow!("Corrupt RAR block header"));
+        }
+
+        let mut data_size = 0u64;
+        match block_type {
+            MAIN_HEADER if flags & MAIN_ENCRYPTED_HEADERS != 0 => {
+                return Err(anyhow!("RAR archive has encrypted headers"));
+            }
+            FILE_HEADER => {
+                let pack_size = read_u32(reader)? as u64;
+                let unpacked_size = read_u32(reader)? as u64;
+                // host OS, file CRC, time, version, method
+                let mut skipped = [0u8; 11];
+                reader.read_exact(&mut skipped)?;
+                let name_size = read_u16(reader)? as usize;
+                let _attributes = read_u32(reader)?;
+                let (high_pack, high_unpacked) = if flags & FILE_LARGE != 0 {
+                    (read_u32(reader)? as u64, read_u32(reader)? as u64)
+                } else {
+                    (0, 0)
+                };
+                let mut name = vec![0u8; name_size];
+                reader.read_exact(&mut name)?;
+                if flags & FILE_UNICODE != 0 {
+                    // The ASCII name comes first, followed by a zero and the packed Unicode form.
+                    if let Some(end) = name.iter().position(|b| *b == 0) {
+                        name.truncate(end);
+                    }
+                }
+
+                data_size = pack_size | (high_pack << 32);
+                entries.push(ArchiveEntry {
+                    path: String::from_utf8_lossy(&name).replace('\\', "/"),
+                    size: unpacked_size | (high_unpacked << 32),
+                    is_dir: flags & FILE_DIRECTORY == FILE_DIRECTORY,
+                });
+            }
+            END_OF_ARCHIVE => break,
+            _ if flags & LONG_BLOCK != 0 => {
+                data_size = read_u32(reader)? as u64;
+            }
+            _ => {}
+        }
+        reader.seek(SeekFrom::Start(block_start + header_size + data_size))?;
+    }
+    Ok(entries)
+}
+
+/// RAR 5 variable-length integer: 7 bits per byte, high bit set on all but the last byte.
+fn read_vint<R: Read>(reader: &mut R) -> Result<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = read_u8(reader)?;
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(anyhow!("Corrupt RAR variable-length integer"))
+}
+
+fn list_rar5<R: Read + Seek>(reader: &mut R) -> Result<Vec<ArchiveEntry>> {
+    const FILE_HEADER: u64 = 2;
+    const ENCRYPTION_HEADER: u64 = 4;
+    const END_OF_ARCHIVE: u64 = 5;
+    const HAS_EXTRA_AREA: u64 = 0x0001;
+    const HAS_DATA_AREA: u64 = 0x0002;
+    const FILE_IS_DIRECTORY: u64 = 0x0001;
+    const FILE_HAS_MTIME: u64 = 0x0002;
+    const FILE_HAS_CRC: u64 = 0x0004;
+
+    let mut entries = Vec::new();
+    loop {
+        let Ok(_crc) = read_u32(reader) else {
+            break;
+        };
+        let header_size = read_vint(reader)?;
+        if header_size == 0 || header_size > MAX_HEADER_SIZE {
+            return Err(anyhow!("Corrupt RAR block header"));
+        }
+        let header_start = reader.stream_position()?;
+        let header_type = read_vint(reader)?;
+        let header_flags = read_vint(reader)?;
+        if header_flags & HAS_EXTRA_AREA != 0 {
+            read_vint(reader)?;
+        }
+        let data_size = if header_flags & HAS_DATA_AREA != 0 { read_vint(reader)? } else { 0 };
+
+        match header_type {
+            ENCRYPTION_HEADER => return Err(anyhow!("RAR archive has encrypted headers")),
+            END_OF_ARCHIVE => break,
+            FILE_HEADER => {
+                let file_flags = read_vint(reader)?;
+                let unpacked_size = read_vint(reader)?;
+                let _attributes = read_vint(reader)?;
+                if file_flags & FILE_HAS_MTIME != 0 {
+                    read_u32(reader)?;
+                }
+                if file_flags & FILE_HAS_CRC != 0 {
+                    read_u32(reader)?;
+                }
+                let _compression = read_vint(reader)?;
+                let _host_os = read_vint(reader)?;
+                let name_length = read_vint(reader)?;
+                if name_length > MAX_HEADER_SIZE {
+                    return Err(anyhow!("Corrupt RAR file name"));
+                }
+                let mut name = vec![0u8; name_length as usize];
+                reader.read_exact(&mut name)?;
+
+                entries.push(ArchiveEntry {
+                    path: String::from_utf8_lossy(&name).into_owned(),
+                    size: unpacked_size,
+                    is_dir: file_flags & FILE_IS_DIRECTORY != 0,
+                });
+            }
+            _ => {}
+        }
+        reader.seek(SeekFrom::Start(header_start + header_size + data_size))?;
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn vint(mut value: u64, out: &mut Vec<u8>) {
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                out.push(byte);
+                return;
+            }
+            out.push(byte | 0x80);
+        }
+    }
+
+    fn rar5_block(header: &[u8], data: &[u8], out: &mut Vec<u8>) {
+        out.extend([0u8; 4]);
+        vint(header.len() as u64, out);
+        out.extend(header);
+        out.extend(data);
+    }
+
+    #[test]
+    fn test_lists_rar5_file_headers() {
+        let mut bytes = Vec::new();
+        // main archive header: type 1, no flags, archive flags 0
+        rar5_block(&[1, 0, 0], &[], &mut bytes);
+
+        let mut file = Vec::new();
+        vint(2, &mut file); // file header
+        vint(0x0002, &mut file); // has data area
+        vint(3, &mut file); // data size
+        vint(0, &mut file); // file flags
+        vint(5000, &mut file); // unpacked size
+        vint(0, &mut file); // attributes
+        vint(0, &mut file); // compression
+        vint(0, &mut file); // host OS
+        let name = b"Forest/Content/SM_Tree.uasset";
+        vint(name.len() as u64, &mut file);
+        file.extend(name);
+        rar5_block(&file, b"xyz", &mut bytes);
+
+        let mut dir = Vec::new();
+        vint(2, &mut dir);
+        vint(0, &mut dir);
+        vint(0x0001, &mut dir); // directory
+        vint(0, &mut dir);
+        vint(0, &mut dir);
+        vint(0, &mut dir);
+        vint(0, &mut dir);
+        vint(6, &mut dir);
+        dir.extend(b"Forest");
+        rar5_block(&dir, &[], &mut bytes);
+        rar5_block(&[5, 0, 0], &[], &mut bytes);
+
+        let entries = list_rar5(&mut Cursor::new(bytes)).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].path, "Forest/Content/SM_Tree.uasset");
+        assert_eq!(entries[0].size, 5000);
+        assert!(!entries[0].is_dir);
+        assert!(entries[1].is_dir);
+    }
+}
@@ -0,0 +1,420 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool};
+use sqlx::Connection;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tokio::sync::watch;
+
+use crate::database::{self, DatabaseManager};
+use crate::models::AppSettings;
+
+const BACKUP_PREFIX: &str = "omnidex-";
+const BACKUP_EXTENSION: &str = "db";
+const RESTORE_STAGING_FILE: &str = "restore-staging.db";
+
+/// Tables a file must contain to be accepted as an Omnidex database.
+const REQUIRED_TABLES: &[&str] = &["assets", "scan_locations", "app_settings"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupInfo {
+    pub path: String,
+    pub file_name: String,
+    pub size_bytes: u64,
+    pub created_date: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupVerification {
+    pub path: String,
+    pub is_valid: bool,
+    pub asset_count: Option<i64>,
+    pub problems: Vec<String>,
+}
+
+/// Folder backups are written to: the configured location, or `backups/` next to the database.
+pub fn backup_dir(settings: &AppSettings) -> PathBuf {
+    match &settings.backup_location {
+        Some(location) => PathBuf::from(location),
+        None => database::data_dir().join("backups"),
+    }
+}
+
+/// Write a consistent copy of the live database with `VACUUM INTO` and verify it.
+/// The database stays fully usable while the copy is taken.
+pub async fn create_backup(db_manager: &DatabaseManager, dir: &Path, label: Option<&str>) -> Result<BackupInfo> {
+    std::fs::create_dir_all(dir)?;
+
+    let timestamp = Utc::now().format("%Y%m%d-%H%M%S");
+    let file_name = match label {
+        Some(label) => format!("{}{}-{}.{}", BACKUP_PREFIX, timestamp, label, BACKUP_EXTENSION),
+        None => format!("{}{}.{}", BACKUP_PREFIX, timestamp, BACKUP_EXTENSION),
+    };
+    let path = dir.join(&file_name);
+    if path.exists() {
+        return Err(anyhow!("Backup file already exists: {}", path.display()));
+    }
+
+    sqlx::query("VACUUM INTO ?")
+        .bind(path.to_string_lossy().into_owned())
+        .execute(&db_manager.pool)
+        .await
+        .map_err(|e| anyhow!("Failed to write backup to {}: {}", path.display(), e))?;
+
+    let verification = verify_backup(&path).await?;
+    if !verification.is_valid {
+        let _ = std::fs::remove_file(&path);
+        return Err(anyhow!("Backup failed verification: {}", verification.problems.join("; ")));
+    }
+
+    tracing::info!("💾 Database backed up to {}", path.display());
+    backup_info(&path)
+}
+
+/// Open a backup read-only and check that it is an intact Omnidex database.
+pub async fn verify_backup(path: &Path) -> Result<BackupVerification> {
+    let mut verification = BackupVerification {
+        path: path.to_string_lossy().into_owned(),
+        is_valid: false,
+        asset_count: None,
+        problems: Vec::new(),
+    };
+
+    if !path.is_file() {
+        verification.problems.push("File does not exist".to_string());
+        return Ok(verification);
+    }
+
+    let options = SqliteConnectOptions::new().filename(path).read_only(true);
+    let pool = match SqlitePool::connect_with(options).await {
+        Ok(pool) => pool,
+        Err(e) => {
+            verification.problems.push(format!("Cannot open as SQLite database: {}", e));
+            return Ok(verification);
+        }
+    };
+
+    match sqlx::query_scalar::<_, String>("PRAGMA integrity_check").fetch_all(&pool).await {
+        Ok(messages) if messages.len() == 1 && messages[0] == "ok" => {}
+        Ok(messages) => verification.problems.extend(messages.into_iter().take(10)),
+        Err(e) => verification.problems.push(format!("Integrity check failed: {}", e)),
+    }
+
+    if verification.problems.is_empty() {
+        let tables: Vec<String> = sqlx::query_scalar("SELECT name FROM sqlite_master WHERE type = 'table'")
+            .fetch_all(&pool)
+            .await
+            .unwrap_or_default();
+        for required in REQUIRED_TABLES {
+            if !tables.iter().any(|t| t == required) {
+                verification.problems.push(format!("Missing table '{}'", required));
+            }
+        }
+        if tables.iter().any(|t| t == "assets") {
+            verification.asset_count = sqlx::query_scalar("SELECT COUNT(*) FROM assets").fetch_one(&pool).await.ok();
+        }
+    }
+
+    pool.close().await;
+    verification.is_valid = verification.problems.is_empty();
+    Ok(verification)
+}
+
+fn backup_info(path: &Path) -> Result<BackupInfo> {
+    let metadata = std::fs::metadata(path)?;
+    Ok(BackupInfo {
+        path: path.to_string_lossy().into_owned(),
+        file_name: path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default(),
+        size_bytes: metadata.len(),
+        created_date: DateTime::<Utc>::from(metadata.modified()?).to_rfc3339(),
+    })
+}
+
+fn backup_files(dir: &Path) -> Vec<(PathBuf, SystemTime)> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut files: Vec<(PathBuf, SystemTime)> = entries
+        .flatten()
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            name.starts_with(BACKUP_PREFIX) && name.ends_with(&format!(".{}", BACKUP_EXTENSION))
+        })
+        .filter_map(|entry| {
+            let modified = entry.metadata().ok()?.modified().ok()?;
+            Some((entry.path(), modified))
+        })
+        .collect();
+    // Newest first
+    files.sort_by_key(|(_, modified)| std::cmp::Reverse(*modified));
+    files
+}
+
+/// All backups in `dir`, newest first.
+pub fn list_backups(dir: &Path) -> Vec<BackupInfo> {
+    backup_files(dir)
+        .into_iter()
+        .filter_map(|(path, _)| backup_info(&path).ok())
+        .collect()
+}
+
+/// Delete backups beyond `keep_count` or older than `max_age`. The newest backup is always kept.
+pub fn rotate_backups(dir: &Path, keep_count: usize, max_age: Duration) -> Vec<PathBuf> {
+    let now = SystemTime::now();
+    let mut removed = Vec::new();
+
+    for (index, (path, modified)) in backup_files(dir).into_iter().enumerate() {
+        if index == 0 {
+            continue;
+        }
+        let too_old = now.duration_since(modified).map(|age| age > max_age).unwrap_or(false);
+        if index >= keep_count || too_old {
+            match std::fs::remove_file(&path) {
+                Ok(()) => removed.push(path),
+                Err(e) => tracing::warn!("Failed to remove old backup {}: {}", path.display(), e),
+            }
+        }
+    }
+    removed
+}
+
+/// Create a backup according to `settings` and rotate the backup folder.
+pub async fn run_backup(db_manager: &DatabaseManager, settings: &AppSettings) -> Result<BackupInfo> {
+    let dir = backup_dir(settings);
+    let info = create_backup(db_manager, &dir, None).await?;
+
+    let max_age = Duration::from_secs(settings.backup_max_age_days.max(1) as u64 * 24 * 60 * 60);
+    let removed = rotate_backups(&dir, settings.backup_keep_count.max(1) as usize, max_age);
+    if !removed.is_empty() {
+        tracing::info!("Rotated out {} old backup(s) from {}", removed.len(), dir.display());
+    }
+    Ok(info)
+}
+
+/// Take periodic backups while `backup_enabled` is on. Settings changes take effect immediately.
+pub async fn run_backup_scheduler(db_manager: DatabaseManager, mut settings_rx: watch::Receiver<AppSettings>) {
+    loop {
+        let settings = settings_rx.borrow_and_update().clone();
+        if !settings.backup_enabled {
+            if settings_rx.changed().await.is_err() {
+                break;
+            }
+            continue;
+        }
+
+        let interval = Duration::from_secs(settings.backup_interval_hours.max(1) as u64 * 60 * 60);
+        let since_last = backup_files(&backup_dir(&settings))
+            .first()
+            .and_then(|(_, modified)| SystemTime::now().duration_since(*modified).ok());
+
+        let wait = match since_last {
+            Some(age) if age < interval => interval - age,
+            _ => {
+                if let Err(e) = run_backup(&db_manager, &settings).await {
+                    tracing::error!("Scheduled backup failed: {}", e);
+                }
+                interval
+            }
+        };
+
+        tokio::select! {
+            changed = settings_rx.changed() => {
+                if changed.is_err() {
+                    break;
+                }
+            }
+            _ = tokio::time::sleep(wait) => {}
+        }
+    }
+}
+
+fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Replace the contents of the live database with a verified backup.
+///
+/// The pool is shared by the whole app and cannot be reopened, so instead of swapping files
+/// the backup is migrated to the current schema in a staging copy, attached, and copied over
+/// table by table inside a single transaction. Either everything is restored or nothing is.
+/// A `pre-restore` backup of the current state is written to `safety_dir` first, and the
+/// staging copy is made in `staging_dir` (the app's data directory).
+pub async fn restore_backup(
+    db_manager: &DatabaseManager,
+    backup_path: &Path,
+    safety_dir: &Path,
+    staging_dir: &Path,
+) -> Result<BackupInfo> {
+    let verification = verify_backup(backup_path).await?;
+    if !verification.is_valid {
+        return Err(anyhow!("Backup is not valid: {}", verification.problems.join("; ")));
+    }
+
+    let safety_backup = create_backup(db_manager, safety_dir, Some("pre-restore")).await?;
+
+    let staging_path = staging_dir.join(RESTORE_STAGING_FILE);
+    if staging_path.exists() {
+        std::fs::remove_file(&staging_path)?;
+    }
+    std::fs::copy(backup_path, &staging_path)?;
+
+    let staging_pool = SqlitePool::connect_with(SqliteConnectOptions::new().filename(&staging_path)).await?;
+    let migrated = database::create_tables(&staging_pool).await;
+    staging_pool.close().await;
+    migrated?;
+
+    let mut conn = db_manager.pool.acquire().await?;
+    sqlx::query("ATTACH DATABASE ? AS restore_src")
+        .bind(staging_path.to_string_lossy().into_owned())
+        .execute(&mut *conn)
+        .await?;
+
+    let copied = copy_attached_tables(&mut conn).await;
+
+    if let Err(e) = sqlx::query("DETACH DATABASE restore_src").execute(&mut *conn).await {
+        tracing::warn!("Failed to detach restore source: {}", e);
+    }
+    drop(conn);
+    let _ = std::fs::remove_file(&staging_path);
+
+    let restored_rows = copied?;
+    tracing::info!("♻️ Restored {} rows from {}", restored_rows, backup_path.display());
+    Ok(safety_backup)
+}
+
+async fn copy_attached_tables(conn: &mut sqlx::pool::PoolConnection<sqlx::Sqlite>) -> Result<u64> {
+    let mut tx = conn.begin().await?;
+    // Rows are copied parent and child tables alike, so only check foreign keys at commit.
+    sqlx::query("PRAGMA defer_foreign_keys = ON").execute(&mut *tx).await?;
+
+    let tables: Vec<String> = sqlx::query_scalar(
+        "SELECT name FROM main.sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'",
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+
+    for table in &tables {
+        sqlx::query(&format!("DELETE FROM main.{}", quote_identifier(table)))
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    let mut restored_rows = 0;
+    for table in &tables {
+        let quoted = quote_identifier(table);
+        let target_columns: Vec<String> = sqlx::query_scalar(&format!("SELECT name FROM pragma_table_info({}, 'main')", sql_string(table)))
+            .fetch_all(&mut *tx)
+            .await?;
+        let source_columns: Vec<String> = sqlx::query_scalar(&format!("SELECT name FROM pragma_table_info({}, 'restore_src')", sql_string(table)))
+            .fetch_all(&mut *tx)
+            .await?;
+
+        let common: Vec<String> = target_columns
+            .iter()
+            .filter(|c| source_columns.contains(c))
+            .map(|c| quote_identifier(c))
+            .collect();
+        if common.is_empty() {
+            continue;
+        }
+
+        let column_list = common.join(", ");
+        let result = sqlx::query(&format!(
+            "INSERT INTO main.{quoted} ({column_list}) SELECT {column_list} FROM restore_src.{quoted}"
+        ))
+        .execute(&mut *tx)
+        .await?;
+        restored_rows += result.rows_affected();
+    }
+
+    tx.commit().await?;
+    Ok(restored_rows)
+}
+
+fn sql_string(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    fn test_dir(test_name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("omnidex-backup-{}-{}", test_name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write_backup(dir: &Path, file_name: &str, age: Duration) -> PathBuf {
+        let path = dir.join(file_name);
+        let file = std::fs::File::create(&path).unwrap();
+        file.set_modified(SystemTime::now() - age).unwrap();
+        path
+    }
+
+    fn remaining(dir: &Path) -> Vec<String> {
+        list_backups(dir).into_iter().map(|backup| backup.file_name).collect()
+    }
+
+    #[test]
+    fn test_rotation_keeps_the_newest_backups() {
+        let dir = test_dir("keep");
+        for day in 1..=5 {
+            write_backup(&dir, &format!("omnidex-2024010{}.db", 6 - day), DAY * day);
+        }
+        write_backup(&dir, "notes.txt", DAY * 9);
+
+        let removed = rotate_backups(&dir, 2, DAY * 30);
+        assert_eq!(removed.len(), 3);
+        assert_eq!(remaining(&dir), ["omnidex-20240105.db", "omnidex-20240104.db"]);
+        assert!(dir.join("notes.txt").exists());
+    }
+
+    #[test]
+    fn test_rotation_removes_old_backups_but_never_the_newest() {
+        let dir = test_dir("age");
+        write_backup(&dir, "omnidex-20240103.db", DAY * 10);
+        write_backup(&dir, "omnidex-20240102.db", DAY * 40);
+        write_backup(&dir, "omnidex-20240101.db", DAY * 50);
+
+        assert_eq!(rotate_backups(&dir, 10, DAY * 30).len(), 2);
+        assert_eq!(remaining(&dir), ["omnidex-20240103.db"]);
+        // Even a backup past the maximum age stays while it is the only one.
+        assert!(rotate_backups(&dir, 10, DAY).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_backups_are_verified_and_restored() {
+        let dir = test_dir("restore");
+        // VACUUM INTO from an in-memory database would write another in-memory database.
+        let db = DatabaseManager::open_file(&dir.join("live.sqlite")).await.unwrap();
+        db.create_scan_location("Vault", "/vault", false, None, None, crate::models::LOCATION_KIND_VAULT)
+            .await
+            .unwrap();
+
+        let backup = create_backup(&db, &dir, Some("test")).await.unwrap();
+        let verification = verify_backup(Path::new(&backup.path)).await.unwrap();
+        assert!(verification.is_valid, "{:?}", verification.problems);
+        assert_eq!(verification.asset_count, Some(0));
+
+        std::fs::write(dir.join("broken.db"), b"not a database").unwrap();
+        assert!(!verify_backup(&dir.join("broken.db")).await.unwrap().is_valid);
+        assert!(restore_backup(&db, &dir.join("broken.db"), &dir, &dir).await.is_err());
+
+        db.create_scan_location("Projects", "/projects", false, None, None, crate::models::LOCATION_KIND_VAULT)
+            .await
+            .unwrap();
+        let safety = restore_backup(&db, Path::new(&backup.path), &dir, &dir).await.unwrap();
+        assert!(safety.file_name.ends_with("-pre-restore.db"));
+
+        let names: Vec<String> = sqlx::query_scalar("SELECT name FROM scan_locations").fetch_all(&db.pool).await.unwrap();
+        assert_eq!(names, ["Vault"]);
+        assert!(!dir.join(RESTORE_STAGING_FILE).exists());
+    }
+}
@@ -0,0 +1,5 @@
+// Headless command-line interface; shares the library and the database with the desktop app.
+
+fn main() {
+    std::process::exit(app_lib::cli::main());
+}
@@ -0,0 +1,569 @@
+//! Category assignment from a declarative, user-editable taxonomy.
+//!
+//! Each category lists aliases and regex rules. An alias matches a textual signal
+//! (folder name, descriptor category, Orbital category) after normalization; a rule matches
+//! the signal of one specific source. The firing rule with the highest confidence picks the
+//! primary category, other categories with enough confidence become secondary ones, and every
+//! assignment records its confidence and a description of the rule that fired.
+//!
+//! Categories form a tree through their `parent`: an asset in `foliage` also counts towards
+//! `nature` and `environment`.
+
+use anyhow::{anyhow, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+#[cfg(feature = "desktop")]
+use tauri::{AppHandle, Manager};
+
+use crate::models::AppSettings;
+#[cfg(feature = "desktop")]
+use crate::settings::SettingsService;
+
+pub mod signals;
+
+pub use signals::CategorySignals;
+
+/// Category given to assets no rule matched.
+pub const DEFAULT_FALLBACK_CATEGORY: &str = "uncategorized";
+
+/// Rule description recorded for assets that fell through to the fallback category.
+pub const FALLBACK_RULE: &str = "fallback";
+
+/// Rule description recorded for categories chosen by the user.
+pub const MANUAL_RULE: &str = "manual";
+
+/// Least confidence for a category to be kept as a secondary one.
+const SECONDARY_MIN_CONFIDENCE: f64 = 0.3;
+
+/// Least share of an asset's packages a package-class rule needs to fire at all. Rules reach
+/// their full confidence once the matching classes make up half of the packages.
+const PACKAGE_MIN_SHARE: f64 = 0.1;
+
+/// Where a signal used for categorization comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignalSource {
+    /// Name of the asset's own folder, archive or file.
+    FolderName,
+    /// Name of the folder the asset sits in.
+    ParentFolder,
+    /// `category` / `asset_type` of a bundled json manifest, or `Category` of a `.uplugin`.
+    Descriptor,
+    /// Unreal asset classes of the packages the asset ships, inferred from their names.
+    PackageClass,
+    /// Categories of the matched Orbital product.
+    OrbitalCategory,
+    /// Lower-case extension of a loose media file.
+    FileExtension,
+}
+
+impl SignalSource {
+    /// Confidence of an alias match on this source. Aliases do not apply to package classes
+    /// and extensions, which are matched by rules only.
+    fn alias_confidence(self) -> Option<f64> {
+        match self {
+            Self::Descriptor => Some(0.9),
+            Self::OrbitalCategory => Some(0.8),
+            Self::FolderName => Some(0.6),
+            Self::ParentFolder => Some(0.5),
+            Self::PackageClass | Self::FileExtension => None,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::FolderName => "folder name",
+            Self::ParentFolder => "parent folder",
+            Self::Descriptor => "descriptor",
+            Self::PackageClass => "package classes",
+            Self::OrbitalCategory => "Orbital category",
+            Self::FileExtension => "file extension",
+        }
+    }
+}
+
+/// A regex evaluated against the signals of one source.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CategoryRule {
+    pub source: SignalSource,
+    pub pattern: String,
+    /// Between 0 and 1. For package classes it is scaled down when fewer than half of the
+    /// packages match.
+    pub confidence: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CategoryDefinition {
+    /// Stored in `assets.asset_type`, e.g. `"3d-model"`. Also matches as an alias.
+    pub id: String,
+    /// Id of the enclosing category, `None` for top-level ones.
+    #[serde(default)]
+    pub parent: Option<String>,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    #[serde(default)]
+    pub rules: Vec<CategoryRule>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CategoryTaxonomy {
+    pub categories: Vec<CategoryDefinition>,
+    #[serde(default = "default_fallback")]
+    pub fallback: String,
+}
+
+fn default_fallback() -> String {
+    DEFAULT_FALLBACK_CATEGORY.to_string()
+}
+
+/// The outcome of categorizing one asset.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CategoryAssignment {
+    pub category: String,
+    pub confidence: f64,
+    /// Human-readable description of the rule that fired, [`FALLBACK_RULE`] or [`MANUAL_RULE`].
+    pub rule: String,
+}
+
+/// Every category of one asset: the primary one, stored in `assets.asset_type`, and
+/// secondary ones, strongest first.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Categorization {
+    pub primary: CategoryAssignment,
+    pub secondary: Vec<CategoryAssignment>,
+}
+
+impl Categorization {
+    pub fn is_manual(&self) -> bool {
+        self.primary.rule == MANUAL_RULE
+    }
+}
+
+/// Lower-case with spaces and underscores turned into dashes, so "Textures & Materials",
+/// "textures_&_materials" and "textures-&-materials" compare equal.
+pub fn normalize_category_name(name: &str) -> String {
+    name.trim().to_lowercase().replace([' ', '_'], "-")
+}
+
+fn category(id: &str, aliases: &[&str], rules: &[(SignalSource, &str, f64)]) -> CategoryDefinition {
+    CategoryDefinition {
+        id: id.to_string(),
+        parent: None,
+        aliases: aliases.iter().map(|a| a.to_string()).collect(),
+        rules: rules
+            .iter()
+            .map(|(source, pattern, confidence)| CategoryRule {
+                source: *source,
+                pattern: pattern.to_string(),
+                confidence: *confidence,
+            })
+            .collect(),
+    }
+}
+
+impl Default for CategoryTaxonomy {
+    /// The categories the frontend knows, with the aliases the scanner has always accepted,
+    /// plus a few sub-categories.
+    fn default() -> Self {
+        use SignalSource::*;
+        let child = |parent: &str, definition: CategoryDefinition| CategoryDefinition {
+            parent: Some(parent.to_string()),
+            ..definition
+        };
+        Self {
+            categories: vec![
+                category("2d-asset", &["2d", "2d-assets", "2d-graphics", "graphics", "images", "sprites"], &[
+                    (FileExtension, "^(png|jpe?g|tga)$", 0.95),
+                ]),
+                category("3d-model", &["3d", "3d-assets", "3d-models", "models", "mesh", "meshes", "props"], &[
+                    (PackageClass, "^(StaticMesh|SkeletalMesh)$", 0.7),
+                    (FileExtension, "^(fbx|obj|gltf|glb)$", 0.95),
+                ]),
+                category("animation", &["animations", "anim", "anims", "motion", "mocap"], &[
+                    (PackageClass, "^(AnimSequence|AnimMontage|AnimBlueprint|BlendSpace)$", 0.75),
+                ]),
+                category("audio", &["sound", "sounds", "audio-files"], &[
+                    (PackageClass, "^(SoundWave|SoundCue)$", 0.8),
+                    (FileExtension, "^(wav|flac|ogg|mp3)$", 0.95),
+                ]),
+                child("audio", category("music", &["soundtrack", "soundtracks"], &[])),
+                child("audio", category("sound-effects", &["sfx", "sound-fx"], &[])),
+                category("education-tutorial", &["tutorial", "tutorials", "learning", "course", "courses"], &[]),
+                category("environment", &["env", "environment-assets", "environments", "landscape", "terrain"], &[
+                    (FolderName, r"(?i)\b(desert|village|city|ruins|dungeon|landscape)\b", 0.4),
+                ]),
+                child("environment", category("nature", &["natural", "nature-&-plants", "nature-and-plants"], &[
+                    (FolderName, r"(?i)\b(forest|jungle|nature|meadow|swamp)\b", 0.45),
+                ])),
+                child("nature", category("foliage", &["trees", "plants", "vegetation", "grass"], &[
+                    (FolderName, r"(?i)\b(foliage|trees?|plants?|grass|vegetation)\b", 0.5),
+                ])),
+                child("environment", category("architecture", &["buildings", "architectural", "interiors"], &[
+                    (FolderName, r"(?i)\b(houses?|castles?|buildings?|interiors?|architecture)\b", 0.45),
+                ])),
+                category("game-system", &["gameplay", "mechanics", "systems"], &[]),
+                category("game-template", &["template", "templates", "blueprint", "blueprints"], &[
+                    (PackageClass, "^Blueprint$", 0.5),
+                ]),
+                category("hdri", &["hdr", "hdri-images", "skybox", "skyboxes"], &[
+                    (FileExtension, "^(hdr|exr)$", 0.95),
+                ]),
+                category("material", &["texture", "textures", "textures-materials", "textures-&-materials", "materials"], &[
+                    (PackageClass, "^(Material|MaterialInstance|MaterialFunction|Texture)$", 0.7),
+                ]),
+                category("smart-asset", &["smart", "intelligent", "procedural"], &[]),
+                category("tool-and-plugin", &["tool", "tools", "plugin", "plugins", "utility", "utilities", "code-plugins"], &[]),
+                category("ui", &["interface", "gui", "hud", "menu", "menus"], &[
+                    (PackageClass, "^WidgetBlueprint$", 0.75),
+                ]),
+                category("vfx", &["effects", "particle", "particles", "visual-effects"], &[
+                    (PackageClass, "^(NiagaraSystem|NiagaraEmitter|ParticleSystem)$", 0.8),
+                ]),
+            ],
+            fallback: default_fallback(),
+        }
+    }
+}
+
+impl CategoryTaxonomy {
+    /// Check the taxonomy can be compiled, returning a message for the first problem.
+    pub fn validate(&self) -> Result<(), String> {
+        CategoryEngine::new(self).map(|_| ()).map_err(|e| e.to_string())
+    }
+}
+
+struct CompiledRule {
+    category: usize,
+    source: SignalSource,
+    pattern: String,
+    regex: Regex,
+    confidence: f64,
+}
+
+/// A compiled [`CategoryTaxonomy`]. Cheap to clone and shared by scan workers.
+#[derive(Clone)]
+pub struct CategoryEngine {
+    inner: Arc<CompiledTaxonomy>,
+}
+
+struct CompiledTaxonomy {
+    ids: Vec<String>,
+    /// Index of each category's parent.
+    parents: Vec<Option<usize>>,
+    /// Normalized alias (including each id) to the index of its category.
+    aliases: BTreeMap<String, usize>,
+    rules: Vec<CompiledRule>,
+    fallback: String,
+}
+
+impl Default for CategoryEngine {
+    fn default() -> Self {
+        Self::new(&CategoryTaxonomy::default()).expect("built-in category taxonomy is valid")
+    }
+}
+
+impl CategoryEngine {
+    pub fn new(taxonomy: &CategoryTaxonomy) -> Result<Self> {
+        if taxonomy.fallback.trim().is_empty() {
+            return Err(anyhow!("Fallback category cannot be empty"));
+        }
+
+        let mut ids = Vec::with_capacity(taxonomy.categories.len());
+        let mut aliases = BTreeMap::new();
+        let mut rules = Vec::new();
+        for (index, definition) in taxonomy.categories.iter().enumerate() {
+            let id = definition.id.trim();
+            if id.is_empty() {
+                return Err(anyhow!("Category #{} has no id", index + 1));
+            }
+            if ids.iter().any(|existing| existing == id) {
+                return Err(anyhow!("Category '{}' is defined more than once", id));
+            }
+            ids.push(id.to_string());
+
+            for alias in std::iter::once(id).chain(definition.aliases.iter().map(String::as_str)) {
+                let normalized = normalize_category_name(alias);
+                if normalized.is_empty() {
+                    continue;
+                }
+                if let Some(other) = aliases.insert(normalized, index) {
+                    if other != index {
+                        return Err(anyhow!("Alias '{}' is used by both '{}' and '{}'", alias, taxonomy.categories[other].id, id));
+                    }
+                }
+            }
+
+            for rule in &definition.rules {
+                if !(0.0..=1.0).contains(&rule.confidence) {
+                    return Err(anyhow!("Rule '{}' of '{}' needs a confidence between 0 and 1", rule.pattern, id));
+                }
+                let regex = Regex::new(&rule.pattern)
+                    .map_err(|e| anyhow!("Invalid pattern '{}' in category '{}': {}", rule.pattern, id, e))?;
+                rules.push(CompiledRule {
+                    category: index,
+                    source: rule.source,
+                    pattern: rule.pattern.clone(),
+                    regex,
+                    confidence: rule.confidence,
+                });
+            }
+        }
+
+        let mut parents = Vec::with_capacity(ids.len());
+        for definition in &taxonomy.categories {
+            let parent = match definition.parent.as_deref().map(str::trim) {
+                None | Some("") => None,
+                Some(parent) => Some(ids.iter().position(|id| id == parent).ok_or_else(|| {
+                    anyhow!("Category '{}' has unknown parent '{}'", definition.id.trim(), parent)
+                })?),
+            };
+            parents.push(parent);
+        }
+        for start in 0..parents.len() {
+            let mut steps = 0;
+            let mut current = parents[start];
+            while let Some(index) = current {
+                steps += 1;
+                if index == start || steps > parents.len() {
+                    return Err(anyhow!("Category '{}' is its own ancestor", ids[start]));
+                }
+                current = parents[index];
+            }
+        }
+
+        Ok(Self {
+            inner: Arc::new(CompiledTaxonomy {
+                ids,
+                parents,
+                aliases,
+                rules,
+                fallback: taxonomy.fallback.trim().to_string(),
+            }),
+        })
+    }
+
+    /// The engine for the taxonomy configured in `settings`, or the built-in one.
+    pub fn from_settings(settings: &AppSettings) -> Self {
+        match &settings.category_taxonomy {
+            Some(taxonomy) => Self::new(taxonomy).unwrap_or_else(|e| {
+                tracing::warn!("Configured category taxonomy is invalid ({}), using the built-in one", e);
+                Self::default()
+            }),
+            None => Self::default(),
+        }
+    }
+
+    /// The engine for the app's current settings.
+    #[cfg(feature = "desktop")]
+    pub fn configured(app_handle: &AppHandle) -> Self {
+        app_handle
+            .try_state::<SettingsService>()
+            .map(|settings| Self::from_settings(&settings.current()))
+            .unwrap_or_default()
+    }
+
+    /// Every category id, in taxonomy order.
+    pub fn category_ids(&self) -> impl Iterator<Item = &str> {
+        self.inner.ids.iter().map(String::as_str)
+    }
+
+    /// `category` followed by its ancestors, innermost first. Ids not in the taxonomy
+    /// (such as the fallback) have no ancestors.
+    pub fn lineage(&self, category: &str) -> Vec<String> {
+        let taxonomy = &*self.inner;
+        let mut lineage = vec![category.to_string()];
+        let mut current = taxonomy.ids.iter().position(|id| id == category).and_then(|index| taxonomy.parents[index]);
+        while let Some(index) = current {
+            lineage.push(taxonomy.ids[index].clone());
+            current = taxonomy.parents[index];
+        }
+        lineage
+    }
+
+    /// `category` and every category below it in the tree.
+    pub fn subtree(&self, category: &str) -> Vec<String> {
+        let taxonomy = &*self.inner;
+        let mut subtree = vec![category.to_string()];
+        let mut index = 0;
+        while index < subtree.len() {
+            let parent = taxonomy.ids.iter().position(|id| *id == subtree[index]);
+            if parent.is_some() {
+                let children = (0..taxonomy.ids.len()).filter(|child| taxonomy.parents[*child] == parent);
+                subtree.extend(children.map(|child| taxonomy.ids[child].clone()));
+            }
+            index += 1;
+        }
+        subtree
+    }
+
+    /// Whether `category` is one of the taxonomy's ids or its fallback.
+    pub fn contains(&self, category: &str) -> bool {
+        category == self.inner.fallback || self.inner.ids.iter().any(|id| id == category)
+    }
+
+    /// Categorize an asset from `signals`. The strongest category becomes the primary one.
+    /// Other categories reaching
+    /// `SECONDARY_MIN_CONFIDENCE` become secondary, except ancestors of the primary one,
+    /// which it already implies.
+    pub fn assign(&self, signals: &CategorySignals) -> Categorization {
+        let taxonomy = &*self.inner;
+        // Strongest firing rule per category.
+        let mut best: BTreeMap<usize, (f64, String)> = BTreeMap::new();
+        let mut consider = |confidence: f64, category: usize, rule: String| {
+            if confidence <= 0.0 {
+                return;
+            }
+            match best.get(&category) {
+                Some((existing, _)) if *existing >= confidence => {}
+                _ => {
+                    best.insert(category, (confidence, rule));
+                }
+            }
+        };
+
+        for (source, value) in signals.text_signals() {
+            let Some(confidence) = source.alias_confidence() else {
+                continue;
+            };
+            if let Some(&category) = taxonomy.aliases.get(&normalize_category_name(value)) {
+                consider(confidence, category, format!("alias matched {} '{}'", source.label(), value));
+            }
+        }
+
+        let package_total: usize = signals.package_classes.values().sum();
+        for rule in &taxonomy.rules {
+            if rule.source == SignalSource::PackageClass {
+                let matched: usize = signals
+                    .package_classes
+                    .iter()
+                    .filter(|(class, _)| rule.regex.is_match(class))
+                    .map(|(_, count)| count)
+                    .sum();
+                let share = if package_total > 0 { matched as f64 / package_total as f64 } else { 0.0 };
+                if share >= PACKAGE_MIN_SHARE {
+                    consider(
+                        rule.confidence * (share * 2.0).min(1.0),
+                        rule.category,
+                        format!("rule /{}/ matched {}/{} package classes", rule.pattern, matched, package_total),
+                    );
+                }
+                continue;
+            }
+            let matched = signals
+                .text_signals()
+                .find(|(source, value)| *source == rule.source && rule.regex.is_match(value));
+            if let Some((source, value)) = matched {
+                consider(rule.confidence, rule.category, format!("rule /{}/ matched {} '{}'", rule.pattern, source.label(), value));
+            }
+        }
+
+        let mut ranked: Vec<(usize, f64, String)> =
+            best.into_iter().map(|(category, (confidence, rule))| (category, confidence, rule)).collect();
+        // Among equal confidences the more specific category wins, then the one listed first.
+        let depth = |category: usize| self.lineage(&taxonomy.ids[category]).len();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| depth(b.0).cmp(&depth(a.0))));
+        let assignment = |(category, confidence, rule): (usize, f64, String)| CategoryAssignment {
+            category: taxonomy.ids[category].clone(),
+            confidence: (confidence * 100.0).round() / 100.0,
+            rule,
+        };
+
+        let mut ranked = ranked.into_iter();
+        let Some(primary) = ranked.next() else {
+            return Categorization {
+                primary: CategoryAssignment {
+                    category: taxonomy.fallback.clone(),
+                    confidence: 0.0,
+                    rule: FALLBACK_RULE.to_string(),
+                },
+                secondary: Vec::new(),
+            };
+        };
+        let primary = assignment(primary);
+        let implied = self.lineage(&primary.category);
+        let secondary = ranked
+            .filter(|(_, confidence, _)| *confidence >= SECONDARY_MIN_CONFIDENCE)
+            .map(assignment)
+            .filter(|a| !implied.contains(&a.category))
+            .collect();
+        Categorization { primary, secondary }
+    }
+}
+
+impl std::fmt::Debug for CategoryEngine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CategoryEngine")
+            .field("categories", &self.inner.ids)
+            .field("rules", &self.inner.rules.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strongest_signal_wins_and_unknown_names_fall_back() {
+        let engine = CategoryEngine::default();
+
+        let by_folder = engine.assign(&CategorySignals {
+            parent_folder: Some("Rain".to_string()),
+            folder_name: Some("Stormy_Pack".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(by_folder.primary.category, DEFAULT_FALLBACK_CATEGORY);
+        assert_eq!(by_folder.primary.rule, FALLBACK_RULE);
+
+        let mut signals = CategorySignals {
+            parent_folder: Some("Textures".to_string()),
+            folder_name: Some("Stylized Forest".to_string()),
+            ..Default::default()
+        };
+        let by_name = engine.assign(&signals);
+        assert_eq!(by_name.primary.category, "material");
+        assert_eq!(by_name.secondary[0].category, "nature");
+
+        signals.package_classes = [("NiagaraSystem".to_string(), 9), ("Texture".to_string(), 3)].into();
+        let by_packages = engine.assign(&signals);
+        assert_eq!((by_packages.primary.category.as_str(), by_packages.primary.confidence), ("vfx", 0.8));
+        let secondary: Vec<_> = by_packages.secondary.iter().map(|a| a.category.as_str()).collect();
+        assert_eq!(secondary, ["material", "nature"]);
+
+        signals.descriptor_categories = vec!["Environments".to_string()];
+        let by_descriptor = engine.assign(&signals);
+        assert_eq!((by_descriptor.primary.category.as_str(), by_descriptor.primary.confidence), ("environment", 0.9));
+        assert!(by_descriptor.primary.rule.contains("descriptor 'Environments'"));
+
+        let mut taxonomy = CategoryTaxonomy::default();
+        taxonomy.categories[0].rules.push(CategoryRule {
+            source: SignalSource::FolderName,
+            pattern: "(".to_string(),
+            confidence: 0.5,
+        });
+        assert!(taxonomy.validate().is_err());
+    }
+
+    #[test]
+    fn test_hierarchy_implies_ancestors() {
+        let engine = CategoryEngine::default();
+        assert_eq!(engine.lineage("foliage"), ["foliage", "nature", "environment"]);
+        assert_eq!(engine.subtree("nature"), ["nature", "foliage"]);
+        assert!(engine.subtree("environment").contains(&"foliage".to_string()));
+
+        let foliage = engine.assign(&CategorySignals {
+            folder_name: Some("Forest Trees".to_string()),
+            parent_folder: Some("Environments".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(foliage.primary.category, "foliage");
+        assert!(foliage.secondary.is_empty(), "ancestors of the primary category are implied");
+
+        let mut taxonomy = CategoryTaxonomy::default();
+        let environment = taxonomy.categories.iter().position(|c| c.id == "environment").unwrap();
+        taxonomy.categories[environment].parent = Some("foliage".to_string());
+        assert!(taxonomy.validate().unwrap_err().contains("own ancestor"));
+    }
+}
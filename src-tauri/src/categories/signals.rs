@@ -0,0 +1,180 @@
+//! Collecting the signals the category engine evaluates, from disk and from indexed data.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use super::SignalSource;
+use crate::{archive, media};
+
+/// Largest descriptor file read for categorization.
+const MAX_DESCRIPTOR_BYTES: u64 = 1024 * 1024;
+
+/// Asset class implied by the naming convention of a package, longest prefixes first
+/// so `MI_` is not taken for `M_`.
+const PACKAGE_CLASS_PREFIXES: &[(&str, &str)] = &[
+    ("ABP_", "AnimBlueprint"),
+    ("WBP_", "WidgetBlueprint"),
+    ("PHYS_", "PhysicsAsset"),
+    ("MI_", "MaterialInstance"),
+    ("MF_", "MaterialFunction"),
+    ("SM_", "StaticMesh"),
+    ("SK_", "SkeletalMesh"),
+    ("BP_", "Blueprint"),
+    ("AM_", "AnimMontage"),
+    ("AS_", "AnimSequence"),
+    ("BS_", "BlendSpace"),
+    ("NS_", "NiagaraSystem"),
+    ("NE_", "NiagaraEmitter"),
+    ("SW_", "SoundWave"),
+    ("SC_", "SoundCue"),
+    ("T_", "Texture"),
+    ("M_", "Material"),
+    ("A_", "AnimSequence"),
+    ("P_", "ParticleSystem"),
+    ("S_", "SoundWave"),
+    ("W_", "WidgetBlueprint"),
+];
+
+/// Everything known about an asset that can point at a category.
+#[derive(Debug, Clone, Default)]
+pub struct CategorySignals {
+    pub folder_name: Option<String>,
+    pub parent_folder: Option<String>,
+    pub descriptor_categories: Vec<String>,
+    /// Package count per inferred asset class.
+    pub package_classes: BTreeMap<String, usize>,
+    pub orbital_categories: Vec<String>,
+    pub file_extension: Option<String>,
+}
+
+impl CategorySignals {
+    /// Signals of an asset folder: its name, its parent's name and the descriptors it ships.
+    pub fn for_folder(folder_path: &Path) -> Self {
+        let mut signals = Self::from_path(folder_path);
+        if let Ok(entries) = fs::read_dir(folder_path) {
+            let mut files: Vec<_> = entries.flatten().map(|e| e.path()).filter(|p| p.is_file()).collect();
+            files.sort();
+            for file in files {
+                let extension = file.extension().and_then(|e| e.to_str()).map(str::to_ascii_lowercase);
+                let small = file.metadata().is_ok_and(|m| m.len() <= MAX_DESCRIPTOR_BYTES);
+                match extension.as_deref() {
+                    Some("json") if small => {
+                        if let Ok(content) = fs::read_to_string(&file) {
+                            signals.add_manifest(&content);
+                        }
+                    }
+                    Some("uplugin") if small => {
+                        if let Ok(content) = fs::read_to_string(&file) {
+                            signals.add_plugin_descriptor(&content);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        signals
+    }
+
+    /// Signals of an archive: its name, its parent's name and the manifest it bundles, if read.
+    pub fn for_archive(archive_path: &Path, metadata_json: Option<&str>) -> Self {
+        let mut signals = Self::from_path(archive_path);
+        if let Some(content) = metadata_json {
+            signals.add_manifest(content);
+        }
+        signals
+    }
+
+    /// Signals of a loose media file: its extension plus its name and parent folder.
+    pub fn for_media_file(file_path: &Path) -> Self {
+        let mut signals = Self::from_path(file_path);
+        signals.file_extension = file_path.extension().and_then(|e| e.to_str()).map(str::to_ascii_lowercase);
+        signals
+    }
+
+    /// Signals of an already indexed asset, read the way the scanner reads its kind of path.
+    pub fn for_indexed_path(path: &Path) -> Self {
+        if path.is_dir() {
+            Self::for_folder(path)
+        } else if archive::is_archive(path) {
+            let metadata_json = archive::list_entries(path)
+                .ok()
+                .and_then(|entries| archive::read_metadata_json(path, &entries));
+            Self::for_archive(path, metadata_json.as_deref())
+        } else if media::media_kind(path).is_some() {
+            Self::for_media_file(path)
+        } else {
+            Self::from_path(path)
+        }
+    }
+
+    fn from_path(path: &Path) -> Self {
+        let name = if path.is_dir() { path.file_name() } else { path.file_stem() };
+        Self {
+            folder_name: name.map(|n| n.to_string_lossy().into_owned()),
+            parent_folder: path.parent().and_then(|p| p.file_name()).map(|n| n.to_string_lossy().into_owned()),
+            ..Default::default()
+        }
+    }
+
+    /// Take the `category` or `asset_type` field of a json manifest.
+    pub fn add_manifest(&mut self, content: &str) {
+        let Ok(json) = serde_json::from_str::<serde_json::Value>(content) else {
+            return;
+        };
+        let category = json
+            .get("category")
+            .and_then(|v| v.as_str())
+            .or_else(|| json.get("asset_type").and_then(|v| v.as_str()));
+        if let Some(category) = category.filter(|c| !c.trim().is_empty()) {
+            self.descriptor_categories.push(category.to_string());
+        }
+    }
+
+    /// Take the `Category` field of a `.uplugin` descriptor.
+    pub fn add_plugin_descriptor(&mut self, content: &str) {
+        let Ok(json) = serde_json::from_str::<serde_json::Value>(content) else {
+            return;
+        };
+        if let Some(category) = json.get("Category").and_then(|v| v.as_str()).filter(|c| !c.trim().is_empty()) {
+            self.descriptor_categories.push(category.to_string());
+        }
+    }
+
+    /// Count the classes of indexed packages (`/Game/Props/SM_Crate` style names).
+    pub fn add_packages<'a>(&mut self, package_names: impl IntoIterator<Item = &'a str>) {
+        for name in package_names {
+            if let Some(class) = package_class(name) {
+                *self.package_classes.entry(class.to_string()).or_default() += 1;
+            }
+        }
+    }
+
+    /// Take the categories of a matched Orbital product, stored as a json array of strings.
+    pub fn add_orbital_categories(&mut self, categories_json: &str) {
+        if let Ok(categories) = serde_json::from_str::<Vec<String>>(categories_json) {
+            self.orbital_categories.extend(categories.into_iter().filter(|c| !c.trim().is_empty()));
+        }
+    }
+
+    /// Every textual signal with its source, strongest sources first.
+    pub(super) fn text_signals(&self) -> impl Iterator<Item = (SignalSource, &str)> {
+        let descriptors = self.descriptor_categories.iter().map(|v| (SignalSource::Descriptor, v.as_str()));
+        let orbital = self.orbital_categories.iter().map(|v| (SignalSource::OrbitalCategory, v.as_str()));
+        descriptors
+            .chain(orbital)
+            .chain(self.folder_name.iter().map(|v| (SignalSource::FolderName, v.as_str())))
+            .chain(self.parent_folder.iter().map(|v| (SignalSource::ParentFolder, v.as_str())))
+            .chain(self.file_extension.iter().map(|v| (SignalSource::FileExtension, v.as_str())))
+    }
+}
+
+/// Asset class a package's name implies, from the usual Unreal naming prefixes.
+pub fn package_class(package_name: &str) -> Option<&'static str> {
+    let short_name = package_name.rsplit('/').next().unwrap_or(package_name);
+    let upper = short_name.to_ascii_uppercase();
+    PACKAGE_CLASS_PREFIXES
+        .iter()
+        .find(|(prefix, _)| upper.starts_with(prefix) && upper.len() > prefix.len())
+        .map(|(_, class)| *class)
+}
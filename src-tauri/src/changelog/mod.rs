@@ -0,0 +1,723 @@
+//! Change log of user edits, for syncing two libraries without a server.
+//!
+//! Every `omnidex.db` is a replica with a random id. Triggers record each edit of a user field
+//! (tags, notes, descriptions, favorites, manual overrides, match decisions, collections and
+//! their members) in `change_log`, stamped with the replica id and a Lamport clock that goes up
+//! with every edit and never falls behind a change received from elsewhere. An asset row that
+//! arrives with user fields already set, such as one added from the team library, is logged too.
+//!
+//! A sync exchanges the entries the other side has not seen yet, either directly with another
+//! database file or through a folder (e.g. a synced cloud drive) where every replica keeps a
+//! `<replica id>.omnidex-changes.json` file. Per asset or collection field the entry with the
+//! highest clock wins, ties going to the higher replica id, so both sides end up with the same
+//! values whatever order they sync in. Assets are identified by their path below their scan
+//! location; entries for assets this library does not have are kept in the log but not applied.
+//! A received match decision only sets the matched product; its listing is fetched on the next
+//! metadata refresh.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::{Pool, Sqlite, SqliteConnection, SqlitePool};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use crate::database::{self, DatabaseManager};
+
+/// `format` of a change set file.
+pub const CHANGE_SET_FORMAT: &str = "omnidex-changes";
+/// Version of the change set file layout. Files written by a newer version are refused.
+pub const CHANGE_SET_VERSION: u32 = 1;
+/// Suffix of the change set files in a sync folder.
+pub const CHANGE_SET_SUFFIX: &str = ".omnidex-changes.json";
+
+pub const ENTITY_ASSET: &str = "asset";
+pub const ENTITY_COLLECTION: &str = "collection";
+pub const ENTITY_MEMBERSHIP: &str = "membership";
+
+/// Separates the collection name from the asset key in a membership key.
+const MEMBERSHIP_SEPARATOR: char = '\u{1f}';
+
+/// Key of an asset in the log: its path below its scan location.
+const ASSET_KEY: &str = "COALESCE(relative_path, file_path)";
+
+/// One recorded edit: `field` of the asset, collection or membership `entity_key` was set to `value`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChangeEntry {
+    pub replica_id: String,
+    pub lamport: i64,
+    pub entity: String,
+    pub entity_key: String,
+    pub field: String,
+    pub value: Value,
+    pub recorded_date: String,
+}
+
+/// Contents of a `<replica id>.omnidex-changes.json` file: every entry the replica knows of.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangeSet {
+    pub format: String,
+    pub version: u32,
+    pub replica_id: String,
+    pub entries: Vec<ChangeEntry>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ChangeSyncReport {
+    pub replica_id: String,
+    /// Replicas whose changes were read.
+    pub peers: Vec<String>,
+    /// Entries this library had not seen yet.
+    pub received: usize,
+    /// Received entries that changed a value here.
+    pub applied: usize,
+    /// Received entries older than an edit already made to the same field.
+    pub superseded: usize,
+    /// Received entries for an asset or collection this library does not have (or, for an
+    /// asset path found under several locations, cannot tell apart).
+    pub unmatched: usize,
+    /// Entries written for the other side.
+    pub sent: usize,
+    pub synced_at: String,
+}
+
+/// What applying a batch of entries did.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ApplyOutcome {
+    pub received: usize,
+    pub applied: usize,
+    pub superseded: usize,
+    pub unmatched: usize,
+}
+
+/// Create the change log, its triggers and, the first time, this replica's id.
+/// Called from `create_tables`, so every library records its edits from then on.
+pub(crate) async fn create_change_log(pool: &Pool<Sqlite>) -> Result<()> {
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS change_log_state (
+            replica_id TEXT NOT NULL,
+            lamport INTEGER NOT NULL,
+            applying INTEGER NOT NULL DEFAULT 0
+        )
+    "#).execute(pool).await?;
+
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS change_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            replica_id TEXT NOT NULL,
+            lamport INTEGER NOT NULL,
+            entity TEXT NOT NULL,
+            entity_key TEXT NOT NULL,
+            field TEXT NOT NULL,
+            value TEXT NOT NULL,
+            recorded_date TEXT NOT NULL,
+            UNIQUE (replica_id, lamport, entity, entity_key, field)
+        )
+    "#).execute(pool).await?;
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_change_log_field ON change_log(entity, entity_key, field)")
+        .execute(pool)
+        .await?;
+
+    let mut tx = pool.begin().await?;
+    let has_state: bool = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM change_log_state)")
+        .fetch_one(&mut *tx)
+        .await?;
+    if !has_state {
+        sqlx::query("INSERT INTO change_log_state (replica_id, lamport, applying) VALUES (?, 0, 0)")
+            .bind(format!("{:032x}", rand::random::<u128>()))
+            .execute(&mut *tx)
+            .await?;
+        record_baseline(&mut tx).await?;
+    }
+    tx.commit().await?;
+
+    for trigger in change_log_triggers() {
+        sqlx::query(&trigger).execute(pool).await?;
+    }
+    Ok(())
+}
+
+/// An asset field that is logged, both when an asset row is updated and when it is inserted
+/// with a value already set (an imported or team library row).
+struct AssetField {
+    field: &'static str,
+    columns: &'static str,
+    /// Condition on NEW/OLD for an update to be logged.
+    changed: &'static str,
+    /// Condition on NEW for an inserted row to be logged.
+    inserted: &'static str,
+    /// JSON value of NEW.
+    value: &'static str,
+}
+
+/// The asset fields that are logged. A match is only a decision when it was stamped as a user
+/// edit; automatic matching and metadata refreshes leave `user_modified_date` alone.
+const ASSET_FIELDS: &[AssetField] = &[
+    AssetField {
+        field: "tags",
+        columns: "tags",
+        changed: "NEW.tags IS NOT OLD.tags",
+        inserted: "NEW.tags IS NOT NULL AND NEW.tags <> ''",
+        value: "json_quote(NEW.tags)",
+    },
+    AssetField {
+        field: "notes",
+        columns: "notes",
+        changed: "NEW.notes IS NOT OLD.notes",
+        inserted: "NEW.notes IS NOT NULL AND NEW.notes <> ''",
+        value: "json_quote(NEW.notes)",
+    },
+    AssetField {
+        field: "description",
+        columns: "description",
+        changed: "NEW.description IS NOT OLD.description",
+        inserted: "NEW.description IS NOT NULL AND NEW.description <> ''",
+        value: "json_quote(NEW.description)",
+    },
+    AssetField {
+        field: "favorite",
+        columns: "is_favorite",
+        changed: "NEW.is_favorite IS NOT OLD.is_favorite",
+        inserted: "NEW.is_favorite",
+        value: "CASE WHEN NEW.is_favorite THEN 'true' ELSE 'false' END",
+    },
+    AssetField {
+        field: "overrides",
+        columns: "orbital_manual_overrides",
+        changed: "NEW.orbital_manual_overrides IS NOT OLD.orbital_manual_overrides",
+        inserted: "NEW.orbital_manual_overrides IS NOT NULL AND NEW.orbital_manual_overrides <> ''",
+        value: "json_quote(NEW.orbital_manual_overrides)",
+    },
+    AssetField {
+        field: "match",
+        columns: "matched_orbital_product_slug, orbital_match_type, orbital_match_confidence, user_modified_date",
+        changed: "(NEW.matched_orbital_product_slug IS NOT OLD.matched_orbital_product_slug
+                   OR NEW.orbital_match_type IS NOT OLD.orbital_match_type
+                   OR NEW.orbital_match_confidence IS NOT OLD.orbital_match_confidence)
+                  AND NEW.user_modified_date IS NOT OLD.user_modified_date",
+        inserted: "NEW.matched_orbital_product_slug IS NOT NULL
+                   AND (NEW.orbital_match_type = 'Manual' OR NEW.user_modified_date IS NOT NULL)",
+        value: "CASE WHEN NEW.matched_orbital_product_slug IS NULL THEN 'null' ELSE json_object(
+            'slug', NEW.matched_orbital_product_slug,
+            'type', NEW.orbital_match_type,
+            'confidence', NEW.orbital_match_confidence) END",
+    },
+];
+
+/// Triggers are skipped while received changes are being applied.
+const NOT_APPLYING: &str = "(SELECT applying FROM change_log_state) = 0";
+const NEXT_CLOCK: &str = "UPDATE change_log_state SET lamport = lamport + 1;";
+const INSERT_ENTRY: &str =
+    "INSERT OR IGNORE INTO change_log (replica_id, lamport, entity, entity_key, field, value, recorded_date)";
+const NOW: &str = "strftime('%Y-%m-%dT%H:%M:%fZ', 'now')";
+
+fn change_log_triggers() -> Vec<String> {
+    // Key of the membership of asset `a` in the collection named `name`.
+    let membership_key = |name: &str| format!("{} || char(31) || COALESCE(a.relative_path, a.file_path)", name);
+
+    let mut triggers = Vec::new();
+    for AssetField { field, columns, changed, inserted, value } in ASSET_FIELDS {
+        let log_entry = format!(
+            "{NEXT_CLOCK}
+             {INSERT_ENTRY}
+             SELECT replica_id, lamport, '{ENTITY_ASSET}', COALESCE(NEW.relative_path, NEW.file_path),
+                    '{field}', {value}, {NOW}
+             FROM change_log_state;"
+        );
+        triggers.push(format!(
+            "CREATE TRIGGER IF NOT EXISTS change_log_asset_{field} AFTER UPDATE OF {columns} ON assets
+             WHEN {changed} AND {NOT_APPLYING}
+             BEGIN
+                 {log_entry}
+             END"
+        ));
+        triggers.push(format!(
+            "CREATE TRIGGER IF NOT EXISTS change_log_asset_{field}_insert AFTER INSERT ON assets
+             WHEN {inserted} AND {NOT_APPLYING}
+             BEGIN
+                 {log_entry}
+             END"
+        ));
+    }
+
+    triggers.push(format!(
+        "CREATE TRIGGER IF NOT EXISTS change_log_collection_insert AFTER INSERT ON collections
+         WHEN {NOT_APPLYING}
+         BEGIN
+             {NEXT_CLOCK}
+             {INSERT_ENTRY}
+             SELECT replica_id, lamport, '{ENTITY_COLLECTION}', NEW.name, 'exists', 'true', {NOW} FROM change_log_state;
+             {INSERT_ENTRY}
+             SELECT replica_id, lamport, '{ENTITY_COLLECTION}', NEW.name, 'description', json_quote(NEW.description), {NOW}
+             FROM change_log_state WHERE NEW.description IS NOT NULL;
+         END"
+    ));
+    triggers.push(format!(
+        "CREATE TRIGGER IF NOT EXISTS change_log_collection_description AFTER UPDATE OF description ON collections
+         WHEN NEW.description IS NOT OLD.description AND NEW.name = OLD.name AND {NOT_APPLYING}
+         BEGIN
+             {NEXT_CLOCK}
+             {INSERT_ENTRY}
+             SELECT replica_id, lamport, '{ENTITY_COLLECTION}', NEW.name, 'description', json_quote(NEW.description), {NOW}
+             FROM change_log_state;
+         END"
+    ));
+    // A rename is the old collection going away and the new one appearing with the same members.
+    triggers.push(format!(
+        "CREATE TRIGGER IF NOT EXISTS change_log_collection_rename AFTER UPDATE OF name ON collections
+         WHEN NEW.name IS NOT OLD.name AND {NOT_APPLYING}
+         BEGIN
+             {NEXT_CLOCK}
+             {INSERT_ENTRY}
+             SELECT replica_id, lamport, '{ENTITY_COLLECTION}', OLD.name, 'exists', 'false', {NOW} FROM change_log_state;
+             {INSERT_ENTRY}
+             SELECT replica_id, lamport, '{ENTITY_COLLECTION}', NEW.name, 'exists', 'true', {NOW} FROM change_log_state;
+             {INSERT_ENTRY}
+             SELECT replica_id, lamport, '{ENTITY_COLLECTION}', NEW.name, 'description', json_quote(NEW.description), {NOW}
+             FROM change_log_state WHERE NEW.description IS NOT NULL;
+             {INSERT_ENTRY}
+             SELECT s.replica_id, s.lamport, '{ENTITY_MEMBERSHIP}', {old_key}, 'member', 'false', {NOW}
+             FROM change_log_state s, collection_assets ca JOIN assets a ON a.id = ca.asset_id
+             WHERE ca.collection_id = NEW.id;
+             {INSERT_ENTRY}
+             SELECT s.replica_id, s.lamport, '{ENTITY_MEMBERSHIP}', {new_key}, 'member', 'true', {NOW}
+             FROM change_log_state s, collection_assets ca JOIN assets a ON a.id = ca.asset_id
+             WHERE ca.collection_id = NEW.id;
+         END",
+        old_key = membership_key("OLD.name"),
+        new_key = membership_key("NEW.name"),
+    ));
+    triggers.push(format!(
+        "CREATE TRIGGER IF NOT EXISTS change_log_collection_delete BEFORE DELETE ON collections
+         WHEN {NOT_APPLYING}
+         BEGIN
+             {NEXT_CLOCK}
+             {INSERT_ENTRY}
+             SELECT s.replica_id, s.lamport, '{ENTITY_MEMBERSHIP}', {key}, 'member', 'false', {NOW}
+             FROM change_log_state s, collection_assets ca JOIN assets a ON a.id = ca.asset_id
+             WHERE ca.collection_id = OLD.id;
+             {INSERT_ENTRY}
+             SELECT replica_id, lamport, '{ENTITY_COLLECTION}', OLD.name, 'exists', 'false', {NOW} FROM change_log_state;
+         END",
+        key = membership_key("OLD.name"),
+    ));
+    triggers.push(format!(
+        "CREATE TRIGGER IF NOT EXISTS change_log_membership_insert AFTER INSERT ON collection_assets
+         WHEN {NOT_APPLYING}
+         BEGIN
+             {NEXT_CLOCK}
+             {INSERT_ENTRY}
+             SELECT s.replica_id, s.lamport, '{ENTITY_MEMBERSHIP}', {key}, 'member', 'true', {NOW}
+             FROM change_log_state s, collections c, assets a
+             WHERE c.id = NEW.collection_id AND a.id = NEW.asset_id;
+         END",
+        key = membership_key("c.name"),
+    ));
+    // Members removed because their collection or asset was deleted are not removals: by the
+    // time a cascade runs, the deleted parent row is already gone and this trigger stays quiet.
+    // Removing an asset from this library (e.g. it is not downloaded here) must not take it
+    // out of collections elsewhere.
+    triggers.push(format!(
+        "CREATE TRIGGER IF NOT EXISTS change_log_membership_delete AFTER DELETE ON collection_assets
+         WHEN {NOT_APPLYING}
+              AND EXISTS (SELECT 1 FROM collections WHERE id = OLD.collection_id)
+              AND EXISTS (SELECT 1 FROM assets WHERE id = OLD.asset_id)
+         BEGIN
+             {NEXT_CLOCK}
+             {INSERT_ENTRY}
+             SELECT s.replica_id, s.lamport, '{ENTITY_MEMBERSHIP}', {key}, 'member', 'false', {NOW}
+             FROM change_log_state s, collections c, assets a
+             WHERE c.id = OLD.collection_id AND a.id = OLD.asset_id;
+         END",
+        key = membership_key("c.name"),
+    ));
+    triggers
+}
+
+/// Log the values a library already has when it starts keeping a change log, so they reach
+/// the other side too. They all get the first clock value.
+async fn record_baseline(conn: &mut SqliteConnection) -> Result<()> {
+    let non_empty = |column: &str| format!("{} IS NOT NULL AND {} <> ''", column, column);
+    let statements = [
+        asset_baseline("tags", "json_quote(tags)", &non_empty("tags")),
+        asset_baseline("notes", "json_quote(notes)", &non_empty("notes")),
+        asset_baseline("description", "json_quote(description)", &non_empty("description")),
+        asset_baseline("favorite", "'true'", "is_favorite"),
+        asset_baseline("overrides", "json_quote(orbital_manual_overrides)", &non_empty("orbital_manual_overrides")),
+        asset_baseline(
+            "match",
+            "json_object('slug', matched_orbital_product_slug, 'type', orbital_match_type, 'confidence', orbital_match_confidence)",
+            "matched_orbital_product_slug IS NOT NULL AND orbital_match_type = 'Manual'",
+        ),
+        format!(
+            "{INSERT_ENTRY} SELECT s.replica_id, 1, '{ENTITY_COLLECTION}', c.name, 'exists', 'true', {NOW}
+             FROM change_log_state s, collections c"
+        ),
+        format!(
+            "{INSERT_ENTRY} SELECT s.replica_id, 1, '{ENTITY_COLLECTION}', c.name, 'description', json_quote(c.description), {NOW}
+             FROM change_log_state s, collections c WHERE c.description IS NOT NULL"
+        ),
+        format!(
+            "{INSERT_ENTRY} SELECT s.replica_id, 1, '{ENTITY_MEMBERSHIP}',
+                c.name || char(31) || COALESCE(a.relative_path, a.file_path), 'member', 'true', {NOW}
+             FROM change_log_state s, collection_assets ca
+             JOIN collections c ON c.id = ca.collection_id
+             JOIN assets a ON a.id = ca.asset_id"
+        ),
+        "UPDATE change_log_state SET lamport = 1 WHERE EXISTS (SELECT 1 FROM change_log)".to_string(),
+    ];
+    for statement in statements {
+        sqlx::query(&statement).execute(&mut *conn).await?;
+    }
+    Ok(())
+}
+
+fn asset_baseline(field: &str, value: &str, condition: &str) -> String {
+    format!(
+        "{INSERT_ENTRY} SELECT s.replica_id, 1, '{ENTITY_ASSET}', {ASSET_KEY}, '{field}', {value}, {NOW}
+         FROM change_log_state s, assets WHERE {condition}"
+    )
+}
+
+/// This library's replica id.
+pub async fn replica_id(pool: &Pool<Sqlite>) -> Result<String> {
+    sqlx::query_scalar("SELECT replica_id FROM change_log_state")
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| anyhow!("This library has no change log yet"))
+}
+
+/// The highest clock value seen from each replica; every entry up to it is in the log.
+pub async fn version_vector(pool: &Pool<Sqlite>) -> Result<BTreeMap<String, i64>> {
+    let rows: Vec<(String, i64)> = sqlx::query_as("SELECT replica_id, MAX(lamport) FROM change_log GROUP BY replica_id")
+        .fetch_all(pool)
+        .await?;
+    Ok(rows.into_iter().collect())
+}
+
+/// Entries a replica with `seen` as its version vector has not got yet, oldest first.
+pub async fn changes_since(pool: &Pool<Sqlite>, seen: &BTreeMap<String, i64>) -> Result<Vec<ChangeEntry>> {
+    let rows: Vec<(String, i64, String, String, String, String, String)> = sqlx::query_as(
+        "SELECT replica_id, lamport, entity, entity_key, field, value, recorded_date
+         FROM change_log ORDER BY lamport, replica_id, id",
+    )
+    .fetch_all(pool)
+    .await?;
+    let entries = rows
+        .into_iter()
+        .map(|(replica_id, lamport, entity, entity_key, field, value, recorded_date)| {
+            Ok(ChangeEntry {
+                value: serde_json::from_str(&value)?,
+                replica_id,
+                lamport,
+                entity,
+                entity_key,
+                field,
+                recorded_date,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(unseen_entries(entries, seen))
+}
+
+/// Add `entries` to the log and apply those that are now the latest edit of their field.
+/// Entries already in the log are ignored, so the same change set can be applied twice.
+pub async fn apply_changes(pool: &Pool<Sqlite>, entries: &[ChangeEntry], now: &str) -> Result<ApplyOutcome> {
+    let mut outcome = ApplyOutcome::default();
+    if entries.is_empty() {
+        return Ok(outcome);
+    }
+
+    let mut sorted: Vec<&ChangeEntry> = entries.iter().collect();
+    sorted.sort_by(|a, b| (a.lamport, &a.replica_id).cmp(&(b.lamport, &b.replica_id)));
+
+    let mut tx = pool.begin().await?;
+    sqlx::query("UPDATE change_log_state SET applying = 1").execute(&mut *tx).await?;
+
+    let asset_rows: Vec<(String, i64)> = sqlx::query_as(&format!("SELECT {}, id FROM assets", ASSET_KEY))
+        .fetch_all(&mut *tx)
+        .await?;
+    let mut assets: HashMap<String, Vec<i64>> = HashMap::new();
+    for (key, id) in asset_rows {
+        assets.entry(key).or_default().push(id);
+    }
+
+    for entry in sorted {
+        let inserted = sqlx::query(&format!("{} VALUES (?, ?, ?, ?, ?, ?, ?)", INSERT_ENTRY))
+            .bind(&entry.replica_id)
+            .bind(entry.lamport)
+            .bind(&entry.entity)
+            .bind(&entry.entity_key)
+            .bind(&entry.field)
+            .bind(entry.value.to_string())
+            .bind(&entry.recorded_date)
+            .execute(&mut *tx)
+            .await?;
+        if inserted.rows_affected() == 0 {
+            continue;
+        }
+        outcome.received += 1;
+
+        let latest: (String, i64) = sqlx::query_as(
+            "SELECT replica_id, lamport FROM change_log WHERE entity = ? AND entity_key = ? AND field = ?
+             ORDER BY lamport DESC, replica_id DESC LIMIT 1",
+        )
+        .bind(&entry.entity)
+        .bind(&entry.entity_key)
+        .bind(&entry.field)
+        .fetch_one(&mut *tx)
+        .await?;
+        if latest != (entry.replica_id.clone(), entry.lamport) {
+            outcome.superseded += 1;
+            continue;
+        }
+
+        if apply_entry(&mut tx, entry, &assets, now).await? {
+            outcome.applied += 1;
+        } else {
+            outcome.unmatched += 1;
+        }
+    }
+
+    // Later local edits must win over everything received.
+    sqlx::query("UPDATE change_log_state SET applying = 0, lamport = MAX(lamport, (SELECT COALESCE(MAX(lamport), 0) FROM change_log))")
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    Ok(outcome)
+}
+
+/// Write one entry's value; `false` when what it refers to is not in this library.
+async fn apply_entry(
+    conn: &mut SqliteConnection,
+    entry: &ChangeEntry,
+    assets: &HashMap<String, Vec<i64>>,
+    now: &str,
+) -> Result<bool> {
+    let asset_id = |key: &str| match assets.get(key).map(Vec::as_slice) {
+        Some([id]) => Some(*id),
+        _ => None,
+    };
+    let text = entry.value.as_str();
+    let flag = entry.value.as_bool().unwrap_or(false);
+
+    match (entry.entity.as_str(), entry.field.as_str()) {
+        (ENTITY_ASSET, field) => {
+            let Some(asset_id) = asset_id(&entry.entity_key) else {
+                return Ok(false);
+            };
+            let query = match field {
+                "tags" => sqlx::query("UPDATE assets SET tags = ?, user_modified_date = ? WHERE id = ?").bind(text),
+                "notes" => sqlx::query("UPDATE assets SET notes = ?, user_modified_date = ? WHERE id = ?").bind(text),
+                "description" => {
+                    sqlx::query("UPDATE assets SET description = ?, user_modified_date = ? WHERE id = ?").bind(text)
+                }
+                "favorite" => sqlx::query("UPDATE assets SET is_favorite = ?, user_modified_date = ? WHERE id = ?").bind(flag),
+                "overrides" => sqlx::query(
+                    "UPDATE assets SET orbital_manual_overrides = ?, user_modified_date = ? WHERE id = ?",
+                )
+                .bind(text),
+                "match" => sqlx::query(
+                    "UPDATE assets SET matched_orbital_product_slug = ?, orbital_match_type = ?,
+                        orbital_match_confidence = ?, user_modified_date = ? WHERE id = ?",
+                )
+                .bind(entry.value["slug"].as_str())
+                .bind(entry.value["type"].as_str())
+                .bind(entry.value["confidence"].as_f64()),
+                _ => return Ok(false),
+            };
+            query.bind(now).bind(asset_id).execute(&mut *conn).await?;
+            Ok(true)
+        }
+        (ENTITY_COLLECTION, "exists") => {
+            if flag {
+                sqlx::query("INSERT OR IGNORE INTO collections (name, created_date, modified_date) VALUES (?, ?, ?)")
+                    .bind(&entry.entity_key)
+                    .bind(now)
+                    .bind(now)
+                    .execute(&mut *conn)
+                    .await?;
+            } else {
+                sqlx::query("DELETE FROM collections WHERE name = ?")
+                    .bind(&entry.entity_key)
+                    .execute(&mut *conn)
+                    .await?;
+            }
+            Ok(true)
+        }
+        (ENTITY_COLLECTION, "description") => {
+            let updated = sqlx::query("UPDATE collections SET description = ?, modified_date = ? WHERE name = ?")
+                .bind(text)
+                .bind(now)
+                .bind(&entry.entity_key)
+                .execute(&mut *conn)
+                .await?;
+            Ok(updated.rows_affected() > 0)
+        }
+        (ENTITY_MEMBERSHIP, "member") => {
+            let Some((collection, asset_key)) = entry.entity_key.split_once(MEMBERSHIP_SEPARATOR) else {
+                return Ok(false);
+            };
+            let collection_id: Option<i64> = sqlx::query_scalar("SELECT id FROM collections WHERE name = ?")
+                .bind(collection)
+                .fetch_optional(&mut *conn)
+                .await?;
+            let (Some(collection_id), Some(asset_id)) = (collection_id, asset_id(asset_key)) else {
+                // Nothing to take out of a collection that is not here.
+                return Ok(!flag);
+            };
+            if flag {
+                sqlx::query("INSERT OR IGNORE INTO collection_assets (collection_id, asset_id, added_date) VALUES (?, ?, ?)")
+                    .bind(collection_id)
+                    .bind(asset_id)
+                    .bind(now)
+                    .execute(&mut *conn)
+                    .await?;
+            } else {
+                sqlx::query("DELETE FROM collection_assets WHERE collection_id = ? AND asset_id = ?")
+                    .bind(collection_id)
+                    .bind(asset_id)
+                    .execute(&mut *conn)
+                    .await?;
+            }
+            Ok(true)
+        }
+        _ => Ok(false),
+    }
+}
+
+/// Sync with another database file, or with a folder of change set files when `target` is a directory.
+pub async fn sync_changes(db_manager: &DatabaseManager, target: &Path) -> Result<ChangeSyncReport> {
+    if target.is_dir() {
+        sync_with_directory(db_manager, target).await
+    } else {
+        sync_with_database(db_manager, target).await
+    }
+}
+
+/// Exchange changes with the `omnidex.db` at `path`, both ways.
+pub async fn sync_with_database(db_manager: &DatabaseManager, path: &Path) -> Result<ChangeSyncReport> {
+    if !path.is_file() {
+        return Err(anyhow!("{} is not an Omnidex database", path.display()));
+    }
+    let other = SqlitePool::connect_with(SqliteConnectOptions::new().filename(path))
+        .await
+        .map_err(|e| anyhow!("Failed to open {}: {}", path.display(), e))?;
+    let result = exchange_with(db_manager, &other).await;
+    other.close().await;
+    result
+}
+
+async fn exchange_with(db_manager: &DatabaseManager, other: &Pool<Sqlite>) -> Result<ChangeSyncReport> {
+    database::create_tables(other).await?;
+    let mut report = new_report(db_manager).await?;
+    let other_id = replica_id(other).await?;
+    ensure_distinct(&report.replica_id, &other_id)?;
+
+    let incoming = changes_since(other, &version_vector(&db_manager.pool).await?).await?;
+    let outgoing = changes_since(&db_manager.pool, &version_vector(other).await?).await?;
+    let pulled = apply_changes(&db_manager.pool, &incoming, &report.synced_at).await?;
+    add_outcome(&mut report, pulled);
+    report.sent = apply_changes(other, &outgoing, &report.synced_at).await?.received;
+    report.peers.push(other_id);
+    Ok(report)
+}
+
+/// Read every other replica's change set in `dir`, then write this library's own.
+pub async fn sync_with_directory(db_manager: &DatabaseManager, dir: &Path) -> Result<ChangeSyncReport> {
+    let mut report = new_report(db_manager).await?;
+    let own_file = format!("{}{}", report.replica_id, CHANGE_SET_SUFFIX);
+
+    let mut files: Vec<_> = std::fs::read_dir(dir)
+        .map_err(|e| anyhow!("Cannot read sync folder {}: {}", dir.display(), e))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.ends_with(CHANGE_SET_SUFFIX) && name != own_file)
+        })
+        .collect();
+    files.sort();
+
+    for file in files {
+        let change_set = read_change_set(&file)?;
+        ensure_distinct(&report.replica_id, &change_set.replica_id)?;
+        let unseen = unseen_entries(change_set.entries, &version_vector(&db_manager.pool).await?);
+        let outcome = apply_changes(&db_manager.pool, &unseen, &report.synced_at).await?;
+        add_outcome(&mut report, outcome);
+        report.peers.push(change_set.replica_id);
+    }
+
+    let entries = changes_since(&db_manager.pool, &BTreeMap::new()).await?;
+    report.sent = entries.len();
+    let change_set = ChangeSet {
+        format: CHANGE_SET_FORMAT.to_string(),
+        version: CHANGE_SET_VERSION,
+        replica_id: report.replica_id.clone(),
+        entries,
+    };
+    write_change_set(&dir.join(own_file), &change_set)?;
+    Ok(report)
+}
+
+pub fn read_change_set(path: &Path) -> Result<ChangeSet> {
+    let contents = std::fs::read_to_string(path).map_err(|e| anyhow!("Cannot read {}: {}", path.display(), e))?;
+    let change_set: ChangeSet =
+        serde_json::from_str(&contents).map_err(|e| anyhow!("{} is not a change set: {}", path.display(), e))?;
+    if change_set.format != CHANGE_SET_FORMAT {
+        return Err(anyhow!("{} is not an Omnidex change set", path.display()));
+    }
+    if change_set.version > CHANGE_SET_VERSION {
+        return Err(anyhow!(
+            "{} was written by a newer version of Omnidex (version {}, this version supports {})",
+            path.display(),
+            change_set.version,
+            CHANGE_SET_VERSION
+        ));
+    }
+    Ok(change_set)
+}
+
+/// Written next to the destination and renamed over it, so a replica reading the folder
+/// (or the cloud drive uploading it) never sees half a file.
+fn write_change_set(path: &Path, change_set: &ChangeSet) -> Result<()> {
+    let temp_path = path.with_extension("json.tmp");
+    std::fs::write(&temp_path, serde_json::to_vec(change_set)?)
+        .map_err(|e| anyhow!("Cannot write {}: {}", temp_path.display(), e))?;
+    std::fs::rename(&temp_path, path).map_err(|e| anyhow!("Cannot write {}: {}", path.display(), e))?;
+    Ok(())
+}
+
+fn unseen_entries(entries: Vec<ChangeEntry>, seen: &BTreeMap<String, i64>) -> Vec<ChangeEntry> {
+    entries
+        .into_iter()
+        .filter(|entry| seen.get(&entry.replica_id).map_or(true, |&max| entry.lamport > max))
+        .collect()
+}
+
+/// A copied `omnidex.db` keeps the replica id of the original; the two cannot be told apart.
+fn ensure_distinct(own_id: &str, other_id: &str) -> Result<()> {
+    if own_id == other_id {
+        return Err(anyhow!(
+            "Both libraries have replica id {}; one is a copy of the other and cannot be synced with it",
+            own_id
+        ));
+    }
+    Ok(())
+}
+
+async fn new_report(db_manager: &DatabaseManager) -> Result<ChangeSyncReport> {
+    Ok(ChangeSyncReport {
+        replica_id: replica_id(&db_manager.pool).await?,
+        synced_at: db_manager.timestamp(),
+        ..Default::default()
+    })
+}
+
+fn add_outcome(report: &mut ChangeSyncReport, outcome: ApplyOutcome) {
+    report.received += outcome.received;
+    report.applied += outcome.applied;
+    report.superseded += outcome.superseded;
+    report.unmatched += outcome.unmatched;
+}
@@ -0,0 +1,285 @@
+//! Parsing the `omnidex-cli` command line.
+
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::transfer::{ExportFormat, MergeStrategy};
+use crate::unreal::version::EngineVersion;
+
+pub const USAGE: &str = "\
+Usage: omnidex-cli [--data-dir <dir>] [--pretty] [--quiet] <command> [options]
+
+Commands:
+  scan [<location-id>...] [--all] [--resume]
+                          Scan the given locations, or every active one with --all
+  search <query> [--limit <n>] [--offset <n>]
+                          Search assets by name, description, tags and marketplace listing
+  export <file> [--format json|csv]
+                          Export tags, notes, favorites, collections and match decisions
+  import <file> [--format json|csv] [--strategy ours|theirs|newest] [--dry-run]
+                          Merge a library export (default strategy: ours)
+  match [--location <id>] Look up marketplace matches for unmatched assets
+  match --asset <id> --url <orbital-url>
+                          Match one asset to a marketplace product
+  stats                   Asset totals and disk usage per location
+  dedupe                  Identical assets, packs inside larger packs and product versions
+  outdated [--engine <version>]
+                          Matched assets with marketplace updates or unsupported engines
+  licenses [--year <year>]
+                          License spend, licenses per seller and assets without a license
+  metadata <asset-id> [--refresh]
+                          Asset metadata merged over its providers, fetching it first with --refresh
+  backup [--list | --verify <file>]
+                          Back up the database to the configured backup location
+  sync <database-or-folder>
+                          Exchange user edits with another omnidex.db or a shared sync folder
+
+Options:
+  --data-dir <dir>        Use the omnidex.db in <dir> instead of the app's data directory
+  --pretty                Indent the JSON output
+  --quiet                 Do not print scan progress to stderr
+  -h, --help              Show this help
+
+Results are printed to stdout as JSON; errors go to stderr with a non-zero exit code.";
+
+/// Results `search` returns unless `--limit` is given.
+const DEFAULT_SEARCH_LIMIT: u32 = 50;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Scan { location_ids: Vec<i64>, all: bool, resume: bool },
+    Search { query: String, limit: u32, offset: u32 },
+    Export { path: PathBuf, format: Option<ExportFormat> },
+    Import { path: PathBuf, format: Option<ExportFormat>, strategy: MergeStrategy, dry_run: bool },
+    /// Auto-match unmatched assets, or match `asset_id` to `url` when both are set.
+    Match { location_id: Option<i64>, asset_id: Option<i64>, url: Option<String> },
+    Stats,
+    Dedupe,
+    /// Check matched assets against their listing and `engine`, or the configured engine.
+    Outdated { engine: Option<EngineVersion> },
+    /// License report, for the licenses bought in `year` or all of them.
+    Licenses { year: Option<i32> },
+    /// Merged provider metadata of an asset, refreshed from every provider first with `refresh`.
+    Metadata { asset_id: i64, refresh: bool },
+    Backup { list: bool, verify: Option<PathBuf> },
+    /// Exchange the change log with another database file or a sync folder.
+    Sync { target: PathBuf },
+    Help,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Invocation {
+    pub data_dir: Option<PathBuf>,
+    pub pretty: bool,
+    pub quiet: bool,
+    pub command: Command,
+}
+
+/// Parse the arguments following the program name.
+pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Invocation, String> {
+    let mut args = ArgList(args.into_iter().collect());
+    let help = args.flag("--help") | args.flag("-h");
+    let data_dir = args.value("--data-dir")?.map(PathBuf::from);
+    let pretty = args.flag("--pretty");
+    let quiet = args.flag("--quiet");
+
+    if help {
+        return Ok(Invocation { data_dir, pretty, quiet, command: Command::Help });
+    }
+
+    let subcommand = match args.0.iter().position(|arg| !arg.starts_with('-')) {
+        Some(index) => args.0.remove(index),
+        None => return Err("No command given".to_string()),
+    };
+
+    let command = match subcommand.as_str() {
+        "scan" => {
+            let all = args.flag("--all");
+            let resume = args.flag("--resume");
+            let location_ids = args
+                .positionals(usize::MAX)?
+                .iter()
+                .map(|id| parse_number(id, "location ID"))
+                .collect::<Result<Vec<i64>, String>>()?;
+            if all != location_ids.is_empty() {
+                return Err("scan needs either location IDs or --all".to_string());
+            }
+            Command::Scan { location_ids, all, resume }
+        }
+        "search" => {
+            let limit = args.parsed("--limit")?.unwrap_or(DEFAULT_SEARCH_LIMIT);
+            let offset = args.parsed("--offset")?.unwrap_or(0);
+            let query = args.positionals(1)?.pop().ok_or("search needs a query")?;
+            Command::Search { query, limit, offset }
+        }
+        "export" => {
+            let format = args.value("--format")?.map(|f| parse_enum(&f, "format")).transpose()?;
+            let path = args.positionals(1)?.pop().ok_or("export needs a destination file")?;
+            Command::Export { path: PathBuf::from(path), format }
+        }
+        "import" => {
+            let format = args.value("--format")?.map(|f| parse_enum(&f, "format")).transpose()?;
+            let strategy = match args.value("--strategy")? {
+                Some(strategy) => parse_enum(&strategy, "strategy")?,
+                None => MergeStrategy::Ours,
+            };
+            let dry_run = args.flag("--dry-run");
+            let path = args.positionals(1)?.pop().ok_or("import needs a file")?;
+            Command::Import { path: PathBuf::from(path), format, strategy, dry_run }
+        }
+        "match" => {
+            let location_id = args.parsed("--location")?;
+            let asset_id = args.parsed("--asset")?;
+            let url = args.value("--url")?;
+            args.positionals(0)?;
+            if asset_id.is_some() != url.is_some() {
+                return Err("match needs both --asset and --url to match a single asset".to_string());
+            }
+            if asset_id.is_some() && location_id.is_some() {
+                return Err("--location cannot be combined with --asset".to_string());
+            }
+            Command::Match { location_id, asset_id, url }
+        }
+        "stats" => {
+            args.positionals(0)?;
+            Command::Stats
+        }
+        "dedupe" => {
+            args.positionals(0)?;
+            Command::Dedupe
+        }
+        "outdated" => {
+            let engine = args.value("--engine")?.map(|version| version.parse()).transpose()?;
+            args.positionals(0)?;
+            Command::Outdated { engine }
+        }
+        "licenses" => {
+            let year = args.parsed("--year")?;
+            args.positionals(0)?;
+            Command::Licenses { year }
+        }
+        "metadata" => {
+            let refresh = args.flag("--refresh");
+            let asset_id = args.positionals(1)?.pop().ok_or("metadata needs an asset ID")?;
+            Command::Metadata { asset_id: parse_number(&asset_id, "asset ID")?, refresh }
+        }
+        "backup" => {
+            let list = args.flag("--list");
+            let verify = args.value("--verify")?.map(PathBuf::from);
+            args.positionals(0)?;
+            if list && verify.is_some() {
+                return Err("--list cannot be combined with --verify".to_string());
+            }
+            Command::Backup { list, verify }
+        }
+        "sync" => {
+            let target = args.positionals(1)?.pop().ok_or("sync needs a database file or folder")?;
+            Command::Sync { target: PathBuf::from(target) }
+        }
+        other => return Err(format!("Unknown command '{}'", other)),
+    };
+
+    Ok(Invocation { data_dir, pretty, quiet, command })
+}
+
+/// Remaining arguments; options are removed from it as they are read.
+struct ArgList(Vec<String>);
+
+impl ArgList {
+    fn flag(&mut self, name: &str) -> bool {
+        match self.0.iter().position(|arg| arg == name) {
+            Some(index) => {
+                self.0.remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn value(&mut self, name: &str) -> Result<Option<String>, String> {
+        let Some(index) = self.0.iter().position(|arg| arg == name) else {
+            return Ok(None);
+        };
+        if index + 1 >= self.0.len() {
+            return Err(format!("{} needs a value", name));
+        }
+        self.0.remove(index);
+        Ok(Some(self.0.remove(index)))
+    }
+
+    fn parsed<T: FromStr>(&mut self, name: &str) -> Result<Option<T>, String> {
+        self.value(name)?.map(|value| parse_number(&value, name)).transpose()
+    }
+
+    /// The positional arguments left once every option was read; unknown options are an error.
+    fn positionals(&mut self, max: usize) -> Result<Vec<String>, String> {
+        let rest = std::mem::take(&mut self.0);
+        if let Some(option) = rest.iter().find(|arg| arg.starts_with("--")) {
+            return Err(format!("Unknown option '{}'", option));
+        }
+        if rest.len() > max {
+            return Err(format!("Unexpected argument '{}'", rest[max]));
+        }
+        Ok(rest)
+    }
+}
+
+fn parse_number<T: FromStr>(value: &str, what: &str) -> Result<T, String> {
+    value.parse().map_err(|_| format!("Invalid {} '{}'", what, value))
+}
+
+/// Parse a lowercase enum value through its serde name.
+fn parse_enum<T: serde::de::DeserializeOwned>(value: &str, what: &str) -> Result<T, String> {
+    serde_json::from_value(serde_json::Value::String(value.to_ascii_lowercase()))
+        .map_err(|_| format!("Invalid {} '{}'", what, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_str(line: &str) -> Result<Invocation, String> {
+        parse(line.split_whitespace().map(str::to_string))
+    }
+
+    #[test]
+    fn parses_global_options_around_the_command() {
+        let invocation = parse_str("--pretty search crate --limit 5 --data-dir /tmp/lib").unwrap();
+        assert!(invocation.pretty);
+        assert_eq!(invocation.data_dir, Some(PathBuf::from("/tmp/lib")));
+        assert_eq!(
+            invocation.command,
+            Command::Search { query: "crate".to_string(), limit: 5, offset: 0 }
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_combinations() {
+        assert!(parse_str("scan").is_err());
+        assert!(parse_str("scan 1 --all").is_err());
+        assert!(parse_str("match --asset 3").is_err());
+        assert!(parse_str("import lib.json --strategy mine").is_err());
+        assert!(parse_str("stats --verbose").is_err());
+        assert!(parse_str("sync").is_err());
+        assert!(parse_str("outdated --engine latest").is_err());
+        assert!(parse_str("licenses --year last").is_err());
+        assert!(parse_str("metadata --refresh").is_err());
+        assert_eq!(
+            parse_str("outdated --engine 5.3").unwrap().command,
+            Command::Outdated { engine: Some(EngineVersion::new(5, 3)) }
+        );
+        assert_eq!(
+            parse_str("import lib.csv --strategy Newest --dry-run").unwrap().command,
+            Command::Import { path: PathBuf::from("lib.csv"), format: None, strategy: MergeStrategy::Newest, dry_run: true }
+        );
+        assert_eq!(
+            parse_str("import library.txt --format csv").unwrap().command,
+            Command::Import {
+                path: PathBuf::from("library.txt"),
+                format: Some(ExportFormat::Csv),
+                strategy: MergeStrategy::Ours,
+                dry_run: false,
+            }
+        );
+    }
+}
@@ -0,0 +1,316 @@
+//! `omnidex-cli`: library operations without the desktop app, run against the same `omnidex.db`.
+//! Results are printed to stdout as JSON so the tool can be scripted, e.g. from a nightly cron job.
+
+pub mod args;
+
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::Value;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::backup;
+use crate::categories::CategoryEngine;
+use crate::changelog;
+use crate::database::{self, DatabaseManager, DATA_DIR_ENV};
+use crate::dedupe;
+use crate::licenses;
+use crate::models::asset::AssetStats;
+use crate::models::{AppSettings, LocationStorage, ScanError, ScanErrorRecord, ScanLocation, ScanProgress, ScanRun};
+use crate::orbital::{self, OrbitalApiClient};
+use crate::providers;
+use crate::scanner::progress::ProgressSink;
+use crate::scanner::{AssetScanner, ScanControl};
+use crate::settings::SettingsService;
+use crate::transfer::{self, ExportFormat};
+use crate::updates;
+use args::{Command, Invocation, USAGE};
+
+/// Entry point of the `omnidex-cli` binary; returns the process exit code.
+pub fn main() -> i32 {
+    let invocation = match args::parse(std::env::args().skip(1)) {
+        Ok(invocation) => invocation,
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, USAGE);
+            return 2;
+        }
+    };
+    if invocation.command == Command::Help {
+        println!("{}", USAGE);
+        return 0;
+    }
+    if let Some(data_dir) = &invocation.data_dir {
+        std::env::set_var(DATA_DIR_ENV, data_dir);
+    }
+
+    let _ = tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("warn")),
+        )
+        .try_init();
+
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("error: Failed to start the async runtime: {}", e);
+            return 1;
+        }
+    };
+    match runtime.block_on(run(&invocation)) {
+        Ok(output) => {
+            let printed = if invocation.pretty {
+                serde_json::to_string_pretty(&output.json)
+            } else {
+                serde_json::to_string(&output.json)
+            };
+            match printed {
+                Ok(json) => println!("{}", json),
+                Err(e) => {
+                    eprintln!("error: Failed to write output: {}", e);
+                    return 1;
+                }
+            }
+            if output.succeeded { 0 } else { 1 }
+        }
+        Err(e) => {
+            eprintln!("error: {:#}", e);
+            1
+        }
+    }
+}
+
+/// Result of a command. A command can partly fail, e.g. when one of several scans fails,
+/// and still print what it did.
+struct Output {
+    json: Value,
+    succeeded: bool,
+}
+
+impl Output {
+    fn of<T: Serialize>(value: &T) -> Result<Self> {
+        Ok(Self { json: serde_json::to_value(value)?, succeeded: true })
+    }
+}
+
+async fn run(invocation: &Invocation) -> Result<Output> {
+    // Fails while the desktop app or another run has the library open.
+    database::lock_data_dir()?;
+    database::init_database().await?;
+    let db_manager = DatabaseManager::new().await.map_err(anyhow::Error::msg)?;
+    let settings = SettingsService::load(db_manager.clone()).await?.current();
+
+    match &invocation.command {
+        Command::Scan { location_ids, all, resume } => {
+            let locations = if *all {
+                db_manager.get_active_scan_locations().await?
+            } else {
+                let mut locations = Vec::with_capacity(location_ids.len());
+                for id in location_ids {
+                    let location = db_manager
+                        .get_scan_location_by_id(*id)
+                        .await?
+                        .ok_or_else(|| anyhow::anyhow!("Scan location {} not found", id))?;
+                    locations.push(location);
+                }
+                locations
+            };
+            scan_locations(&db_manager, &settings, locations, *resume, invocation.quiet).await
+        }
+        Command::Search { query, limit, offset } => {
+            Output::of(&db_manager.search_assets(query, *limit, *offset).await?)
+        }
+        Command::Export { path, format } => {
+            let format = format.unwrap_or_else(|| ExportFormat::from_path(path));
+            let exported = transfer::export_to_file(&db_manager.pool, path, format).await?;
+            Output::of(&ExportSummary { path: path.to_string_lossy().into_owned(), exported })
+        }
+        Command::Import { path, format, strategy, dry_run } => {
+            let format = format.unwrap_or_else(|| ExportFormat::from_path(path));
+            let export = transfer::read_export(path, format)?;
+            Output::of(&transfer::import_export(&db_manager.pool, &export, *strategy, *dry_run).await?)
+        }
+        Command::Match { location_id, asset_id, url } => {
+            let client = OrbitalApiClient::new()?;
+            match (asset_id, url) {
+                (Some(asset_id), Some(url)) => {
+                    Output::of(&orbital::match_asset_to_url(&db_manager, &client, *asset_id, url).await?)
+                }
+                _ => match_unmatched_assets(&db_manager, &settings, client, *location_id).await,
+            }
+        }
+        Command::Stats => Output::of(&LibraryStats {
+            assets: db_manager.get_asset_stats().await?,
+            locations: db_manager.get_location_storage().await?,
+        }),
+        Command::Dedupe => Output::of(&dedupe::find_duplicate_groups(&db_manager).await?),
+        Command::Outdated { engine } => {
+            let engine = match engine {
+                Some(engine) => Some(*engine),
+                None => updates::engine_in_use(&db_manager, &settings).await?,
+            };
+            Output::of(&updates::find_outdated_assets(&db_manager, engine).await?)
+        }
+        Command::Licenses { year } => Output::of(&licenses::license_report(&db_manager, *year).await?),
+        Command::Metadata { asset_id, refresh } => {
+            if *refresh {
+                let providers = providers::default_providers(Arc::new(OrbitalApiClient::new()?))?;
+                providers::refresh_asset(&db_manager, &providers, *asset_id).await?;
+            }
+            Output::of(&providers::merged_metadata(&db_manager, *asset_id, &settings.metadata_provider_order).await?)
+        }
+        Command::Backup { list, verify } => match verify {
+            Some(path) => Output::of(&backup::verify_backup(path).await?),
+            None if *list => Output::of(&backup::list_backups(&backup::backup_dir(&settings))),
+            None => Output::of(&backup::run_backup(&db_manager, &settings).await?),
+        },
+        Command::Sync { target } => Output::of(&changelog::sync_changes(&db_manager, target).await?),
+        Command::Help => Output::of(&USAGE),
+    }
+}
+
+#[derive(Serialize)]
+struct ExportSummary {
+    path: String,
+    exported: usize,
+}
+
+#[derive(Serialize)]
+struct LibraryStats {
+    #[serde(flatten)]
+    assets: AssetStats,
+    locations: Vec<LocationStorage>,
+}
+
+#[derive(Serialize)]
+struct LocationScanResult {
+    location_id: i64,
+    name: String,
+    /// The recorded run, with its outcome and counts.
+    run: Option<ScanRun>,
+    error: Option<String>,
+}
+
+#[derive(Serialize)]
+struct MatchedAsset {
+    asset_id: i64,
+    name: String,
+    product_slug: String,
+}
+
+#[derive(Serialize)]
+struct MatchSummary {
+    checked: usize,
+    matched: Vec<MatchedAsset>,
+    errors: Vec<ScanError>,
+}
+
+/// Prints scan progress to stderr, keeping stdout for the result.
+struct ConsoleProgress {
+    location_name: String,
+}
+
+impl ProgressSink for ConsoleProgress {
+    fn scan_progress(&self, progress: &ScanProgress) {
+        eprintln!(
+            "[{}] {} {}/{}",
+            self.location_name, progress.status, progress.processed_items, progress.total_items
+        );
+    }
+
+    fn scan_error(&self, error: &ScanErrorRecord) {
+        eprintln!(
+            "[{}] {:?}: {} {}",
+            self.location_name,
+            error.kind,
+            error.path.as_deref().unwrap_or_default(),
+            error.message
+        );
+    }
+}
+
+/// Pause the scan (or cancel the lookups) on Ctrl-C instead of killing the process mid-batch,
+/// so a scan stays resumable with `scan --resume`.
+fn stop_on_ctrl_c(flag: Arc<AtomicBool>) {
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            eprintln!("Stopping after the current batch...");
+            flag.store(true, Ordering::Relaxed);
+        }
+    });
+}
+
+/// Scan the locations one after another. Returns the run of every location; the command
+/// fails when any scan did.
+async fn scan_locations(
+    db_manager: &DatabaseManager,
+    settings: &AppSettings,
+    locations: Vec<ScanLocation>,
+    resume: bool,
+    quiet: bool,
+) -> Result<Output> {
+    let control = ScanControl::new();
+    stop_on_ctrl_c(control.pause.clone());
+    let categories = CategoryEngine::from_settings(settings);
+    let mut results = Vec::with_capacity(locations.len());
+
+    for location in locations {
+        if control.pause.load(Ordering::Relaxed) {
+            break;
+        }
+        let location_id = location.id.unwrap_or(0);
+        let mut scanner = AssetScanner::new(db_manager.clone(), OrbitalApiClient::new()?, control.cancel.clone())
+            .with_pause_token(control.pause.clone())
+            .with_resume(resume)
+            .with_concurrency(settings.scan_concurrency.max(1) as usize)
+            .with_categories(categories.clone());
+        if !quiet {
+            scanner = scanner.with_progress(Arc::new(ConsoleProgress { location_name: location.name.clone() }));
+        }
+
+        let error = match scanner.scan_location(&location).await {
+            Ok(()) => {
+                if let Err(e) = db_manager.update_scan_location_last_scanned(location_id).await {
+                    tracing::error!("Failed to update last scan timestamp: {}", e);
+                }
+                None
+            }
+            Err(e) => Some(format!("{:#}", e)),
+        };
+        let run = db_manager.get_scan_history(Some(location_id), 1).await?.into_iter().next();
+        results.push(LocationScanResult { location_id, name: location.name, run, error });
+    }
+
+    let succeeded = results.iter().all(|result| result.error.is_none());
+    Ok(Output { succeeded, ..Output::of(&results)? })
+}
+
+/// Look up marketplace matches for every asset that has none yet.
+async fn match_unmatched_assets(
+    db_manager: &DatabaseManager,
+    settings: &AppSettings,
+    client: OrbitalApiClient,
+    location_id: Option<i64>,
+) -> Result<Output> {
+    let cancel = Arc::new(AtomicBool::new(false));
+    stop_on_ctrl_c(cancel.clone());
+    let scanner = AssetScanner::new(db_manager.clone(), client, cancel)
+        .with_concurrency(settings.scan_concurrency.max(1) as usize);
+
+    let unmatched = db_manager.get_unmatched_assets(location_id).await?;
+    let checked = unmatched.len();
+    let (assets, errors) = scanner.match_assets(unmatched).await;
+    let matched = assets
+        .into_iter()
+        .filter_map(|asset| {
+            Some(MatchedAsset {
+                asset_id: asset.id?,
+                product_slug: asset.matched_orbital_product_slug?,
+                name: asset.name,
+            })
+        })
+        .collect();
+    Output::of(&MatchSummary { checked, matched, errors })
+}
+
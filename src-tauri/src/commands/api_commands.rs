@@ -0,0 +1,53 @@
+use tauri::{AppHandle, State};
+
+use crate::api;
+use crate::commands::settings_commands::emit_settings_changed;
+use crate::models::AppSettings;
+use crate::settings::SettingsService;
+
+/// Turns the local HTTP API on or off
+///
+/// # Arguments
+/// * `enabled` - Whether to serve the API on `127.0.0.1:<api_port>`
+///
+/// # Returns
+/// * `Ok(AppSettings)` - All settings after the change, including the token tools must send
+/// * `Err(String)` - Error message if the settings could not be saved
+///
+/// # Behavior
+/// - Generates an API token the first time the API is enabled
+/// - The server starts or stops as soon as the setting is saved
+#[tauri::command]
+pub async fn set_api_enabled(
+    enabled: bool,
+    app_handle: AppHandle,
+    settings_service: State<'_, SettingsService>,
+) -> Result<AppSettings, String> {
+    let mut settings = settings_service.current();
+    settings.api_enabled = enabled;
+    if enabled && settings.api_token.is_none() {
+        settings.api_token = Some(api::generate_token());
+    }
+    let updated = settings_service
+        .update(settings)
+        .await
+        .map_err(|e| format!("Failed to update API settings: {}", e))?;
+    emit_settings_changed(&app_handle, &updated);
+    Ok(updated)
+}
+
+/// Replaces the API token, locking out every tool that uses the old one
+#[tauri::command]
+pub async fn regenerate_api_token(
+    app_handle: AppHandle,
+    settings_service: State<'_, SettingsService>,
+) -> Result<AppSettings, String> {
+    let mut settings = settings_service.current();
+    settings.api_token = Some(api::generate_token());
+    let updated = settings_service
+        .update(settings)
+        .await
+        .map_err(|e| format!("Failed to update API token: {}", e))?;
+    emit_settings_changed(&app_handle, &updated);
+    Ok(updated)
+}
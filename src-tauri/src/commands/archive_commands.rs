@@ -0,0 +1,126 @@
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+use tauri::{AppHandle, Emitter, State};
+
+use crate::archive::{extract_archive, ArchiveFormat};
+use crate::database::DatabaseManager;
+use crate::models::{ArchiveExtraction, LibraryChange};
+use crate::orbital::OrbitalApiClient;
+use crate::scanner::roots::RootSelector;
+use crate::scanner::watcher::FolderChange;
+use crate::categories::CategoryEngine;
+use crate::scanner::AssetScanner;
+
+/// Unpacks an archive asset into a folder of a scan location
+///
+/// # Arguments
+/// * `asset_id` - ID of the archive asset
+/// * `location_id` - ID of the asset vault to unpack into
+/// * `folder_name` - Name of the new folder, defaults to the asset name
+///
+/// # Returns
+/// * `Ok(ArchiveExtraction)` - Where the archive was unpacked and how many files were written
+/// * `Err(String)` - Error message if the asset is not an archive, the location is not an asset
+///   vault, the folder already exists, or the archive could not be read
+///
+/// # Behavior
+/// - The archive itself is left in place and stays indexed
+/// - Zip and 7z archives can be unpacked; RAR archives are indexed only
+/// - Entries that would land outside the new folder are skipped
+/// - If the new folder is an asset root of the location it is indexed right away and a
+///   `library-changed` event is emitted
+#[tauri::command]
+pub async fn extract_archive_asset(
+    asset_id: i64,
+    location_id: i64,
+    folder_name: Option<String>,
+    app_handle: AppHandle,
+    db_manager_state: State<'_, DatabaseManager>,
+) -> Result<ArchiveExtraction, String> {
+    let asset = db_manager_state
+        .get_asset_by_id(asset_id)
+        .await
+        .map_err(|e| format!("Failed to fetch asset: {}", e))?;
+    let archive_path = PathBuf::from(&asset.file_path);
+    let format = ArchiveFormat::from_path(&archive_path)
+        .filter(|_| archive_path.is_file())
+        .ok_or_else(|| format!("Asset '{}' is not an archive on disk", asset.name))?;
+    if !format.can_extract() {
+        return Err("RAR archives are indexed only; unpack them with another tool.".to_string());
+    }
+
+    let location = db_manager_state
+        .get_scan_location_by_id(location_id)
+        .await
+        .map_err(|e| format!("Failed to fetch scan location: {}", e))?
+        .ok_or_else(|| format!("Scan location {} not found", location_id))?;
+    if location.is_project() {
+        return Err("Archives can only be extracted into asset vaults, not Unreal projects.".to_string());
+    }
+    if location.is_media() {
+        return Err("Archives can only be extracted into asset vaults, not media locations.".to_string());
+    }
+
+    let folder_name = folder_name.unwrap_or_else(|| asset.name.clone());
+    let mut components = Path::new(folder_name.trim()).components();
+    let (Some(Component::Normal(name)), None) = (components.next(), components.next()) else {
+        return Err(format!("Invalid folder name: {}", folder_name));
+    };
+    let root = PathBuf::from(&location.path);
+    let destination = root.join(name);
+    if destination.exists() {
+        return Err(format!("{} already exists", destination.display()));
+    }
+
+    let files_extracted = {
+        let destination = destination.clone();
+        tauri::async_runtime::spawn_blocking(move || extract_archive(&archive_path, &destination))
+            .await
+            .map_err(|e| format!("Extraction task failed: {}", e))?
+            .map_err(|e| format!("Failed to extract '{}': {}", asset.name, e))?
+    };
+
+    let selector = RootSelector::for_location(&location).map_err(|e| e.to_string())?;
+    let mut extracted_asset_id = None;
+    if let Some(folder) = selector.root_containing(&root, &destination) {
+        let orbital_api_client =
+            OrbitalApiClient::new().map_err(|e| format!("Failed to create Orbital API client: {}", e))?;
+        let scanner = AssetScanner::new(
+            db_manager_state.inner().clone(),
+            orbital_api_client,
+            Arc::new(AtomicBool::new(false)),
+        )
+        .with_categories(CategoryEngine::configured(&app_handle));
+        let mut change = LibraryChange {
+            location_id,
+            ..Default::default()
+        };
+        match scanner.sync_watched_folder(&folder, location_id, &root, &selector).await {
+            Ok(FolderChange::Added(id)) => {
+                change.added.push(id);
+                extracted_asset_id = Some(id);
+            }
+            Ok(FolderChange::Updated(id)) => {
+                change.updated.push(id);
+                extracted_asset_id = Some(id);
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!("Failed to index extracted folder {}: {}", folder.display(), e),
+        }
+        if extracted_asset_id.is_some() {
+            if let Err(e) = app_handle.emit("library-changed", &change) {
+                tracing::error!("Failed to emit library-changed event: {}", e);
+            }
+        }
+    }
+
+    Ok(ArchiveExtraction {
+        asset_id,
+        location_id,
+        destination: destination.to_string_lossy().into_owned(),
+        files_extracted,
+        extracted_asset_id,
+    })
+}
@@ -1,8 +1,6 @@
 use tauri::State;
 use std::collections::HashSet;
 use crate::orbital::api::OrbitalApiClient;
-use crate::models::asset::Asset;
-use url;
 
 /// Toggles the favorite status for multiple assets
 /// 
@@ -30,12 +28,13 @@ pub async fn toggle_favorite_status(
     // Use parameterized query with ? placeholders to prevent SQL injection
     let placeholders = asset_ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
     let query = format!(
-        "UPDATE assets SET is_favorite = ? WHERE id IN ({})",
+        "UPDATE assets SET is_favorite = ?, user_modified_date = ? WHERE id IN ({})",
         placeholders
     );
 
     let mut sqlx_query = sqlx::query(&query);
     sqlx_query = sqlx_query.bind(if is_favorited { 1 } else { 0 });
+    sqlx_query = sqlx_query.bind(chrono::Utc::now().to_rfc3339());
     
     for id in asset_ids {
         sqlx_query = sqlx_query.bind(id);
@@ -106,8 +105,9 @@ pub async fn add_tags_to_assets(
 
         let new_tags = existing_tags.into_iter().collect::<Vec<String>>().join(",");
 
-        sqlx::query("UPDATE assets SET tags = ? WHERE id = ?")
+        sqlx::query("UPDATE assets SET tags = ?, user_modified_date = ? WHERE id = ?")
             .bind(&new_tags)
+            .bind(chrono::Utc::now().to_rfc3339())
             .bind(id)
             .execute(db)
             .await
@@ -144,58 +144,14 @@ pub async fn match_asset_manually(
     url: String,
     orbital_client: State<'_, OrbitalApiClient>,
 ) -> Result<(), String> {
-    // Validate URL to ensure it's from orbital-market.com
-    let parsed_url = url::Url::parse(&url)
-        .map_err(|_| "Invalid URL format".to_string())?;
-    
-    if parsed_url.host_str() != Some("orbital-market.com") {
-        return Err("URL must be from orbital-market.com".to_string());
-    }
-
-    let product_id = orbital_client
-        .extract_product_id_from_url(&url)
-        .ok_or("Could not extract product ID from URL".to_string())?;
-
-    let orbital_asset = orbital_client
-        .fetch_asset_details(&product_id)
-        .await
-        .map_err(|e| format!("Failed to fetch asset details from Orbital: {}", e))?;
-
-    let db = crate::database::get_db();
-
-    // Check if the asset exists
-    let asset: Option<Asset> = sqlx::query_as("SELECT * FROM assets WHERE id = ?")
-        .bind(asset_id)
-        .fetch_optional(db)
-        .await
-        .map_err(|e| format!("Failed to check if asset exists: {}", e))?;
-
-    if asset.is_none() {
-        return Err(format!("Asset with ID {} not found", asset_id));
-    }
-
     // Get the database manager for the orbital updates
     let db_manager = crate::database::DatabaseManager::new().await
         .map_err(|e| format!("Failed to get database manager: {}", e))?;
 
-    // Update the asset with orbital details
-    db_manager
-        .update_asset_with_orbital_details(asset_id, &orbital_asset)
+    crate::orbital::match_asset_to_url(&db_manager, orbital_client.inner(), asset_id, &url)
         .await
-        .map_err(|e| format!("Failed to update asset with Orbital details: {}", e))?;
-
-    // Mark the asset as manually matched
-    db_manager
-        .update_asset_orbital_match_details(
-            asset_id,
-            orbital_asset.product_slug.clone(),
-            Some(1.0), // Manual matches get 100% confidence
-            Some("Manual".to_string()),
-        )
-        .await
-        .map_err(|e| format!("Failed to update asset with match details: {}", e))?;
-
-    Ok(())
+        .map(|_| ())
+        .map_err(|e| e.to_string())
 }
 
 /// Permanently deletes multiple assets from the database
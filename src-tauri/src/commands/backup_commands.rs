@@ -0,0 +1,100 @@
+use std::path::Path;
+use tauri::{AppHandle, Emitter, State};
+use tracing::info;
+
+use crate::backup::{self, BackupInfo, BackupVerification};
+use crate::database::{self, DatabaseManager};
+use crate::scanner::get_scanner_manager;
+use crate::scanner::watcher::{get_watcher_manager, start_configured_watchers};
+use crate::settings::SettingsService;
+
+/// Creates a database backup immediately, regardless of the backup schedule
+///
+/// # Returns
+/// * `Ok(BackupInfo)` - The written and verified backup
+/// * `Err(String)` - Error message if the backup could not be written or failed verification
+///
+/// # Behavior
+/// - Uses the configured backup location (or the default one next to the database)
+/// - Rotates old backups by count and age afterwards
+/// - Emits `backup-created` on success
+#[tauri::command]
+pub async fn create_backup_now(
+    app_handle: AppHandle,
+    db_manager: State<'_, DatabaseManager>,
+    settings_service: State<'_, SettingsService>,
+) -> Result<BackupInfo, String> {
+    let backup = backup::run_backup(&db_manager, &settings_service.current())
+        .await
+        .map_err(|e| format!("Failed to create backup: {}", e))?;
+
+    if let Err(e) = app_handle.emit("backup-created", &backup) {
+        tracing::error!("Failed to emit backup-created event: {}", e);
+    }
+    Ok(backup)
+}
+
+#[tauri::command]
+pub async fn list_backups(settings_service: State<'_, SettingsService>) -> Result<Vec<BackupInfo>, String> {
+    let dir = backup::backup_dir(&settings_service.current());
+    Ok(backup::list_backups(&dir))
+}
+
+#[tauri::command]
+pub async fn verify_backup(path: String) -> Result<BackupVerification, String> {
+    backup::verify_backup(Path::new(&path))
+        .await
+        .map_err(|e| format!("Failed to verify backup: {}", e))
+}
+
+/// Restores the library from a backup file while the app is running
+///
+/// # Arguments
+/// * `path` - Path to a backup created by Omnidex (or any copy of `omnidex.db`)
+///
+/// # Returns
+/// * `Ok(BackupInfo)` - The safety backup of the state before the restore
+/// * `Err(String)` - Error message if the file is invalid or the restore failed
+///
+/// # Behavior
+/// - Refuses to run while scans are in progress
+/// - Validates the backup (integrity check and required tables) before touching anything
+/// - Writes a `pre-restore` backup first, then replaces all data in one transaction
+/// - Reloads settings, restarts filesystem watchers and emits `database-restored`
+#[tauri::command]
+pub async fn restore_backup(
+    path: String,
+    app_handle: AppHandle,
+    db_manager: State<'_, DatabaseManager>,
+    settings_service: State<'_, SettingsService>,
+) -> Result<BackupInfo, String> {
+    if let Some(manager) = get_scanner_manager().lock().unwrap().as_ref() {
+        if manager.active_scan_count() > 0 {
+            return Err("Cannot restore a backup while scans are running.".to_string());
+        }
+    }
+
+    // Watchers would write into the tables while they are being replaced.
+    if let Some(manager) = get_watcher_manager().lock().unwrap().as_mut() {
+        manager.stop_all();
+    }
+
+    let safety_dir = backup::backup_dir(&settings_service.current());
+    let result = backup::restore_backup(&db_manager, Path::new(&path), &safety_dir, &database::data_dir()).await;
+
+    match settings_service.reload().await {
+        Ok(settings) => {
+            let _ = app_handle.emit("settings-changed", &settings);
+        }
+        Err(e) => tracing::warn!("Failed to reload settings after restore: {}", e),
+    }
+    start_configured_watchers(db_manager.inner().clone(), app_handle.clone()).await;
+
+    let safety_backup = result.map_err(|e| format!("Failed to restore backup: {}", e))?;
+    info!("Database restored from {}", path);
+
+    if let Err(e) = app_handle.emit("database-restored", &path) {
+        tracing::error!("Failed to emit database-restored event: {}", e);
+    }
+    Ok(safety_backup)
+}
@@ -0,0 +1,168 @@
+use std::collections::HashSet;
+use std::path::Path;
+use tauri::{AppHandle, State};
+
+use crate::categories::{
+    Categorization, CategoryAssignment, CategoryEngine, CategorySignals, CategoryTaxonomy, MANUAL_RULE,
+};
+use crate::commands::settings_commands::emit_settings_changed;
+use crate::database::DatabaseManager;
+use crate::models::Asset;
+use crate::settings::SettingsService;
+
+/// Returns the category taxonomy assets are categorized with
+///
+/// # Returns
+/// * `Ok(CategoryTaxonomy)` - The custom taxonomy if one is configured, otherwise the built-in one
+#[tauri::command]
+pub async fn get_category_taxonomy(settings_service: State<'_, SettingsService>) -> Result<CategoryTaxonomy, String> {
+    Ok(settings_service.current().category_taxonomy.unwrap_or_default())
+}
+
+/// Replaces the category taxonomy
+///
+/// # Arguments
+/// * `taxonomy` - The new taxonomy, or `None` to go back to the built-in one
+///
+/// # Returns
+/// * `Ok(CategoryTaxonomy)` - The taxonomy now in effect
+/// * `Err(String)` - Duplicate ids or aliases, an invalid regex or an out-of-range confidence
+///
+/// # Behavior
+/// - Newly scanned assets use the new taxonomy right away; existing assets keep their
+///   category until `post_process_asset_categories` runs
+/// - Emits `settings-changed` on success
+#[tauri::command]
+pub async fn set_category_taxonomy(
+    taxonomy: Option<CategoryTaxonomy>,
+    app_handle: AppHandle,
+    settings_service: State<'_, SettingsService>,
+) -> Result<CategoryTaxonomy, String> {
+    let value = serde_json::to_value(&taxonomy).map_err(|e| e.to_string())?;
+    let updated = settings_service
+        .set("category_taxonomy", value)
+        .await
+        .map_err(|e| format!("Failed to set category taxonomy: {}", e))?;
+    emit_settings_changed(&app_handle, &updated);
+    Ok(updated.category_taxonomy.unwrap_or_default())
+}
+
+/// Lists an asset's categories and explains how each was chosen
+///
+/// # Returns
+/// * `Ok(Some(Categorization))` - The primary and secondary categories with their confidence and rule
+/// * `Ok(None)` - The asset was categorized before assignments were recorded
+#[tauri::command]
+pub async fn get_asset_categories(
+    asset_id: i64,
+    db_manager_state: State<'_, DatabaseManager>,
+) -> Result<Option<Categorization>, String> {
+    db_manager_state
+        .get_asset_categorization(asset_id)
+        .await
+        .map_err(|e| format!("Failed to fetch asset categories: {}", e))
+}
+
+/// Sets an asset's categories by hand
+///
+/// # Arguments
+/// * `asset_id` - ID of the asset
+/// * `primary` - Category stored as the asset's type
+/// * `secondary` - Further categories the asset belongs to
+///
+/// # Returns
+/// * `Ok(Categorization)` - The categories now recorded
+/// * `Err(String)` - A category that is not in the taxonomy, or a database error
+///
+/// # Behavior
+/// - Manually set categories are kept when `post_process_asset_categories` re-evaluates the library
+#[tauri::command]
+pub async fn set_asset_categories(
+    asset_id: i64,
+    primary: String,
+    secondary: Vec<String>,
+    app_handle: AppHandle,
+    db_manager_state: State<'_, DatabaseManager>,
+) -> Result<Categorization, String> {
+    let engine = CategoryEngine::configured(&app_handle);
+    if let Some(unknown) = std::iter::once(&primary).chain(&secondary).find(|c| !engine.contains(c)) {
+        return Err(format!("Unknown category '{}'", unknown));
+    }
+
+    let manual = |category: String| CategoryAssignment {
+        category,
+        confidence: 1.0,
+        rule: MANUAL_RULE.to_string(),
+    };
+    let mut seen = HashSet::from([primary.clone()]);
+    let categorization = Categorization {
+        primary: manual(primary),
+        secondary: secondary.into_iter().filter(|c| seen.insert(c.clone())).map(manual).collect(),
+    };
+    db_manager_state
+        .apply_categorization(asset_id, &categorization)
+        .await
+        .map_err(|e| format!("Failed to update asset categories: {}", e))?;
+    Ok(categorization)
+}
+
+/// Re-evaluates the category of every asset with the configured taxonomy
+///
+/// # Returns
+/// * `Ok(i64)` - Number of assets whose primary category changed
+/// * `Err(String)` - Error message if the assets cannot be read or updated
+///
+/// # Behavior
+/// - Uses every signal available: folder and parent names and bundled descriptors read from
+///   disk, the classes of indexed Unreal packages and the categories of the matched Orbital product
+/// - Records every category with its confidence and the rule that fired, changed or not
+/// - Leaves assets whose categories were set by hand alone
+#[tauri::command]
+pub async fn post_process_asset_categories(
+    app_handle: AppHandle,
+    db_manager_state: State<'_, DatabaseManager>,
+) -> Result<i64, String> {
+    let engine = CategoryEngine::configured(&app_handle);
+    let assets = sqlx::query_as::<_, Asset>("SELECT * FROM assets")
+        .fetch_all(&db_manager_state.pool)
+        .await
+        .map_err(|e| format!("Failed to fetch assets for category processing: {}", e))?;
+
+    let mut changed_count = 0;
+    for asset in assets {
+        let Some(asset_id) = asset.id else {
+            continue;
+        };
+        let recorded = db_manager_state
+            .get_asset_categorization(asset_id)
+            .await
+            .map_err(|e| format!("Failed to fetch asset categories: {}", e))?;
+        if recorded.is_some_and(|c| c.is_manual()) {
+            continue;
+        }
+        let package_names = db_manager_state
+            .get_asset_package_names(asset_id)
+            .await
+            .map_err(|e| format!("Failed to fetch asset packages: {}", e))?;
+
+        let file_path = asset.file_path.clone();
+        let mut signals = tauri::async_runtime::spawn_blocking(move || CategorySignals::for_indexed_path(Path::new(&file_path)))
+            .await
+            .map_err(|e| format!("Failed to read asset {}: {}", asset.name, e))?;
+        signals.add_packages(package_names.iter().map(String::as_str));
+        if let Some(categories) = &asset.orbital_categories {
+            signals.add_orbital_categories(categories);
+        }
+
+        let categorization = engine.assign(&signals);
+        if categorization.primary.category != asset.asset_type {
+            changed_count += 1;
+        }
+        db_manager_state
+            .apply_categorization(asset_id, &categorization)
+            .await
+            .map_err(|e| format!("Failed to update asset category: {}", e))?;
+    }
+
+    Ok(changed_count)
+}
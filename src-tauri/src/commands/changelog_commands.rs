@@ -0,0 +1,28 @@
+use std::path::Path;
+use tauri::State;
+
+use crate::changelog::{self, ChangeSyncReport};
+use crate::database::DatabaseManager;
+
+/// Syncs tags, notes, favorites, overrides, match decisions and collections with another library
+///
+/// # Arguments
+/// * `target` - Another `omnidex.db`, or a folder shared between machines (e.g. a synced drive)
+///
+/// # Returns
+/// * `Ok(ChangeSyncReport)` - How many changes were received, applied and sent
+/// * `Err(String)` - Error message if the target cannot be read or written
+///
+/// # Behavior
+/// - With a database file, changes are exchanged both ways directly
+/// - With a folder, every other library's change set in it is read and this library's is written
+/// - The most recent edit of each field wins on both sides, whichever syncs first
+#[tauri::command]
+pub async fn sync_library_changes(
+    target: String,
+    db_manager_state: State<'_, DatabaseManager>,
+) -> Result<ChangeSyncReport, String> {
+    changelog::sync_changes(&db_manager_state, Path::new(&target))
+        .await
+        .map_err(|e| format!("Library sync failed: {}", e))
+}
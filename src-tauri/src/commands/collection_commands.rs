@@ -0,0 +1,152 @@
+use tauri::State;
+
+use crate::database::database_manager::COLLECTION_SELECT;
+use crate::database::{get_db, DatabaseManager};
+use crate::models::asset_card::{AssetCardData, SortOption};
+use crate::models::collection::Collection;
+
+async fn fetch_collection(id: i64) -> Result<Collection, String> {
+    sqlx::query_as::<_, Collection>(&format!("{} WHERE c.id = ?", COLLECTION_SELECT))
+        .bind(id)
+        .fetch_one(get_db())
+        .await
+        .map_err(|e| format!("Failed to fetch collection: {}", e))
+}
+
+/// Creates a new, empty collection
+///
+/// # Arguments
+/// * `name` - Unique, human-readable name
+/// * `description` - Optional description
+#[tauri::command]
+pub async fn create_collection(name: String, description: Option<String>) -> Result<Collection, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Collection name cannot be empty".to_string());
+    }
+
+    let now = chrono::Utc::now().to_rfc3339();
+    let id: i64 = sqlx::query_scalar(
+        "INSERT INTO collections (name, description, created_date, modified_date) VALUES (?, ?, ?, ?) RETURNING id",
+    )
+    .bind(&name)
+    .bind(&description)
+    .bind(&now)
+    .bind(&now)
+    .fetch_one(get_db())
+    .await
+    .map_err(|e| format!("Failed to create collection: {}", e))?;
+
+    fetch_collection(id).await
+}
+
+#[tauri::command]
+pub async fn get_collections(db_manager_state: State<'_, DatabaseManager>) -> Result<Vec<Collection>, String> {
+    db_manager_state
+        .get_collections()
+        .await
+        .map_err(|e| format!("Failed to fetch collections: {}", e))
+}
+
+#[tauri::command]
+pub async fn update_collection(id: i64, name: String, description: Option<String>) -> Result<Collection, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Collection name cannot be empty".to_string());
+    }
+
+    sqlx::query("UPDATE collections SET name = ?, description = ?, modified_date = ? WHERE id = ?")
+        .bind(&name)
+        .bind(&description)
+        .bind(chrono::Utc::now().to_rfc3339())
+        .bind(id)
+        .execute(get_db())
+        .await
+        .map_err(|e| format!("Failed to update collection: {}", e))?;
+
+    fetch_collection(id).await
+}
+
+/// Deletes a collection. The assets in it are not touched.
+#[tauri::command]
+pub async fn delete_collection(id: i64) -> Result<(), String> {
+    sqlx::query("DELETE FROM collections WHERE id = ?")
+        .bind(id)
+        .execute(get_db())
+        .await
+        .map_err(|e| format!("Failed to delete collection: {}", e))?;
+    Ok(())
+}
+
+/// Adds assets to a collection; assets already in it are ignored
+///
+/// # Security
+/// Uses parameterized queries to prevent SQL injection attacks
+#[tauri::command]
+pub async fn add_assets_to_collection(collection_id: i64, asset_ids: Vec<i64>) -> Result<Collection, String> {
+    let db = get_db();
+    let now = chrono::Utc::now().to_rfc3339();
+    let mut tx = db.begin().await.map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+    for asset_id in asset_ids {
+        sqlx::query("INSERT OR IGNORE INTO collection_assets (collection_id, asset_id, added_date) VALUES (?, ?, ?)")
+            .bind(collection_id)
+            .bind(asset_id)
+            .bind(&now)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to add asset {} to collection: {}", asset_id, e))?;
+    }
+    sqlx::query("UPDATE collections SET modified_date = ? WHERE id = ?")
+        .bind(&now)
+        .bind(collection_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to update collection: {}", e))?;
+
+    tx.commit().await.map_err(|e| format!("Failed to commit collection changes: {}", e))?;
+    fetch_collection(collection_id).await
+}
+
+#[tauri::command]
+pub async fn remove_assets_from_collection(collection_id: i64, asset_ids: Vec<i64>) -> Result<Collection, String> {
+    if !asset_ids.is_empty() {
+        let placeholders = asset_ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+        let query = format!(
+            "DELETE FROM collection_assets WHERE collection_id = ? AND asset_id IN ({})",
+            placeholders
+        );
+
+        let mut sqlx_query = sqlx::query(&query).bind(collection_id);
+        for id in asset_ids {
+            sqlx_query = sqlx_query.bind(id);
+        }
+        sqlx_query
+            .execute(get_db())
+            .await
+            .map_err(|e| format!("Failed to remove assets from collection: {}", e))?;
+
+        sqlx::query("UPDATE collections SET modified_date = ? WHERE id = ?")
+            .bind(chrono::Utc::now().to_rfc3339())
+            .bind(collection_id)
+            .execute(get_db())
+            .await
+            .map_err(|e| format!("Failed to update collection: {}", e))?;
+    }
+
+    fetch_collection(collection_id).await
+}
+
+#[tauri::command]
+pub async fn get_collection_assets(
+    collection_id: i64,
+    sort_by: Option<SortOption>,
+    limit: u32,
+    offset: u32,
+    db_manager_state: State<'_, DatabaseManager>,
+) -> Result<Vec<AssetCardData>, String> {
+    db_manager_state
+        .get_collection_assets(collection_id, sort_by.unwrap_or(SortOption::NameAsc), limit, offset)
+        .await
+        .map_err(|e| format!("Failed to fetch collection assets: {}", e))
+}
@@ -0,0 +1,59 @@
+use tauri::State;
+
+use crate::database::DatabaseManager;
+use crate::dedupe::{self, DuplicateGroup, DuplicateResolution, ResolutionReport, ResolutionRequest};
+
+/// Finds identical assets, packs contained in larger packs, and several versions of one product
+///
+/// # Returns
+/// * `Ok(Vec<DuplicateGroup>)` - Groups with the suggested copy to keep first, most reclaimable space first
+/// * `Err(String)` - Error message if the library cannot be read
+///
+/// # Behavior
+/// - Identical means the same files by relative path, size and modification time; assets scanned
+///   before fingerprints were stored are only found after a rescan
+/// - Assets nested inside one another are never grouped
+#[tauri::command]
+pub async fn find_duplicate_groups(
+    db_manager_state: State<'_, DatabaseManager>,
+) -> Result<Vec<DuplicateGroup>, String> {
+    dedupe::find_duplicate_groups(&db_manager_state)
+        .await
+        .map_err(|e| format!("Failed to find duplicates: {}", e))
+}
+
+/// Keeps one asset of a duplicate group and removes, hard-links or archives the others
+///
+/// # Arguments
+/// * `request` - The asset to keep, the copies, the action and, for `archive`, the archive folder
+///
+/// # Returns
+/// * `Ok(ResolutionReport)` - What was done with each copy and the space freed
+/// * `Err(String)` - Error message if the request is invalid or the kept asset is missing
+///
+/// # Behavior
+/// - With `dry_run`, nothing is changed and the report shows what would be done
+/// - Hard links are only made when every file matches the kept copy byte for byte
+/// - A copy is only removed or archived once each of its files is found in the kept asset;
+///   other versions of a product are not compared and can only be archived
+/// - Removed and archived copies hand their tags, favorite, notes and collections to the kept asset
+/// - A copy that fails is reported and the others are still resolved
+#[tauri::command]
+pub async fn resolve_duplicates(
+    request: ResolutionRequest,
+    db_manager_state: State<'_, DatabaseManager>,
+) -> Result<ResolutionReport, String> {
+    dedupe::resolve_duplicates(&db_manager_state, &request)
+        .await
+        .map_err(|e| format!("Failed to resolve duplicates: {}", e))
+}
+
+/// Lists every duplicate resolution, the latest first
+#[tauri::command]
+pub async fn get_duplicate_resolutions(
+    db_manager_state: State<'_, DatabaseManager>,
+) -> Result<Vec<DuplicateResolution>, String> {
+    dedupe::get_resolutions(&db_manager_state)
+        .await
+        .map_err(|e| format!("Failed to fetch duplicate resolutions: {}", e))
+}
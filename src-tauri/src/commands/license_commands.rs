@@ -0,0 +1,137 @@
+use tauri::State;
+
+use crate::database::DatabaseManager;
+use crate::licenses::{self, AssetLicense, LicenseInput, SellerLicenses, SpendReport, UnlicensedAsset};
+
+/// Records a license for an asset
+///
+/// # Arguments
+/// * `asset_id` - ID of the licensed asset
+/// * `license` - Owner, purchase date, price, license type and seats, order reference and seller
+///
+/// # Returns
+/// * `Ok(AssetLicense)` - The recorded license
+/// * `Err(String)` - Error message if the license is invalid or the asset does not exist
+///
+/// # Behavior
+/// - Without a price, a dollar license takes the matched Orbital price and is marked as such
+/// - Without a seller, the matched Orbital seller is used
+#[tauri::command]
+pub async fn add_asset_license(
+    asset_id: i64,
+    license: LicenseInput,
+    db_manager_state: State<'_, DatabaseManager>,
+) -> Result<AssetLicense, String> {
+    licenses::add_license(&db_manager_state, asset_id, &license)
+        .await
+        .map_err(|e| format!("Failed to add license: {}", e))
+}
+
+/// Replaces a license record, filling empty fields from the listing as when it was added
+#[tauri::command]
+pub async fn update_asset_license(
+    license_id: i64,
+    license: LicenseInput,
+    db_manager_state: State<'_, DatabaseManager>,
+) -> Result<AssetLicense, String> {
+    licenses::update_license(&db_manager_state, license_id, &license)
+        .await
+        .map_err(|e| format!("Failed to update license: {}", e))
+}
+
+/// Attaches a license to an asset, such as an orphaned license to the asset's new copy
+///
+/// # Arguments
+/// * `license_id` - ID of the license
+/// * `asset_id` - ID of the asset it now belongs to
+///
+/// # Returns
+/// * `Ok(AssetLicense)` - The license with the asset's current name and path
+/// * `Err(String)` - Error message if the license or the asset does not exist
+#[tauri::command]
+pub async fn attach_asset_license(
+    license_id: i64,
+    asset_id: i64,
+    db_manager_state: State<'_, DatabaseManager>,
+) -> Result<AssetLicense, String> {
+    licenses::attach_license(&db_manager_state, license_id, asset_id)
+        .await
+        .map_err(|e| format!("Failed to attach license: {}", e))
+}
+
+#[tauri::command]
+pub async fn delete_asset_license(license_id: i64, db_manager_state: State<'_, DatabaseManager>) -> Result<(), String> {
+    licenses::delete_license(&db_manager_state, license_id)
+        .await
+        .map_err(|e| format!("Failed to delete license: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_asset_licenses(
+    asset_id: i64,
+    db_manager_state: State<'_, DatabaseManager>,
+) -> Result<Vec<AssetLicense>, String> {
+    licenses::get_asset_licenses(&db_manager_state, asset_id)
+        .await
+        .map_err(|e| format!("Failed to fetch licenses: {}", e))
+}
+
+/// Reports what was spent on licenses
+///
+/// # Arguments
+/// * `year` - Only count licenses bought in this year; `None` counts every license
+///
+/// # Returns
+/// * `Ok(SpendReport)` - License and seat counts and the spend per currency
+/// * `Err(String)` - Error message if the library cannot be read
+///
+/// # Behavior
+/// - Each total says how much of it comes from Orbital list prices rather than entered prices
+/// - Licenses without any price are counted separately
+#[tauri::command]
+pub async fn get_license_spend(
+    year: Option<i32>,
+    db_manager_state: State<'_, DatabaseManager>,
+) -> Result<SpendReport, String> {
+    licenses::spend_report(&db_manager_state, year)
+        .await
+        .map_err(|e| format!("Failed to report license spend: {}", e))
+}
+
+/// Lists licenses, seats and spend per seller, optionally for the licenses bought in `year`
+#[tauri::command]
+pub async fn get_licenses_by_seller(
+    year: Option<i32>,
+    db_manager_state: State<'_, DatabaseManager>,
+) -> Result<Vec<SellerLicenses>, String> {
+    licenses::licenses_by_seller(&db_manager_state, year)
+        .await
+        .map_err(|e| format!("Failed to report licenses per seller: {}", e))
+}
+
+/// Lists library assets without any license record
+#[tauri::command]
+pub async fn get_unlicensed_assets(
+    db_manager_state: State<'_, DatabaseManager>,
+) -> Result<Vec<UnlicensedAsset>, String> {
+    licenses::unlicensed_assets(&db_manager_state)
+        .await
+        .map_err(|e| format!("Failed to fetch unlicensed assets: {}", e))
+}
+
+/// Lists licenses whose asset left the library
+///
+/// # Returns
+/// * `Ok(Vec<AssetLicense>)` - Each license with the name and path its asset had
+///
+/// # Behavior
+/// - A license loses its asset when the asset is deleted, moved or renamed on disk, removed
+///   with its scan location, or resolved as a duplicate; the record itself is never deleted
+#[tauri::command]
+pub async fn get_orphaned_licenses(
+    db_manager_state: State<'_, DatabaseManager>,
+) -> Result<Vec<AssetLicense>, String> {
+    licenses::orphaned_licenses(&db_manager_state)
+        .await
+        .map_err(|e| format!("Failed to fetch orphaned licenses: {}", e))
+}
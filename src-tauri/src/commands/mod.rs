@@ -1,19 +1,51 @@
+pub mod api_commands;
+pub mod archive_commands;
 pub mod asset_commands;
+pub mod backup_commands;
+pub mod category_commands;
+pub mod changelog_commands;
+pub mod collection_commands;
+pub mod duplicate_commands;
+pub mod license_commands;
 pub mod orbital_commands;
+pub mod project_commands;
+pub mod provider_commands;
 pub mod scan_commands;
+pub mod settings_commands;
+pub mod storage_commands;
 pub mod system_commands;
+pub mod team_commands;
+pub mod transfer_commands;
+pub mod update_commands;
 
-use crate::database::get_db;
+use crate::categories::CategoryEngine;
+use crate::database::{get_db, DatabaseManager};
 use crate::models::asset::{Asset, AssetFilter};
 use crate::models::asset_card::{AssetCardData, SortOption};
 use crate::models::asset_details::AssetForFrontend;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use tauri::{AppHandle, State};
 
 // Re-export commands from other modules
+pub use api_commands::*;
+pub use archive_commands::*;
 pub use asset_commands::{toggle_favorite_status, add_tags_to_assets, delete_assets, match_asset_manually};
+pub use backup_commands::*;
+pub use category_commands::*;
+pub use changelog_commands::*;
+pub use collection_commands::*;
+pub use duplicate_commands::*;
+pub use license_commands::*;
+pub use project_commands::*;
+pub use provider_commands::*;
+pub use transfer_commands::*;
 pub use orbital_commands::*;
 pub use scan_commands::*;
+pub use settings_commands::*;
+pub use storage_commands::*;
 pub use system_commands::*;
+pub use team_commands::*;
+pub use update_commands::*;
 
 // Asset querying and search commands that remain in mod.rs
 #[tauri::command]
@@ -72,8 +104,9 @@ pub async fn get_assets(
 pub async fn update_asset_favorite(id: i64, is_favorite: bool) -> Result<(), String> {
     let db = get_db();
     
-    sqlx::query("UPDATE assets SET is_favorite = ? WHERE id = ?")
+    sqlx::query("UPDATE assets SET is_favorite = ?, user_modified_date = ? WHERE id = ?")
         .bind(is_favorite)
+        .bind(chrono::Utc::now().to_rfc3339())
         .bind(id)
         .execute(db)
         .await
@@ -87,8 +120,9 @@ pub async fn update_asset_tags(id: i64, tags: Vec<String>) -> Result<(), String>
     let db = get_db();
     let tags_str = tags.join(",");
     
-    sqlx::query("UPDATE assets SET tags = ? WHERE id = ?")
+    sqlx::query("UPDATE assets SET tags = ?, user_modified_date = ? WHERE id = ?")
         .bind(&tags_str)
+        .bind(chrono::Utc::now().to_rfc3339())
         .bind(id)
         .execute(db)
         .await
@@ -101,8 +135,9 @@ pub async fn update_asset_tags(id: i64, tags: Vec<String>) -> Result<(), String>
 pub async fn update_asset_description(id: i64, description: Option<String>) -> Result<(), String> {
     let db = get_db();
     
-    sqlx::query("UPDATE assets SET description = ? WHERE id = ?")
+    sqlx::query("UPDATE assets SET description = ?, user_modified_date = ? WHERE id = ?")
         .bind(&description)
+        .bind(chrono::Utc::now().to_rfc3339())
         .bind(id)
         .execute(db)
         .await
@@ -125,31 +160,53 @@ pub async fn update_asset_metadata(id: i64, metadata: Option<String>) -> Result<
     Ok(())
 }
 
+/// Counts the assets in each category
+///
+/// # Returns
+/// * `Ok(HashMap<String, i64>)` - Number of assets per category id
+///
+/// # Behavior
+/// - An asset counts towards every category it is in, primary or secondary, and towards all of
+///   their ancestors, so each node of the taxonomy reports the assets anywhere below it
+/// - Each asset is counted at most once per node
 #[tauri::command]
-pub async fn get_category_counts() -> Result<HashMap<String, i64>, String> {
-    let db = get_db();
-    
-    let rows: Vec<(String, i64)> = sqlx::query_as(
-        "SELECT asset_type, COUNT(*) as count FROM assets GROUP BY asset_type ORDER BY count DESC"
-    )
-    .fetch_all(db)
-    .await
-    .map_err(|e| format!("Failed to get category counts: {}", e))?;
+pub async fn get_category_counts(
+    app_handle: AppHandle,
+    db_manager_state: State<'_, DatabaseManager>,
+) -> Result<HashMap<String, i64>, String> {
+    let engine = CategoryEngine::configured(&app_handle);
+    let pairs = db_manager_state
+        .get_asset_category_pairs()
+        .await
+        .map_err(|e| format!("Failed to get category counts: {}", e))?;
+
+    let mut nodes_by_asset: HashMap<i64, HashSet<String>> = HashMap::new();
+    for (asset_id, category) in pairs {
+        nodes_by_asset.entry(asset_id).or_default().extend(engine.lineage(&category));
+    }
 
     let mut counts = HashMap::new();
-    for (category, count) in rows {
-        counts.insert(category, count);
+    for node in nodes_by_asset.into_values().flatten() {
+        *counts.entry(node).or_insert(0) += 1;
     }
 
     Ok(counts)
 }
 
+/// Lists the assets in a category for the asset grid
+///
+/// # Arguments
+/// * `category` - Category id, or `"All"` for the whole library
+///
+/// # Behavior
+/// - Includes assets that are in the category or any category below it, as primary or secondary category
 #[tauri::command]
 pub async fn get_assets_by_category(
     category: String,
     sort_by: Option<SortOption>,
     limit: u32,
     offset: u32,
+    app_handle: AppHandle,
 ) -> Result<Vec<AssetCardData>, String> {
     let db = get_db();
     
@@ -162,99 +219,55 @@ pub async fn get_assets_by_category(
         SortOption::DateDesc => "ORDER BY modified_date DESC",
     };
     
-    let (query, bind_category) = if category == "All" {
+    let subtree = if category == "All" {
         // For "All" category, don't filter by asset_type
-        (format!(
-            "SELECT id, name, asset_type, file_size, thumbnail_path, orbital_thumbnail_url, orbital_rating_average, orbital_rating_count, is_favorite, created_date 
-             FROM assets 
-             {} 
-             LIMIT ? OFFSET ?",
-            sort_clause
-        ), false)
+        Vec::new()
     } else {
-        // For specific categories, filter by asset_type
-        (format!(
-            "SELECT id, name, asset_type, file_size, thumbnail_path, orbital_thumbnail_url, orbital_rating_average, orbital_rating_count, is_favorite, created_date 
-             FROM assets 
-             WHERE asset_type = ? 
-             {} 
-             LIMIT ? OFFSET ?",
-            sort_clause
-        ), true)
+        CategoryEngine::configured(&app_handle).subtree(&category)
     };
-
-    let assets: Vec<AssetCardData> = if bind_category {
-        sqlx::query_as(&query)
-            .bind(&category)
-            .bind(limit)
-            .bind(offset)
-            .fetch_all(db)
-            .await
-            .map_err(|e| format!("Failed to fetch assets by category: {}", e))?
+    let where_clause = if subtree.is_empty() {
+        String::new()
     } else {
-        sqlx::query_as(&query)
-            .bind(limit)
-            .bind(offset)
-            .fetch_all(db)
-            .await
-            .map_err(|e| format!("Failed to fetch assets: {}", e))?
+        let placeholders = vec!["?"; subtree.len()].join(", ");
+        format!(
+            "WHERE asset_type IN ({0}) OR id IN (SELECT asset_id FROM asset_categories WHERE category IN ({0}))",
+            placeholders
+        )
     };
+    let query = format!(
+        "SELECT id, name, asset_type, file_size, thumbnail_path, orbital_thumbnail_url, orbital_rating_average, orbital_rating_count, is_favorite, created_date 
+         FROM assets 
+         {} 
+         {} 
+         LIMIT ? OFFSET ?",
+        where_clause, sort_clause
+    );
+
+    let mut assets_query = sqlx::query_as::<_, AssetCardData>(&query);
+    // The subtree is bound twice: once for asset_type and once for the category table.
+    for node in subtree.iter().chain(subtree.iter()) {
+        assets_query = assets_query.bind(node);
+    }
+    let assets = assets_query
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(db)
+        .await
+        .map_err(|e| format!("Failed to fetch assets by category: {}", e))?;
 
     Ok(assets)
 }
 
 #[tauri::command]
-pub async fn get_asset_details(id: i64) -> Result<AssetForFrontend, String> {
-    let db = get_db();
-    
-    let asset: Asset = sqlx::query_as("SELECT * FROM assets WHERE id = ?")
-        .bind(id)
-        .fetch_one(db)
+pub async fn get_asset_details(
+    id: i64,
+    db_manager_state: State<'_, DatabaseManager>,
+) -> Result<AssetForFrontend, String> {
+    db_manager_state
+        .get_asset_details(id)
         .await
-        .map_err(|e| format!("Failed to fetch asset details: {}", e))?;
-
-    // Convert Asset to AssetForFrontend
-    let asset_for_frontend = AssetForFrontend {
-        id: asset.id,
-        name: asset.name,
-        file_path: asset.file_path,
-        asset_type: asset.asset_type,
-        file_size: asset.file_size,
-        created_date: asset.created_date,
-        modified_date: asset.modified_date,
-        first_indexed_timestamp: asset.first_indexed_timestamp,
-        thumbnail_path: asset.thumbnail_path,
-        tags: asset.tags.map(|t| t.split(',').map(|s| s.trim().to_string()).collect()).unwrap_or_default(),
-        description: asset.description,
-        scan_location_id: asset.scan_location_id,
-        is_favorite: asset.is_favorite,
-        last_accessed: asset.last_accessed,
-        file_hash: asset.file_hash,
-        metadata: asset.metadata,
-        orbital_title: asset.orbital_title,
-        orbital_description: asset.orbital_description,
-        orbital_technical_details: asset.orbital_technical_details,
-        orbital_seller_name: asset.orbital_seller_name,
-        orbital_price: asset.orbital_price,
-        orbital_release_date: asset.orbital_release_date,
-        orbital_last_modified: asset.orbital_last_modified,
-        orbital_rating_average: asset.orbital_rating_average,
-        orbital_rating_count: asset.orbital_rating_count,
-        orbital_categories: asset.orbital_categories,
-        orbital_supported_versions: asset.orbital_supported_versions,
-        orbital_gallery_images: asset.orbital_gallery_images,
-        orbital_thumbnail_url: asset.orbital_thumbnail_url,
-        orbital_source_url: asset.orbital_source_url,
-        orbital_raw_json: asset.orbital_raw_json,
-        orbital_last_checked_timestamp: asset.orbital_last_checked_timestamp,
-        matched_orbital_product_slug: asset.matched_orbital_product_slug,
-        orbital_match_confidence: asset.orbital_match_confidence,
-        orbital_match_type: asset.orbital_match_type,
-        notes: asset.notes,
-        orbital_manual_overrides: asset.orbital_manual_overrides,
-    };
-
-    Ok(asset_for_frontend)
+        .map_err(|e| format!("Failed to fetch asset details: {}", e))?
+        .ok_or_else(|| format!("Asset with id {} not found", id))
 }
 
 #[tauri::command]
@@ -262,39 +275,12 @@ pub async fn search_assets(
     query: String,
     limit: u32,
     offset: u32,
+    db_manager_state: State<'_, DatabaseManager>,
 ) -> Result<Vec<AssetCardData>, String> {
-    let db = get_db();
-    
-    let search_query = format!("%{}%", query);
-    
-    let assets: Vec<AssetCardData> = sqlx::query_as(
-        r#"
-        SELECT id, name, asset_type, file_size, thumbnail_path, orbital_thumbnail_url, orbital_rating_average, orbital_rating_count, is_favorite, created_date 
-        FROM assets 
-        WHERE name LIKE ? 
-           OR description LIKE ? 
-           OR tags LIKE ?
-           OR orbital_title LIKE ?
-           OR orbital_description LIKE ?
-        ORDER BY 
-            CASE WHEN name LIKE ? THEN 1 ELSE 2 END,
-            name ASC
-        LIMIT ? OFFSET ?
-        "#
-    )
-    .bind(&search_query)
-    .bind(&search_query)
-    .bind(&search_query)
-    .bind(&search_query)
-    .bind(&search_query)
-    .bind(&search_query)
-    .bind(limit)
-    .bind(offset)
-    .fetch_all(db)
-    .await
-    .map_err(|e| format!("Failed to search assets: {}", e))?;
-
-    Ok(assets)
+    db_manager_state
+        .search_assets(&query, limit, offset)
+        .await
+        .map_err(|e| format!("Failed to search assets: {}", e))
 }
 
  
\ No newline at end of file
@@ -0,0 +1,76 @@
+use std::path::Path;
+use tauri::State;
+
+use crate::database::DatabaseManager;
+use crate::models::{AssetProjectUsage, ProjectAssetMatch};
+use crate::unreal::import::{self, ImportMode, ProjectImportReport};
+
+/// Imports an indexed asset pack into an Unreal project
+///
+/// # Arguments
+/// * `asset_id` - ID of the asset to import
+/// * `uproject_path` - Path of the target project's `.uproject` file
+/// * `mode` - `copy` (default) or `hard_link`
+/// * `dry_run` - When true nothing is written; the report lists files and conflicts
+/// * `overwrite` - Replace files that already exist in the project
+///
+/// # Returns
+/// * `Ok(ProjectImportReport)` - Files involved, conflicting package paths and what was written
+/// * `Err(String)` - Error message if the asset or project is invalid or copying failed
+///
+/// # Behavior
+/// - Packs with a `.uplugin` go to `Plugins/<Name>/`, others merge their `Content/` into the project's
+/// - If any destination already exists and `overwrite` is not set, nothing is written
+/// - A successful import is recorded in the asset's "used in" list
+#[tauri::command]
+pub async fn import_asset_into_project(
+    asset_id: i64,
+    uproject_path: String,
+    mode: Option<ImportMode>,
+    dry_run: bool,
+    overwrite: Option<bool>,
+    db_manager_state: State<'_, DatabaseManager>,
+) -> Result<ProjectImportReport, String> {
+    import::import_asset(
+        &db_manager_state,
+        asset_id,
+        Path::new(&uproject_path),
+        mode.unwrap_or(ImportMode::Copy),
+        dry_run,
+        overwrite.unwrap_or(false),
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Lists the Unreal projects an asset has been imported into
+#[tauri::command]
+pub async fn get_asset_projects(
+    asset_id: i64,
+    db_manager_state: State<'_, DatabaseManager>,
+) -> Result<Vec<AssetProjectUsage>, String> {
+    db_manager_state
+        .get_asset_project_usage(asset_id)
+        .await
+        .map_err(|e| format!("Failed to fetch project usage: {}", e))
+}
+
+/// Lists the library assets found in an indexed Unreal project location
+///
+/// # Arguments
+/// * `location_id` - ID of a scan location of kind `project`
+///
+/// # Returns
+/// * `Ok(Vec<ProjectAssetMatch>)` - One entry per library asset with packages in the project;
+///   `status` is `complete`, `modified` (some packages differ or were moved) or `partial`
+/// * `Err(String)` - Error message if the lookup fails
+#[tauri::command]
+pub async fn get_project_assets(
+    location_id: i64,
+    db_manager_state: State<'_, DatabaseManager>,
+) -> Result<Vec<ProjectAssetMatch>, String> {
+    db_manager_state
+        .get_project_asset_matches(location_id)
+        .await
+        .map_err(|e| format!("Failed to fetch project assets: {}", e))
+}
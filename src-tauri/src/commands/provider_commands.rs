@@ -0,0 +1,109 @@
+use std::sync::Arc;
+use tauri::State;
+
+use crate::database::DatabaseManager;
+use crate::orbital::OrbitalApiClient;
+use crate::providers::{self, MergedMetadata, MetadataProvider, ProviderOutcome, ProviderRecord};
+use crate::settings::SettingsService;
+
+fn metadata_providers(orbital_client: &OrbitalApiClient) -> Result<Vec<Box<dyn MetadataProvider>>, String> {
+    providers::default_providers(Arc::new(orbital_client.clone()))
+        .map_err(|e| format!("Failed to create metadata providers: {}", e))
+}
+
+/// Fetches an asset's product from every metadata provider
+///
+/// # Arguments
+/// * `asset_id` - ID of the asset
+///
+/// # Returns
+/// * `Ok(Vec<ProviderOutcome>)` - Whether each provider fetched, found nothing or failed
+/// * `Err(String)` - Error message if the asset does not exist
+///
+/// # Behavior
+/// - Providers run in turn: the asset's manifest, Orbital, Fab, then linked web pages
+/// - Each provider fetches the product it was linked to, else the one another provider's result
+///   links to, else the one it matched before, and only then searches by the asset's name
+/// - Each result replaces the provider's previous result for the asset
+/// - A provider that fails is reported and the others still run
+#[tauri::command]
+pub async fn refresh_asset_metadata(
+    asset_id: i64,
+    db_manager_state: State<'_, DatabaseManager>,
+    orbital_client: State<'_, OrbitalApiClient>,
+) -> Result<Vec<ProviderOutcome>, String> {
+    let providers = metadata_providers(&orbital_client)?;
+    providers::refresh_asset(&db_manager_state, &providers, asset_id)
+        .await
+        .map_err(|e| format!("Failed to refresh metadata: {}", e))
+}
+
+/// Links an asset to a product at one metadata provider
+///
+/// # Arguments
+/// * `asset_id` - ID of the asset
+/// * `provider` - Provider id: `manifest`, `orbital`, `fab` or `web`
+/// * `reference` - The product's ID, URL or, for `manifest`, file path
+///
+/// # Returns
+/// * `Ok(ProviderRecord)` - The stored result, with full confidence
+/// * `Err(String)` - Error message if the provider is unknown or the product cannot be fetched
+///
+/// # Behavior
+/// - Later refreshes keep fetching this product instead of searching
+#[tauri::command]
+pub async fn set_asset_provider_reference(
+    asset_id: i64,
+    provider: String,
+    reference: String,
+    db_manager_state: State<'_, DatabaseManager>,
+    orbital_client: State<'_, OrbitalApiClient>,
+) -> Result<ProviderRecord, String> {
+    let providers = metadata_providers(&orbital_client)?;
+    let provider = providers::find_provider(&providers, &provider).map_err(|e| e.to_string())?;
+    providers::link_asset(&db_manager_state, provider, asset_id, &reference)
+        .await
+        .map_err(|e| format!("Failed to link asset to {}: {}", provider.id(), e))
+}
+
+/// Lists every metadata provider's stored result for an asset
+///
+/// # Arguments
+/// * `asset_id` - ID of the asset
+///
+/// # Returns
+/// * `Ok(Vec<ProviderRecord>)` - Each result with its reference, how it was matched and when it was fetched
+#[tauri::command]
+pub async fn get_asset_provider_metadata(
+    asset_id: i64,
+    db_manager_state: State<'_, DatabaseManager>,
+) -> Result<Vec<ProviderRecord>, String> {
+    providers::get_provider_records(&db_manager_state, asset_id)
+        .await
+        .map_err(|e| format!("Failed to get provider metadata: {}", e))
+}
+
+/// Merges an asset's metadata over its providers
+///
+/// # Arguments
+/// * `asset_id` - ID of the asset
+///
+/// # Returns
+/// * `Ok(MergedMetadata)` - The merged metadata and the provider each field came from
+/// * `Err(String)` - Error message if the asset does not exist
+///
+/// # Behavior
+/// - Each field takes the value of the first provider that has one, in the order of the
+///   `metadata_provider_order` setting
+/// - The Orbital details the scanner matched stand in for Orbital until it is refreshed
+#[tauri::command]
+pub async fn get_merged_asset_metadata(
+    asset_id: i64,
+    db_manager_state: State<'_, DatabaseManager>,
+    settings_service: State<'_, SettingsService>,
+) -> Result<MergedMetadata, String> {
+    let order = settings_service.current().metadata_provider_order;
+    providers::merged_metadata(&db_manager_state, asset_id, &order)
+        .await
+        .map_err(|e| format!("Failed to merge metadata: {}", e))
+}
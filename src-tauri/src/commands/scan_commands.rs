@@ -1,12 +1,29 @@
-use crate::database::{get_db, DatabaseManager};
-use crate::models::scan_location::ScanLocation;
+use crate::categories::CategoryEngine;
+use crate::database::{get_db, relative_asset_path, resolve_asset_path, DatabaseManager};
+use crate::models::scan_location::{
+    AssetRootPreview, AssetRootRules, PreviewRoot, RelocationReport, ScanLocation, LOCATION_KIND_PROJECT,
+    LOCATION_KIND_MEDIA, LOCATION_KIND_VAULT,
+};
+use crate::models::{ScanErrorFilter, ScanErrorRecord, ScanJob, ScanRetryReport, ScanRun};
+use crate::scanner::inventory::inventory_location;
+use crate::scanner::roots::RootSelector;
 use crate::orbital::OrbitalApiClient;
-use crate::scanner::{get_scanner_manager, AssetScanner};
-use std::sync::atomic::AtomicBool;
+use crate::scanner::watcher::{start_watching_location, stop_watching_location};
+use crate::scanner::{get_scanner_manager, AssetScanner, ScanControl};
+use crate::unreal::find_uproject;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tauri::{AppHandle, State};
+use crate::settings::SettingsService;
+use tauri::{AppHandle, Manager, State};
 use tracing::{error, info, warn};
 
+/// How many assets are checked under the new root before a relocation is committed.
+const RELOCATION_SAMPLE_SIZE: i64 = 20;
+
+/// Scan runs returned by `get_scan_history` unless a limit is given.
+const DEFAULT_SCAN_HISTORY_LIMIT: i64 = 50;
+
 /// Creates a new scan location in the database
 /// 
 /// # Arguments
@@ -15,12 +32,16 @@ use tracing::{error, info, warn};
 /// * `scan_recursive` - Whether to scan subdirectories recursively
 /// * `file_extensions` - Optional comma-separated list of file extensions to scan (e.g., "uasset,umap")
 /// * `description` - Optional description of the scan location
+/// * `location_kind` - `vault` (default) for asset packs, `project` for an Unreal project folder,
+///   or `media` to index loose textures, HDRIs, audio and meshes as individual assets
 /// 
 /// # Returns
 /// * `Ok(ScanLocation)` - The created scan location with assigned ID
 /// * `Err(String)` - Error message if creation fails
 /// 
 /// # Behavior
+/// - Project locations must contain a `.uproject` file
+/// - For media locations, `file_extensions` narrows which supported formats are indexed
 /// - Automatically sets `is_active` to true for new locations
 /// - Records creation timestamp in RFC3339 format
 /// - Returns the complete scan location record including assigned ID
@@ -31,28 +52,24 @@ pub async fn create_scan_location(
     scan_recursive: bool,
     file_extensions: Option<String>,
     description: Option<String>,
+    location_kind: Option<String>,
+    db_manager_state: State<'_, DatabaseManager>,
 ) -> Result<ScanLocation, String> {
-    let db = get_db();
-    let created_date = chrono::Utc::now().to_rfc3339();
+    let location_kind = location_kind.unwrap_or_else(|| LOCATION_KIND_VAULT.to_string());
+    match location_kind.as_str() {
+        LOCATION_KIND_VAULT | LOCATION_KIND_MEDIA => {}
+        LOCATION_KIND_PROJECT => {
+            if find_uproject(Path::new(&path)).is_none() {
+                return Err(format!("No .uproject file found in {}", path));
+            }
+        }
+        other => return Err(format!("Unknown location kind: {}", other)),
+    }
     
-    let result = sqlx::query_as::<_, ScanLocation>(
-        r#"
-        INSERT INTO scan_locations (name, path, is_active, scan_recursive, file_extensions, created_date, description)
-        VALUES (?, ?, 1, ?, ?, ?, ?)
-        RETURNING *
-        "#,
-    )
-    .bind(&name)
-    .bind(&path)
-    .bind(scan_recursive)
-    .bind(&file_extensions)
-    .bind(&created_date)
-    .bind(&description)
-    .fetch_one(db)
-    .await
-    .map_err(|e| format!("Failed to create scan location: {}", e))?;
-
-    Ok(result)
+    db_manager_state
+        .create_scan_location(&name, &path, scan_recursive, file_extensions.as_deref(), description.as_deref(), &location_kind)
+        .await
+        .map_err(|e| format!("Failed to create scan location: {}", e))
 }
 
 #[tauri::command]
@@ -67,7 +84,23 @@ pub async fn get_scan_locations() -> Result<Vec<ScanLocation>, String> {
     Ok(locations)
 }
 
+/// Updates the settings of a scan location
+///
+/// # Arguments
+/// * `id` - ID of the scan location
+/// * `name`, `path`, `is_active`, `scan_recursive`, `file_extensions`, `description` - The new values
+///
+/// # Returns
+/// * `Ok(ScanLocation)` - The updated scan location
+/// * `Err(String)` - Error message if the location does not exist or its path cannot be changed
+///
+/// # Behavior
+/// - A new path is checked like `relocate_scan_location` does: if any sampled asset is missing
+///   under it, nothing is changed and the missing assets are reported in the error
+/// - The new path, the rewritten asset paths and the other settings are saved in one transaction
+/// - A watched location's watcher is restarted while it is active and stopped while it is not
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn update_scan_location(
     id: i64,
     name: String,
@@ -76,27 +109,55 @@ pub async fn update_scan_location(
     scan_recursive: bool,
     file_extensions: Option<String>,
     description: Option<String>,
+    app_handle: AppHandle,
+    db_manager_state: State<'_, DatabaseManager>,
 ) -> Result<ScanLocation, String> {
-    let db = get_db();
-    
-    let result = sqlx::query_as::<_, ScanLocation>(
-        r#"
-        UPDATE scan_locations 
-        SET name = ?, path = ?, is_active = ?, scan_recursive = ?, file_extensions = ?, description = ?
-        WHERE id = ?
-        RETURNING *
-        "#,
-    )
-    .bind(&name)
-    .bind(&path)
-    .bind(is_active)
-    .bind(scan_recursive)
-    .bind(&file_extensions)
-    .bind(&description)
-    .bind(id)
-    .fetch_one(db)
-    .await
-    .map_err(|e| format!("Failed to update scan location: {}", e))?;
+    let previous = db_manager_state
+        .get_scan_location_by_id(id)
+        .await
+        .map_err(|e| format!("Failed to fetch scan location: {}", e))?
+        .ok_or_else(|| format!("Scan location {} not found", id))?;
+
+    // Asset paths are stored relative to the location, so a new root is only taken once
+    // the location's assets are found under it.
+    if previous.path != path {
+        let report = check_relocation(id, &previous, &path, db_manager_state.inner()).await?;
+        if !report.missing.is_empty() {
+            return Err(format!(
+                "{} of {} sampled assets are missing under {} (e.g. {}); use Relocate to move the location anyway",
+                report.missing.len(),
+                report.sampled,
+                path,
+                report.missing[0]
+            ));
+        }
+        // The old watcher points at the old root.
+        stop_watching_location(id);
+    }
+
+    let (result, assets_updated) = db_manager_state
+        .update_scan_location(
+            id,
+            &name,
+            &path,
+            is_active,
+            scan_recursive,
+            file_extensions.as_deref(),
+            description.as_deref(),
+        )
+        .await
+        .map_err(|e| format!("Failed to update scan location: {}", e))?;
+    if previous.path != path {
+        info!("Relocated scan location {} from {} to {} ({} assets)", id, previous.path, path, assets_updated);
+    }
+
+    // The watcher follows the active flag and maps changes based on the recursive flag.
+    if result.watch_enabled && result.is_active {
+        start_watching_location(&result, db_manager_state.inner().clone(), app_handle)
+            .map_err(|e| format!("Failed to restart watcher: {}", e))?;
+    } else {
+        stop_watching_location(id);
+    }
 
     Ok(result)
 }
@@ -111,13 +172,17 @@ pub async fn delete_scan_location(id: i64) -> Result<(), String> {
         .await
         .map_err(|e| format!("Failed to delete scan location: {}", e))?;
 
+    stop_watching_location(id);
+
     Ok(())
 }
 
 #[tauri::command]
 pub async fn update_scan_recursive_setting(
     location_id: i64,
-    scan_recursive: bool
+    scan_recursive: bool,
+    app_handle: AppHandle,
+    db_manager_state: State<'_, DatabaseManager>,
 ) -> Result<ScanLocation, String> {
     let db = get_db();
     
@@ -135,9 +200,311 @@ pub async fn update_scan_recursive_setting(
     .await
     .map_err(|e| format!("Failed to update scan recursive setting: {}", e))?;
 
+    // The watcher maps changes to asset folders based on this flag, so restart it.
+    if result.watch_enabled && result.is_active {
+        start_watching_location(&result, db_manager_state.inner().clone(), app_handle)
+            .map_err(|e| format!("Failed to restart watcher: {}", e))?;
+    }
+
     Ok(result)
 }
 
+/// Turns live filesystem watching on or off for a scan location
+///
+/// # Arguments
+/// * `location_id` - ID of the scan location
+/// * `watch_enabled` - Whether changes under the location should be picked up automatically
+///
+/// # Returns
+/// * `Ok(ScanLocation)` - The updated scan location
+/// * `Err(String)` - Error message if the setting or the watcher could not be changed
+///
+/// # Behavior
+/// - The setting is persisted, so watchers are restored on the next start
+/// - While watching, added, changed and removed asset folders are synced and a
+///   `library-changed` event is emitted with the affected asset IDs
+#[tauri::command]
+pub async fn set_scan_location_watching(
+    location_id: i64,
+    watch_enabled: bool,
+    app_handle: AppHandle,
+    db_manager_state: State<'_, DatabaseManager>,
+) -> Result<ScanLocation, String> {
+    let db = get_db();
+
+    let is_project = db_manager_state
+        .get_scan_location_by_id(location_id)
+        .await
+        .map_err(|e| format!("Failed to fetch scan location: {}", e))?
+        .is_some_and(|location| location.is_project());
+    if watch_enabled && is_project {
+        return Err("Watching is only available for asset vaults, not Unreal projects.".to_string());
+    }
+
+    let result = sqlx::query_as::<_, ScanLocation>(
+        r#"
+        UPDATE scan_locations
+        SET watch_enabled = ?
+        WHERE id = ?
+        RETURNING *
+        "#,
+    )
+    .bind(watch_enabled)
+    .bind(location_id)
+    .fetch_one(db)
+    .await
+    .map_err(|e| format!("Failed to update watch setting: {}", e))?;
+
+    if watch_enabled && result.is_active {
+        start_watching_location(&result, db_manager_state.inner().clone(), app_handle)
+            .map_err(|e| format!("Failed to start watcher: {}", e))?;
+    } else {
+        stop_watching_location(location_id);
+    }
+
+    Ok(result)
+}
+
+/// Sets the rules that decide which folders of a scan location become assets
+///
+/// # Arguments
+/// * `location_id` - ID of the scan location
+/// * `rules` - The asset-root rules, or `None` to go back to the `scan_recursive` behaviour
+///
+/// # Returns
+/// * `Ok(ScanLocation)` - The updated scan location
+/// * `Err(String)` - Error message if a glob is invalid or the location could not be updated
+///
+/// # Behavior
+/// - Takes effect on the next scan; use `preview_asset_roots` to check the rules first
+/// - A running watcher is restarted so it maps changes onto the new roots
+#[tauri::command]
+pub async fn set_scan_location_root_rules(
+    location_id: i64,
+    rules: Option<AssetRootRules>,
+    app_handle: AppHandle,
+    db_manager_state: State<'_, DatabaseManager>,
+) -> Result<ScanLocation, String> {
+    let db = get_db();
+
+    let rules_json = match rules.filter(|rules| *rules != AssetRootRules::default()) {
+        Some(rules) => {
+            RootSelector::new(&rules, false).map_err(|e| e.to_string())?;
+            Some(serde_json::to_string(&rules).map_err(|e| format!("Failed to serialize rules: {}", e))?)
+        }
+        None => None,
+    };
+
+    let result = sqlx::query_as::<_, ScanLocation>(
+        r#"
+        UPDATE scan_locations
+        SET root_rules = ?
+        WHERE id = ?
+        RETURNING *
+        "#,
+    )
+    .bind(&rules_json)
+    .bind(location_id)
+    .fetch_one(db)
+    .await
+    .map_err(|e| format!("Failed to update asset-root rules: {}", e))?;
+
+    if result.watch_enabled && result.is_active {
+        start_watching_location(&result, db_manager_state.inner().clone(), app_handle)
+            .map_err(|e| format!("Failed to restart watcher: {}", e))?;
+    }
+
+    Ok(result)
+}
+
+/// Shows which folders a scan would index with a given rule set, without changing anything
+///
+/// # Arguments
+/// * `path` - Folder to evaluate, usually the path of a scan location
+/// * `scan_recursive` - Behaviour to preview when `rules` has no root rules
+/// * `rules` - The asset-root rules to try
+///
+/// # Returns
+/// * `Ok(AssetRootPreview)` - The asset roots with their size, and indexed assets the rules would not select
+/// * `Err(String)` - Error message if the folder does not exist or a glob is invalid
+#[tauri::command]
+pub async fn preview_asset_roots(
+    path: String,
+    scan_recursive: bool,
+    rules: Option<AssetRootRules>,
+    db_manager_state: State<'_, DatabaseManager>,
+) -> Result<AssetRootPreview, String> {
+    let root = PathBuf::from(&path);
+    if !root.is_dir() {
+        return Err(format!("Folder does not exist: {}", path));
+    }
+    let selector = RootSelector::new(&rules.unwrap_or_default(), scan_recursive).map_err(|e| e.to_string())?;
+
+    let walk_root = root.clone();
+    let location_inventory = tauri::async_runtime::spawn_blocking(move || inventory_location(&walk_root, &selector))
+        .await
+        .map_err(|e| format!("Failed to walk {}: {}", path, e))?;
+
+    let indexed: HashSet<String> = db_manager_state
+        .get_asset_paths_under(&path)
+        .await
+        .map_err(|e| format!("Failed to fetch indexed assets: {}", e))?
+        .into_iter()
+        .collect();
+
+    let roots: Vec<PreviewRoot> = location_inventory
+        .folders
+        .iter()
+        .map(|folder| {
+            let totals = location_inventory.inventories.get(folder).map(|i| i.totals).unwrap_or_default();
+            PreviewRoot {
+                relative_path: relative_asset_path(&root, folder),
+                file_count: totals.file_count,
+                total_bytes: totals.total_bytes,
+                indexed: indexed.contains(folder.to_string_lossy().as_ref()),
+            }
+        })
+        .collect();
+
+    let selected: HashSet<String> = location_inventory
+        .folders
+        .iter()
+        .map(|folder| folder.to_string_lossy().into_owned())
+        .collect();
+    let mut not_selected: Vec<String> = indexed
+        .iter()
+        .filter(|asset_path| !selected.contains(*asset_path) && Path::new(asset_path.as_str()) != root)
+        .map(|asset_path| relative_asset_path(&root, Path::new(asset_path)))
+        .collect();
+    not_selected.sort();
+
+    Ok(AssetRootPreview {
+        path,
+        roots,
+        not_selected,
+        walk_errors: location_inventory.errors,
+    })
+}
+
+/// Re-points a scan location at a new root, e.g. after a drive letter or mount point changed
+///
+/// # Arguments
+/// * `location_id` - ID of the scan location to move
+/// * `new_path` - The folder that now contains the location's assets
+/// * `force` - Commit even if some sampled assets are missing under the new root
+///
+/// # Returns
+/// * `Ok(RelocationReport)` - What was checked and whether the change was committed
+/// * `Err(String)` - Error message if the location or new folder is invalid
+///
+/// # Behavior
+/// - Checks up to 20 random assets for existence under `new_path` before changing anything
+/// - If any are missing and `force` is not set, nothing is written and `committed` is false
+/// - Otherwise the location path and every asset path are rewritten in one transaction
+/// - Refuses while the location is being scanned; restarts its watcher afterwards
+#[tauri::command]
+pub async fn relocate_scan_location(
+    location_id: i64,
+    new_path: String,
+    force: Option<bool>,
+    app_handle: AppHandle,
+    db_manager_state: State<'_, DatabaseManager>,
+) -> Result<RelocationReport, String> {
+    let location = db_manager_state
+        .get_scan_location_by_id(location_id)
+        .await
+        .map_err(|e| format!("Failed to fetch scan location: {}", e))?
+        .ok_or_else(|| format!("Scan location {} not found", location_id))?;
+
+    relocate_verified(location_id, location, new_path, force.unwrap_or(false), app_handle, db_manager_state.inner()).await
+}
+
+/// Checks that a sample of `location`'s assets exists under `new_path`, without writing anything.
+/// The returned report lists the missing assets and is not yet committed.
+async fn check_relocation(
+    location_id: i64,
+    location: &ScanLocation,
+    new_path: &str,
+    db_manager: &DatabaseManager,
+) -> Result<RelocationReport, String> {
+    let new_root = Path::new(new_path);
+    if !new_root.is_dir() {
+        return Err(format!("New location is not a folder: {}", new_path));
+    }
+
+    if let Some(manager) = get_scanner_manager().lock().unwrap().as_ref() {
+        if manager.is_scan_active(location_id) {
+            return Err("Cannot relocate a scan location while it is being scanned.".to_string());
+        }
+    }
+
+    let sample = db_manager
+        .sample_asset_relative_paths(location_id, RELOCATION_SAMPLE_SIZE)
+        .await
+        .map_err(|e| format!("Failed to sample assets: {}", e))?;
+    let missing: Vec<String> = sample
+        .iter()
+        .filter(|relative| !resolve_asset_path(new_root, relative).exists())
+        .cloned()
+        .collect();
+
+    Ok(RelocationReport {
+        location_id,
+        old_path: location.path.clone(),
+        new_path: new_path.to_string(),
+        sampled: sample.len(),
+        missing,
+        committed: false,
+        assets_updated: 0,
+    })
+}
+
+/// Moves `location` to `new_path` after checking a sample of its assets exists there.
+/// Nothing is written when any sampled asset is missing, unless `force` is set.
+async fn relocate_verified(
+    location_id: i64,
+    location: ScanLocation,
+    new_path: String,
+    force: bool,
+    app_handle: AppHandle,
+    db_manager: &DatabaseManager,
+) -> Result<RelocationReport, String> {
+    let mut report = check_relocation(location_id, &location, &new_path, db_manager).await?;
+
+    if !report.missing.is_empty() && !force {
+        warn!(
+            "Not relocating {}: {} of {} sampled assets are missing under {}",
+            location.path,
+            report.missing.len(),
+            report.sampled,
+            new_path
+        );
+        return Ok(report);
+    }
+
+    // The old watcher points at the old root.
+    stop_watching_location(location_id);
+
+    report.assets_updated = db_manager
+        .relocate_scan_location(location_id, Path::new(&new_path))
+        .await
+        .map_err(|e| format!("Failed to relocate scan location: {}", e))?;
+    report.committed = true;
+    info!("Relocated scan location {} from {} to {} ({} assets)", location_id, location.path, new_path, report.assets_updated);
+
+    if location.watch_enabled && location.is_active {
+        let relocated = ScanLocation {
+            path: new_path,
+            ..location
+        };
+        if let Err(e) = start_watching_location(&relocated, db_manager.clone(), app_handle) {
+            warn!("Failed to restart watcher after relocation: {}", e);
+        }
+    }
+
+    Ok(report)
+}
+
 #[tauri::command]
 pub async fn clear_assets_from_scan_location(location_id: i64) -> Result<i64, String> {
     let db = get_db();
@@ -156,66 +523,22 @@ pub async fn scan_all_locations(
     app_handle: AppHandle,
     db_manager_state: State<'_, DatabaseManager>,
 ) -> Result<(), String> {
-    let db = get_db();
-    let locations = sqlx::query_as::<_, ScanLocation>("SELECT * FROM scan_locations WHERE is_active = 1")
-        .fetch_all(db)
+    let locations = db_manager_state
+        .get_active_scan_locations()
         .await
         .map_err(|e| format!("Failed to fetch scan locations: {}", e))?;
 
+    if get_scanner_manager().lock().unwrap().is_none() {
+        error!("ScannerManager is not initialized.");
+        return Err("ScannerManager is not initialized.".to_string());
+    }
+
     for location in locations {
         if let Some(location_id) = location.id {
-            let mut scanner_manager_guard = get_scanner_manager().lock().unwrap();
-            if let Some(manager) = scanner_manager_guard.as_mut() {
-                if manager.is_scan_active(location_id) {
-                    warn!(
-                        "Scan is already in progress for location ID: {}",
-                        location_id
-                    );
-                    continue; // Skip to the next location
-                }
-            } else {
-                error!("ScannerManager is not initialized.");
-                return Err("ScannerManager is not initialized.".to_string());
+            // Locations that are already being scanned are skipped
+            if let Err(e) = spawn_location_scan(location, false, &app_handle, db_manager_state.inner().clone()) {
+                warn!("Skipping scan of location ID {}: {}", location_id, e);
             }
-
-            let cancel_token = Arc::new(AtomicBool::new(false));
-            if let Some(manager) = scanner_manager_guard.as_mut() {
-                manager.register_scanner(location_id, cancel_token.clone());
-            }
-
-            // Drop the lock before starting the async task
-            drop(scanner_manager_guard);
-
-            let app_handle_clone = app_handle.clone();
-            let db_manager = db_manager_state.inner().clone();
-
-            tauri::async_runtime::spawn(async move {
-                let orbital_api_client = OrbitalApiClient::new()
-                    .map_err(|e| format!("Failed to create Orbital API client: {}", e));
-
-                if let Err(e) = orbital_api_client {
-                    error!("{}", e);
-                    // Optionally emit an error event to the frontend here
-                    return;
-                }
-
-                let asset_scanner = AssetScanner::new(
-                    db_manager,
-                    orbital_api_client.unwrap(),
-                    cancel_token.clone(),
-                );
-
-                info!("Starting scan for location: {}", location.name);
-                match asset_scanner.scan_directory_and_process_folders(&location, &app_handle_clone).await {
-                    Ok(_) => info!("Scan completed for location: {}", location.name),
-                    Err(e) => error!("Scan failed for location: {}: {}", location.name, e),
-                }
-
-                // Clean up after scan completion or failure
-                if let Some(manager) = get_scanner_manager().lock().unwrap().as_mut() {
-                    manager.remove_scanner(location_id);
-                }
-            });
         }
     }
 
@@ -237,61 +560,95 @@ pub async fn start_scan(
         .await
         .map_err(|e| format!("Failed to fetch scan location: {}", e))?;
 
-    // Check if a scan is already in progress for this location
-    let mut scanner_manager_guard = get_scanner_manager().lock().unwrap();
-    if let Some(manager) = scanner_manager_guard.as_mut() {
+    spawn_location_scan(location, false, &app_handle, db_manager_state.inner().clone())
+}
+
+/// Continues the paused or interrupted scan of a location from its last checkpoint
+/// 
+/// # Arguments
+/// * `location_id` - ID of the scan location whose scan job should resume
+/// 
+/// # Returns
+/// * `Ok(())` - The scan was started in the background
+/// * `Err(String)` - Error message if there is nothing to resume or a scan is already running
+/// 
+/// # Behavior
+/// - Skips the folders, archives and files written before the checkpoint
+/// - Starts from the beginning if the checkpoint folder no longer exists
+/// - Progress is reported through `scan-progress` events as for `start_scan`
+#[tauri::command]
+pub async fn resume_scan(
+    location_id: i64,
+    app_handle: AppHandle,
+    db_manager_state: State<'_, DatabaseManager>,
+) -> Result<(), String> {
+    let db_manager = db_manager_state.inner().clone();
+    if db_manager
+        .get_resumable_scan_job(location_id)
+        .await
+        .map_err(|e| format!("Failed to fetch scan job: {}", e))?
+        .is_none()
+    {
+        return Err("There is no paused or interrupted scan for this location.".to_string());
+    }
+    let location = db_manager
+        .get_scan_location_by_id(location_id)
+        .await
+        .map_err(|e| format!("Failed to fetch scan location: {}", e))?
+        .ok_or_else(|| format!("Scan location {} not found", location_id))?;
+
+    spawn_location_scan(location, true, &app_handle, db_manager)
+}
+
+/// Register a scan of `location` and run it in the background.
+fn spawn_location_scan(
+    location: ScanLocation,
+    resume: bool,
+    app_handle: &AppHandle,
+    db_manager: DatabaseManager,
+) -> Result<(), String> {
+    let location_id = location.id.ok_or("Scan location has no ID.")?;
+
+    // Check if a scan is already in progress for this location, then register it
+    let control = ScanControl::new();
+    {
+        let mut scanner_manager_guard = get_scanner_manager().lock().unwrap();
+        let manager = scanner_manager_guard
+            .as_mut()
+            .ok_or_else(|| "ScannerManager is not initialized.".to_string())?;
         if manager.is_scan_active(location_id) {
             return Err("Scan is already in progress for this location.".to_string());
         }
-    } else {
-        return Err("ScannerManager is not initialized.".to_string());
+        manager.register_scanner(location_id, control.clone());
     }
 
-    // Create the cancel token and register the scanner
-    let cancel_token = Arc::new(AtomicBool::new(false));
-    if let Some(manager) = scanner_manager_guard.as_mut() {
-        manager.register_scanner(location_id, cancel_token.clone());
-    }
-
-    // Drop the lock before starting the async task
-    drop(scanner_manager_guard);
-
     let app_handle_clone = app_handle.clone();
-    let db_manager = db_manager_state.inner().clone();
 
     tauri::async_runtime::spawn(async move {
         let orbital_api_client = OrbitalApiClient::new()
             .map_err(|e| format!("Failed to create Orbital API client: {}", e));
 
-        if let Err(e) = orbital_api_client {
-            error!("{}", e);
-            // Optionally emit an error event to the frontend here
-            return;
-        }
+        match orbital_api_client {
+            Err(e) => error!("{}", e),
+            Ok(orbital_api_client) => {
+                let asset_scanner = AssetScanner::new(db_manager.clone(), orbital_api_client, control.cancel.clone())
+                    .with_pause_token(control.pause.clone())
+                    .with_resume(resume)
+                    .with_concurrency(scan_concurrency(&app_handle_clone))
+                    .with_categories(CategoryEngine::configured(&app_handle_clone))
+                    .with_progress(Arc::new(app_handle_clone.clone()));
 
-        let asset_scanner = AssetScanner::new(
-            db_manager,
-            orbital_api_client.unwrap(),
-            cancel_token.clone(),
-        );
-
-        info!("Starting scan for location: {}", location.name);
-        match asset_scanner.scan_directory_and_process_folders(&location, &app_handle_clone).await {
-            Ok(_) => {
-                info!("Scan completed for location: {}", location.name);
-                // Update the last scan timestamp for the location
-                let db = get_db();
-                let now_timestamp = chrono::Utc::now().to_rfc3339();
-                if let Err(e) = sqlx::query("UPDATE scan_locations SET last_scan = ? WHERE id = ?")
-                    .bind(now_timestamp)
-                    .bind(location_id)
-                    .execute(db)
-                    .await
-                {
-                    error!("Failed to update last scan timestamp: {}", e);
+                info!("Starting scan for location: {}", location.name);
+                match asset_scanner.scan_location(&location).await {
+                    Ok(_) => {
+                        info!("Scan completed for location: {}", location.name);
+                        if let Err(e) = db_manager.update_scan_location_last_scanned(location_id).await {
+                            error!("Failed to update last scan timestamp: {}", e);
+                        }
+                    }
+                    Err(e) => error!("Scan failed for location: {}: {}", location.name, e),
                 }
             }
-            Err(e) => error!("Scan failed for location: {}: {}", location.name, e),
         }
 
         // Clean up after scan completion or failure
@@ -318,6 +675,141 @@ pub async fn cancel_scan(location_id: i64) -> Result<bool, String> {
     }
 }
 
+/// Pauses the running scan of a location after its current batch
+/// 
+/// # Arguments
+/// * `location_id` - ID of the scan location whose scan should pause
+/// 
+/// # Returns
+/// * `Ok(true)` - The scan was asked to pause
+/// * `Ok(false)` - No scan was running for this location
+/// * `Err(String)` - Error message if the scanner manager is not initialized
+/// 
+/// # Behavior
+/// - The scan job keeps its checkpoint and shows up in `get_resumable_scans`
+/// - Emits a `scan-progress` event with status `Paused` once the scan has stopped
+#[tauri::command]
+pub async fn pause_scan(location_id: i64) -> Result<bool, String> {
+    let mut scanner_manager_guard = get_scanner_manager().lock().unwrap();
+    match scanner_manager_guard.as_mut() {
+        Some(manager) => Ok(manager.pause_scan(location_id)),
+        None => Err("ScannerManager is not initialized.".to_string()),
+    }
+}
+
+/// Lists the scan jobs that were paused or interrupted by closing the app
+/// 
+/// # Returns
+/// * `Ok(Vec<ScanJob>)` - Resumable jobs with their checkpoint and counts, most recent first
+/// * `Err(String)` - Error message if the jobs cannot be read
+#[tauri::command]
+pub async fn get_resumable_scans(db_manager_state: State<'_, DatabaseManager>) -> Result<Vec<ScanJob>, String> {
+    db_manager_state
+        .get_resumable_scan_jobs()
+        .await
+        .map_err(|e| format!("Failed to fetch resumable scans: {}", e))
+}
+
+/// Returns the scan history: one entry per run, with its duration, counts and errors
+/// 
+/// # Arguments
+/// * `location_id` - Optional scan location to limit the history to
+/// * `limit` - Maximum number of runs to return (default 50)
+/// 
+/// # Returns
+/// * `Ok(Vec<ScanRun>)` - Runs, most recent first
+/// * `Err(String)` - Error message if the history cannot be read
+/// 
+/// # Behavior
+/// - A paused scan that is resumed later shows up as two runs of the same job
+/// - Runs cut short by closing the app have the outcome `interrupted`
+#[tauri::command]
+pub async fn get_scan_history(
+    location_id: Option<i64>,
+    limit: Option<i64>,
+    db_manager_state: State<'_, DatabaseManager>,
+) -> Result<Vec<ScanRun>, String> {
+    db_manager_state
+        .get_scan_history(location_id, limit.unwrap_or(DEFAULT_SCAN_HISTORY_LIMIT).max(1))
+        .await
+        .map_err(|e| format!("Failed to fetch scan history: {}", e))
+}
+
+/// Lists the errors recorded by scans, newest first
+/// 
+/// # Arguments
+/// * `filter` - Which errors to return: by location, scan run, error kind or part of the path.
+///   Resolved errors are left out unless `include_resolved` is set
+/// 
+/// # Returns
+/// * `Ok(Vec<ScanErrorRecord>)` - Matching errors with their kind, path and message
+/// * `Err(String)` - Error message if the errors cannot be read
+#[tauri::command]
+pub async fn get_scan_errors(
+    filter: Option<ScanErrorFilter>,
+    db_manager_state: State<'_, DatabaseManager>,
+) -> Result<Vec<ScanErrorRecord>, String> {
+    db_manager_state
+        .get_scan_errors(&filter.unwrap_or_default())
+        .await
+        .map_err(|e| format!("Failed to fetch scan errors: {}", e))
+}
+
+/// Retries the folders, archives and files of a location that failed during earlier scans
+/// 
+/// # Arguments
+/// * `location_id` - ID of the scan location whose failed paths should be retried
+/// 
+/// # Returns
+/// * `Ok(ScanRetryReport)` - How many paths were retried, how many now succeed and the errors of the rest
+/// * `Err(String)` - Error message if the location is being scanned or the retry cannot start
+/// 
+/// # Behavior
+/// - Each failed path is read again and written to the database; failed marketplace lookups are repeated
+/// - Errors of paths that now succeed are marked resolved
+/// - Paths that are not valid UTF-8 are skipped, as they have to be renamed first
+#[tauri::command]
+pub async fn retry_failed_scan_paths(
+    location_id: i64,
+    app_handle: AppHandle,
+    db_manager_state: State<'_, DatabaseManager>,
+) -> Result<ScanRetryReport, String> {
+    let db_manager = db_manager_state.inner().clone();
+    let location = db_manager
+        .get_scan_location_by_id(location_id)
+        .await
+        .map_err(|e| format!("Failed to fetch scan location: {}", e))?
+        .ok_or_else(|| format!("Scan location {} not found", location_id))?;
+
+    // Hold the location like a scan, so no scan starts while paths are retried
+    let control = ScanControl::new();
+    {
+        let mut scanner_manager_guard = get_scanner_manager().lock().unwrap();
+        let manager = scanner_manager_guard
+            .as_mut()
+            .ok_or_else(|| "ScannerManager is not initialized.".to_string())?;
+        if manager.is_scan_active(location_id) {
+            return Err("Scan is already in progress for this location.".to_string());
+        }
+        manager.register_scanner(location_id, control.clone());
+    }
+
+    let result = match OrbitalApiClient::new() {
+        Ok(orbital_api_client) => AssetScanner::new(db_manager, orbital_api_client, control.cancel.clone())
+            .with_pause_token(control.pause.clone())
+            .with_categories(CategoryEngine::configured(&app_handle))
+            .retry_failed_paths(&location)
+            .await
+            .map_err(|e| format!("Failed to retry failed paths: {}", e)),
+        Err(e) => Err(format!("Failed to create Orbital API client: {}", e)),
+    };
+
+    if let Some(manager) = get_scanner_manager().lock().unwrap().as_mut() {
+        manager.remove_scanner(location_id);
+    }
+    result
+}
+
 /// Cancels all active scans
 /// 
 /// # Returns
@@ -337,4 +829,12 @@ pub async fn cancel_all_scans() -> Result<usize, String> {
     } else {
         Err("ScannerManager is not initialized.".to_string())
     }
-} 
\ No newline at end of file
+} 
+
+/// Number of folders a scan may process at once, from the settings.
+fn scan_concurrency(app_handle: &AppHandle) -> usize {
+    app_handle
+        .try_state::<SettingsService>()
+        .map(|settings| settings.current().scan_concurrency.max(1) as usize)
+        .unwrap_or(crate::scanner::DEFAULT_SCAN_CONCURRENCY)
+}
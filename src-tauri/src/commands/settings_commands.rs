@@ -0,0 +1,79 @@
+use tauri::{AppHandle, Emitter, State};
+
+use crate::models::AppSettings;
+use crate::settings::SettingsService;
+
+pub(crate) fn emit_settings_changed(app_handle: &AppHandle, settings: &AppSettings) {
+    if let Err(e) = app_handle.emit("settings-changed", settings) {
+        tracing::error!("Failed to emit settings-changed event: {}", e);
+    }
+}
+
+#[tauri::command]
+pub async fn get_settings(settings_service: State<'_, SettingsService>) -> Result<AppSettings, String> {
+    Ok(settings_service.current())
+}
+
+/// Replaces all settings at once
+///
+/// # Arguments
+/// * `settings` - The complete settings object
+///
+/// # Returns
+/// * `Ok(AppSettings)` - The stored settings
+/// * `Err(String)` - Validation or database error
+///
+/// # Behavior
+/// - Emits `settings-changed` with the new settings on success
+#[tauri::command]
+pub async fn update_settings(
+    settings: AppSettings,
+    app_handle: AppHandle,
+    settings_service: State<'_, SettingsService>,
+) -> Result<AppSettings, String> {
+    let updated = settings_service
+        .update(settings)
+        .await
+        .map_err(|e| format!("Failed to update settings: {}", e))?;
+    emit_settings_changed(&app_handle, &updated);
+    Ok(updated)
+}
+
+/// Changes a single setting
+///
+/// # Arguments
+/// * `key` - Setting name as it appears in `AppSettings` (e.g. `thumbnail_size`)
+/// * `value` - New value; must have the type of that field
+///
+/// # Returns
+/// * `Ok(AppSettings)` - All settings after the change
+/// * `Err(String)` - Unknown key, wrong type or a value that fails validation
+#[tauri::command]
+pub async fn set_setting(
+    key: String,
+    value: serde_json::Value,
+    app_handle: AppHandle,
+    settings_service: State<'_, SettingsService>,
+) -> Result<AppSettings, String> {
+    let updated = settings_service
+        .set(&key, value)
+        .await
+        .map_err(|e| format!("Failed to set setting: {}", e))?;
+    emit_settings_changed(&app_handle, &updated);
+    Ok(updated)
+}
+
+/// Restores a single setting, or all settings when `key` is omitted, to the default
+#[tauri::command]
+pub async fn reset_settings(
+    key: Option<String>,
+    app_handle: AppHandle,
+    settings_service: State<'_, SettingsService>,
+) -> Result<AppSettings, String> {
+    let updated = settings_service
+        .reset(key.as_deref())
+        .await
+        .map_err(|e| format!("Failed to reset settings: {}", e))?;
+    emit_settings_changed(&app_handle, &updated);
+    Ok(updated)
+}
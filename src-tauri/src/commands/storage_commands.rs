@@ -0,0 +1,83 @@
+use tauri::State;
+
+use crate::database::database_manager::{INVENTORY_BY_EXTENSION, INVENTORY_BY_FOLDER};
+use crate::database::DatabaseManager;
+use crate::models::{AssetSizeBreakdown, StorageOverview};
+
+/// Number of assets listed in the storage overview's "largest assets" section.
+const LARGEST_ASSETS_LIMIT: i64 = 20;
+
+/// Breaks down the disk usage of a single asset
+///
+/// # Arguments
+/// * `asset_id` - ID of the asset
+///
+/// # Returns
+/// * `Ok(AssetSizeBreakdown)` - File counts and bytes by extension and by top-level subfolder
+/// * `Err(String)` - Error message if the asset does not exist or the lookup fails
+///
+/// # Behavior
+/// - Uses the inventory recorded during the last scan; nothing is read from disk
+/// - Assets not scanned since inventories were introduced have empty groups until the next scan
+#[tauri::command]
+pub async fn get_asset_size_breakdown(
+    asset_id: i64,
+    db_manager_state: State<'_, DatabaseManager>,
+) -> Result<AssetSizeBreakdown, String> {
+    let asset = db_manager_state
+        .get_asset_by_id(asset_id)
+        .await
+        .map_err(|e| format!("Failed to fetch asset: {}", e))?;
+    let by_extension = db_manager_state
+        .get_asset_inventory(asset_id, INVENTORY_BY_EXTENSION)
+        .await
+        .map_err(|e| format!("Failed to fetch asset inventory: {}", e))?;
+    let by_folder = db_manager_state
+        .get_asset_inventory(asset_id, INVENTORY_BY_FOLDER)
+        .await
+        .map_err(|e| format!("Failed to fetch asset inventory: {}", e))?;
+
+    Ok(AssetSizeBreakdown {
+        asset_id,
+        name: asset.name,
+        total_files: by_extension.iter().map(|entry| entry.file_count).sum(),
+        total_bytes: asset.file_size,
+        by_extension,
+        by_folder,
+    })
+}
+
+/// Summarises disk usage across the library for the storage dashboard
+///
+/// # Returns
+/// * `Ok(StorageOverview)` - Totals by extension and scan location, plus the largest assets
+/// * `Err(String)` - Error message if a query fails
+///
+/// # Behavior
+/// - Only assets directly below their scan location are counted, so folders indexed by
+///   recursive scans do not count their files more than once
+#[tauri::command]
+pub async fn get_storage_overview(
+    db_manager_state: State<'_, DatabaseManager>,
+) -> Result<StorageOverview, String> {
+    let by_extension = db_manager_state
+        .get_library_inventory_by_extension()
+        .await
+        .map_err(|e| format!("Failed to fetch library inventory: {}", e))?;
+    let by_location = db_manager_state
+        .get_location_storage()
+        .await
+        .map_err(|e| format!("Failed to fetch location storage: {}", e))?;
+    let largest_assets = db_manager_state
+        .get_largest_assets(LARGEST_ASSETS_LIMIT)
+        .await
+        .map_err(|e| format!("Failed to fetch largest assets: {}", e))?;
+
+    Ok(StorageOverview {
+        total_files: by_extension.iter().map(|entry| entry.file_count).sum(),
+        total_bytes: by_location.iter().map(|location| location.total_bytes).sum(),
+        by_extension,
+        by_location,
+        largest_assets,
+    })
+}
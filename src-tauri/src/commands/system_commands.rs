@@ -1,33 +1,15 @@
 use crate::database::{get_db, DatabaseManager};
 use crate::models::asset::AssetStats;
 use std::path::Path;
-use tauri::{AppHandle, State, Emitter};
-
-#[cfg(target_os = "linux")]
-use std::path::PathBuf;
+use crate::settings::SettingsService;
+use tauri::{AppHandle, State, Emitter, Manager};
 
 #[tauri::command]
-pub async fn get_asset_stats() -> Result<AssetStats, String> {
-    let db = get_db();
-    
-    let total_count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM assets")
-        .fetch_one(db)
-        .await
-        .map_err(|e| format!("Failed to get total asset count: {}", e))?;
-        
-
-        
-    let total_size: Option<i64> = sqlx::query_scalar("SELECT SUM(file_size) FROM assets")
-        .fetch_one(db)
+pub async fn get_asset_stats(db_manager_state: State<'_, DatabaseManager>) -> Result<AssetStats, String> {
+    db_manager_state
+        .get_asset_stats()
         .await
-        .map_err(|e| format!("Failed to get total size: {}", e))?;
-        
-    Ok(AssetStats {
-        total_assets: total_count,
-        total_size: total_size.unwrap_or(0),
-        asset_type_counts: vec![], // Empty for now, could be filled later
-        recent_assets: vec![], // Empty for now, could be filled later
-    })
+        .map_err(|e| format!("Failed to get asset stats: {}", e))
 }
 
 #[tauri::command]
@@ -63,6 +45,16 @@ pub async fn wipe_entire_database(
     
     match db_manager.wipe_database().await {
         Ok(_) => {
+            // The settings table was dropped too; bring the cached settings back in line.
+            if let Some(settings_service) = app_handle.try_state::<SettingsService>() {
+                match settings_service.reset(None).await {
+                    Ok(settings) => {
+                        let _ = app_handle.emit("settings-changed", &settings);
+                    }
+                    Err(e) => tracing::warn!("Failed to restore default settings after wipe: {}", e),
+                }
+            }
+
             // Emit an event to inform the frontend about the database wipe
             if let Err(e) = app_handle.emit("database-wiped", ()) {
                 eprintln!("Failed to emit database-wiped event: {}", e);
@@ -140,9 +132,10 @@ pub async fn open_file_location(path: String) -> Result<(), String> {
         let mut success = false;
         
         for fm in &file_managers {
-            if let Ok(_) = std::process::Command::new(fm)
+            if std::process::Command::new(fm)
                 .arg(parent)
                 .spawn()
+                .is_ok()
             {
                 success = true;
                 break;
@@ -191,27 +184,13 @@ pub async fn open_url(url: String) -> Result<(), String> {
 }
 
 #[tauri::command]
-pub async fn get_duplicate_assets() -> Result<Vec<crate::models::asset::Asset>, String> {
-    let db = get_db();
-    
-    let assets = sqlx::query_as::<_, crate::models::asset::Asset>(
-        r#"
-        SELECT * FROM assets 
-        WHERE file_hash IN (
-            SELECT file_hash 
-            FROM assets 
-            WHERE file_hash IS NOT NULL 
-            GROUP BY file_hash 
-            HAVING COUNT(*) > 1
-        )
-        ORDER BY file_hash, name
-        "#
-    )
-    .fetch_all(db)
-    .await
-    .map_err(|e| format!("Failed to fetch duplicate assets: {}", e))?;
-    
-    Ok(assets)
+pub async fn get_duplicate_assets(
+    db_manager_state: State<'_, DatabaseManager>,
+) -> Result<Vec<crate::models::asset::Asset>, String> {
+    db_manager_state
+        .get_duplicate_assets()
+        .await
+        .map_err(|e| format!("Failed to fetch duplicate assets: {}", e))
 }
 
 #[tauri::command]
@@ -225,30 +204,4 @@ pub async fn get_favorite_assets_count() -> Result<i64, String> {
 
     Ok(count)
 }
-
-#[tauri::command]
-pub async fn post_process_asset_categories() -> Result<i64, String> {
-    let db = get_db();
-    
-    let assets: Vec<(i64, String)> = sqlx::query_as("SELECT id, name FROM assets")
-        .fetch_all(db)
-        .await
-        .map_err(|e| format!("Failed to fetch assets for category processing: {}", e))?;
-
-    let mut updated_count = 0;
-
-    for (id, name) in assets {
-        let category = crate::scanner::utils::determine_asset_type_from_path(Path::new(&name));
-        
-        sqlx::query("UPDATE assets SET asset_type = ? WHERE id = ?")
-            .bind(&category)
-            .bind(id)
-            .execute(db)
-            .await
-            .map_err(|e| format!("Failed to update asset category: {}", e))?;
-        
-        updated_count += 1;
-    }
-
-    Ok(updated_count)
-} 
\ No newline at end of file
+ 
\ No newline at end of file
@@ -0,0 +1,57 @@
+use std::path::Path;
+use tauri::State;
+
+use crate::database::DatabaseManager;
+use crate::models::ScanLocation;
+use crate::settings::SettingsService;
+use crate::team::{self, TeamSyncReport};
+
+/// Shares a scan location with the team library, or makes it private again
+///
+/// # Arguments
+/// * `location_id` - ID of the scan location
+/// * `team_key` - Name the location has in the team library; `None` stops sharing it
+///
+/// # Returns
+/// * `Ok(ScanLocation)` - The updated location
+/// * `Err(String)` - Error message if the location does not exist
+///
+/// # Behavior
+/// - Every member links their own copy of the vault under the same key, whatever its path
+/// - Assets are paired with the team library again by relative path on the next sync
+#[tauri::command]
+pub async fn set_scan_location_team_key(
+    location_id: i64,
+    team_key: Option<String>,
+    db_manager_state: State<'_, DatabaseManager>,
+) -> Result<ScanLocation, String> {
+    let team_key = team_key.map(|key| key.trim().to_string()).filter(|key| !key.is_empty());
+    db_manager_state
+        .set_scan_location_team_key(location_id, team_key.as_deref())
+        .await
+        .map_err(|e| format!("Failed to update team key: {}", e))
+}
+
+/// Syncs the shared scan locations with the team library
+///
+/// # Returns
+/// * `Ok(TeamSyncReport)` - What was shared, pushed and pulled, and any conflicts
+/// * `Err(String)` - Error message if no team library is configured or it is unreachable
+///
+/// # Behavior
+/// - Waits while another member is syncing
+/// - Conflicting edits keep the newer value; favorites are never shared
+#[tauri::command]
+pub async fn sync_team_library(
+    db_manager_state: State<'_, DatabaseManager>,
+    settings_service: State<'_, SettingsService>,
+) -> Result<TeamSyncReport, String> {
+    let settings = settings_service.current();
+    let team_path = settings
+        .team_library_path
+        .clone()
+        .ok_or_else(|| "No team library is configured".to_string())?;
+    team::sync_team_library(&db_manager_state, Path::new(&team_path), &team::team_user_name(&settings))
+        .await
+        .map_err(|e| format!("Team sync failed: {}", e))
+}
@@ -0,0 +1,68 @@
+use std::path::Path;
+use tauri::{AppHandle, Emitter, State};
+
+use crate::database::DatabaseManager;
+use crate::transfer::{self, ExportFormat, ImportReport, MergeStrategy};
+
+/// Exports tags, notes, favorites, collections, manual overrides and match decisions
+///
+/// # Arguments
+/// * `path` - Destination file
+/// * `format` - `json` or `csv`; guessed from the file extension when omitted
+///
+/// # Returns
+/// * `Ok(usize)` - Number of assets written
+/// * `Err(String)` - Error message if the export could not be written
+#[tauri::command]
+pub async fn export_library(
+    path: String,
+    format: Option<ExportFormat>,
+    db_manager: State<'_, DatabaseManager>,
+) -> Result<usize, String> {
+    let path = Path::new(&path);
+    let format = format.unwrap_or_else(|| ExportFormat::from_path(path));
+
+    transfer::export_to_file(&db_manager.pool, path, format)
+        .await
+        .map_err(|e| format!("Failed to export library: {}", e))
+}
+
+/// Merges a library export into the local library
+///
+/// # Arguments
+/// * `path` - File written by `export_library`
+/// * `strategy` - `theirs`, `ours` or `newest`; decides conflicts between filled values
+/// * `dry_run` - When true nothing is written and the report shows what would change
+///
+/// # Returns
+/// * `Ok(ImportReport)` - Matched, unmatched and ambiguous records plus per-asset field changes
+/// * `Err(String)` - Error message if the file is invalid or the import failed
+///
+/// # Behavior
+/// - Records are matched by fingerprint, then Orbital slug, then relative path within the
+///   scan location of the same name
+/// - Empty imported values never clear local ones; collection memberships are only added
+/// - A real import is applied in a single transaction and emits `library-imported`
+#[tauri::command]
+pub async fn import_library(
+    path: String,
+    strategy: MergeStrategy,
+    dry_run: bool,
+    app_handle: AppHandle,
+    db_manager: State<'_, DatabaseManager>,
+) -> Result<ImportReport, String> {
+    let path = Path::new(&path);
+    let export = transfer::read_export(path, ExportFormat::from_path(path))
+        .map_err(|e| format!("Failed to read library export: {}", e))?;
+
+    let report = transfer::import_export(&db_manager.pool, &export, strategy, dry_run)
+        .await
+        .map_err(|e| format!("Failed to import library: {}", e))?;
+
+    if !dry_run {
+        if let Err(e) = app_handle.emit("library-imported", &report) {
+            tracing::error!("Failed to emit library-imported event: {}", e);
+        }
+    }
+    Ok(report)
+}
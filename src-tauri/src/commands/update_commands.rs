@@ -0,0 +1,31 @@
+use tauri::State;
+
+use crate::database::DatabaseManager;
+use crate::settings::SettingsService;
+use crate::updates::{self, OutdatedReport};
+
+/// Lists matched assets whose copy on disk is behind its marketplace listing
+///
+/// # Returns
+/// * `Ok(OutdatedReport)` - The engine checked against and every flagged asset
+/// * `Err(String)` - Error message if the library cannot be read
+///
+/// # Behavior
+/// - Detects the installed engine of assets that changed since the last check, from their
+///   descriptor or else their package headers
+/// - Flags `update_available` when the listing changed after the copy was downloaded
+/// - Flags `engine_unsupported` when the copy cannot be used with the engine in use (the
+///   `engine_version` setting, or the newest engine of the indexed projects)
+/// - Flags `newer_engine_support` when the listing supports a newer engine than the copy
+#[tauri::command]
+pub async fn get_outdated_assets(
+    db_manager_state: State<'_, DatabaseManager>,
+    settings_service: State<'_, SettingsService>,
+) -> Result<OutdatedReport, String> {
+    let engine_in_use = updates::engine_in_use(&db_manager_state, &settings_service.current())
+        .await
+        .map_err(|e| format!("Failed to find the engine in use: {}", e))?;
+    updates::find_outdated_assets(&db_manager_state, engine_in_use)
+        .await
+        .map_err(|e| format!("Failed to check for updates: {}", e))
+}
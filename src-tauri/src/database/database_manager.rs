@@ -1,77 +1,1025 @@
-use sqlx::{Pool, Sqlite};
-use std::sync::OnceLock;
+//! The library's store: every read and write of `omnidex.db` goes through a `DatabaseManager`.
+//!
+//! The Orbital layer only needs the few writes of [`AssetStore`](super::AssetStore), which this
+//! manager implements. The rest of the library's logic is SQL run against this schema, so it takes
+//! the manager itself; tests and embedders use [`DatabaseManager::open_in_memory`] or
+//! [`DatabaseManager::open_file`], which run the same schema and queries without the app's global pool.
+
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+use sqlx::{Connection, Pool, Sqlite, SqliteConnection};
+use std::sync::{Arc, OnceLock};
 use anyhow::Result;
-use crate::models::{Asset, ScanLocation};
+use crate::categories::{CategoryAssignment, Categorization};
+use crate::clock::{Clock, SystemClock};
+use crate::models::asset_card::{AssetCardData, SortOption};
+use crate::models::asset_details::AssetForFrontend;
+use crate::models::{
+    Asset, AssetProjectUsage, Collection, AssetStats, AssetStorage, InventoryEntry, LocationStorage, ProjectAssetMatch, ScanError,
+    ScanErrorFilter, ScanErrorKind, ScanErrorRecord, ScanJob, ScanLocation, ScanRun, SCAN_STATUS_CANCELLED, SCAN_STATUS_INTERRUPTED, SCAN_STATUS_PAUSED, SCAN_STATUS_RUNNING,
+};
+use crate::scanner::inventory::FolderInventory;
+use crate::scanner::ScannedFolder;
 use crate::orbital::models::OrbitalAsset;
-use chrono::Utc;
-use super::create_tables;
+use crate::unreal::UnrealProject;
+use std::path::Path;
+use super::{create_tables, resolve_asset_path};
 
 pub static DB_POOL: OnceLock<Pool<Sqlite>> = OnceLock::new();
 
+/// `group_kind` values of the `asset_inventory` table.
+pub const INVENTORY_BY_EXTENSION: &str = "extension";
+pub const INVENTORY_BY_FOLDER: &str = "folder";
+
+/// Scan errors returned by `get_scan_errors` unless the filter sets a limit.
+const DEFAULT_SCAN_ERROR_LIMIT: i64 = 500;
+
+/// CTE selecting the assets that sit directly below their scan location. Recursive scans also
+/// index every nested folder, so summing all assets would count the same files repeatedly.
+const OUTERMOST_ASSETS: &str = r#"
+    outermost AS (
+        SELECT id, name, scan_location_id, file_size FROM assets
+        WHERE relative_path IS NULL OR instr(relative_path, '/') = 0
+    )
+"#;
+
+/// Collections with the number of assets in each; append a WHERE or ORDER BY clause.
+pub(crate) const COLLECTION_SELECT: &str = r#"
+    SELECT c.id, c.name, c.description, c.created_date, c.modified_date,
+           (SELECT COUNT(*) FROM collection_assets ca WHERE ca.collection_id = c.id) AS asset_count
+    FROM collections c
+"#;
+
 // Define DatabaseManager struct
 #[derive(Clone)]
 pub struct DatabaseManager {
-    pub pool: &'static Pool<Sqlite>,
+    pub pool: Pool<Sqlite>,
+    clock: Arc<dyn Clock>,
 }
 
 impl DatabaseManager {
+    /// The manager of the app's database, set up by `init_database`.
     pub async fn new() -> Result<Self, String> {
         match DB_POOL.get() {
-            Some(pool) => Ok(Self { pool }),
+            Some(pool) => Ok(Self::from_pool(pool.clone())),
             None => Err("Database pool not initialized. Call init_database first.".to_string()),
         }
     }
 
+    pub fn from_pool(pool: Pool<Sqlite>) -> Self {
+        Self { pool, clock: Arc::new(SystemClock) }
+    }
+
+    /// A fresh database that lives in memory until the last clone of the manager is dropped, for tests and embedding.
+    pub async fn open_in_memory() -> Result<Self> {
+        // Every connection to `:memory:` opens its own database, so the pool keeps exactly one.
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .idle_timeout(None)
+            .max_lifetime(None)
+            .connect("sqlite::memory:")
+            .await?;
+        create_tables(&pool).await?;
+        Ok(Self::from_pool(pool))
+    }
+
+    /// The database in the file at `path`, created if it does not exist, e.g. a second library to sync with.
+    pub async fn open_file(path: &Path) -> Result<Self> {
+        let options = SqliteConnectOptions::new().filename(path).create_if_missing(true);
+        let pool = SqlitePoolOptions::new().connect_with(options).await?;
+        create_tables(&pool).await?;
+        Ok(Self::from_pool(pool))
+    }
+
+    /// Take timestamps from `clock` instead of the system clock.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// The current time as stored in the database.
+    pub fn timestamp(&self) -> String {
+        self.clock.timestamp()
+    }
+
     pub async fn get_asset_by_id(&self, asset_id: i64) -> Result<Asset> {
         sqlx::query_as::<_, Asset>("SELECT * FROM assets WHERE id = ?")
             .bind(asset_id)
-            .fetch_one(self.pool)
+            .fetch_one(&self.pool)
             .await
             .map_err(|e| anyhow::anyhow!("Asset with id {} not found: {}", asset_id, e))
     }
 
+    /// An asset as the details panel shows it, or `None` when there is no asset with this ID.
+    pub async fn get_asset_details(&self, asset_id: i64) -> Result<Option<AssetForFrontend>> {
+        let asset = sqlx::query_as::<_, Asset>("SELECT * FROM assets WHERE id = ?")
+            .bind(asset_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Error fetching asset {}: {}", asset_id, e))?;
+        Ok(asset.map(AssetForFrontend::from))
+    }
+
     pub async fn get_scan_location_by_id(&self, location_id: i64) -> Result<Option<ScanLocation>> {
         sqlx::query_as::<_, ScanLocation>("SELECT * FROM scan_locations WHERE id = ?")
             .bind(location_id)
-            .fetch_optional(self.pool)
+            .fetch_optional(&self.pool)
             .await
             .map_err(|e| anyhow::anyhow!("Error fetching ScanLocation with id {}: {}", location_id, e))
     }
 
+    pub async fn create_scan_location(
+        &self,
+        name: &str,
+        path: &str,
+        scan_recursive: bool,
+        file_extensions: Option<&str>,
+        description: Option<&str>,
+        location_kind: &str,
+    ) -> Result<ScanLocation> {
+        sqlx::query_as::<_, ScanLocation>(
+            r#"
+            INSERT INTO scan_locations (name, path, is_active, scan_recursive, file_extensions, created_date, description, location_kind)
+            VALUES (?, ?, 1, ?, ?, ?, ?, ?)
+            RETURNING *
+            "#,
+        )
+        .bind(name)
+        .bind(path)
+        .bind(scan_recursive)
+        .bind(file_extensions)
+        .bind(self.timestamp())
+        .bind(description)
+        .bind(location_kind)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Error creating scan location: {}", e))
+    }
+
+    /// Share a location with the team library under `team_key`, or make it private again with `None`.
+    /// Its assets start over with the team library: the next sync pairs them by relative path.
+    pub async fn set_scan_location_team_key(&self, location_id: i64, team_key: Option<&str>) -> Result<ScanLocation> {
+        let mut tx = self.pool.begin().await?;
+        let location = sqlx::query_as::<_, ScanLocation>("UPDATE scan_locations SET team_key = ? WHERE id = ? RETURNING *")
+            .bind(team_key)
+            .bind(location_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Scan location {} not found", location_id))?;
+        sqlx::query("UPDATE assets SET team_revision = NULL, team_synced_date = NULL WHERE scan_location_id = ?")
+            .bind(location_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(location)
+    }
+
+    /// Scan locations shared with the team library.
+    pub async fn get_team_scan_locations(&self) -> Result<Vec<ScanLocation>> {
+        sqlx::query_as::<_, ScanLocation>("SELECT * FROM scan_locations WHERE team_key IS NOT NULL ORDER BY id")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Error fetching team scan locations: {}", e))
+    }
+
     pub async fn get_asset_by_path(&self, file_path: &str) -> Result<Option<Asset>> {
         sqlx::query_as::<_, Asset>("SELECT * FROM assets WHERE file_path = ?")
             .bind(file_path)
-            .fetch_optional(self.pool)
+            .fetch_optional(&self.pool)
             .await
             .map_err(|e| anyhow::anyhow!("Error fetching asset by path {}: {}", file_path, e))
     }
 
+    /// Delete the asset at `folder_path` and every asset nested below it, returning the removed ids.
+    pub async fn delete_assets_under_path(&self, folder_path: &str) -> Result<Vec<i64>> {
+        let escaped = folder_path.replace('!', "!!").replace('%', "!%").replace('_', "!_");
+        let nested_pattern = format!("{}{}%", escaped, std::path::MAIN_SEPARATOR);
+
+        sqlx::query_scalar::<_, i64>(
+            "DELETE FROM assets WHERE file_path = ? OR file_path LIKE ? ESCAPE '!' RETURNING id"
+        )
+        .bind(folder_path)
+        .bind(nested_pattern)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Error deleting assets under path {}: {}", folder_path, e))
+    }
+
+    /// Paths of every asset at or below `folder_path`.
+    pub async fn get_asset_paths_under(&self, folder_path: &str) -> Result<Vec<String>> {
+        let escaped = folder_path.replace('!', "!!").replace('%', "!%").replace('_', "!_");
+        let nested_pattern = format!("{}{}%", escaped, std::path::MAIN_SEPARATOR);
+
+        sqlx::query_scalar::<_, String>(
+            "SELECT file_path FROM assets WHERE file_path = ? OR file_path LIKE ? ESCAPE '!' ORDER BY file_path"
+        )
+        .bind(folder_path)
+        .bind(nested_pattern)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Error fetching assets under path {}: {}", folder_path, e))
+    }
+
+    /// Refresh the on-disk size and modification time of an already indexed asset.
+    pub async fn update_asset_file_stats(&self, asset_id: i64, file_size: i64, modified_date: &str) -> Result<()> {
+        sqlx::query("UPDATE assets SET file_size = ?, modified_date = ? WHERE id = ?")
+            .bind(file_size)
+            .bind(modified_date)
+            .bind(asset_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Write a batch of scanned folders in one transaction. Folders that are not indexed yet
+    /// are inserted; known ones get their size, modification time and fingerprint refreshed.
+    /// The inventory of every folder is replaced.
+    ///
+    /// Each folder is written under its own savepoint, so a folder that fails is rolled back
+    /// without losing the rest of the batch. Returns, in order, the stored asset of each folder
+    /// and whether it was newly inserted, or why it could not be saved.
+    pub async fn save_scanned_folders(&self, folders: &[ScannedFolder]) -> Result<Vec<Result<(Asset, bool)>>> {
+        let now = self.timestamp();
+        let mut tx = self.pool.begin().await?;
+        let mut saved = Vec::with_capacity(folders.len());
+
+        for folder in folders {
+            let mut savepoint = tx.begin().await?;
+            let result = save_scanned_folder(&mut savepoint, folder, &now).await;
+            if result.is_ok() {
+                savepoint.commit().await?;
+            } else {
+                savepoint.rollback().await?;
+            }
+            saved.push(result);
+        }
+
+        tx.commit().await?;
+        Ok(saved)
+    }
+
+    /// Recorded inventory of an asset for one grouping (`extension` or `folder`), largest first.
+    pub async fn get_asset_inventory(&self, asset_id: i64, group_kind: &str) -> Result<Vec<InventoryEntry>> {
+        sqlx::query_as::<_, InventoryEntry>(
+            r#"
+            SELECT group_key, file_count, total_bytes FROM asset_inventory
+            WHERE asset_id = ? AND group_kind = ?
+            ORDER BY total_bytes DESC, group_key
+            "#
+        )
+        .bind(asset_id)
+        .bind(group_kind)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Error fetching inventory of asset {}: {}", asset_id, e))
+    }
+
+    /// Extension totals across the library, counting each file once.
+    pub async fn get_library_inventory_by_extension(&self) -> Result<Vec<InventoryEntry>> {
+        let query = format!(
+            r#"
+            WITH {OUTERMOST_ASSETS}
+            SELECT ai.group_key, SUM(ai.file_count) AS file_count, SUM(ai.total_bytes) AS total_bytes
+            FROM asset_inventory ai
+            JOIN outermost o ON o.id = ai.asset_id
+            WHERE ai.group_kind = ?
+            GROUP BY ai.group_key
+            ORDER BY total_bytes DESC, ai.group_key
+            "#
+        );
+        sqlx::query_as::<_, InventoryEntry>(&query)
+            .bind(INVENTORY_BY_EXTENSION)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Error fetching library inventory: {}", e))
+    }
+
+    /// Disk usage per scan location, counting each file once.
+    pub async fn get_location_storage(&self) -> Result<Vec<LocationStorage>> {
+        let query = format!(
+            r#"
+            WITH {OUTERMOST_ASSETS}
+            SELECT sl.id AS location_id, sl.name, sl.path,
+                   COUNT(o.id) AS asset_count,
+                   COALESCE(SUM(o.file_size), 0) AS total_bytes
+            FROM scan_locations sl
+            LEFT JOIN outermost o ON o.scan_location_id = sl.id
+            GROUP BY sl.id
+            ORDER BY total_bytes DESC, sl.name
+            "#
+        );
+        sqlx::query_as::<_, LocationStorage>(&query)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Error fetching location storage: {}", e))
+    }
+
+    /// The biggest assets that are not nested inside another asset.
+    pub async fn get_largest_assets(&self, limit: i64) -> Result<Vec<AssetStorage>> {
+        let query = format!(
+            r#"
+            WITH {OUTERMOST_ASSETS}
+            SELECT id AS asset_id, name, file_size AS total_bytes
+            FROM outermost
+            ORDER BY file_size DESC, name
+            LIMIT ?
+            "#
+        );
+        sqlx::query_as::<_, AssetStorage>(&query)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Error fetching largest assets: {}", e))
+    }
+
+    /// Assets whose name, description, tags or Orbital listing contain `query`, name matches first.
+    pub async fn search_assets(&self, query: &str, limit: u32, offset: u32) -> Result<Vec<AssetCardData>> {
+        let search_query = format!("%{}%", query);
+        sqlx::query_as::<_, AssetCardData>(
+            r#"
+            SELECT id, name, asset_type, file_size, thumbnail_path, orbital_thumbnail_url, orbital_rating_average, orbital_rating_count, is_favorite, created_date 
+            FROM assets 
+            WHERE name LIKE ? 
+               OR description LIKE ? 
+               OR tags LIKE ?
+               OR orbital_title LIKE ?
+               OR orbital_description LIKE ?
+            ORDER BY 
+                CASE WHEN name LIKE ? THEN 1 ELSE 2 END,
+                name ASC
+            LIMIT ? OFFSET ?
+            "#
+        )
+        .bind(&search_query)
+        .bind(&search_query)
+        .bind(&search_query)
+        .bind(&search_query)
+        .bind(&search_query)
+        .bind(&search_query)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Error searching assets: {}", e))
+    }
+
+    pub async fn get_collections(&self) -> Result<Vec<Collection>> {
+        sqlx::query_as::<_, Collection>(&format!("{} ORDER BY c.name ASC", COLLECTION_SELECT))
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Error fetching collections: {}", e))
+    }
+
+    pub async fn get_collection_assets(
+        &self,
+        collection_id: i64,
+        sort_by: SortOption,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Vec<AssetCardData>> {
+        let query = format!(
+            "SELECT id, name, asset_type, file_size, thumbnail_path, orbital_thumbnail_url, orbital_rating_average, orbital_rating_count, is_favorite, created_date
+             FROM assets
+             WHERE id IN (SELECT asset_id FROM collection_assets WHERE collection_id = ?)
+             ORDER BY {}
+             LIMIT ? OFFSET ?",
+            sort_by.to_sql()
+        );
+
+        sqlx::query_as(&query)
+            .bind(collection_id)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Error fetching assets of collection {}: {}", collection_id, e))
+    }
+
+    pub async fn get_asset_stats(&self) -> Result<AssetStats> {
+        let total_assets: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM assets")
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Error counting assets: {}", e))?;
+        let total_size: Option<i64> = sqlx::query_scalar("SELECT SUM(file_size) FROM assets")
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Error summing asset sizes: {}", e))?;
+
+        Ok(AssetStats {
+            total_assets,
+            total_size: total_size.unwrap_or(0),
+            asset_type_counts: vec![], // Empty for now, could be filled later
+            recent_assets: vec![], // Empty for now, could be filled later
+        })
+    }
+
+    /// Assets sharing their file hash with another asset, ordered so copies sit next to each other.
+    pub async fn get_duplicate_assets(&self) -> Result<Vec<Asset>> {
+        sqlx::query_as::<_, Asset>(
+            r#"
+            SELECT * FROM assets 
+            WHERE file_hash IN (
+                SELECT file_hash 
+                FROM assets 
+                WHERE file_hash IS NOT NULL 
+                GROUP BY file_hash 
+                HAVING COUNT(*) > 1
+            )
+            ORDER BY file_hash, name
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Error fetching duplicate assets: {}", e))
+    }
+
+    /// Assets without a marketplace match, optionally limited to one scan location.
+    pub async fn get_unmatched_assets(&self, location_id: Option<i64>) -> Result<Vec<Asset>> {
+        sqlx::query_as::<_, Asset>(
+            "SELECT * FROM assets WHERE matched_orbital_product_slug IS NULL AND (?1 IS NULL OR scan_location_id = ?1) ORDER BY name",
+        )
+        .bind(location_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Error fetching unmatched assets: {}", e))
+    }
+
+    pub async fn get_active_scan_locations(&self) -> Result<Vec<ScanLocation>> {
+        sqlx::query_as::<_, ScanLocation>("SELECT * FROM scan_locations WHERE is_active = 1")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Error fetching active scan locations: {}", e))
+    }
+
+    pub async fn get_watched_scan_locations(&self) -> Result<Vec<ScanLocation>> {
+        sqlx::query_as::<_, ScanLocation>("SELECT * FROM scan_locations WHERE is_active = 1 AND watch_enabled = 1 AND location_kind IN ('vault', 'media')")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Error fetching watched scan locations: {}", e))
+    }
+
+    /// The recorded categories of an asset, if it has been categorized since assignments were kept.
+    pub async fn get_asset_categorization(&self, asset_id: i64) -> Result<Option<Categorization>> {
+        let rows: Vec<(String, f64, String, bool)> = sqlx::query_as(
+            "SELECT category, confidence, rule, is_primary FROM asset_categories
+             WHERE asset_id = ? ORDER BY is_primary DESC, confidence DESC, category",
+        )
+        .bind(asset_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut assignments = rows.into_iter().map(|(category, confidence, rule, is_primary)| {
+            (CategoryAssignment { category, confidence, rule }, is_primary)
+        });
+        match assignments.next() {
+            Some((primary, true)) => Ok(Some(Categorization {
+                primary,
+                secondary: assignments.map(|(assignment, _)| assignment).collect(),
+            })),
+            _ => Ok(None),
+        }
+    }
+
+    /// Set an asset's categories; the primary one also becomes its `asset_type`.
+    pub async fn apply_categorization(&self, asset_id: i64, categorization: &Categorization) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("UPDATE assets SET asset_type = ? WHERE id = ?")
+            .bind(&categorization.primary.category)
+            .bind(asset_id)
+            .execute(&mut *tx)
+            .await?;
+        write_categorization(&mut tx, asset_id, categorization, &self.timestamp()).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Every category each asset is in: its `asset_type` plus any recorded secondary categories.
+    pub async fn get_asset_category_pairs(&self) -> Result<Vec<(i64, String)>> {
+        sqlx::query_as(
+            "SELECT id, asset_type FROM assets
+             UNION
+             SELECT c.asset_id, c.category FROM asset_categories c JOIN assets a ON a.id = c.asset_id",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Error fetching asset categories: {}", e))
+    }
+
+    /// Names of the Unreal packages indexed for an asset.
+    pub async fn get_asset_package_names(&self, asset_id: i64) -> Result<Vec<String>> {
+        sqlx::query_scalar("SELECT package_name FROM asset_packages WHERE asset_id = ?")
+            .bind(asset_id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Error fetching packages of asset {}: {}", asset_id, e))
+    }
+
+    /// Remember that an asset was imported into a project; re-imports refresh the entry.
+    pub async fn record_project_usage(&self, asset_id: i64, project: &UnrealProject, import_mode: &str) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO asset_project_usage (asset_id, project_path, project_name, import_mode, imported_date)
+            VALUES (?, ?, ?, ?, ?)
+            ON CONFLICT(asset_id, project_path) DO UPDATE SET
+                project_name = excluded.project_name,
+                import_mode = excluded.import_mode,
+                imported_date = excluded.imported_date
+            "#
+        )
+        .bind(asset_id)
+        .bind(project.uproject_path.to_string_lossy().as_ref())
+        .bind(&project.name)
+        .bind(import_mode)
+        .bind(self.timestamp())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn get_asset_project_usage(&self, asset_id: i64) -> Result<Vec<AssetProjectUsage>> {
+        sqlx::query_as::<_, AssetProjectUsage>(
+            "SELECT * FROM asset_project_usage WHERE asset_id = ? ORDER BY imported_date DESC"
+        )
+        .bind(asset_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Error fetching project usage of asset {}: {}", asset_id, e))
+    }
+
+    /// Library assets found in an indexed project location, with per-asset package counts.
+    pub async fn get_project_asset_matches(&self, location_id: i64) -> Result<Vec<ProjectAssetMatch>> {
+        let mut matches = sqlx::query_as::<_, ProjectAssetMatch>(
+            r#"
+            SELECT a.id AS asset_id, a.name,
+                   (SELECT COUNT(*) FROM asset_packages ap WHERE ap.asset_id = a.id) AS library_packages,
+                   SUM(pp.match_kind = 'identical') AS identical,
+                   SUM(pp.match_kind = 'modified') AS modified,
+                   SUM(pp.match_kind = 'moved') AS moved
+            FROM project_packages pp
+            JOIN assets a ON a.id = pp.matched_asset_id
+            WHERE pp.location_id = ?
+            GROUP BY a.id
+            ORDER BY a.name
+            "#
+        )
+        .bind(location_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Error fetching project assets for location {}: {}", location_id, e))?;
+
+        for m in &mut matches {
+            m.status = if m.identical + m.modified + m.moved < m.library_packages {
+                "partial"
+            } else if m.modified > 0 || m.moved > 0 {
+                "modified"
+            } else {
+                "complete"
+            }
+            .to_string();
+        }
+        Ok(matches)
+    }
+
+    /// Pick up to `limit` random relative asset paths of a location, used to sanity-check a relocation.
+    pub async fn sample_asset_relative_paths(&self, location_id: i64, limit: i64) -> Result<Vec<String>> {
+        sqlx::query_scalar::<_, String>(
+            "SELECT relative_path FROM assets WHERE scan_location_id = ? AND relative_path IS NOT NULL ORDER BY RANDOM() LIMIT ?"
+        )
+        .bind(location_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Error sampling assets of location {}: {}", location_id, e))
+    }
+
+    /// Point a scan location at `new_root` and re-resolve the absolute path of every asset in it
+    /// from its stored relative path. Everything happens in one transaction; returns the number
+    /// of assets updated.
+    pub async fn relocate_scan_location(&self, location_id: i64, new_root: &Path) -> Result<u64> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("UPDATE scan_locations SET path = ? WHERE id = ?")
+            .bind(new_root.to_string_lossy().as_ref())
+            .bind(location_id)
+            .execute(&mut *tx)
+            .await?;
+        let updated = Self::repoint_location_assets(&mut tx, location_id, new_root).await?;
+
+        tx.commit().await?;
+        Ok(updated)
+    }
+
+    /// Save the editable settings of a scan location. A changed `path` re-resolves the absolute
+    /// path of every asset in it as `relocate_scan_location` does, in the same transaction as
+    /// the settings, so a failed write leaves the location at its old root. Returns the updated
+    /// location and the number of assets whose path was rewritten.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn update_scan_location(
+        &self,
+        location_id: i64,
+        name: &str,
+        path: &str,
+        is_active: bool,
+        scan_recursive: bool,
+        file_extensions: Option<&str>,
+        description: Option<&str>,
+    ) -> Result<(ScanLocation, u64)> {
+        let mut tx = self.pool.begin().await?;
+
+        let previous_path: String = sqlx::query_scalar("SELECT path FROM scan_locations WHERE id = ?")
+            .bind(location_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Scan location {} not found", location_id))?;
+        let updated = if previous_path != path {
+            Self::repoint_location_assets(&mut tx, location_id, Path::new(path)).await?
+        } else {
+            0
+        };
+
+        let location = sqlx::query_as::<_, ScanLocation>(
+            r#"
+            UPDATE scan_locations
+            SET name = ?, path = ?, is_active = ?, scan_recursive = ?, file_extensions = ?, description = ?
+            WHERE id = ?
+            RETURNING *
+            "#,
+        )
+        .bind(name)
+        .bind(path)
+        .bind(is_active)
+        .bind(scan_recursive)
+        .bind(file_extensions)
+        .bind(description)
+        .bind(location_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| anyhow::anyhow!("Error updating scan location {}: {}", location_id, e))?;
+
+        tx.commit().await?;
+        Ok((location, updated))
+    }
+
+    /// Rewrite the absolute path of every asset of a location under `new_root`; returns how many.
+    async fn repoint_location_assets(conn: &mut SqliteConnection, location_id: i64, new_root: &Path) -> Result<u64> {
+        let assets: Vec<(i64, String)> = sqlx::query_as(
+            "SELECT id, relative_path FROM assets WHERE scan_location_id = ? AND relative_path IS NOT NULL"
+        )
+        .bind(location_id)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        for (asset_id, relative_path) in &assets {
+            sqlx::query("UPDATE assets SET file_path = ? WHERE id = ?")
+                .bind(resolve_asset_path(new_root, relative_path).to_string_lossy().as_ref())
+                .bind(asset_id)
+                .execute(&mut *conn)
+                .await?;
+        }
+        Ok(assets.len() as u64)
+    }
+
     pub async fn wipe_database(&self) -> Result<()> {
+        sqlx::query("DROP TABLE IF EXISTS scan_errors")
+            .execute(&self.pool)
+            .await?;
+        sqlx::query("DROP TABLE IF EXISTS scan_runs")
+            .execute(&self.pool)
+            .await?;
+        sqlx::query("DROP TABLE IF EXISTS scan_jobs")
+            .execute(&self.pool)
+            .await?;
+        sqlx::query("DROP TABLE IF EXISTS asset_categories")
+            .execute(&self.pool)
+            .await?;
+        sqlx::query("DROP TABLE IF EXISTS project_packages")
+            .execute(&self.pool)
+            .await?;
+        sqlx::query("DROP TABLE IF EXISTS asset_inventory")
+            .execute(&self.pool)
+            .await?;
+        sqlx::query("DROP TABLE IF EXISTS asset_packages")
+            .execute(&self.pool)
+            .await?;
+        sqlx::query("DROP TABLE IF EXISTS asset_project_usage")
+            .execute(&self.pool)
+            .await?;
+        sqlx::query("DROP TABLE IF EXISTS asset_provider_metadata")
+            .execute(&self.pool)
+            .await?;
+        sqlx::query("DROP TABLE IF EXISTS asset_licenses")
+            .execute(&self.pool)
+            .await?;
+        sqlx::query("DROP TABLE IF EXISTS asset_versions")
+            .execute(&self.pool)
+            .await?;
+        sqlx::query("DROP TABLE IF EXISTS duplicate_resolutions")
+            .execute(&self.pool)
+            .await?;
+        sqlx::query("DROP TABLE IF EXISTS collection_assets")
+            .execute(&self.pool)
+            .await?;
+        sqlx::query("DROP TABLE IF EXISTS collections")
+            .execute(&self.pool)
+            .await?;
         sqlx::query("DROP TABLE IF EXISTS assets")
-            .execute(self.pool)
+            .execute(&self.pool)
             .await?;
         sqlx::query("DROP TABLE IF EXISTS scan_locations")
-            .execute(self.pool)
+            .execute(&self.pool)
             .await?;
         sqlx::query("DROP TABLE IF EXISTS app_settings")
-            .execute(self.pool)
+            .execute(&self.pool)
             .await?;
 
-        create_tables(self.pool).await?;
+        create_tables(&self.pool).await?;
 
         Ok(())
     }
 
     pub async fn update_scan_location_last_scanned(&self, location_id: i64) -> Result<()> {
-        let now_timestamp = chrono::Utc::now().to_rfc3339();
+        let now_timestamp = self.timestamp();
         sqlx::query("UPDATE scan_locations SET last_scan = ? WHERE id = ?")
             .bind(now_timestamp)
             .bind(location_id)
-        .execute(self.pool)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Start a scan job for a location, or pick up its paused or interrupted job when `resume` is set.
+    /// Starting over cancels whatever unfinished job the location had.
+    pub async fn begin_scan_job(&self, location_id: i64, resume: bool) -> Result<ScanJob> {
+        let now = self.timestamp();
+        if resume {
+            if let Some(job) = self.get_resumable_scan_job(location_id).await? {
+                return sqlx::query_as::<_, ScanJob>(
+                    "UPDATE scan_jobs SET status = ?, updated_date = ? WHERE id = ? RETURNING *",
+                )
+                .bind(SCAN_STATUS_RUNNING)
+                .bind(&now)
+                .bind(job.id)
+                .fetch_one(&self.pool)
+                .await
+                .map_err(|e| anyhow::anyhow!("Error resuming scan job {}: {}", job.id, e));
+            }
+        }
+
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("UPDATE scan_jobs SET status = ?, updated_date = ? WHERE location_id = ? AND status IN (?, ?, ?)")
+            .bind(SCAN_STATUS_CANCELLED)
+            .bind(&now)
+            .bind(location_id)
+            .bind(SCAN_STATUS_RUNNING)
+            .bind(SCAN_STATUS_PAUSED)
+            .bind(SCAN_STATUS_INTERRUPTED)
+            .execute(&mut *tx)
+            .await?;
+        let job = sqlx::query_as::<_, ScanJob>(
+            "INSERT INTO scan_jobs (location_id, status, created_date, updated_date) VALUES (?, ?, ?, ?) RETURNING *",
+        )
+        .bind(location_id)
+        .bind(SCAN_STATUS_RUNNING)
+        .bind(&now)
+        .bind(&now)
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(job)
+    }
+
+    /// Persist the progress of a running job after a batch has been written.
+    pub async fn checkpoint_scan_job(&self, job: &ScanJob) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE scan_jobs SET checkpoint_path = ?, processed_items = ?, total_items = ?, assets_found = ?,
+                error_count = ?, updated_date = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(&job.checkpoint_path)
+        .bind(job.processed_items)
+        .bind(job.total_items)
+        .bind(job.assets_found)
+        .bind(job.error_count)
+        .bind(self.timestamp())
+        .bind(job.id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Persist the final progress of a job run and the status it ended in.
+    pub async fn finish_scan_job(&self, job: &ScanJob, status: &str) -> Result<()> {
+        self.checkpoint_scan_job(job).await?;
+        sqlx::query("UPDATE scan_jobs SET status = ?, active_ms = ? WHERE id = ?")
+            .bind(status)
+            .bind(job.active_ms)
+            .bind(job.id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Open a scan history entry for a run of `job`.
+    pub async fn start_scan_run(&self, job: &ScanJob) -> Result<i64> {
+        let id = sqlx::query_scalar("INSERT INTO scan_runs (job_id, location_id, started_date) VALUES (?, ?, ?) RETURNING id")
+            .bind(job.id)
+            .bind(job.location_id)
+            .bind(self.timestamp())
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(id)
+    }
+
+    /// Close a scan history entry with what the run did.
+    pub async fn finish_scan_run(
+        &self,
+        run_id: i64,
+        outcome: &str,
+        duration_ms: i64,
+        processed_items: i64,
+        assets_found: i64,
+        error_count: i64,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE scan_runs SET finished_date = ?, duration_ms = ?, outcome = ?, processed_items = ?,
+                assets_found = ?, error_count = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(self.timestamp())
+        .bind(duration_ms)
+        .bind(outcome)
+        .bind(processed_items)
+        .bind(assets_found)
+        .bind(error_count)
+        .bind(run_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Mark jobs and runs left running by a previous session as interrupted. Called at startup.
+    pub async fn mark_interrupted_scan_jobs(&self) -> Result<u64> {
+        let now = self.timestamp();
+        let mut tx = self.pool.begin().await?;
+        let interrupted = sqlx::query("UPDATE scan_jobs SET status = ?, updated_date = ? WHERE status = ?")
+            .bind(SCAN_STATUS_INTERRUPTED)
+            .bind(&now)
+            .bind(SCAN_STATUS_RUNNING)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        sqlx::query("UPDATE scan_runs SET outcome = ?, finished_date = ? WHERE outcome IS NULL")
+            .bind(SCAN_STATUS_INTERRUPTED)
+            .bind(&now)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(interrupted)
+    }
+
+    pub async fn get_resumable_scan_job(&self, location_id: i64) -> Result<Option<ScanJob>> {
+        sqlx::query_as::<_, ScanJob>(
+            "SELECT * FROM scan_jobs WHERE location_id = ? AND status IN (?, ?) ORDER BY id DESC LIMIT 1",
+        )
+        .bind(location_id)
+        .bind(SCAN_STATUS_PAUSED)
+        .bind(SCAN_STATUS_INTERRUPTED)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Error fetching scan job of location {}: {}", location_id, e))
+    }
+
+    /// Paused and interrupted jobs of every location, most recently updated first.
+    pub async fn get_resumable_scan_jobs(&self) -> Result<Vec<ScanJob>> {
+        sqlx::query_as::<_, ScanJob>("SELECT * FROM scan_jobs WHERE status IN (?, ?) ORDER BY updated_date DESC")
+            .bind(SCAN_STATUS_PAUSED)
+            .bind(SCAN_STATUS_INTERRUPTED)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Error fetching resumable scan jobs: {}", e))
+    }
+
+    /// Most recent scan runs, optionally of a single location.
+    pub async fn get_scan_history(&self, location_id: Option<i64>, limit: i64) -> Result<Vec<ScanRun>> {
+        sqlx::query_as::<_, ScanRun>(
+            "SELECT * FROM scan_runs WHERE (? IS NULL OR location_id = ?) ORDER BY started_date DESC, id DESC LIMIT ?",
+        )
+        .bind(location_id)
+        .bind(location_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Error fetching scan history: {}", e))
+    }
+
+    /// Store the errors a scan run raised, returning the stored rows.
+    pub async fn record_scan_errors(&self, job: &ScanJob, run_id: i64, errors: &[ScanError]) -> Result<Vec<ScanErrorRecord>> {
+        if errors.is_empty() {
+            return Ok(Vec::new());
+        }
+        let now = self.timestamp();
+        let mut tx = self.pool.begin().await?;
+        let mut records = Vec::with_capacity(errors.len());
+        for error in errors {
+            let record = sqlx::query_as::<_, ScanErrorRecord>(
+                r#"
+                INSERT INTO scan_errors (run_id, job_id, location_id, path, kind, message, occurred_date)
+                VALUES (?, ?, ?, ?, ?, ?, ?)
+                RETURNING *
+                "#,
+            )
+            .bind(run_id)
+            .bind(job.id)
+            .bind(job.location_id)
+            .bind(&error.path)
+            .bind(error.kind)
+            .bind(&error.message)
+            .bind(&now)
+            .fetch_one(&mut *tx)
+            .await?;
+            records.push(record);
+        }
+        tx.commit().await?;
+        Ok(records)
+    }
+
+    /// Scan errors matching `filter`, newest first.
+    pub async fn get_scan_errors(&self, filter: &ScanErrorFilter) -> Result<Vec<ScanErrorRecord>> {
+        let path_pattern = filter.path_contains.as_ref().map(|part| format!("%{}%", part));
+        sqlx::query_as::<_, ScanErrorRecord>(
+            r#"
+            SELECT * FROM scan_errors
+            WHERE (? IS NULL OR location_id = ?)
+              AND (? IS NULL OR run_id = ?)
+              AND (? IS NULL OR kind = ?)
+              AND (? IS NULL OR path LIKE ?)
+              AND (? OR resolved_date IS NULL)
+            ORDER BY id DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(filter.location_id)
+        .bind(filter.location_id)
+        .bind(filter.run_id)
+        .bind(filter.run_id)
+        .bind(filter.kind)
+        .bind(filter.kind)
+        .bind(&path_pattern)
+        .bind(&path_pattern)
+        .bind(filter.include_resolved)
+        .bind(filter.limit.unwrap_or(DEFAULT_SCAN_ERROR_LIMIT).max(1))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Error fetching scan errors: {}", e))
+    }
+
+    /// Unresolved errors of a location, one per path and kind: a path that failed in several
+    /// runs is retried once.
+    pub async fn get_unresolved_scan_errors(&self, location_id: i64) -> Result<Vec<ScanErrorRecord>> {
+        sqlx::query_as::<_, ScanErrorRecord>(
+            r#"
+            SELECT * FROM scan_errors
+            WHERE location_id = ? AND resolved_date IS NULL
+              AND id IN (SELECT MAX(id) FROM scan_errors WHERE location_id = ? AND resolved_date IS NULL GROUP BY path, kind)
+            ORDER BY id
+            "#,
+        )
+        .bind(location_id)
+        .bind(location_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Error fetching scan errors of location {}: {}", location_id, e))
+    }
+
+    /// Mark every unresolved error of `path` and `kind` in a location as resolved.
+    pub async fn resolve_scan_errors(&self, location_id: i64, path: &str, kind: ScanErrorKind) -> Result<()> {
+        sqlx::query(
+            "UPDATE scan_errors SET resolved_date = ? WHERE location_id = ? AND path = ? AND kind = ? AND resolved_date IS NULL",
+        )
+        .bind(self.timestamp())
+        .bind(location_id)
+        .bind(path)
+        .bind(kind)
+        .execute(&self.pool)
         .await?;
         Ok(())
     }
 
+    /// Replace the message of an error whose retry failed again.
+    pub async fn update_scan_error(&self, error_id: i64, error: &ScanError) -> Result<()> {
+        sqlx::query("UPDATE scan_errors SET kind = ?, message = ?, occurred_date = ? WHERE id = ?")
+            .bind(error.kind)
+            .bind(&error.message)
+            .bind(self.timestamp())
+            .bind(error_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
     pub async fn update_asset_orbital_match_details(
         &self,
         asset_id: i64,
@@ -84,77 +1032,24 @@ impl DatabaseManager {
             UPDATE assets
             SET matched_orbital_product_slug = ?,
                 orbital_match_confidence = ?,
-                orbital_match_type = ?
+                orbital_match_type = ?,
+                user_modified_date = ?
             WHERE id = ?
             "#
         )
         .bind(product_slug)
         .bind(confidence)
         .bind(match_type_str)
+        .bind(self.timestamp())
         .bind(asset_id)
-        .execute(self.pool)
+        .execute(&self.pool)
         .await?;
         Ok(())
     }
 
     pub async fn insert_asset(&self, asset: &Asset) -> Result<i64> {
-        let result = sqlx::query(
-            r#"
-            INSERT INTO assets (
-                name, file_path, asset_type, file_size, created_date, modified_date,
-                first_indexed_timestamp, thumbnail_path, tags, description,
-                scan_location_id, is_favorite, last_accessed, file_hash, metadata,
-                orbital_title, orbital_description, orbital_technical_details, orbital_seller_name,
-                orbital_price, orbital_release_date, orbital_last_modified, orbital_rating_average,
-                orbital_rating_count, orbital_categories, orbital_supported_versions, orbital_gallery_images,
-                orbital_thumbnail_url, orbital_source_url, orbital_raw_json, orbital_last_checked_timestamp,
-                matched_orbital_product_slug, orbital_match_confidence, orbital_match_type,
-                notes, orbital_manual_overrides
-            ) VALUES (
-                ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?
-            )
-            "#
-        )
-        .bind(&asset.name)
-        .bind(&asset.file_path)
-        .bind(&asset.asset_type)
-        .bind(asset.file_size)
-        .bind(&asset.created_date)
-        .bind(&asset.modified_date)
-        .bind(asset.first_indexed_timestamp.as_deref())
-        .bind(asset.thumbnail_path.as_deref())
-        .bind(asset.tags.as_deref())
-        .bind(asset.description.as_deref())
-        .bind(asset.scan_location_id)
-        .bind(asset.is_favorite)
-        .bind(asset.last_accessed.as_deref())
-        .bind(asset.file_hash.as_deref())
-        .bind(asset.metadata.as_deref())
-        .bind(asset.orbital_title.as_deref())
-        .bind(asset.orbital_description.as_deref())
-        .bind(asset.orbital_technical_details.as_deref())
-        .bind(asset.orbital_seller_name.as_deref())
-        .bind(asset.orbital_price)
-        .bind(asset.orbital_release_date.as_deref())
-        .bind(asset.orbital_last_modified.as_deref())
-        .bind(asset.orbital_rating_average)
-        .bind(asset.orbital_rating_count)
-        .bind(asset.orbital_categories.as_deref())
-        .bind(asset.orbital_supported_versions.as_deref())
-        .bind(asset.orbital_gallery_images.as_deref())
-        .bind(asset.orbital_thumbnail_url.as_deref())
-        .bind(asset.orbital_source_url.as_deref())
-        .bind(asset.orbital_raw_json.as_deref())
-        .bind(asset.orbital_last_checked_timestamp.as_deref())
-        .bind(asset.matched_orbital_product_slug.as_deref())
-        .bind(asset.orbital_match_confidence)
-        .bind(asset.orbital_match_type.as_deref())
-        .bind(asset.notes.as_deref())
-        .bind(asset.orbital_manual_overrides.as_deref())
-        .execute(self.pool)
-        .await?;
-
-        Ok(result.last_insert_rowid())
+        let mut conn = self.pool.acquire().await?;
+        insert_asset_with(&mut conn, asset).await
     }
 
     /// Fetch all assets that have cached raw Orbital JSON data
@@ -162,7 +1057,7 @@ impl DatabaseManager {
         sqlx::query_as::<_, Asset>(
             "SELECT * FROM assets WHERE orbital_raw_json IS NOT NULL"
         )
-        .fetch_all(self.pool)
+        .fetch_all(&self.pool)
         .await
         .map_err(|e| anyhow::anyhow!("Error fetching assets with raw JSON: {}", e))
     }
@@ -170,12 +1065,13 @@ impl DatabaseManager {
     /// Update the `orbital_manual_overrides` column for a specific asset
     pub async fn update_asset_manual_overrides(&self, asset_id: i64, overrides_json: &str) -> Result<()> {
         sqlx::query(
-            "UPDATE assets SET orbital_manual_overrides = ?, modified_date = ? WHERE id = ?"
+            "UPDATE assets SET orbital_manual_overrides = ?, modified_date = ?, user_modified_date = ? WHERE id = ?"
         )
         .bind(overrides_json)
-        .bind(Utc::now().to_rfc3339())
+        .bind(self.timestamp())
+        .bind(self.timestamp())
         .bind(asset_id)
-        .execute(self.pool)
+        .execute(&self.pool)
         .await
         .map(|_| ())
         .map_err(|e| anyhow::anyhow!("Failed to update manual overrides: {}", e))
@@ -185,4 +1081,147 @@ impl DatabaseManager {
     pub async fn update_asset_with_orbital_details(&self, asset_id: i64, orbital_data: &OrbitalAsset) -> Result<()> {
         crate::database::orbital_helpers::update_asset_with_orbital_details(self, asset_id, orbital_data).await
     }
-}
\ No newline at end of file
+}
+
+async fn save_scanned_folder(conn: &mut SqliteConnection, folder: &ScannedFolder, now: &str) -> Result<(Asset, bool)> {
+    let existing = sqlx::query_as::<_, Asset>("SELECT * FROM assets WHERE file_path = ?")
+        .bind(&folder.asset.file_path)
+        .fetch_optional(&mut *conn)
+        .await?;
+
+    let (asset, inserted) = match existing {
+        Some(mut asset) => {
+            sqlx::query("UPDATE assets SET file_size = ?, modified_date = ?, file_hash = ? WHERE id = ?")
+                .bind(folder.asset.file_size)
+                .bind(&folder.asset.modified_date)
+                .bind(&folder.asset.file_hash)
+                .bind(asset.id)
+                .execute(&mut *conn)
+                .await?;
+            asset.file_size = folder.asset.file_size;
+            asset.modified_date = folder.asset.modified_date.clone();
+            asset.file_hash = folder.asset.file_hash.clone();
+            (asset, false)
+        }
+        None => {
+            let mut asset = folder.asset.clone();
+            let asset_id = insert_asset_with(conn, &asset).await?;
+            write_categorization(conn, asset_id, &folder.categories, now).await?;
+            asset.id = Some(asset_id);
+            (asset, true)
+        }
+    };
+
+    if let Some(asset_id) = asset.id {
+        write_asset_inventory(conn, asset_id, &folder.inventory).await?;
+    }
+    Ok((asset, inserted))
+}
+
+async fn insert_asset_with(conn: &mut SqliteConnection, asset: &Asset) -> Result<i64> {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO assets (
+            name, file_path, relative_path, asset_type, file_size, created_date, modified_date,
+            first_indexed_timestamp, thumbnail_path, tags, description,
+            scan_location_id, is_favorite, last_accessed, file_hash, metadata,
+            orbital_title, orbital_description, orbital_technical_details, orbital_seller_name,
+            orbital_price, orbital_release_date, orbital_last_modified, orbital_rating_average,
+            orbital_rating_count, orbital_categories, orbital_supported_versions, orbital_gallery_images,
+            orbital_thumbnail_url, orbital_source_url, orbital_raw_json, orbital_last_checked_timestamp,
+            matched_orbital_product_slug, orbital_match_confidence, orbital_match_type,
+            notes, orbital_manual_overrides
+        ) VALUES (
+            ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?
+        )
+        "#
+    )
+    .bind(&asset.name)
+    .bind(&asset.file_path)
+    .bind(asset.relative_path.as_deref())
+    .bind(&asset.asset_type)
+    .bind(asset.file_size)
+    .bind(&asset.created_date)
+    .bind(&asset.modified_date)
+    .bind(asset.first_indexed_timestamp.as_deref())
+    .bind(asset.thumbnail_path.as_deref())
+    .bind(asset.tags.as_deref())
+    .bind(asset.description.as_deref())
+    .bind(asset.scan_location_id)
+    .bind(asset.is_favorite)
+    .bind(asset.last_accessed.as_deref())
+    .bind(asset.file_hash.as_deref())
+    .bind(asset.metadata.as_deref())
+    .bind(asset.orbital_title.as_deref())
+    .bind(asset.orbital_description.as_deref())
+    .bind(asset.orbital_technical_details.as_deref())
+    .bind(asset.orbital_seller_name.as_deref())
+    .bind(asset.orbital_price)
+    .bind(asset.orbital_release_date.as_deref())
+    .bind(asset.orbital_last_modified.as_deref())
+    .bind(asset.orbital_rating_average)
+    .bind(asset.orbital_rating_count)
+    .bind(asset.orbital_categories.as_deref())
+    .bind(asset.orbital_supported_versions.as_deref())
+    .bind(asset.orbital_gallery_images.as_deref())
+    .bind(asset.orbital_thumbnail_url.as_deref())
+    .bind(asset.orbital_source_url.as_deref())
+    .bind(asset.orbital_raw_json.as_deref())
+    .bind(asset.orbital_last_checked_timestamp.as_deref())
+    .bind(asset.matched_orbital_product_slug.as_deref())
+    .bind(asset.orbital_match_confidence)
+    .bind(asset.orbital_match_type.as_deref())
+    .bind(asset.notes.as_deref())
+    .bind(asset.orbital_manual_overrides.as_deref())
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(result.last_insert_rowid())
+}
+
+async fn write_categorization(conn: &mut SqliteConnection, asset_id: i64, categorization: &Categorization, now: &str) -> Result<()> {
+    sqlx::query("DELETE FROM asset_categories WHERE asset_id = ?")
+        .bind(asset_id)
+        .execute(&mut *conn)
+        .await?;
+
+    let assignments = std::iter::once((&categorization.primary, true)).chain(categorization.secondary.iter().map(|a| (a, false)));
+    for (assignment, is_primary) in assignments {
+        sqlx::query(
+            "INSERT OR IGNORE INTO asset_categories (asset_id, category, confidence, rule, is_primary, assigned_date) VALUES (?, ?, ?, ?, ?, ?)"
+        )
+        .bind(asset_id)
+        .bind(&assignment.category)
+        .bind(assignment.confidence)
+        .bind(&assignment.rule)
+        .bind(is_primary)
+        .bind(now)
+        .execute(&mut *conn)
+        .await?;
+    }
+    Ok(())
+}
+
+async fn write_asset_inventory(conn: &mut SqliteConnection, asset_id: i64, inventory: &FolderInventory) -> Result<()> {
+    sqlx::query("DELETE FROM asset_inventory WHERE asset_id = ?")
+        .bind(asset_id)
+        .execute(&mut *conn)
+        .await?;
+
+    let groups = [(INVENTORY_BY_EXTENSION, &inventory.by_extension), (INVENTORY_BY_FOLDER, &inventory.by_folder)];
+    for (kind, entries) in groups {
+        for (key, totals) in entries {
+            sqlx::query(
+                "INSERT INTO asset_inventory (asset_id, group_kind, group_key, file_count, total_bytes) VALUES (?, ?, ?, ?, ?)"
+            )
+            .bind(asset_id)
+            .bind(kind)
+            .bind(key)
+            .bind(totals.file_count)
+            .bind(totals.total_bytes)
+            .execute(&mut *conn)
+            .await?;
+        }
+    }
+    Ok(())
+}
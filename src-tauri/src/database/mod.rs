@@ -1,17 +1,27 @@
 pub mod database_manager;
 pub use database_manager::DatabaseManager;
 pub mod orbital_helpers;
+pub mod store;
+pub use store::AssetStore;
 
 use anyhow::Result;
+use fs2::FileExt;
 use sqlx::{Pool, Sqlite, sqlite::SqlitePool, Row};
+use std::fs::{File, OpenOptions};
+use std::path::{Component, Path, PathBuf};
+use std::sync::OnceLock;
 
 use crate::database::database_manager::DB_POOL;
 
-/// Initialize the global SQLite connection pool and create tables if they do not exist.
-///
-/// This mirrors the old implementation that lived in `mod_temp.rs`, but now reflects the
-/// current schema that contains Orbital-related columns (rating, version, overrides, …).
-pub async fn init_database() -> Result<()> {
+/// Points the app and the command-line tool at another data directory.
+pub const DATA_DIR_ENV: &str = "OMNIDEX_DATA_DIR";
+
+/// Directory holding `omnidex.db` (and, unless configured otherwise, its backups).
+pub fn data_dir() -> PathBuf {
+    if let Some(dir) = std::env::var_os(DATA_DIR_ENV).filter(|dir| !dir.is_empty()) {
+        return PathBuf::from(dir);
+    }
+
     // Determine a writable directory within the user profile
     let app_data_dir = if cfg!(target_os = "windows") {
         std::env::var("APPDATA").unwrap_or_else(|_| std::env::var("USERPROFILE").unwrap_or_else(|_| ".".to_string()))
@@ -21,12 +31,53 @@ pub async fn init_database() -> Result<()> {
         std::env::var("HOME").map(|home| format!("{home}/.local/share")).unwrap_or_else(|_| ".".to_string())
     };
 
-    let db_dir = std::path::Path::new(&app_data_dir).join("Omnidex");
-    if !db_dir.exists() {
-        std::fs::create_dir_all(&db_dir)?;
+    Path::new(&app_data_dir).join("Omnidex")
+}
+
+pub fn database_path() -> PathBuf {
+    data_dir().join("omnidex.db")
+}
+
+/// File in the data directory that the app and the command-line tool lock while they run.
+const LOCK_FILE: &str = "omnidex.lock";
+
+static DATA_DIR_LOCK: OnceLock<File> = OnceLock::new();
+
+/// Lock the data directory for the rest of the process, so the app and the command-line tool
+/// never work on the same `omnidex.db` at once. Fails right away if another process holds it.
+pub fn lock_data_dir() -> Result<()> {
+    if DATA_DIR_LOCK.get().is_none() {
+        let _ = DATA_DIR_LOCK.set(lock_dir(&data_dir())?);
+    }
+    Ok(())
+}
+
+/// Take the lock file in `dir`; it is held until the returned file is closed.
+fn lock_dir(dir: &Path) -> Result<File> {
+    std::fs::create_dir_all(dir)?;
+    let file = OpenOptions::new().create(true).truncate(false).write(true).open(dir.join(LOCK_FILE))?;
+    file.try_lock_exclusive().map_err(|e| {
+        if e.kind() == fs2::lock_contended_error().kind() {
+            anyhow::anyhow!("{} is in use by another Omnidex app or command-line tool", dir.display())
+        } else {
+            anyhow::anyhow!("Failed to lock {}: {}", dir.display(), e)
+        }
+    })?;
+    Ok(file)
+}
+
+/// Initialize the global SQLite connection pool and create tables if they do not exist.
+///
+/// This mirrors the old implementation that lived in `mod_temp.rs`, but now reflects the
+/// current schema that contains Orbital-related columns (rating, version, overrides, …).
+pub async fn init_database() -> Result<()> {
+    let database_path = database_path();
+    if let Some(db_dir) = database_path.parent() {
+        if !db_dir.exists() {
+            std::fs::create_dir_all(db_dir)?;
+        }
     }
 
-    let database_path = db_dir.join("omnidex.db");
     if !database_path.exists() {
         std::fs::File::create(&database_path)?;
     }
@@ -46,7 +97,7 @@ pub async fn init_database() -> Result<()> {
 
 /// Low-level helper that creates (or upgrades) the SQLite schema.
 /// Only additive changes are performed; destructive migrations should be handled separately.
-pub(super) async fn create_tables(pool: &Pool<Sqlite>) -> Result<()> {
+pub(crate) async fn create_tables(pool: &Pool<Sqlite>) -> Result<()> {
     // scan_locations
     sqlx::query(r#"
         CREATE TABLE IF NOT EXISTS scan_locations (
@@ -58,7 +109,11 @@ pub(super) async fn create_tables(pool: &Pool<Sqlite>) -> Result<()> {
             scan_recursive BOOLEAN NOT NULL DEFAULT 0,
             file_extensions TEXT,
             created_date TEXT NOT NULL,
-            description TEXT
+            description TEXT,
+            watch_enabled BOOLEAN NOT NULL DEFAULT 0,
+            location_kind TEXT NOT NULL DEFAULT 'vault',
+            root_rules TEXT,
+            team_key TEXT
         )
     "#).execute(pool).await?;
 
@@ -68,6 +123,7 @@ pub(super) async fn create_tables(pool: &Pool<Sqlite>) -> Result<()> {
             id INTEGER PRIMARY KEY AUTOINCREMENT,
             name TEXT NOT NULL,
             file_path TEXT NOT NULL UNIQUE,
+            relative_path TEXT,
             asset_type TEXT NOT NULL,
             file_size INTEGER NOT NULL,
             created_date TEXT NOT NULL,
@@ -108,6 +164,12 @@ pub(super) async fn create_tables(pool: &Pool<Sqlite>) -> Result<()> {
             -- User additions
             notes TEXT,
             orbital_manual_overrides TEXT,
+            user_modified_date TEXT,
+            packages_indexed_date TEXT,
+
+            -- Team library sync state
+            team_revision INTEGER,
+            team_synced_date TEXT,
 
             FOREIGN KEY (scan_location_id) REFERENCES scan_locations(id) ON DELETE CASCADE
         )
@@ -126,37 +188,376 @@ pub(super) async fn create_tables(pool: &Pool<Sqlite>) -> Result<()> {
         )
     "#).execute(pool).await?;
 
+    // collections – user-curated groups of assets
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS collections (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE,
+            description TEXT,
+            created_date TEXT NOT NULL,
+            modified_date TEXT NOT NULL
+        )
+    "#).execute(pool).await?;
+
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS collection_assets (
+            collection_id INTEGER NOT NULL,
+            asset_id INTEGER NOT NULL,
+            added_date TEXT NOT NULL,
+            PRIMARY KEY (collection_id, asset_id),
+            FOREIGN KEY (collection_id) REFERENCES collections(id) ON DELETE CASCADE,
+            FOREIGN KEY (asset_id) REFERENCES assets(id) ON DELETE CASCADE
+        )
+    "#).execute(pool).await?;
+
+    // asset_project_usage – which Unreal projects an asset was imported into
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS asset_project_usage (
+            asset_id INTEGER NOT NULL,
+            project_path TEXT NOT NULL,
+            project_name TEXT NOT NULL,
+            import_mode TEXT NOT NULL,
+            imported_date TEXT NOT NULL,
+            PRIMARY KEY (asset_id, project_path),
+            FOREIGN KEY (asset_id) REFERENCES assets(id) ON DELETE CASCADE
+        )
+    "#).execute(pool).await?;
+
+    // asset_packages – Unreal packages shipped in each library asset, for project cross-referencing
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS asset_packages (
+            asset_id INTEGER NOT NULL,
+            relative_path TEXT NOT NULL,
+            package_name TEXT NOT NULL,
+            file_size INTEGER NOT NULL,
+            content_hash TEXT,
+            PRIMARY KEY (asset_id, relative_path),
+            FOREIGN KEY (asset_id) REFERENCES assets(id) ON DELETE CASCADE
+        )
+    "#).execute(pool).await?;
+
+    // asset_inventory – file counts and bytes per asset, grouped by extension and by top-level subfolder
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS asset_inventory (
+            asset_id INTEGER NOT NULL,
+            group_kind TEXT NOT NULL,
+            group_key TEXT NOT NULL,
+            file_count INTEGER NOT NULL,
+            total_bytes INTEGER NOT NULL,
+            PRIMARY KEY (asset_id, group_kind, group_key),
+            FOREIGN KEY (asset_id) REFERENCES assets(id) ON DELETE CASCADE
+        )
+    "#).execute(pool).await?;
+
+    // asset_categories – every category of each asset and how it was chosen; the primary one mirrors assets.asset_type
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS asset_categories (
+            asset_id INTEGER NOT NULL,
+            category TEXT NOT NULL,
+            confidence REAL NOT NULL,
+            rule TEXT NOT NULL,
+            is_primary BOOLEAN NOT NULL DEFAULT 0,
+            assigned_date TEXT NOT NULL,
+            PRIMARY KEY (asset_id, category),
+            FOREIGN KEY (asset_id) REFERENCES assets(id) ON DELETE CASCADE
+        )
+    "#).execute(pool).await?;
+
+    // project_packages – packages found in indexed Unreal projects and what they match in the library
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS project_packages (
+            location_id INTEGER NOT NULL,
+            relative_path TEXT NOT NULL,
+            package_name TEXT NOT NULL,
+            file_size INTEGER NOT NULL,
+            modified_date TEXT NOT NULL,
+            content_hash TEXT NOT NULL,
+            file_version_ue4 INTEGER,
+            file_version_ue5 INTEGER,
+            package_flags INTEGER,
+            matched_asset_id INTEGER,
+            match_kind TEXT,
+            PRIMARY KEY (location_id, relative_path),
+            FOREIGN KEY (location_id) REFERENCES scan_locations(id) ON DELETE CASCADE,
+            FOREIGN KEY (matched_asset_id) REFERENCES assets(id) ON DELETE SET NULL
+        )
+    "#).execute(pool).await?;
+
+    // scan_jobs – folder scans persisted with a checkpoint so they can resume after a pause or restart
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS scan_jobs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            location_id INTEGER NOT NULL,
+            status TEXT NOT NULL,
+            checkpoint_path TEXT,
+            processed_items INTEGER NOT NULL DEFAULT 0,
+            total_items INTEGER NOT NULL DEFAULT 0,
+            assets_found INTEGER NOT NULL DEFAULT 0,
+            error_count INTEGER NOT NULL DEFAULT 0,
+            active_ms INTEGER NOT NULL DEFAULT 0,
+            created_date TEXT NOT NULL,
+            updated_date TEXT NOT NULL,
+            FOREIGN KEY (location_id) REFERENCES scan_locations(id) ON DELETE CASCADE
+        )
+    "#).execute(pool).await?;
+
+    // scan_runs – scan history: one row per run of a scan job
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS scan_runs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            job_id INTEGER NOT NULL,
+            location_id INTEGER NOT NULL,
+            started_date TEXT NOT NULL,
+            finished_date TEXT,
+            duration_ms INTEGER,
+            outcome TEXT,
+            processed_items INTEGER NOT NULL DEFAULT 0,
+            assets_found INTEGER NOT NULL DEFAULT 0,
+            error_count INTEGER NOT NULL DEFAULT 0,
+            FOREIGN KEY (job_id) REFERENCES scan_jobs(id) ON DELETE CASCADE
+        )
+    "#).execute(pool).await?;
+
+    // scan_errors – typed errors per scan run and path; resolved once a retry of the path succeeds
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS scan_errors (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            run_id INTEGER NOT NULL,
+            job_id INTEGER NOT NULL,
+            location_id INTEGER NOT NULL,
+            path TEXT,
+            kind TEXT NOT NULL,
+            message TEXT NOT NULL,
+            occurred_date TEXT NOT NULL,
+            resolved_date TEXT,
+            FOREIGN KEY (run_id) REFERENCES scan_runs(id) ON DELETE CASCADE
+        )
+    "#).execute(pool).await?;
+
+    // duplicate_resolutions – what was done with each duplicate copy the user resolved
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS duplicate_resolutions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            action TEXT NOT NULL,
+            asset_name TEXT NOT NULL,
+            path TEXT NOT NULL,
+            kept_asset_id INTEGER,
+            kept_path TEXT NOT NULL,
+            archive_path TEXT,
+            bytes_reclaimed INTEGER NOT NULL,
+            resolved_date TEXT NOT NULL,
+            FOREIGN KEY (kept_asset_id) REFERENCES assets(id) ON DELETE SET NULL
+        )
+    "#).execute(pool).await?;
+
+    // asset_versions – engine and release of the copy on disk, re-detected when the asset changes
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS asset_versions (
+            asset_id INTEGER PRIMARY KEY,
+            engine_version TEXT,
+            version_name TEXT,
+            source TEXT NOT NULL,
+            is_plugin BOOLEAN NOT NULL DEFAULT 0,
+            files_date TEXT,
+            detected_date TEXT NOT NULL,
+            FOREIGN KEY (asset_id) REFERENCES assets(id) ON DELETE CASCADE
+        )
+    "#).execute(pool).await?;
+
+    // asset_licenses – who owns the license of an asset and what was paid for it; kept, with the
+    // asset's name and path, when the asset leaves the library
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS asset_licenses (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            asset_id INTEGER,
+            asset_name TEXT NOT NULL,
+            asset_path TEXT NOT NULL,
+            owner TEXT NOT NULL,
+            purchase_date TEXT,
+            price_paid REAL,
+            currency TEXT NOT NULL DEFAULT 'USD',
+            price_from_marketplace BOOLEAN NOT NULL DEFAULT 0,
+            license_type TEXT NOT NULL,
+            seats INTEGER NOT NULL DEFAULT 1,
+            order_reference TEXT,
+            seller TEXT,
+            created_date TEXT NOT NULL,
+            modified_date TEXT NOT NULL,
+            FOREIGN KEY (asset_id) REFERENCES assets(id) ON DELETE SET NULL
+        )
+    "#).execute(pool).await?;
+
+    // asset_provider_metadata – each metadata provider's result for an asset, as JSON
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS asset_provider_metadata (
+            asset_id INTEGER NOT NULL,
+            provider TEXT NOT NULL,
+            reference TEXT NOT NULL,
+            match_kind TEXT NOT NULL,
+            confidence REAL NOT NULL,
+            fetched_date TEXT NOT NULL,
+            metadata TEXT NOT NULL,
+            PRIMARY KEY (asset_id, provider),
+            FOREIGN KEY (asset_id) REFERENCES assets(id) ON DELETE CASCADE
+        )
+    "#).execute(pool).await?;
+
     // indexes
     sqlx::query("CREATE INDEX IF NOT EXISTS idx_assets_type ON assets(asset_type)").execute(pool).await?;
     sqlx::query("CREATE INDEX IF NOT EXISTS idx_assets_location ON assets(scan_location_id)").execute(pool).await?;
     sqlx::query("CREATE INDEX IF NOT EXISTS idx_assets_favorite ON assets(is_favorite)").execute(pool).await?;
     sqlx::query("CREATE INDEX IF NOT EXISTS idx_assets_file_hash ON assets(file_hash)").execute(pool).await?;
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_collection_assets_asset ON collection_assets(asset_id)").execute(pool).await?;
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_asset_packages_name ON asset_packages(package_name)").execute(pool).await?;
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_asset_packages_hash ON asset_packages(content_hash)").execute(pool).await?;
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_project_packages_asset ON project_packages(matched_asset_id)").execute(pool).await?;
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_asset_categories_category ON asset_categories(category)").execute(pool).await?;
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_scan_jobs_location ON scan_jobs(location_id, status)").execute(pool).await?;
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_scan_runs_location ON scan_runs(location_id, started_date)").execute(pool).await?;
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_scan_errors_location ON scan_errors(location_id, kind)").execute(pool).await?;
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_scan_errors_run ON scan_errors(run_id)").execute(pool).await?;
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_asset_licenses_asset ON asset_licenses(asset_id)").execute(pool).await?;
 
     // --- Lightweight migration: ensure recently added columns exist -------------------------
     // This avoids breaking older user databases created before new columns were introduced.
-    // We currently only need to guarantee the `orbital_manual_overrides` column.
-    // If more columns are added later, extend this list.
+    // If more columns are added later, extend these lists.
+    ensure_columns(pool, "assets", &[
+        ("orbital_manual_overrides", "TEXT"),
+        ("user_modified_date", "TEXT"),
+        ("relative_path", "TEXT"),
+        ("packages_indexed_date", "TEXT"),
+        ("team_revision", "INTEGER"),
+        ("team_synced_date", "TEXT"),
+    ]).await?;
+    ensure_columns(pool, "scan_locations", &[
+        ("watch_enabled", "BOOLEAN NOT NULL DEFAULT 0"),
+        ("location_kind", "TEXT NOT NULL DEFAULT 'vault'"),
+        ("root_rules", "TEXT"),
+        ("team_key", "TEXT"),
+    ]).await?;
+    backfill_relative_paths(pool).await?;
+    drop_folder_name_hashes(pool).await?;
+    crate::changelog::create_change_log(pool).await?;
+
+    Ok(())
+}
 
-    let existing_columns: Vec<String> = sqlx::query("PRAGMA table_info(assets)")
+/// Add any of `columns` (name, SQL definition) that are missing from `table`.
+/// SQLite allows a simple ALTER TABLE for this.
+async fn ensure_columns(pool: &Pool<Sqlite>, table: &str, columns: &[(&str, &str)]) -> Result<()> {
+    let existing_columns: Vec<String> = sqlx::query(&format!("PRAGMA table_info({})", table))
         .fetch_all(pool)
         .await?
         .into_iter()
         .map(|row| row.get::<String, _>("name"))
         .collect();
 
-    if !existing_columns.iter().any(|c| c == "orbital_manual_overrides") {
-        // Add the column. SQLite allows a simple ALTER TABLE for this.
-        sqlx::query("ALTER TABLE assets ADD COLUMN orbital_manual_overrides TEXT")
-            .execute(pool)
+    for (column, definition) in columns {
+        if !existing_columns.iter().any(|c| c == column) {
+            sqlx::query(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition))
+                .execute(pool)
+                .await?;
+            tracing::info!("🔄 Migrated {} table: added missing column '{}'.", table, column);
+        }
+    }
+    Ok(())
+}
+
+/// Fill `relative_path` for assets indexed before paths were stored relative to their scan location.
+async fn backfill_relative_paths(pool: &Pool<Sqlite>) -> Result<()> {
+    let rows: Vec<(i64, String, String)> = sqlx::query_as(
+        "SELECT a.id, a.file_path, l.path FROM assets a
+         JOIN scan_locations l ON l.id = a.scan_location_id
+         WHERE a.relative_path IS NULL",
+    )
+    .fetch_all(pool)
+    .await?;
+    if rows.is_empty() {
+        return Ok(());
+    }
+
+    let mut tx = pool.begin().await?;
+    for (id, file_path, location_path) in &rows {
+        sqlx::query("UPDATE assets SET relative_path = ? WHERE id = ?")
+            .bind(relative_asset_path(Path::new(location_path), Path::new(file_path)))
+            .bind(id)
+            .execute(&mut *tx)
             .await?;
-        tracing::info!("🔄 Migrated assets table: added missing column 'orbital_manual_overrides'.");
     }
+    tx.commit().await?;
+    tracing::info!("🔄 Stored relative paths for {} existing assets.", rows.len());
+    Ok(())
+}
 
+/// Before content fingerprints, `file_hash` held a hash of the folder name (at most 16 hex
+/// digits), which made every folder with the same name look like a copy. Those are dropped;
+/// the next scan stores the fingerprint.
+async fn drop_folder_name_hashes(pool: &Pool<Sqlite>) -> Result<()> {
+    let dropped = sqlx::query("UPDATE assets SET file_hash = NULL WHERE length(file_hash) < 32")
+        .execute(pool)
+        .await?
+        .rows_affected();
+    if dropped > 0 {
+        tracing::info!("🔄 Cleared {} folder name hashes; rescan to fingerprint those assets.", dropped);
+    }
     Ok(())
 }
 
+/// Path of an asset relative to its scan location `root`, joined with `/` so the stored
+/// value is the same on every platform. Paths outside `root` keep all their normal components.
+pub fn relative_asset_path(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(part) => Some(part.to_string_lossy()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Absolute path of an asset stored as `relative` under the scan location `root`.
+pub fn resolve_asset_path(root: &Path, relative: &str) -> PathBuf {
+    relative
+        .split('/')
+        .filter(|part| !part.is_empty())
+        .fold(root.to_path_buf(), |path, part| path.join(part))
+}
+
 /// Convenience accessor used by command handlers that prefer raw `sqlx` queries.
 /// Panics if the pool has not been initialised yet (should only happen very early in startup).
 pub fn get_db() -> &'static Pool<Sqlite> {
     DB_POOL.get().expect("Database has not been initialised. Call init_database() first")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relative_paths_use_forward_slashes() {
+        let root = Path::new("vault");
+        let file = root.join("Packs").join("Forest");
+        assert_eq!(relative_asset_path(root, &file), "Packs/Forest");
+        let mount = Path::new("mnt").join("vault");
+        assert_eq!(resolve_asset_path(&mount, "Packs/Forest"), mount.join("Packs").join("Forest"));
+    }
+
+    #[test]
+    fn test_paths_outside_the_root_keep_their_components() {
+        let file = Path::new("other").join("Packs").join("Forest");
+        assert_eq!(relative_asset_path(Path::new("vault"), &file), "other/Packs/Forest");
+        assert_eq!(resolve_asset_path(Path::new("vault"), "/Packs//Forest/"), Path::new("vault").join("Packs").join("Forest"));
+    }
+
+    #[test]
+    fn test_data_dir_lock_is_exclusive() {
+        let dir = std::env::temp_dir().join(format!("omnidex-lock-{}", std::process::id()));
+        let held = lock_dir(&dir).unwrap();
+        assert!(lock_dir(&dir).unwrap_err().to_string().contains("in use"));
+        drop(held);
+        assert!(lock_dir(&dir).is_ok());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -74,7 +74,7 @@ pub async fn update_asset_with_orbital_details(
     .bind(None::<f64>)
     .bind(None::<String>)
     .bind(asset_id)
-    .execute(db_manager.pool)
+    .execute(&db_manager.pool)
     .await?;
 
     Ok(())
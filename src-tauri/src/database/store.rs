@@ -0,0 +1,51 @@
+//! The part of the store the Orbital layer writes matches through, so matching can be tested
+//! against a fake. Everything else reads and writes the database through `DatabaseManager`.
+
+use anyhow::Result;
+use async_trait::async_trait;
+
+use super::DatabaseManager;
+use crate::orbital::models::OrbitalAsset;
+
+#[async_trait]
+pub trait AssetStore: Send + Sync {
+    /// Whether there is an asset with this ID.
+    async fn asset_exists(&self, asset_id: i64) -> Result<bool>;
+
+    /// Replace the Orbital metadata stored for an asset.
+    async fn update_asset_with_orbital_details(&self, asset_id: i64, orbital_data: &OrbitalAsset) -> Result<()>;
+
+    /// Record which product an asset was matched to, and how.
+    async fn update_asset_orbital_match_details(
+        &self,
+        asset_id: i64,
+        product_slug: Option<String>,
+        confidence: Option<f64>,
+        match_type_str: Option<String>,
+    ) -> Result<()>;
+}
+
+#[async_trait]
+impl AssetStore for DatabaseManager {
+    async fn asset_exists(&self, asset_id: i64) -> Result<bool> {
+        let existing = sqlx::query_scalar::<_, i64>("SELECT id FROM assets WHERE id = ?")
+            .bind(asset_id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(existing.is_some())
+    }
+
+    async fn update_asset_with_orbital_details(&self, asset_id: i64, orbital_data: &OrbitalAsset) -> Result<()> {
+        DatabaseManager::update_asset_with_orbital_details(self, asset_id, orbital_data).await
+    }
+
+    async fn update_asset_orbital_match_details(
+        &self,
+        asset_id: i64,
+        product_slug: Option<String>,
+        confidence: Option<f64>,
+        match_type_str: Option<String>,
+    ) -> Result<()> {
+        DatabaseManager::update_asset_orbital_match_details(self, asset_id, product_slug, confidence, match_type_str).await
+    }
+}
@@ -0,0 +1,745 @@
+//! Finding copies of the same content in the library, and cleaning them up.
+//!
+//! Three kinds of duplicate groups are reported:
+//! - identical: assets with the same fingerprint, i.e. the same files by relative path, size and
+//!   modification time;
+//! - superset: Unreal packs whose packages all ship, at the same size, in one larger pack;
+//! - versions: assets matched to the same Orbital product whose contents differ.
+//!
+//! Assets nested inside one another are never grouped. Resolving a group keeps one asset and
+//! removes, hard-links or archives the others, after comparing their contents on disk. What
+//! the user added to a removed or archived copy (tags, favorite, notes, collections, licenses,
+//! project imports, manual categories and links) is carried over to the kept asset, and every
+//! resolved copy is recorded in `duplicate_resolutions`.
+
+use anyhow::{anyhow, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::categories::MANUAL_RULE;
+use crate::database::DatabaseManager;
+use crate::models::Asset;
+use crate::scanner::utils::hash_file_contents;
+use crate::transfer::{non_empty, split_tags};
+
+/// Ordered from the closest relation to the loosest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateKind {
+    Identical,
+    Superset,
+    Versions,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateMember {
+    pub asset_id: i64,
+    pub name: String,
+    pub file_path: String,
+    pub file_size: i64,
+    pub modified_date: String,
+    /// Version number found in the name, e.g. `5.3` in `CastleKit_UE5.3`.
+    pub version: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateGroup {
+    pub kind: DuplicateKind,
+    /// The shared fingerprint or Orbital product slug, or the name of the pack holding the others.
+    pub key: String,
+    /// The copy suggested to keep; it is listed first in `members`.
+    pub keep_asset_id: i64,
+    pub members: Vec<DuplicateMember>,
+    /// Bytes freed by removing every member but the kept one.
+    pub reclaimable_bytes: i64,
+}
+
+/// What to do with the copies that are not kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResolutionAction {
+    /// Delete the copy from disk and from the library.
+    Remove,
+    /// Replace each file of the copy with a hard link to the kept asset's identical file.
+    /// Both assets stay in the library. Only possible on the same volume.
+    HardLink,
+    /// Move the copy into an archive folder and take it out of the library.
+    Archive,
+}
+
+impl ResolutionAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ResolutionAction::Remove => "remove",
+            ResolutionAction::HardLink => "hard_link",
+            ResolutionAction::Archive => "archive",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ResolutionRequest {
+    pub keep_asset_id: i64,
+    /// The copies to remove, hard-link or archive.
+    pub asset_ids: Vec<i64>,
+    pub action: ResolutionAction,
+    /// Folder the copies are moved into; needed for `archive`.
+    #[serde(default)]
+    pub archive_dir: Option<PathBuf>,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// A resolved copy, as recorded in `duplicate_resolutions`.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct DuplicateResolution {
+    /// `None` in a dry run, where nothing is recorded.
+    pub id: Option<i64>,
+    pub action: String,
+    pub asset_name: String,
+    pub path: String,
+    /// `None` once the kept asset was removed from the library as well.
+    pub kept_asset_id: Option<i64>,
+    pub kept_path: String,
+    pub archive_path: Option<String>,
+    pub bytes_reclaimed: i64,
+    pub resolved_date: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ResolutionFailure {
+    pub asset_id: i64,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ResolutionReport {
+    pub dry_run: bool,
+    pub resolved: Vec<DuplicateResolution>,
+    pub failed: Vec<ResolutionFailure>,
+    pub bytes_reclaimed: i64,
+}
+
+#[derive(Debug, Clone, FromRow)]
+struct Candidate {
+    id: i64,
+    name: String,
+    file_path: String,
+    file_size: i64,
+    modified_date: String,
+    file_hash: Option<String>,
+    matched_orbital_product_slug: Option<String>,
+    is_favorite: bool,
+    user_modified_date: Option<String>,
+}
+
+impl Candidate {
+    fn member(&self) -> DuplicateMember {
+        DuplicateMember {
+            asset_id: self.id,
+            name: self.name.clone(),
+            file_path: self.file_path.clone(),
+            file_size: self.file_size,
+            modified_date: self.modified_date.clone(),
+            version: version_in_name(&self.name),
+        }
+    }
+}
+
+/// Every duplicate group in the library, the most reclaimable space first.
+pub async fn find_duplicate_groups(db_manager: &DatabaseManager) -> Result<Vec<DuplicateGroup>> {
+    let candidates: Vec<Candidate> = sqlx::query_as(
+        "SELECT id, name, file_path, file_size, modified_date, file_hash, matched_orbital_product_slug,
+                is_favorite, user_modified_date
+         FROM assets ORDER BY id",
+    )
+    .fetch_all(&db_manager.pool)
+    .await?;
+    let packages: Vec<(i64, String, i64)> =
+        sqlx::query_as("SELECT asset_id, package_name, file_size FROM asset_packages")
+            .fetch_all(&db_manager.pool)
+            .await?;
+
+    let mut groups = identical_groups(&candidates);
+    groups.extend(superset_groups(&candidates, packages));
+    groups.extend(version_groups(&candidates));
+    groups.sort_by_key(|group| Reverse(group.reclaimable_bytes));
+    Ok(groups)
+}
+
+fn identical_groups(candidates: &[Candidate]) -> Vec<DuplicateGroup> {
+    let mut by_hash: BTreeMap<&str, Vec<&Candidate>> = BTreeMap::new();
+    for candidate in candidates {
+        if let Some(hash) = candidate.file_hash.as_deref() {
+            by_hash.entry(hash).or_default().push(candidate);
+        }
+    }
+
+    by_hash
+        .into_iter()
+        .filter_map(|(hash, copies)| {
+            let copies = outermost(copies);
+            // Prefer the copy the user has worked with, then the one indexed first.
+            let keep = copies
+                .iter()
+                .max_by_key(|c| (c.is_favorite, c.user_modified_date.is_some(), Reverse(c.id)))?;
+            (copies.len() > 1).then(|| build_group(DuplicateKind::Identical, hash.to_string(), keep, &copies))
+        })
+        .collect()
+}
+
+/// Packs whose every package (by name and size) is in a larger pack, grouped under the largest
+/// pack that holds them.
+fn superset_groups(candidates: &[Candidate], packages: Vec<(i64, String, i64)>) -> Vec<DuplicateGroup> {
+    let by_id: HashMap<i64, &Candidate> = candidates.iter().map(|c| (c.id, c)).collect();
+    let mut sets: BTreeMap<i64, HashSet<(String, i64)>> = BTreeMap::new();
+    for (asset_id, package_name, file_size) in packages {
+        sets.entry(asset_id).or_default().insert((package_name, file_size));
+    }
+    let mut holders: HashMap<&(String, i64), Vec<i64>> = HashMap::new();
+    for (asset_id, set) in &sets {
+        for package in set {
+            holders.entry(package).or_default().push(*asset_id);
+        }
+    }
+
+    let mut contained: BTreeMap<i64, Vec<i64>> = BTreeMap::new();
+    for (asset_id, set) in &sets {
+        let Some(candidate) = by_id.get(asset_id) else {
+            continue;
+        };
+        // Only packs holding the rarest package can hold all of them.
+        let Some(rarest) = set.iter().min_by_key(|package| holders[package].len()) else {
+            continue;
+        };
+        let superset = holders[rarest]
+            .iter()
+            .filter(|other| *other != asset_id)
+            .filter_map(|other| Some((other, &sets[other], by_id.get(other)?)))
+            .filter(|(_, other_set, other)| {
+                other_set.len() > set.len() && set.is_subset(other_set) && !is_nested(&candidate.file_path, &other.file_path)
+            })
+            .max_by_key(|(other, other_set, _)| (other_set.len(), Reverse(**other)));
+        if let Some((superset_id, _, _)) = superset {
+            contained.entry(*superset_id).or_default().push(*asset_id);
+        }
+    }
+
+    contained
+        .into_iter()
+        .map(|(superset_id, subset_ids)| {
+            let keep = by_id[&superset_id];
+            let members: Vec<&Candidate> = std::iter::once(keep).chain(subset_ids.iter().map(|id| by_id[id])).collect();
+            build_group(DuplicateKind::Superset, keep.name.clone(), keep, &members)
+        })
+        .collect()
+}
+
+/// Assets matched to the same product, unless they are all identical (reported as such).
+fn version_groups(candidates: &[Candidate]) -> Vec<DuplicateGroup> {
+    let mut by_slug: BTreeMap<&str, Vec<&Candidate>> = BTreeMap::new();
+    for candidate in candidates {
+        if let Some(slug) = candidate.matched_orbital_product_slug.as_deref() {
+            by_slug.entry(slug).or_default().push(candidate);
+        }
+    }
+
+    by_slug
+        .into_iter()
+        .filter_map(|(slug, copies)| {
+            let copies = outermost(copies);
+            let fingerprints: HashSet<Option<&str>> = copies.iter().map(|c| c.file_hash.as_deref()).collect();
+            if copies.len() < 2 || (fingerprints.len() == 1 && !fingerprints.contains(&None)) {
+                return None;
+            }
+            // Keep the most recently changed copy, most likely the latest version.
+            let keep = copies.iter().max_by_key(|c| (c.modified_date.as_str(), Reverse(c.id)))?;
+            Some(build_group(DuplicateKind::Versions, slug.to_string(), keep, &copies))
+        })
+        .collect()
+}
+
+fn build_group(kind: DuplicateKind, key: String, keep: &Candidate, copies: &[&Candidate]) -> DuplicateGroup {
+    let others: Vec<&&Candidate> = copies.iter().filter(|c| c.id != keep.id).collect();
+    DuplicateGroup {
+        kind,
+        key,
+        keep_asset_id: keep.id,
+        reclaimable_bytes: others.iter().map(|c| c.file_size).sum(),
+        members: std::iter::once(keep.member()).chain(others.iter().map(|c| c.member())).collect(),
+    }
+}
+
+/// `copies` without those inside another one of them.
+fn outermost(copies: Vec<&Candidate>) -> Vec<&Candidate> {
+    copies
+        .iter()
+        .filter(|c| {
+            !copies
+                .iter()
+                .any(|other| other.id != c.id && Path::new(&c.file_path).starts_with(&other.file_path))
+        })
+        .copied()
+        .collect()
+}
+
+fn is_nested(a: &str, b: &str) -> bool {
+    Path::new(a).starts_with(b) || Path::new(b).starts_with(a)
+}
+
+static VERSION_PATTERN: OnceLock<Regex> = OnceLock::new();
+
+/// A dotted version number in an asset name, e.g. `5.3` in `CastleKit_UE5.3` or `2.1` in `Rocks v2.1`.
+fn version_in_name(name: &str) -> Option<String> {
+    VERSION_PATTERN
+        .get_or_init(|| Regex::new(r"(?i)(?:^|[^a-z0-9])(?:ue|v)?(\d+(?:\.\d+)+)").unwrap())
+        .captures(name).map(|captures| captures[1].to_string())
+}
+
+/// Keep `keep_asset_id` and remove, hard-link or archive the other copies. Each copy must be
+/// in a duplicate group with the kept asset, and is resolved on its own; one that fails is
+/// reported and the rest still go ahead.
+pub async fn resolve_duplicates(db_manager: &DatabaseManager, request: &ResolutionRequest) -> Result<ResolutionReport> {
+    if request.asset_ids.contains(&request.keep_asset_id) {
+        return Err(anyhow!("Asset {} cannot be both kept and resolved", request.keep_asset_id));
+    }
+    if request.action == ResolutionAction::Archive && request.archive_dir.is_none() {
+        return Err(anyhow!("Archiving duplicates needs an archive folder"));
+    }
+    let kept = db_manager.get_asset_by_id(request.keep_asset_id).await?;
+    if !Path::new(&kept.file_path).exists() {
+        return Err(anyhow!("The asset to keep is not on disk at {}", kept.file_path));
+    }
+
+    // The groups are found again here, so only copies of the kept asset are ever touched.
+    let groups = find_duplicate_groups(db_manager).await?;
+
+    let mut report = ResolutionReport { dry_run: request.dry_run, ..Default::default() };
+    for &asset_id in &request.asset_ids {
+        let resolved = match relation(&groups, request.keep_asset_id, asset_id) {
+            Some(kind) => resolve_copy(db_manager, &kept, asset_id, kind, request).await,
+            None => Err(anyhow!("Asset {} is not a duplicate of asset {}", asset_id, request.keep_asset_id)),
+        };
+        match resolved {
+            Ok(resolution) => {
+                report.bytes_reclaimed += resolution.bytes_reclaimed;
+                report.resolved.push(resolution);
+            }
+            Err(e) => {
+                tracing::warn!("Failed to resolve duplicate asset {}: {}", asset_id, e);
+                report.failed.push(ResolutionFailure { asset_id, error: e.to_string() });
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// The closest kind of group `keep` and `copy` are both in, if any.
+fn relation(groups: &[DuplicateGroup], keep: i64, copy: i64) -> Option<DuplicateKind> {
+    let is_member = |group: &DuplicateGroup, id: i64| group.members.iter().any(|m| m.asset_id == id);
+    groups
+        .iter()
+        .filter(|group| is_member(group, keep) && is_member(group, copy))
+        .map(|group| group.kind)
+        .min()
+}
+
+async fn resolve_copy(
+    db_manager: &DatabaseManager,
+    kept: &Asset,
+    asset_id: i64,
+    kind: DuplicateKind,
+    request: &ResolutionRequest,
+) -> Result<DuplicateResolution> {
+    let copy = db_manager.get_asset_by_id(asset_id).await?;
+    if is_nested(&copy.file_path, &kept.file_path) {
+        return Err(anyhow!("{} and the kept asset are inside one another", copy.name));
+    }
+    if kind == DuplicateKind::Versions && request.action != ResolutionAction::Archive {
+        return Err(anyhow!(
+            "{} is another version of the kept asset, so it can only be archived, not removed or linked",
+            copy.name
+        ));
+    }
+    let copy_path = PathBuf::from(&copy.file_path);
+    let kept_path = PathBuf::from(&kept.file_path);
+    let dry_run = request.dry_run;
+    if request.action != ResolutionAction::HardLink {
+        verify_contents(kept, &copy, kind).await?;
+    }
+
+    let (bytes_reclaimed, archive_path) = match request.action {
+        ResolutionAction::Remove => {
+            if !dry_run {
+                tokio::task::spawn_blocking(move || remove_path(&copy_path)).await??;
+            }
+            (copy.file_size, None)
+        }
+        ResolutionAction::Archive => {
+            let archive_dir = request.archive_dir.clone().unwrap_or_default();
+            let destination = unique_destination(&archive_dir, &copy_path)?;
+            if !dry_run {
+                let target = destination.clone();
+                tokio::task::spawn_blocking(move || move_path(&copy_path, &target)).await??;
+            }
+            (copy.file_size, Some(destination.to_string_lossy().into_owned()))
+        }
+        ResolutionAction::HardLink => {
+            let bytes =
+                tokio::task::spawn_blocking(move || link_identical_files(&kept_path, &copy_path, dry_run)).await??;
+            (bytes, None)
+        }
+    };
+
+    let mut resolution = DuplicateResolution {
+        id: None,
+        action: request.action.as_str().to_string(),
+        asset_name: copy.name.clone(),
+        path: copy.file_path.clone(),
+        kept_asset_id: kept.id,
+        kept_path: kept.file_path.clone(),
+        archive_path,
+        bytes_reclaimed,
+        resolved_date: db_manager.timestamp(),
+    };
+    if dry_run {
+        return Ok(resolution);
+    }
+
+    let mut tx = db_manager.pool.begin().await?;
+    if request.action != ResolutionAction::HardLink {
+        merge_into_kept(&mut tx, &copy, kept, &resolution.resolved_date).await?;
+        sqlx::query("DELETE FROM assets WHERE id = ?").bind(copy.id).execute(&mut *tx).await?;
+    }
+    let id = sqlx::query(
+        "INSERT INTO duplicate_resolutions
+            (action, asset_name, path, kept_asset_id, kept_path, archive_path, bytes_reclaimed, resolved_date)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&resolution.action)
+    .bind(&resolution.asset_name)
+    .bind(&resolution.path)
+    .bind(resolution.kept_asset_id)
+    .bind(&resolution.kept_path)
+    .bind(&resolution.archive_path)
+    .bind(resolution.bytes_reclaimed)
+    .bind(&resolution.resolved_date)
+    .execute(&mut *tx)
+    .await?
+    .last_insert_rowid();
+    tx.commit().await?;
+
+    resolution.id = Some(id);
+    Ok(resolution)
+}
+
+/// Before a copy is deleted or moved away, check on disk that the kept asset holds its
+/// contents: every file at the same path for an identical copy, every file anywhere in the
+/// larger pack for a pack inside it. Groups are found by file metadata, so this is what keeps
+/// an edited copy from being lost. Versions of a product differ by definition; they are only
+/// ever archived, so nothing is lost, and are not compared.
+async fn verify_contents(kept: &Asset, copy: &Asset, kind: DuplicateKind) -> Result<()> {
+    let kept_path = PathBuf::from(&kept.file_path);
+    let copy_path = PathBuf::from(&copy.file_path);
+    match kind {
+        DuplicateKind::Identical => {
+            tokio::task::spawn_blocking(move || identical_file_pairs(&kept_path, &copy_path)).await??;
+        }
+        DuplicateKind::Superset => {
+            tokio::task::spawn_blocking(move || verify_files_contained(&kept_path, &copy_path)).await??;
+        }
+        DuplicateKind::Versions => {}
+    }
+    Ok(())
+}
+
+/// Give the kept asset what the user added to the copy that is going away.
+async fn merge_into_kept(conn: &mut sqlx::SqliteConnection, copy: &Asset, kept: &Asset, now: &str) -> Result<()> {
+    let mut tags = split_tags(kept.tags.as_deref());
+    for tag in split_tags(copy.tags.as_deref()) {
+        if !tags.iter().any(|t| t.eq_ignore_ascii_case(&tag)) {
+            tags.push(tag);
+        }
+    }
+    let tags = (!tags.is_empty()).then(|| tags.join(","));
+    let notes = non_empty(kept.notes.clone()).or_else(|| non_empty(copy.notes.clone()));
+    let description = non_empty(kept.description.clone()).or_else(|| non_empty(copy.description.clone()));
+    let is_favorite = kept.is_favorite || copy.is_favorite;
+    let overrides = non_empty(kept.orbital_manual_overrides.clone()).or_else(|| non_empty(copy.orbital_manual_overrides.clone()));
+
+    let changed = tags != kept.tags
+        || notes != kept.notes
+        || description != kept.description
+        || is_favorite != kept.is_favorite
+        || overrides != kept.orbital_manual_overrides;
+    if changed {
+        sqlx::query(
+            "UPDATE assets SET tags = ?, notes = ?, description = ?, is_favorite = ?, orbital_manual_overrides = ?,
+                user_modified_date = ?
+             WHERE id = ?",
+        )
+        .bind(tags)
+        .bind(notes)
+        .bind(description)
+        .bind(is_favorite)
+        .bind(overrides)
+        .bind(now)
+        .bind(kept.id)
+        .execute(&mut *conn)
+        .await?;
+    }
+    sqlx::query(
+        "INSERT OR IGNORE INTO collection_assets (collection_id, asset_id, added_date)
+         SELECT collection_id, ?, added_date FROM collection_assets WHERE asset_id = ?",
+    )
+    .bind(kept.id)
+    .bind(copy.id)
+    .execute(&mut *conn)
+    .await?;
+    // Licenses were bought for the product, not for the folder.
+    sqlx::query("UPDATE asset_licenses SET asset_id = ?, asset_name = ?, asset_path = ? WHERE asset_id = ?")
+        .bind(kept.id)
+        .bind(&kept.name)
+        .bind(&kept.file_path)
+        .bind(copy.id)
+        .execute(&mut *conn)
+        .await?;
+    sqlx::query(
+        "INSERT OR IGNORE INTO asset_project_usage (asset_id, project_path, project_name, import_mode, imported_date)
+         SELECT ?, project_path, project_name, import_mode, imported_date FROM asset_project_usage WHERE asset_id = ?",
+    )
+    .bind(kept.id)
+    .bind(copy.id)
+    .execute(&mut *conn)
+    .await?;
+    // Products the user linked by hand, unless the kept asset has its own link to that provider.
+    sqlx::query(
+        "INSERT OR REPLACE INTO asset_provider_metadata
+            (asset_id, provider, reference, match_kind, confidence, fetched_date, metadata)
+         SELECT ?1, provider, reference, match_kind, confidence, fetched_date, metadata
+         FROM asset_provider_metadata AS c
+         WHERE c.asset_id = ?2 AND c.match_kind = 'manual'
+           AND NOT EXISTS (SELECT 1 FROM asset_provider_metadata AS k
+                           WHERE k.asset_id = ?1 AND k.provider = c.provider AND k.match_kind = 'manual')",
+    )
+    .bind(kept.id)
+    .bind(copy.id)
+    .execute(&mut *conn)
+    .await?;
+    // Categories the user chose, unless the kept asset was categorized by hand as well.
+    let manual = |asset_id: Option<i64>| {
+        sqlx::query_scalar::<_, bool>("SELECT EXISTS (SELECT 1 FROM asset_categories WHERE asset_id = ? AND rule = ?)")
+            .bind(asset_id)
+            .bind(MANUAL_RULE)
+    };
+    if manual(copy.id).fetch_one(&mut *conn).await? && !manual(kept.id).fetch_one(&mut *conn).await? {
+        sqlx::query("DELETE FROM asset_categories WHERE asset_id = ?").bind(kept.id).execute(&mut *conn).await?;
+        sqlx::query("UPDATE asset_categories SET asset_id = ? WHERE asset_id = ?")
+            .bind(kept.id)
+            .bind(copy.id)
+            .execute(&mut *conn)
+            .await?;
+        sqlx::query("UPDATE assets SET asset_type = ? WHERE id = ?")
+            .bind(&copy.asset_type)
+            .bind(kept.id)
+            .execute(&mut *conn)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Delete a folder or file; one that is already gone counts as deleted.
+fn remove_path(path: &Path) -> Result<()> {
+    let result = if path.is_dir() { std::fs::remove_dir_all(path) } else { std::fs::remove_file(path) };
+    match result {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(anyhow!("Cannot delete {}: {}", path.display(), e)),
+        _ => Ok(()),
+    }
+}
+
+/// `<dir>/<name>`, or `<dir>/<name> (2)` and so on when that is taken.
+fn unique_destination(dir: &Path, path: &Path) -> Result<PathBuf> {
+    let name = |part: Option<&std::ffi::OsStr>| part.map(|p| p.to_string_lossy().into_owned());
+    let (base, extension) = match (path.is_file(), name(path.file_stem()), name(path.extension())) {
+        (true, Some(stem), Some(extension)) => (stem, format!(".{}", extension)),
+        _ => (name(path.file_name()).ok_or_else(|| anyhow!("{} has no name", path.display()))?, String::new()),
+    };
+    (1..1000)
+        .map(|n| match n {
+            1 => dir.join(format!("{}{}", base, extension)),
+            n => dir.join(format!("{} ({}){}", base, n, extension)),
+        })
+        .find(|candidate| !candidate.exists())
+        .ok_or_else(|| anyhow!("No free name for {} in {}", base, dir.display()))
+}
+
+/// Move a folder or file, copying it when it goes to another volume.
+fn move_path(source: &Path, destination: &Path) -> Result<()> {
+    if let Some(parent) = destination.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| anyhow!("Cannot create {}: {}", parent.display(), e))?;
+    }
+    if std::fs::rename(source, destination).is_ok() {
+        return Ok(());
+    }
+    if let Err(e) = copy_path(source, destination) {
+        let _ = remove_path(destination);
+        return Err(anyhow!("Cannot move {} to {}: {}", source.display(), destination.display(), e));
+    }
+    remove_path(source)
+}
+
+fn copy_path(source: &Path, destination: &Path) -> std::io::Result<()> {
+    if source.is_file() {
+        return std::fs::copy(source, destination).map(|_| ());
+    }
+    std::fs::create_dir_all(destination)?;
+    for entry in std::fs::read_dir(source)? {
+        let entry = entry?;
+        copy_path(&entry.path(), &destination.join(entry.file_name()))?;
+    }
+    Ok(())
+}
+
+/// Pairs of a file in `copy` and the file at the same relative path in `kept`, after comparing
+/// every pair by content. Fails on the first file that is missing from `kept` or differs.
+/// Pairs that already are the same file are left out.
+fn identical_file_pairs(kept: &Path, copy: &Path) -> Result<Vec<(PathBuf, PathBuf)>> {
+    let pairs: Vec<(PathBuf, PathBuf)> = if copy.is_file() {
+        vec![(kept.to_path_buf(), copy.to_path_buf())]
+    } else {
+        jwalk::WalkDir::new(copy)
+            .sort(true)
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_file())
+            .filter_map(|entry| {
+                let path = entry.path();
+                let relative = path.strip_prefix(copy).ok()?.to_path_buf();
+                Some((kept.join(relative), path))
+            })
+            .collect()
+    };
+
+    let mut identical = Vec::new();
+    for (source, target) in pairs {
+        if same_file(&source, &target) {
+            continue;
+        }
+        if !same_contents(&source, &target)? {
+            return Err(anyhow!("{} differs from the kept copy", target.display()));
+        }
+        identical.push((source, target));
+    }
+    Ok(identical)
+}
+
+/// Whether two files hold the same bytes, by size and then SHA-256.
+fn same_contents(source: &Path, target: &Path) -> Result<bool> {
+    let source_size = source
+        .metadata()
+        .map(|m| m.len())
+        .map_err(|_| anyhow!("{} has no counterpart in the kept copy", target.display()))?;
+    let target_size = target.metadata()?.len();
+    Ok(source_size == target_size && hash_file_contents(source)? == hash_file_contents(target)?)
+}
+
+/// Check that every file of `copy`, not only its packages, is somewhere in `kept` with the same
+/// contents, so removing the smaller pack loses nothing. Only files of `kept` with the size of
+/// a file of `copy` are hashed.
+fn verify_files_contained(kept: &Path, copy: &Path) -> Result<()> {
+    let files = |dir: &Path| -> Result<Vec<(PathBuf, u64)>> {
+        jwalk::WalkDir::new(dir)
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_file())
+            .map(|entry| {
+                let path = entry.path();
+                let size = path.metadata().map_err(|e| anyhow!("Cannot read {}: {}", path.display(), e))?.len();
+                Ok((path, size))
+            })
+            .collect()
+    };
+    let copy_files = files(copy)?;
+    let sizes: HashSet<u64> = copy_files.iter().map(|(_, size)| *size).collect();
+    let mut kept_contents = HashSet::new();
+    for (path, size) in files(kept)? {
+        if sizes.contains(&size) {
+            kept_contents.insert((size, hash_file_contents(&path)?));
+        }
+    }
+
+    for (path, size) in copy_files {
+        if !kept_contents.contains(&(size, hash_file_contents(&path)?)) {
+            return Err(anyhow!("{} is not in the kept pack", path.display()));
+        }
+    }
+    Ok(())
+}
+
+/// Replace every file of `copy` with a hard link to the same file in `kept`. All files are
+/// compared by content first, and nothing is linked unless every one of them matches.
+/// Returns the bytes freed; files that already are the same file are not counted again.
+fn link_identical_files(kept: &Path, copy: &Path, dry_run: bool) -> Result<i64> {
+    let to_link = identical_file_pairs(kept, copy)?;
+    let mut bytes = 0;
+    for (_, target) in &to_link {
+        bytes += target.metadata()?.len() as i64;
+    }
+
+    if !dry_run {
+        for (source, target) in &to_link {
+            let file_name = target.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+            let temp = target.with_file_name(format!(".{}.omnidex-link", file_name));
+            std::fs::hard_link(source, &temp)
+                .map_err(|e| anyhow!("Cannot hard-link {}: {}", target.display(), e))?;
+            if let Err(e) = std::fs::rename(&temp, target) {
+                let _ = std::fs::remove_file(&temp);
+                return Err(anyhow!("Cannot replace {}: {}", target.display(), e));
+            }
+        }
+    }
+    Ok(bytes)
+}
+
+#[cfg(unix)]
+fn same_file(a: &Path, b: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    match (a.metadata(), b.metadata()) {
+        (Ok(a), Ok(b)) => a.dev() == b.dev() && a.ino() == b.ino(),
+        _ => false,
+    }
+}
+
+#[cfg(not(unix))]
+fn same_file(_a: &Path, _b: &Path) -> bool {
+    false
+}
+
+/// Every recorded resolution, the latest first.
+pub async fn get_resolutions(db_manager: &DatabaseManager) -> Result<Vec<DuplicateResolution>> {
+    sqlx::query_as(
+        "SELECT id, action, asset_name, path, kept_asset_id, kept_path, archive_path, bytes_reclaimed, resolved_date
+         FROM duplicate_resolutions ORDER BY id DESC",
+    )
+    .fetch_all(&db_manager.pool)
+    .await
+    .map_err(|e| anyhow!("Error fetching duplicate resolutions: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_finds_versions_in_names() {
+        assert_eq!(version_in_name("CastleKit_UE5.3").as_deref(), Some("5.3"));
+        assert_eq!(version_in_name("Rocks v2.1.0").as_deref(), Some("2.1.0"));
+        assert_eq!(version_in_name("Forest Pack 2"), None);
+        assert_eq!(version_in_name("Pack4.27"), None);
+    }
+}
@@ -1,20 +1,42 @@
 // Module declarations
+pub mod api;
+pub mod archive;
+pub mod backup;
+pub mod categories;
+pub mod changelog;
+pub mod clock;
+pub mod cli;
+#[cfg(feature = "desktop")]
 pub mod commands;
 pub mod database;
+pub mod dedupe;
+pub mod licenses;
+pub mod media;
 pub mod orbital;
+pub mod providers;
 pub mod scanner;
 pub mod models;
+pub mod settings;
+pub mod team;
+pub mod transfer;
+pub mod unreal;
+pub mod updates;
 
-use crate::database::DatabaseManager;
-use crate::orbital::OrbitalApiClient;
-use tauri::{Manager};
-use tracing::{info, error};
-use tauri_plugin_log::{Target, TargetKind, Builder as LogBuilder};
-use log::LevelFilter;
-
-// Import the scanner initializer
-use crate::scanner::init_scanner_manager;
+// The desktop app; without the `desktop` feature the crate is the headless library and CLI.
+#[cfg(feature = "desktop")]
+use {
+    crate::database::DatabaseManager,
+    crate::orbital::OrbitalApiClient,
+    crate::scanner::init_scanner_manager,
+    crate::scanner::watcher::{init_watcher_manager, start_configured_watchers},
+    crate::settings::SettingsService,
+    log::LevelFilter,
+    tauri::Manager,
+    tauri_plugin_log::{Builder as LogBuilder, Target, TargetKind},
+    tracing::{error, info},
+};
 
+#[cfg(feature = "desktop")]
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     let context = tauri::generate_context!();
@@ -41,6 +63,13 @@ pub fn run() {
             // Initialize scanner manager
             init_scanner_manager();
             info!("Scanner manager initialized.");
+            init_watcher_manager();
+
+            // Refuse to start while the command-line tool or another window has the library open.
+            if let Err(e) = database::lock_data_dir() {
+                error!("{}", e);
+                return Err(e.into());
+            }
 
             // Initialize database on startup
             let app_handle_clone = app.handle().clone();
@@ -53,7 +82,34 @@ pub fn run() {
                 match DatabaseManager::new().await {
                     Ok(db_manager) => {
                         info!("DatabaseManager created successfully.");
-                        app_handle_clone.manage(db_manager);
+                        // Scans still marked running were cut short when the app last closed.
+                        match db_manager.mark_interrupted_scan_jobs().await {
+                            Ok(0) => {}
+                            Ok(count) => info!("{} interrupted scan(s) can be resumed.", count),
+                            Err(e) => error!("Failed to mark interrupted scans: {}", e),
+                        }
+                        app_handle_clone.manage(db_manager.clone());
+                        match SettingsService::load(db_manager.clone()).await {
+                            Ok(settings_service) => {
+                                tauri::async_runtime::spawn(backup::run_backup_scheduler(
+                                    db_manager.clone(),
+                                    settings_service.subscribe(),
+                                ));
+                                tauri::async_runtime::spawn(api::run_api_server(
+                                    db_manager.clone(),
+                                    settings_service.subscribe(),
+                                ));
+                                tauri::async_runtime::spawn(team::run_team_sync_scheduler(
+                                    db_manager.clone(),
+                                    settings_service.subscribe(),
+                                ));
+                                app_handle_clone.manage(settings_service);
+                            }
+                            Err(e) => {
+                                error!("Failed to load settings: {}", e);
+                            }
+                        }
+                        start_configured_watchers(db_manager, app_handle_clone.clone()).await;
                     }
                     Err(e) => {
                         error!("Failed to create DatabaseManager: {}", e);
@@ -74,6 +130,10 @@ pub fn run() {
             commands::update_scan_location,
             commands::delete_scan_location,
             commands::update_scan_recursive_setting,
+            commands::set_scan_location_watching,
+            commands::set_scan_location_root_rules,
+            commands::preview_asset_roots,
+            commands::relocate_scan_location,
             commands::clear_assets_from_scan_location,
             // Asset Commands
             commands::get_assets,
@@ -98,7 +158,30 @@ pub fn run() {
             commands::start_scan,
             commands::cancel_scan,
             commands::cancel_all_scans,
+            commands::pause_scan,
+            commands::resume_scan,
+            commands::get_resumable_scans,
+            commands::get_scan_history,
+            commands::get_scan_errors,
+            commands::retry_failed_scan_paths,
             commands::get_duplicate_assets,
+            commands::find_duplicate_groups,
+            commands::resolve_duplicates,
+            commands::get_duplicate_resolutions,
+            commands::get_outdated_assets,
+            commands::add_asset_license,
+            commands::update_asset_license,
+            commands::attach_asset_license,
+            commands::delete_asset_license,
+            commands::get_asset_licenses,
+            commands::get_license_spend,
+            commands::get_licenses_by_seller,
+            commands::get_unlicensed_assets,
+            commands::get_orphaned_licenses,
+            commands::refresh_asset_metadata,
+            commands::set_asset_provider_reference,
+            commands::get_asset_provider_metadata,
+            commands::get_merged_asset_metadata,
             // File System Commands
             commands::open_file_location,
             commands::open_url,
@@ -109,7 +192,48 @@ pub fn run() {
             commands::clear_all_assets,
             commands::clear_all_scan_locations,
             commands::wipe_entire_database,
-            commands::get_database_stats
+            commands::get_database_stats,
+            // Settings Commands
+            commands::get_settings,
+            commands::update_settings,
+            commands::set_setting,
+            commands::reset_settings,
+            commands::set_api_enabled,
+            commands::regenerate_api_token,
+            // Team Library Commands
+            commands::set_scan_location_team_key,
+            commands::sync_team_library,
+            commands::sync_library_changes,
+            // Backup Commands
+            commands::create_backup_now,
+            commands::list_backups,
+            commands::verify_backup,
+            commands::restore_backup,
+            // Collection Commands
+            commands::create_collection,
+            commands::get_collections,
+            commands::update_collection,
+            commands::delete_collection,
+            commands::add_assets_to_collection,
+            commands::remove_assets_from_collection,
+            commands::get_collection_assets,
+            // Library Export/Import Commands
+            commands::export_library,
+            commands::import_library,
+            // Unreal Project Commands
+            commands::import_asset_into_project,
+            commands::get_asset_projects,
+            commands::get_project_assets,
+            // Storage Commands
+            commands::get_asset_size_breakdown,
+            commands::get_storage_overview,
+            // Archive Commands
+            commands::extract_archive_asset,
+            // Category Commands
+            commands::get_category_taxonomy,
+            commands::set_category_taxonomy,
+            commands::get_asset_categories,
+            commands::set_asset_categories
         ])
         .run(context)
         .expect("error while running tauri application");
@@ -117,29 +241,29 @@ pub fn run() {
 
 #[cfg(test)]
 mod tests {
-    use super::*;
     use crate::orbital::OrbitalManager;
-    use tracing::info;
 
     #[test]
     fn test_slug_generation() {
-        info!("🧪 Testing slug generation with problematic cases...");
-        
-        if let Ok(manager) = OrbitalManager::new() {
-            let test_cases = vec![
-                "MC Skydive (5 0 )",
-                "Mage Animation Set",
-                "Some Asset (4 18)",
-                "Character Pack UE5.3",
-                "Environment Assets v2.1",
-                "Building Kit (UE4.27)",
-            ];
-            
-            for case in test_cases {
-                info!("Testing: '{}'", case);
-                let variations = manager.test_slug_generation(case);
-                info!("Generated {} variations: {:?}", variations.len(), variations);
-            }
+        let manager = OrbitalManager::new().unwrap();
+        let test_cases = [
+            ("MC Skydive (5 0 )", "mc-skydive"),
+            ("Mage Animation Set", "mage-animation-set"),
+            ("Some Asset (4 18)", "some-asset"),
+            ("Character Pack UE5.3", "character-pack"),
+            ("Environment Assets v2.1", "environment-assets"),
+            ("Building Kit (UE4.27)", "building-kit"),
+        ];
+
+        for (case, expected) in test_cases {
+            let variations = manager.test_slug_generation(case);
+            assert!(
+                variations.iter().any(|slug| slug == expected),
+                "'{}' gave {:?}, expected '{}' among them",
+                case,
+                variations,
+                expected
+            );
         }
     }
 }
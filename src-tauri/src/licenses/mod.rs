@@ -0,0 +1,368 @@
+//! Who owns the license of each asset, what was paid for it and where it was bought.
+//!
+//! An asset can have several license records, e.g. a personal license upgraded later or extra
+//! seats bought separately. A record without a price or seller takes them from the matched
+//! Orbital listing, and is marked as such so reports can tell entered prices from list prices.
+//!
+//! Licenses are a legal record and outlive their asset: when the asset leaves the library (it
+//! was deleted, moved or renamed on disk, or resolved as a duplicate), the license keeps the
+//! asset's name and path, is reported as orphaned, and can be attached to another asset.
+
+use anyhow::{anyhow, Result};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use crate::database::DatabaseManager;
+
+pub const LICENSE_TYPES: &[&str] = &["personal", "professional"];
+
+/// Currency of prices entered without one, and of Orbital list prices.
+pub const DEFAULT_CURRENCY: &str = "USD";
+
+/// A license as entered by the user; empty fields fall back to the asset's listing.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct LicenseInput {
+    pub owner: String,
+    /// `YYYY-MM-DD`
+    pub purchase_date: Option<String>,
+    /// `None` uses the matched Orbital price.
+    pub price_paid: Option<f64>,
+    pub currency: Option<String>,
+    /// One of [`LICENSE_TYPES`].
+    pub license_type: String,
+    /// Defaults to one seat.
+    pub seats: Option<i64>,
+    pub order_reference: Option<String>,
+    /// `None` uses the matched Orbital seller.
+    pub seller: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AssetLicense {
+    pub id: i64,
+    /// `None` once the asset left the library.
+    pub asset_id: Option<i64>,
+    /// Name and path of the asset when the license was recorded or last attached.
+    pub asset_name: String,
+    pub asset_path: String,
+    pub owner: String,
+    pub purchase_date: Option<String>,
+    pub price_paid: Option<f64>,
+    pub currency: String,
+    /// The price is the Orbital list price rather than one entered by the user.
+    pub price_from_marketplace: bool,
+    pub license_type: String,
+    pub seats: i64,
+    pub order_reference: Option<String>,
+    pub seller: Option<String>,
+    pub created_date: String,
+    pub modified_date: String,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct SpendTotal {
+    pub currency: String,
+    pub total: f64,
+    /// Part of `total` taken from Orbital list prices.
+    pub from_marketplace: f64,
+    pub licenses: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SpendReport {
+    /// Purchase year the report covers; `None` covers every purchase.
+    pub year: Option<i32>,
+    pub licenses: i64,
+    pub seats: i64,
+    /// Spend per currency, largest first.
+    pub totals: Vec<SpendTotal>,
+    /// Licenses without a price, neither entered nor listed.
+    pub without_price: i64,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct SellerLicenses {
+    /// `None` for licenses whose seller is unknown.
+    pub seller: Option<String>,
+    pub currency: String,
+    pub licenses: i64,
+    pub seats: i64,
+    pub assets: i64,
+    pub spend: f64,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct UnlicensedAsset {
+    pub asset_id: i64,
+    pub name: String,
+    pub file_path: String,
+    pub seller: Option<String>,
+    pub marketplace_price: Option<f64>,
+    pub product_slug: Option<String>,
+}
+
+/// Everything the yearly license review asks for.
+#[derive(Debug, Clone, Serialize)]
+pub struct LicenseReport {
+    pub spend: SpendReport,
+    pub by_seller: Vec<SellerLicenses>,
+    pub unlicensed: Vec<UnlicensedAsset>,
+    /// Licenses whose asset left the library.
+    pub orphaned: Vec<AssetLicense>,
+}
+
+const LICENSE_SELECT: &str = "SELECT * FROM asset_licenses";
+
+/// Matches licenses bought in the year bound to it, or every license when it is NULL.
+const YEAR_FILTER: &str = "(?1 IS NULL OR substr(purchase_date, 1, 4) = printf('%04d', ?1))";
+
+/// A validated license with the listing's defaults filled in.
+struct ResolvedLicense {
+    owner: String,
+    purchase_date: Option<String>,
+    price_paid: Option<f64>,
+    currency: String,
+    price_from_marketplace: bool,
+    license_type: String,
+    seats: i64,
+    order_reference: Option<String>,
+    seller: Option<String>,
+}
+
+async fn resolve(db_manager: &DatabaseManager, asset_id: Option<i64>, input: &LicenseInput) -> Result<ResolvedLicense> {
+    let owner = input.owner.trim();
+    if owner.is_empty() {
+        return Err(anyhow!("A license needs an owner"));
+    }
+    let license_type = input.license_type.trim().to_ascii_lowercase();
+    if !LICENSE_TYPES.contains(&license_type.as_str()) {
+        return Err(anyhow!("Invalid license type '{}', expected one of {:?}", input.license_type, LICENSE_TYPES));
+    }
+    let seats = input.seats.unwrap_or(1);
+    if seats < 1 {
+        return Err(anyhow!("A license covers at least one seat, got {}", seats));
+    }
+    if input.price_paid.is_some_and(|price| !price.is_finite() || price < 0.0) {
+        return Err(anyhow!("Price paid cannot be negative"));
+    }
+    let purchase_date = non_empty(input.purchase_date.as_deref());
+    if let Some(date) = &purchase_date {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map_err(|_| anyhow!("Invalid purchase date '{}', expected YYYY-MM-DD", date))?;
+    }
+    let currency = non_empty(input.currency.as_deref())
+        .map(|currency| currency.to_ascii_uppercase())
+        .unwrap_or_else(|| DEFAULT_CURRENCY.to_string());
+    if currency.len() != 3 || !currency.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err(anyhow!("Invalid currency '{}', expected a code such as USD", currency));
+    }
+
+    // An orphaned license has no listing to take defaults from.
+    let (listed_price, listed_seller) = match asset_id {
+        Some(asset_id) => listing(db_manager, asset_id).await?,
+        None => (None, None),
+    };
+
+    // List prices are in US dollars, so they only stand in for a price paid in dollars.
+    let listed_price = listed_price.filter(|_| currency == DEFAULT_CURRENCY);
+    let price_from_marketplace = input.price_paid.is_none() && listed_price.is_some();
+    Ok(ResolvedLicense {
+        owner: owner.to_string(),
+        purchase_date,
+        price_paid: input.price_paid.or(listed_price),
+        currency,
+        price_from_marketplace,
+        license_type,
+        seats,
+        order_reference: non_empty(input.order_reference.as_deref()),
+        seller: non_empty(input.seller.as_deref()).or(non_empty(listed_seller.as_deref())),
+    })
+}
+
+/// The matched Orbital price and seller of an asset.
+async fn listing(db_manager: &DatabaseManager, asset_id: i64) -> Result<(Option<f64>, Option<String>)> {
+    sqlx::query_as("SELECT orbital_price, orbital_seller_name FROM assets WHERE id = ?")
+        .bind(asset_id)
+        .fetch_optional(&db_manager.pool)
+        .await?
+        .ok_or_else(|| anyhow!("Asset {} not found", asset_id))
+}
+
+fn non_empty(value: Option<&str>) -> Option<String> {
+    value.map(str::trim).filter(|value| !value.is_empty()).map(str::to_string)
+}
+
+async fn get_license(db_manager: &DatabaseManager, license_id: i64) -> Result<AssetLicense> {
+    sqlx::query_as(&format!("{} WHERE id = ?", LICENSE_SELECT))
+        .bind(license_id)
+        .fetch_optional(&db_manager.pool)
+        .await?
+        .ok_or_else(|| anyhow!("License {} not found", license_id))
+}
+
+pub async fn add_license(db_manager: &DatabaseManager, asset_id: i64, input: &LicenseInput) -> Result<AssetLicense> {
+    let license = resolve(db_manager, Some(asset_id), input).await?;
+    let asset = db_manager.get_asset_by_id(asset_id).await?;
+    let now = db_manager.timestamp();
+    let id: i64 = sqlx::query_scalar(
+        "INSERT INTO asset_licenses
+         (asset_id, asset_name, asset_path, owner, purchase_date, price_paid, currency, price_from_marketplace,
+          license_type, seats, order_reference, seller, created_date, modified_date)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?) RETURNING id",
+    )
+    .bind(asset_id)
+    .bind(&asset.name)
+    .bind(&asset.file_path)
+    .bind(&license.owner)
+    .bind(&license.purchase_date)
+    .bind(license.price_paid)
+    .bind(&license.currency)
+    .bind(license.price_from_marketplace)
+    .bind(&license.license_type)
+    .bind(license.seats)
+    .bind(&license.order_reference)
+    .bind(&license.seller)
+    .bind(&now)
+    .bind(&now)
+    .fetch_one(&db_manager.pool)
+    .await?;
+    get_license(db_manager, id).await
+}
+
+/// Replace a license with `input`, filling empty fields from the listing again.
+pub async fn update_license(db_manager: &DatabaseManager, license_id: i64, input: &LicenseInput) -> Result<AssetLicense> {
+    let existing = get_license(db_manager, license_id).await?;
+    let license = resolve(db_manager, existing.asset_id, input).await?;
+    sqlx::query(
+        "UPDATE asset_licenses SET owner = ?, purchase_date = ?, price_paid = ?, currency = ?,
+         price_from_marketplace = ?, license_type = ?, seats = ?, order_reference = ?, seller = ?, modified_date = ?
+         WHERE id = ?",
+    )
+    .bind(&license.owner)
+    .bind(&license.purchase_date)
+    .bind(license.price_paid)
+    .bind(&license.currency)
+    .bind(license.price_from_marketplace)
+    .bind(&license.license_type)
+    .bind(license.seats)
+    .bind(&license.order_reference)
+    .bind(&license.seller)
+    .bind(db_manager.timestamp())
+    .bind(license_id)
+    .execute(&db_manager.pool)
+    .await?;
+    get_license(db_manager, license_id).await
+}
+
+/// Attach a license to `asset_id`, e.g. an orphaned license to the asset's new copy.
+pub async fn attach_license(db_manager: &DatabaseManager, license_id: i64, asset_id: i64) -> Result<AssetLicense> {
+    get_license(db_manager, license_id).await?;
+    let asset = db_manager.get_asset_by_id(asset_id).await?;
+    sqlx::query("UPDATE asset_licenses SET asset_id = ?, asset_name = ?, asset_path = ?, modified_date = ? WHERE id = ?")
+        .bind(asset_id)
+        .bind(&asset.name)
+        .bind(&asset.file_path)
+        .bind(db_manager.timestamp())
+        .bind(license_id)
+        .execute(&db_manager.pool)
+        .await?;
+    get_license(db_manager, license_id).await
+}
+
+pub async fn delete_license(db_manager: &DatabaseManager, license_id: i64) -> Result<()> {
+    let deleted = sqlx::query("DELETE FROM asset_licenses WHERE id = ?")
+        .bind(license_id)
+        .execute(&db_manager.pool)
+        .await?
+        .rows_affected();
+    if deleted == 0 {
+        return Err(anyhow!("License {} not found", license_id));
+    }
+    Ok(())
+}
+
+/// The licenses of an asset, the latest purchase first.
+pub async fn get_asset_licenses(db_manager: &DatabaseManager, asset_id: i64) -> Result<Vec<AssetLicense>> {
+    Ok(sqlx::query_as(&format!(
+        "{} WHERE asset_id = ? ORDER BY purchase_date DESC NULLS LAST, id DESC",
+        LICENSE_SELECT
+    ))
+    .bind(asset_id)
+    .fetch_all(&db_manager.pool)
+    .await?)
+}
+
+/// Total spend per currency on licenses bought in `year`, or on every license.
+pub async fn spend_report(db_manager: &DatabaseManager, year: Option<i32>) -> Result<SpendReport> {
+    let (licenses, seats, without_price): (i64, i64, i64) = sqlx::query_as(&format!(
+        "SELECT COUNT(*), COALESCE(SUM(seats), 0), COALESCE(SUM(price_paid IS NULL), 0)
+         FROM asset_licenses WHERE {}",
+        YEAR_FILTER
+    ))
+    .bind(year)
+    .fetch_one(&db_manager.pool)
+    .await?;
+
+    let totals = sqlx::query_as(&format!(
+        "SELECT currency, SUM(price_paid) AS total,
+                SUM(CASE WHEN price_from_marketplace THEN price_paid ELSE 0.0 END) AS from_marketplace,
+                COUNT(*) AS licenses
+         FROM asset_licenses WHERE price_paid IS NOT NULL AND {}
+         GROUP BY currency ORDER BY total DESC",
+        YEAR_FILTER
+    ))
+    .bind(year)
+    .fetch_all(&db_manager.pool)
+    .await?;
+
+    Ok(SpendReport { year, licenses, seats, totals, without_price })
+}
+
+/// Licenses, seats and spend per seller and currency, the biggest spend first.
+pub async fn licenses_by_seller(db_manager: &DatabaseManager, year: Option<i32>) -> Result<Vec<SellerLicenses>> {
+    Ok(sqlx::query_as(&format!(
+        "SELECT seller, currency, COUNT(*) AS licenses, SUM(seats) AS seats,
+                COUNT(DISTINCT asset_id) AS assets, COALESCE(SUM(price_paid), 0.0) AS spend
+         FROM asset_licenses WHERE {}
+         GROUP BY seller, currency
+         ORDER BY spend DESC, licenses DESC, seller COLLATE NOCASE",
+        YEAR_FILTER
+    ))
+    .bind(year)
+    .fetch_all(&db_manager.pool)
+    .await?)
+}
+
+/// Vault and media assets without any license record, by name.
+pub async fn unlicensed_assets(db_manager: &DatabaseManager) -> Result<Vec<UnlicensedAsset>> {
+    Ok(sqlx::query_as(
+        "SELECT a.id AS asset_id, a.name, a.file_path, a.orbital_seller_name AS seller,
+                a.orbital_price AS marketplace_price, a.matched_orbital_product_slug AS product_slug
+         FROM assets a JOIN scan_locations l ON l.id = a.scan_location_id
+         WHERE l.location_kind != 'project'
+           AND NOT EXISTS (SELECT 1 FROM asset_licenses al WHERE al.asset_id = a.id)
+         ORDER BY a.name COLLATE NOCASE",
+    )
+    .fetch_all(&db_manager.pool)
+    .await?)
+}
+
+/// Licenses whose asset left the library, by the name it had.
+pub async fn orphaned_licenses(db_manager: &DatabaseManager) -> Result<Vec<AssetLicense>> {
+    Ok(sqlx::query_as(&format!(
+        "{} WHERE asset_id IS NULL ORDER BY asset_name COLLATE NOCASE, id",
+        LICENSE_SELECT
+    ))
+    .fetch_all(&db_manager.pool)
+    .await?)
+}
+
+pub async fn license_report(db_manager: &DatabaseManager, year: Option<i32>) -> Result<LicenseReport> {
+    Ok(LicenseReport {
+        spend: spend_report(db_manager, year).await?,
+        by_seller: licenses_by_seller(db_manager, year).await?,
+        unlicensed: unlicensed_assets(db_manager).await?,
+        orphaned: orphaned_licenses(db_manager).await?,
+    })
+}
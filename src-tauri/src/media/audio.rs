@@ -0,0 +1,175 @@
+//! Duration, sample rate and channel layout from audio headers.
+
+use super::MediaMetadata;
+use anyhow::{anyhow, Result};
+use std::io::{Read, Seek, SeekFrom};
+
+/// How far from the end of an Ogg stream the last page is looked for.
+const OGG_TAIL_BYTES: u64 = 64 * 1024;
+
+pub fn read_wav<R: Read + Seek>(reader: &mut R, metadata: &mut MediaMetadata) -> Result<()> {
+    let mut header = [0u8; 12];
+    reader.read_exact(&mut header)?;
+    if &header[..4] != b"RIFF" || &header[8..12] != b"WAVE" {
+        return Err(anyhow!("Not a WAV file"));
+    }
+
+    let mut byte_rate = None;
+    let mut data_size = None;
+    while byte_rate.is_none() || data_size.is_none() {
+        let mut chunk = [0u8; 8];
+        if reader.read_exact(&mut chunk).is_err() {
+            break;
+        }
+        let size = u32::from_le_bytes(chunk[4..8].try_into()?) as u64;
+        match &chunk[..4] {
+            b"fmt " if size >= 16 => {
+                let mut format = [0u8; 16];
+                reader.read_exact(&mut format)?;
+                metadata.channels = Some(u16::from_le_bytes([format[2], format[3]]) as u32);
+                metadata.sample_rate = Some(u32::from_le_bytes(format[4..8].try_into()?));
+                byte_rate = Some(u32::from_le_bytes(format[8..12].try_into()?) as u64);
+                metadata.bit_depth = Some(u16::from_le_bytes([format[14], format[15]]) as u32);
+                reader.seek(SeekFrom::Current((size - 16 + size % 2) as i64))?;
+            }
+            b"data" => {
+                data_size = Some(size);
+                reader.seek(SeekFrom::Current((size + size % 2) as i64))?;
+            }
+            _ => {
+                reader.seek(SeekFrom::Current((size + size % 2) as i64))?;
+            }
+        }
+    }
+
+    if let (Some(byte_rate), Some(data_size)) = (byte_rate, data_size) {
+        if byte_rate > 0 {
+            metadata.duration_seconds = Some(data_size as f64 / byte_rate as f64);
+        }
+    }
+    Ok(())
+}
+
+pub fn read_flac<R: Read>(reader: &mut R, metadata: &mut MediaMetadata) -> Result<()> {
+    // Marker, metadata block header, then STREAMINFO, which is always the first block.
+    let mut header = [0u8; 8 + 34];
+    reader.read_exact(&mut header)?;
+    if &header[..4] != b"fLaC" || header[4] & 0x7f != 0 {
+        return Err(anyhow!("Not a FLAC file"));
+    }
+    let info = &header[8..];
+    // 20 bits sample rate, 3 bits channels - 1, 5 bits bits-per-sample - 1, 36 bits total samples.
+    let packed = u64::from_be_bytes(info[10..18].try_into()?);
+    let sample_rate = (packed >> 44) as u32;
+    let total_samples = packed & 0xf_ffff_ffff;
+    metadata.sample_rate = Some(sample_rate);
+    metadata.channels = Some(((packed >> 41) & 0x7) as u32 + 1);
+    metadata.bit_depth = Some(((packed >> 36) & 0x1f) as u32 + 1);
+    if sample_rate > 0 && total_samples > 0 {
+        metadata.duration_seconds = Some(total_samples as f64 / sample_rate as f64);
+    }
+    Ok(())
+}
+
+/// Ogg Vorbis or Opus. The duration comes from the granule position of the last page.
+pub fn read_ogg<R: Read + Seek>(reader: &mut R, metadata: &mut MediaMetadata) -> Result<()> {
+    let mut page = [0u8; 27];
+    reader.read_exact(&mut page)?;
+    if &page[..4] != b"OggS" {
+        return Err(anyhow!("Not an Ogg file"));
+    }
+    let segments = page[26] as i64;
+    reader.seek(SeekFrom::Current(segments))?;
+    let mut packet = [0u8; 19];
+    reader.read_exact(&mut packet)?;
+
+    // Opus always runs at 48 kHz; the header's rate is the input rate before encoding.
+    let (granule_rate, pre_skip) = if &packet[1..7] == b"vorbis" && packet[0] == 1 {
+        let sample_rate = u32::from_le_bytes(packet[12..16].try_into()?);
+        metadata.channels = Some(packet[11] as u32);
+        metadata.sample_rate = Some(sample_rate);
+        (sample_rate, 0)
+    } else if &packet[..8] == b"OpusHead" {
+        metadata.channels = Some(packet[9] as u32);
+        metadata.sample_rate = Some(u32::from_le_bytes(packet[12..16].try_into()?));
+        (48_000, u16::from_le_bytes([packet[10], packet[11]]) as u64)
+    } else {
+        return Err(anyhow!("Unsupported Ogg codec"));
+    };
+
+    let len = reader.seek(SeekFrom::End(0))?;
+    let start = len.saturating_sub(OGG_TAIL_BYTES);
+    reader.seek(SeekFrom::Start(start))?;
+    let mut tail = Vec::new();
+    reader.read_to_end(&mut tail)?;
+    let last_page = tail.windows(4).rposition(|w| w == b"OggS");
+    if let Some(offset) = last_page.filter(|offset| offset + 14 <= tail.len()) {
+        let granule = u64::from_le_bytes(tail[offset + 6..offset + 14].try_into()?);
+        if granule_rate > 0 && granule != u64::MAX {
+            metadata.duration_seconds = Some(granule.saturating_sub(pre_skip) as f64 / granule_rate as f64);
+        }
+    }
+    Ok(())
+}
+
+/// MPEG audio layer III. Uses the Xing/Info frame count when present, otherwise assumes a constant bitrate.
+pub fn read_mp3<R: Read + Seek>(reader: &mut R, metadata: &mut MediaMetadata) -> Result<()> {
+    const BITRATES_V1: [u32; 15] = [0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320];
+    const BITRATES_V2: [u32; 15] = [0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160];
+    const SAMPLE_RATES_V1: [u32; 3] = [44_100, 48_000, 32_000];
+
+    let len = reader.seek(SeekFrom::End(0))?;
+    reader.seek(SeekFrom::Start(0))?;
+    let mut id3 = [0u8; 10];
+    reader.read_exact(&mut id3)?;
+    let audio_start = if &id3[..3] == b"ID3" {
+        let size = id3[6..10].iter().fold(0u64, |size, byte| (size << 7) | (*byte & 0x7f) as u64);
+        10 + size
+    } else {
+        0
+    };
+
+    reader.seek(SeekFrom::Start(audio_start))?;
+    let mut frame = vec![0u8; 4096];
+    let read = reader.read(&mut frame)?;
+    frame.truncate(read);
+    let sync = frame
+        .windows(2)
+        .position(|w| w[0] == 0xff && w[1] & 0xe0 == 0xe0)
+        .filter(|offset| offset + 4 <= frame.len())
+        .ok_or_else(|| anyhow!("No MPEG frame found"))?;
+    let header = &frame[sync..];
+
+    let version = (header[1] >> 3) & 0x3; // 0 = 2.5, 2 = 2, 3 = 1
+    let layer = (header[1] >> 1) & 0x3; // 1 = layer III
+    let bitrate_index = (header[2] >> 4) as usize;
+    let rate_index = ((header[2] >> 2) & 0x3) as usize;
+    if version == 1 || layer != 1 || bitrate_index == 0 || bitrate_index == 15 || rate_index == 3 {
+        return Err(anyhow!("Unsupported MPEG frame"));
+    }
+    let is_v1 = version == 3;
+    let sample_rate = SAMPLE_RATES_V1[rate_index] >> if is_v1 { 0 } else if version == 2 { 1 } else { 2 };
+    let bitrate = if is_v1 { BITRATES_V1[bitrate_index] } else { BITRATES_V2[bitrate_index] } * 1000;
+    let mono = header[3] >> 6 == 3;
+    metadata.sample_rate = Some(sample_rate);
+    metadata.channels = Some(if mono { 1 } else { 2 });
+
+    let samples_per_frame = if is_v1 { 1152 } else { 576 };
+    let side_info = match (is_v1, mono) {
+        (true, false) => 32,
+        (true, true) | (false, false) => 17,
+        (false, true) => 9,
+    };
+    let xing = sync + 4 + side_info;
+    let frames = frame
+        .get(xing..xing + 12)
+        .filter(|tag| &tag[..4] == b"Xing" || &tag[..4] == b"Info")
+        .filter(|tag| tag[7] & 0x1 != 0)
+        .map(|tag| u32::from_be_bytes([tag[8], tag[9], tag[10], tag[11]]));
+
+    metadata.duration_seconds = Some(match frames {
+        Some(frames) => frames as f64 * samples_per_frame as f64 / sample_rate as f64,
+        None => (len - audio_start - sync as u64) as f64 * 8.0 / bitrate as f64,
+    });
+    Ok(())
+}
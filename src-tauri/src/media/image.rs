@@ -0,0 +1,180 @@
+//! Dimensions and bit depth from image headers.
+
+use super::MediaMetadata;
+use anyhow::{anyhow, Result};
+use std::io::{BufRead, Read};
+
+pub fn read_png<R: Read>(reader: &mut R, metadata: &mut MediaMetadata) -> Result<()> {
+    // Signature, IHDR length and type, then the IHDR fields.
+    let mut header = [0u8; 26];
+    reader.read_exact(&mut header)?;
+    if &header[..8] != b"\x89PNG\r\n\x1a\n" || &header[12..16] != b"IHDR" {
+        return Err(anyhow!("Not a PNG file"));
+    }
+    metadata.width = Some(u32::from_be_bytes(header[16..20].try_into()?));
+    metadata.height = Some(u32::from_be_bytes(header[20..24].try_into()?));
+    metadata.bit_depth = Some(header[24] as u32);
+    metadata.channels = match header[25] {
+        0 => Some(1),
+        2 | 3 => Some(3),
+        4 => Some(2),
+        6 => Some(4),
+        _ => None,
+    };
+    Ok(())
+}
+
+pub fn read_jpeg<R: Read>(reader: &mut R, metadata: &mut MediaMetadata) -> Result<()> {
+    let mut marker = [0u8; 2];
+    reader.read_exact(&mut marker)?;
+    if marker != [0xff, 0xd8] {
+        return Err(anyhow!("Not a JPEG file"));
+    }
+    loop {
+        reader.read_exact(&mut marker)?;
+        if marker[0] != 0xff {
+            return Err(anyhow!("Corrupt JPEG marker"));
+        }
+        // Markers may be padded with any number of 0xff bytes.
+        while marker[1] == 0xff {
+            let mut next = [0u8; 1];
+            reader.read_exact(&mut next)?;
+            marker[1] = next[0];
+        }
+        match marker[1] {
+            0x01 | 0xd0..=0xd7 => continue,
+            0xd9 | 0xda => return Err(anyhow!("JPEG has no frame header")),
+            _ => {}
+        }
+        let mut length = [0u8; 2];
+        reader.read_exact(&mut length)?;
+        let length = u16::from_be_bytes(length) as u64;
+        if length < 2 {
+            return Err(anyhow!("Corrupt JPEG segment"));
+        }
+        let is_frame_header = matches!(marker[1], 0xc0..=0xcf) && !matches!(marker[1], 0xc4 | 0xc8 | 0xcc);
+        if is_frame_header {
+            let mut frame = [0u8; 6];
+            reader.read_exact(&mut frame)?;
+            metadata.bit_depth = Some(frame[0] as u32);
+            metadata.height = Some(u16::from_be_bytes([frame[1], frame[2]]) as u32);
+            metadata.width = Some(u16::from_be_bytes([frame[3], frame[4]]) as u32);
+            metadata.channels = Some(frame[5] as u32);
+            return Ok(());
+        }
+        std::io::copy(&mut reader.take(length - 2), &mut std::io::sink())?;
+    }
+}
+
+pub fn read_tga<R: Read>(reader: &mut R, metadata: &mut MediaMetadata) -> Result<()> {
+    let mut header = [0u8; 18];
+    reader.read_exact(&mut header)?;
+    metadata.width = Some(u16::from_le_bytes([header[12], header[13]]) as u32);
+    metadata.height = Some(u16::from_le_bytes([header[14], header[15]]) as u32);
+    let (channels, bit_depth) = match header[16] {
+        8 => (1, 8),
+        15 | 16 => (3, 5),
+        24 => (3, 8),
+        32 => (4, 8),
+        other => return Err(anyhow!("Unsupported TGA pixel depth {}", other)),
+    };
+    metadata.channels = Some(channels);
+    metadata.bit_depth = Some(bit_depth);
+    Ok(())
+}
+
+/// Radiance `.hdr`: a text header, a blank line, then a resolution line such as `-Y 2048 +X 4096`.
+pub fn read_radiance<R: BufRead>(reader: &mut R, metadata: &mut MediaMetadata) -> Result<()> {
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    if !line.starts_with("#?") {
+        return Err(anyhow!("Not a Radiance HDR file"));
+    }
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Err(anyhow!("Radiance header has no resolution line"));
+        }
+        if line.trim().is_empty() {
+            break;
+        }
+    }
+    line.clear();
+    reader.read_line(&mut line)?;
+    let parts: Vec<&str> = line.split_whitespace().collect();
+    let [first_axis, first, _, second] = parts.as_slice() else {
+        return Err(anyhow!("Corrupt Radiance resolution line"));
+    };
+    let (first, second) = (first.parse::<u32>()?, second.parse::<u32>()?);
+    let (width, height) = if first_axis.ends_with('Y') { (second, first) } else { (first, second) };
+    metadata.width = Some(width);
+    metadata.height = Some(height);
+    metadata.channels = Some(3);
+    metadata.bit_depth = Some(32);
+    Ok(())
+}
+
+/// OpenEXR: attributes follow the magic number until an empty name.
+pub fn read_exr<R: BufRead>(reader: &mut R, metadata: &mut MediaMetadata) -> Result<()> {
+    let mut magic = [0u8; 8];
+    reader.read_exact(&mut magic)?;
+    if magic[..4] != [0x76, 0x2f, 0x31, 0x01] {
+        return Err(anyhow!("Not an OpenEXR file"));
+    }
+    loop {
+        let name = read_c_string(reader)?;
+        if name.is_empty() {
+            return Ok(());
+        }
+        let _type_name = read_c_string(reader)?;
+        let mut size = [0u8; 4];
+        reader.read_exact(&mut size)?;
+        let size = i32::from_le_bytes(size);
+        if !(0..=16 * 1024 * 1024).contains(&size) {
+            return Err(anyhow!("Corrupt OpenEXR attribute {}", name));
+        }
+        let mut value = vec![0u8; size as usize];
+        reader.read_exact(&mut value)?;
+
+        match name.as_str() {
+            "dataWindow" if value.len() == 16 => {
+                let int = |i: usize| i32::from_le_bytes(value[i * 4..i * 4 + 4].try_into().unwrap_or_default());
+                metadata.width = Some((int(2) - int(0) + 1).max(0) as u32);
+                metadata.height = Some((int(3) - int(1) + 1).max(0) as u32);
+            }
+            "channels" => {
+                let (channels, bit_depth) = exr_channels(&value);
+                metadata.channels = Some(channels);
+                metadata.bit_depth = bit_depth;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Channel count and widest sample type of an EXR `chlist`.
+fn exr_channels(list: &[u8]) -> (u32, Option<u32>) {
+    let mut channels = 0;
+    let mut bit_depth = None;
+    let mut rest = list;
+    while let Some(end) = rest.iter().position(|b| *b == 0) {
+        if end == 0 || rest.len() < end + 1 + 16 {
+            break;
+        }
+        let pixel_type = i32::from_le_bytes(rest[end + 1..end + 5].try_into().unwrap_or_default());
+        let bits = if pixel_type == 1 { 16 } else { 32 };
+        bit_depth = Some(bit_depth.unwrap_or(0).max(bits));
+        channels += 1;
+        rest = &rest[end + 1 + 16..];
+    }
+    (channels, bit_depth)
+}
+
+fn read_c_string<R: BufRead>(reader: &mut R) -> Result<String> {
+    let mut bytes = Vec::new();
+    reader.read_until(0, &mut bytes)?;
+    if bytes.pop() != Some(0) {
+        return Err(anyhow!("Unexpected end of file"));
+    }
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
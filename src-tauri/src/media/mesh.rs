@@ -0,0 +1,224 @@
+//! Mesh, vertex and material counts from glTF, OBJ and FBX files.
+
+use super::MediaMetadata;
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::io::{BufRead, Read, Seek, SeekFrom};
+
+const FBX_BINARY_MAGIC: &[u8] = b"Kaydara FBX Binary  \0";
+
+/// Largest JSON chunk read from a glTF file; the geometry itself lives in buffers.
+const MAX_GLTF_JSON_BYTES: u64 = 64 * 1024 * 1024;
+
+pub fn read_gltf<R: Read>(reader: &mut R, metadata: &mut MediaMetadata) -> Result<()> {
+    let mut json = String::new();
+    reader.take(MAX_GLTF_JSON_BYTES).read_to_string(&mut json)?;
+    count_gltf(&serde_json::from_str(&json)?, metadata);
+    Ok(())
+}
+
+pub fn read_glb<R: Read>(reader: &mut R, metadata: &mut MediaMetadata) -> Result<()> {
+    // 12-byte file header, then the JSON chunk's length and type.
+    let mut header = [0u8; 20];
+    reader.read_exact(&mut header)?;
+    if &header[..4] != b"glTF" || &header[16..20] != b"JSON" {
+        return Err(anyhow!("Not a binary glTF file"));
+    }
+    let chunk_length = u32::from_le_bytes(header[12..16].try_into()?) as u64;
+    if chunk_length > MAX_GLTF_JSON_BYTES {
+        return Err(anyhow!("glTF JSON chunk is too large"));
+    }
+    let mut json = vec![0u8; chunk_length as usize];
+    reader.read_exact(&mut json)?;
+    count_gltf(&serde_json::from_slice(&json)?, metadata);
+    Ok(())
+}
+
+fn count_gltf(document: &Value, metadata: &mut MediaMetadata) {
+    let array_len = |key: &str| document.get(key).and_then(Value::as_array).map_or(0, |a| a.len() as u64);
+    let accessors = document.get("accessors").and_then(Value::as_array);
+    let vertex_count = document
+        .get("meshes")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|mesh| mesh.get("primitives").and_then(Value::as_array))
+        .flatten()
+        .filter_map(|primitive| primitive.pointer("/attributes/POSITION").and_then(Value::as_u64))
+        .filter_map(|index| accessors?.get(index as usize)?.get("count")?.as_u64())
+        .sum();
+
+    metadata.mesh_count = Some(array_len("meshes"));
+    metadata.material_count = Some(array_len("materials"));
+    metadata.vertex_count = Some(vertex_count);
+}
+
+pub fn read_obj<R: BufRead>(reader: &mut R, metadata: &mut MediaMetadata) -> Result<()> {
+    let mut vertices = 0u64;
+    let mut objects = 0u64;
+    let mut materials = HashSet::new();
+    let mut line = Vec::new();
+    while reader.read_until(b'\n', &mut line)? > 0 {
+        let text = String::from_utf8_lossy(&line);
+        let mut parts = text.split_whitespace();
+        match parts.next() {
+            Some("v") => vertices += 1,
+            Some("o") => objects += 1,
+            Some("usemtl") => {
+                if let Some(name) = parts.next() {
+                    materials.insert(name.to_string());
+                }
+            }
+            _ => {}
+        }
+        line.clear();
+    }
+    metadata.vertex_count = Some(vertices);
+    metadata.mesh_count = Some(if objects == 0 && vertices > 0 { 1 } else { objects });
+    metadata.material_count = Some(materials.len() as u64);
+    Ok(())
+}
+
+pub fn read_fbx<R: BufRead + Seek>(reader: &mut R, metadata: &mut MediaMetadata) -> Result<()> {
+    let mut magic = [0u8; 21];
+    reader.read_exact(&mut magic)?;
+    if magic == FBX_BINARY_MAGIC {
+        return read_fbx_binary(reader, metadata);
+    }
+    reader.seek(SeekFrom::Start(0))?;
+    read_fbx_ascii(reader, metadata)
+}
+
+/// FBX node record header. Files from version 7.5 on use 64-bit offsets.
+struct FbxNode {
+    end_offset: u64,
+    property_bytes: u64,
+    name: String,
+}
+
+fn read_fbx_node<R: Read>(reader: &mut R, wide: bool) -> Result<FbxNode> {
+    let mut read_offset = || -> Result<u64> {
+        if wide {
+            let mut buf = [0u8; 8];
+            reader.read_exact(&mut buf)?;
+            Ok(u64::from_le_bytes(buf))
+        } else {
+            let mut buf = [0u8; 4];
+            reader.read_exact(&mut buf)?;
+            Ok(u32::from_le_bytes(buf) as u64)
+        }
+    };
+    let end_offset = read_offset()?;
+    let _property_count = read_offset()?;
+    let property_bytes = read_offset()?;
+    let mut name_length = [0u8; 1];
+    reader.read_exact(&mut name_length)?;
+    let mut name = vec![0u8; name_length[0] as usize];
+    reader.read_exact(&mut name)?;
+    Ok(FbxNode {
+        end_offset,
+        property_bytes,
+        name: String::from_utf8_lossy(&name).into_owned(),
+    })
+}
+
+/// Walk the child records of a node ending at `end`, calling `visit` with each child
+/// positioned at the start of its properties.
+fn for_each_fbx_child<R: Read + Seek>(
+    reader: &mut R,
+    wide: bool,
+    end: u64,
+    mut visit: impl FnMut(&mut R, &FbxNode) -> Result<()>,
+) -> Result<()> {
+    loop {
+        let start = reader.stream_position()?;
+        if start >= end {
+            return Ok(());
+        }
+        let node = read_fbx_node(reader, wide)?;
+        if node.end_offset == 0 {
+            return Ok(());
+        }
+        if node.end_offset <= start {
+            return Err(anyhow!("Corrupt FBX node {}", node.name));
+        }
+        visit(reader, &node)?;
+        reader.seek(SeekFrom::Start(node.end_offset))?;
+    }
+}
+
+fn read_fbx_binary<R: Read + Seek>(reader: &mut R, metadata: &mut MediaMetadata) -> Result<()> {
+    let mut version = [0u8; 6];
+    reader.read_exact(&mut version)?;
+    let wide = u32::from_le_bytes(version[2..6].try_into()?) >= 7500;
+    let file_end = reader.seek(SeekFrom::End(0))?;
+    reader.seek(SeekFrom::Start(27))?;
+
+    let mut meshes = 0u64;
+    let mut vertices = 0u64;
+    let mut materials = 0u64;
+    for_each_fbx_child(reader, wide, file_end, |reader, node| {
+        if node.name != "Objects" {
+            return Ok(());
+        }
+        reader.seek(SeekFrom::Current(node.property_bytes as i64))?;
+        for_each_fbx_child(reader, wide, node.end_offset, |reader, object| {
+            match object.name.as_str() {
+                "Material" => materials += 1,
+                "Geometry" => {
+                    reader.seek(SeekFrom::Current(object.property_bytes as i64))?;
+                    let mut geometry_vertices = None;
+                    for_each_fbx_child(reader, wide, object.end_offset, |reader, child| {
+                        if child.name == "Vertices" {
+                            // Array property: type code, element count, encoding, compressed length.
+                            let mut header = [0u8; 5];
+                            reader.read_exact(&mut header)?;
+                            if header[0] == b'd' || header[0] == b'f' {
+                                geometry_vertices = Some(u32::from_le_bytes(header[1..5].try_into()?) as u64 / 3);
+                            }
+                        }
+                        Ok(())
+                    })?;
+                    if let Some(count) = geometry_vertices {
+                        meshes += 1;
+                        vertices += count;
+                    }
+                }
+                _ => {}
+            }
+            Ok(())
+        })
+    })?;
+
+    metadata.mesh_count = Some(meshes);
+    metadata.vertex_count = Some(vertices);
+    metadata.material_count = Some(materials);
+    Ok(())
+}
+
+/// ASCII FBX: geometry objects carry `Vertices: *N { ... }` with N coordinates.
+fn read_fbx_ascii<R: BufRead>(reader: &mut R, metadata: &mut MediaMetadata) -> Result<()> {
+    let mut meshes = 0u64;
+    let mut vertices = 0u64;
+    let mut materials = 0u64;
+    let mut line = Vec::new();
+    while reader.read_until(b'\n', &mut line)? > 0 {
+        let text = String::from_utf8_lossy(&line);
+        let text = text.trim_start();
+        if let Some(count) = text.strip_prefix("Vertices: *") {
+            let digits: String = count.chars().take_while(char::is_ascii_digit).collect();
+            if let Ok(count) = digits.parse::<u64>() {
+                meshes += 1;
+                vertices += count / 3;
+            }
+        } else if text.starts_with("Material: ") {
+            materials += 1;
+        }
+        line.clear();
+    }
+    metadata.mesh_count = Some(meshes);
+    metadata.vertex_count = Some(vertices);
+    metadata.material_count = Some(materials);
+    Ok(())
+}
@@ -0,0 +1,206 @@
+//! Standalone media files (textures, HDRIs, audio and meshes) indexed as assets of their own.
+//!
+//! Metadata is read from file headers only, so indexing a large file stays cheap.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+mod audio;
+mod image;
+mod mesh;
+
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "tga", "hdr", "exr"];
+const AUDIO_EXTENSIONS: &[&str] = &["wav", "flac", "ogg", "mp3"];
+const MESH_EXTENSIONS: &[&str] = &["fbx", "obj", "gltf", "glb"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MediaKind {
+    Image,
+    Audio,
+    Mesh,
+}
+
+/// What the header of a media file says, stored in the asset's `metadata` under `"media"`.
+/// Fields that do not apply to the kind, or could not be read, are left out.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MediaMetadata {
+    pub kind: MediaKind,
+    /// Lower-case file extension.
+    pub format: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+    /// Bits per channel (images) or per sample (audio).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bit_depth: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channels: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_seconds: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sample_rate: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vertex_count: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mesh_count: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub material_count: Option<u64>,
+}
+
+impl MediaMetadata {
+    pub fn new(kind: MediaKind, format: &str) -> Self {
+        Self {
+            kind,
+            format: format.to_string(),
+            width: None,
+            height: None,
+            bit_depth: None,
+            channels: None,
+            duration_seconds: None,
+            sample_rate: None,
+            vertex_count: None,
+            mesh_count: None,
+            material_count: None,
+        }
+    }
+}
+
+fn extension(path: &Path) -> Option<String> {
+    path.extension().and_then(|e| e.to_str()).map(str::to_ascii_lowercase)
+}
+
+pub fn media_kind(path: &Path) -> Option<MediaKind> {
+    let extension = extension(path)?;
+    let extension = extension.as_str();
+    if IMAGE_EXTENSIONS.contains(&extension) {
+        Some(MediaKind::Image)
+    } else if AUDIO_EXTENSIONS.contains(&extension) {
+        Some(MediaKind::Audio)
+    } else if MESH_EXTENSIONS.contains(&extension) {
+        Some(MediaKind::Mesh)
+    } else {
+        None
+    }
+}
+
+/// Whether `path` is a media file a media location indexes. `allowed` narrows the supported
+/// extensions to the location's own list; an empty list allows all of them.
+pub fn is_indexed_media(path: &Path, allowed: &[String]) -> bool {
+    media_kind(path).is_some()
+        && (allowed.is_empty() || extension(path).is_some_and(|e| allowed.contains(&e)))
+}
+
+/// Read the header of a media file.
+pub fn read_metadata(path: &Path) -> Result<MediaMetadata> {
+    let kind = media_kind(path).ok_or_else(|| anyhow::anyhow!("Not a supported media file: {}", path.display()))?;
+    let format = extension(path).unwrap_or_default();
+    let mut metadata = MediaMetadata::new(kind, &format);
+    let mut reader = BufReader::new(File::open(path)?);
+
+    match format.as_str() {
+        "png" => image::read_png(&mut reader, &mut metadata)?,
+        "jpg" | "jpeg" => image::read_jpeg(&mut reader, &mut metadata)?,
+        "tga" => image::read_tga(&mut reader, &mut metadata)?,
+        "hdr" => image::read_radiance(&mut reader, &mut metadata)?,
+        "exr" => image::read_exr(&mut reader, &mut metadata)?,
+        "wav" => audio::read_wav(&mut reader, &mut metadata)?,
+        "flac" => audio::read_flac(&mut reader, &mut metadata)?,
+        "ogg" => audio::read_ogg(&mut reader, &mut metadata)?,
+        "mp3" => audio::read_mp3(&mut reader, &mut metadata)?,
+        "gltf" => mesh::read_gltf(&mut reader, &mut metadata)?,
+        "glb" => mesh::read_glb(&mut reader, &mut metadata)?,
+        "obj" => mesh::read_obj(&mut reader, &mut metadata)?,
+        "fbx" => mesh::read_fbx(&mut reader, &mut metadata)?,
+        _ => {}
+    }
+    Ok(metadata)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fbx_node(out: &mut Vec<u8>, name: &str, properties: &[u8], children: impl FnOnce(&mut Vec<u8>)) {
+        let start = out.len();
+        out.extend(0u32.to_le_bytes());
+        out.extend((!properties.is_empty() as u32).to_le_bytes());
+        out.extend((properties.len() as u32).to_le_bytes());
+        out.push(name.len() as u8);
+        out.extend(name.as_bytes());
+        out.extend(properties);
+        let before_children = out.len();
+        children(out);
+        if out.len() > before_children {
+            out.extend([0u8; 13]);
+        }
+        let end = out.len() as u32;
+        out[start..start + 4].copy_from_slice(&end.to_le_bytes());
+    }
+
+    #[test]
+    fn test_reads_headers_of_common_formats() {
+        let dir = std::env::temp_dir().join(format!("omnidex-media-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let mut wav = b"RIFF\0\0\0\0WAVEfmt \x10\0\0\0\x01\0\x02\0".to_vec();
+        wav.extend(44_100u32.to_le_bytes());
+        wav.extend((44_100u32 * 4).to_le_bytes());
+        wav.extend([4, 0, 16, 0]);
+        wav.extend(b"data");
+        wav.extend((44_100u32 * 4 * 2).to_le_bytes());
+        std::fs::write(dir.join("rain.wav"), &wav).unwrap();
+
+        let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+        png.extend(2048u32.to_be_bytes());
+        png.extend(1024u32.to_be_bytes());
+        png.extend([16, 6]);
+        std::fs::write(dir.join("T_Rock.png"), &png).unwrap();
+
+        std::fs::write(dir.join("sky.hdr"), b"#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y 2048 +X 4096\n").unwrap();
+        std::fs::write(dir.join("crate.obj"), "o Crate\nv 0 0 0\nv 1 0 0\nv 1 1 0\nusemtl Wood\nf 1 2 3\n").unwrap();
+        std::fs::write(
+            dir.join("tree.gltf"),
+            r#"{"meshes":[{"primitives":[{"attributes":{"POSITION":0}}]}],"accessors":[{"count":120}],"materials":[{},{}]}"#,
+        )
+        .unwrap();
+
+        let mut fbx = b"Kaydara FBX Binary  \0\x1a\0".to_vec();
+        fbx.extend(7400u32.to_le_bytes());
+        fbx_node(&mut fbx, "Objects", &[], |out| {
+            fbx_node(out, "Geometry", &[], |out| {
+                let mut vertices = vec![b'd'];
+                vertices.extend(9u32.to_le_bytes());
+                vertices.extend(0u32.to_le_bytes());
+                vertices.extend(72u32.to_le_bytes());
+                vertices.extend([0u8; 72]);
+                fbx_node(out, "Vertices", &vertices, |_| {});
+            });
+            fbx_node(out, "Material", &[], |_| {});
+        });
+        fbx.extend([0u8; 13]);
+        std::fs::write(dir.join("rock.fbx"), &fbx).unwrap();
+
+        let wav = read_metadata(&dir.join("rain.wav")).unwrap();
+        let fbx = read_metadata(&dir.join("rock.fbx")).unwrap();
+        let png = read_metadata(&dir.join("T_Rock.png")).unwrap();
+        let hdr = read_metadata(&dir.join("sky.hdr")).unwrap();
+        let obj = read_metadata(&dir.join("crate.obj")).unwrap();
+        let gltf = read_metadata(&dir.join("tree.gltf")).unwrap();
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert_eq!((wav.sample_rate, wav.channels, wav.bit_depth), (Some(44_100), Some(2), Some(16)));
+        assert_eq!(wav.duration_seconds, Some(2.0));
+        assert_eq!((png.width, png.height, png.bit_depth, png.channels), (Some(2048), Some(1024), Some(16), Some(4)));
+        assert_eq!((hdr.width, hdr.height), (Some(4096), Some(2048)));
+        assert_eq!((obj.vertex_count, obj.mesh_count, obj.material_count), (Some(3), Some(1), Some(1)));
+        assert_eq!((gltf.vertex_count, gltf.material_count), (Some(120), Some(2)));
+        assert_eq!((fbx.vertex_count, fbx.mesh_count, fbx.material_count), (Some(3), Some(1), Some(1)));
+        assert!(is_indexed_media(Path::new("a.WAV"), &[]));
+        assert!(!is_indexed_media(Path::new("a.wav"), &["fbx".to_string()]));
+    }
+}
@@ -1,6 +1,9 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
+use crate::categories::CategoryTaxonomy;
+use crate::unreal::version::EngineVersion;
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct AppSetting {
     pub id: Option<i64>,
@@ -12,7 +15,8 @@ pub struct AppSetting {
     pub modified_date: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AppSettings {
     pub theme: String,
     pub auto_scan: bool,
@@ -22,4 +26,116 @@ pub struct AppSettings {
     pub default_view: String,
     pub backup_enabled: bool,
     pub backup_location: Option<String>,
-} 
\ No newline at end of file
+    pub backup_interval_hours: i32,
+    pub backup_keep_count: i32,
+    pub backup_max_age_days: i32,
+    pub scan_concurrency: i32,
+    /// Custom category taxonomy; `None` uses the built-in one.
+    pub category_taxonomy: Option<CategoryTaxonomy>,
+    /// Serve the local HTTP API on `127.0.0.1:api_port`.
+    pub api_enabled: bool,
+    pub api_port: i32,
+    /// Bearer token API clients must send; required while the API is enabled.
+    pub api_token: Option<String>,
+    /// Shared team library file, usually on a network share; `None` keeps the library private.
+    pub team_library_path: Option<String>,
+    /// Name recorded with this user's edits in the team library; defaults to the OS user name.
+    pub team_user_name: Option<String>,
+    /// Minutes between automatic team syncs; 0 only syncs on request.
+    pub team_sync_interval_minutes: i32,
+    /// Unreal Engine release you work with, e.g. `5.3`; `None` uses the newest of the indexed projects.
+    pub engine_version: Option<String>,
+    /// Metadata providers in merge precedence, highest first; providers left out follow in
+    /// their default order.
+    pub metadata_provider_order: Vec<String>,
+}
+
+pub const VALID_THEMES: &[&str] = &["system", "light", "dark"];
+pub const VALID_THUMBNAIL_SIZES: &[&str] = &["small", "medium", "large"];
+pub const VALID_VIEWS: &[&str] = &["grid", "list"];
+/// Shortest API token accepted, so it cannot be guessed by trying.
+pub const MIN_API_TOKEN_LENGTH: usize = 16;
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            theme: "system".to_string(),
+            auto_scan: false,
+            scan_interval_minutes: 60,
+            thumbnail_size: "medium".to_string(),
+            show_hidden_files: false,
+            default_view: "grid".to_string(),
+            backup_enabled: false,
+            backup_location: None,
+            backup_interval_hours: 24,
+            backup_keep_count: 10,
+            backup_max_age_days: 30,
+            scan_concurrency: 4,
+            category_taxonomy: None,
+            api_enabled: false,
+            api_port: 7717,
+            api_token: None,
+            team_library_path: None,
+            team_user_name: None,
+            team_sync_interval_minutes: 15,
+            engine_version: None,
+            metadata_provider_order: crate::providers::PROVIDER_IDS.iter().map(|id| id.to_string()).collect(),
+        }
+    }
+}
+
+impl AppSettings {
+    /// Check every field against its allowed values, returning a message for the first violation.
+    pub fn validate(&self) -> Result<(), String> {
+        if !VALID_THEMES.contains(&self.theme.as_str()) {
+            return Err(format!("Invalid theme '{}', expected one of {:?}", self.theme, VALID_THEMES));
+        }
+        if !VALID_THUMBNAIL_SIZES.contains(&self.thumbnail_size.as_str()) {
+            return Err(format!("Invalid thumbnail size '{}', expected one of {:?}", self.thumbnail_size, VALID_THUMBNAIL_SIZES));
+        }
+        if !VALID_VIEWS.contains(&self.default_view.as_str()) {
+            return Err(format!("Invalid default view '{}', expected one of {:?}", self.default_view, VALID_VIEWS));
+        }
+        if !(1..=10_080).contains(&self.scan_interval_minutes) {
+            return Err(format!("Scan interval must be between 1 and 10080 minutes, got {}", self.scan_interval_minutes));
+        }
+        if !(1..=720).contains(&self.backup_interval_hours) {
+            return Err(format!("Backup interval must be between 1 and 720 hours, got {}", self.backup_interval_hours));
+        }
+        if self.backup_keep_count < 1 {
+            return Err(format!("At least one backup must be kept, got {}", self.backup_keep_count));
+        }
+        if self.backup_max_age_days < 1 {
+            return Err(format!("Backup retention must be at least one day, got {}", self.backup_max_age_days));
+        }
+        if !(1..=32).contains(&self.scan_concurrency) {
+            return Err(format!("Scan concurrency must be between 1 and 32, got {}", self.scan_concurrency));
+        }
+        if matches!(&self.backup_location, Some(location) if location.trim().is_empty()) {
+            return Err("Backup location cannot be an empty path".to_string());
+        }
+        if !(1024..=65_535).contains(&self.api_port) {
+            return Err(format!("API port must be between 1024 and 65535, got {}", self.api_port));
+        }
+        if self.api_enabled && !matches!(&self.api_token, Some(token) if token.len() >= MIN_API_TOKEN_LENGTH) {
+            return Err(format!("The API needs a token of at least {} characters", MIN_API_TOKEN_LENGTH));
+        }
+        if matches!(&self.team_library_path, Some(path) if path.trim().is_empty()) {
+            return Err("Team library path cannot be an empty path".to_string());
+        }
+        if matches!(&self.team_user_name, Some(name) if name.trim().is_empty()) {
+            return Err("Team user name cannot be empty".to_string());
+        }
+        if !(0..=1440).contains(&self.team_sync_interval_minutes) {
+            return Err(format!("Team sync interval must be between 0 and 1440 minutes, got {}", self.team_sync_interval_minutes));
+        }
+        if let Some(version) = &self.engine_version {
+            version.parse::<EngineVersion>()?;
+        }
+        crate::providers::validate_order(&self.metadata_provider_order)?;
+        if let Some(taxonomy) = &self.category_taxonomy {
+            taxonomy.validate()?;
+        }
+        Ok(())
+    }
+}
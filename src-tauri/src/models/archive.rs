@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+/// Outcome of unpacking an archive asset into a scan location.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveExtraction {
+    pub asset_id: i64,
+    pub location_id: i64,
+    /// Folder the archive was unpacked into.
+    pub destination: String,
+    pub files_extracted: usize,
+    /// Asset indexed for the extracted folder, if it is an asset root of the location.
+    pub extracted_asset_id: Option<i64>,
+}
@@ -7,6 +7,7 @@ pub struct Asset {
     pub id: Option<i64>,
     pub name: String,
     pub file_path: String,
+    pub relative_path: Option<String>, // Relative to the scan location, with '/' separators
     pub asset_type: String,
     pub file_size: i64,
     pub created_date: String,
@@ -46,6 +47,8 @@ pub struct Asset {
 
     pub notes: Option<String>,
     pub orbital_manual_overrides: Option<String>, // JSON string for manual edits
+    pub user_modified_date: Option<String>, // Last edit of tags, notes, favorites, overrides or match
+    pub packages_indexed_date: Option<String>, // When asset_packages was last rebuilt for this asset
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,23 +16,18 @@ pub struct AssetCardData {
     pub created_date: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum SortOption {
     NameAsc,
     NameDesc,
     DateAsc,
+    #[default]
     DateDesc,
     DateAddedAsc,
     DateAddedDesc,
 }
 
-impl Default for SortOption {
-    fn default() -> Self {
-        SortOption::DateDesc
-    }
-}
-
 impl SortOption {
     pub fn to_sql(&self) -> &'static str {
         match self {
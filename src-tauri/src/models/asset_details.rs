@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use super::Asset;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AssetDetails {
     pub id: i64,
@@ -51,4 +53,48 @@ pub struct AssetForFrontend {
 
     pub notes: Option<String>,
     pub orbital_manual_overrides: Option<String>,
-} 
\ No newline at end of file
+} 
+
+impl From<Asset> for AssetForFrontend {
+    fn from(asset: Asset) -> Self {
+        AssetForFrontend {
+            id: asset.id,
+            name: asset.name,
+            file_path: asset.file_path,
+            asset_type: asset.asset_type,
+            file_size: asset.file_size,
+            created_date: asset.created_date,
+            modified_date: asset.modified_date,
+            first_indexed_timestamp: asset.first_indexed_timestamp,
+            thumbnail_path: asset.thumbnail_path,
+            tags: asset.tags.map(|t| t.split(',').map(|s| s.trim().to_string()).collect()).unwrap_or_default(),
+            description: asset.description,
+            scan_location_id: asset.scan_location_id,
+            is_favorite: asset.is_favorite,
+            last_accessed: asset.last_accessed,
+            file_hash: asset.file_hash,
+            metadata: asset.metadata,
+            orbital_title: asset.orbital_title,
+            orbital_description: asset.orbital_description,
+            orbital_technical_details: asset.orbital_technical_details,
+            orbital_seller_name: asset.orbital_seller_name,
+            orbital_price: asset.orbital_price,
+            orbital_release_date: asset.orbital_release_date,
+            orbital_last_modified: asset.orbital_last_modified,
+            orbital_rating_average: asset.orbital_rating_average,
+            orbital_rating_count: asset.orbital_rating_count,
+            orbital_categories: asset.orbital_categories,
+            orbital_supported_versions: asset.orbital_supported_versions,
+            orbital_gallery_images: asset.orbital_gallery_images,
+            orbital_thumbnail_url: asset.orbital_thumbnail_url,
+            orbital_source_url: asset.orbital_source_url,
+            orbital_raw_json: asset.orbital_raw_json,
+            orbital_last_checked_timestamp: asset.orbital_last_checked_timestamp,
+            matched_orbital_product_slug: asset.matched_orbital_product_slug,
+            orbital_match_confidence: asset.orbital_match_confidence,
+            orbital_match_type: asset.orbital_match_type,
+            notes: asset.notes,
+            orbital_manual_overrides: asset.orbital_manual_overrides,
+        }
+    }
+}
@@ -1,9 +1,19 @@
+pub mod archive;
 pub mod asset;
 pub mod scan_location;
 pub mod app_setting;
 pub mod asset_card;
 pub mod asset_details;
+pub mod collection;
+pub mod project;
+pub mod scan_job;
+pub mod storage;
 
+pub use archive::*;
 pub use asset::*;
 pub use scan_location::*;
-pub use app_setting::*; 
\ No newline at end of file
+pub use app_setting::*;
+pub use collection::*;
+pub use project::*;
+pub use scan_job::*;
+pub use storage::*; 
\ No newline at end of file
@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Records that an asset was imported into an Unreal project ("used in").
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(FromRow)]
+pub struct AssetProjectUsage {
+    pub asset_id: i64,
+    pub project_name: String,
+    pub project_path: String, // Path of the .uproject file
+    pub import_mode: String,
+    pub imported_date: String,
+}
+
+/// A library asset found in an indexed Unreal project, with how completely it is present.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(FromRow)]
+pub struct ProjectAssetMatch {
+    pub asset_id: i64,
+    pub name: String,
+    pub library_packages: i64,
+    pub identical: i64,
+    pub modified: i64,
+    pub moved: i64,
+    /// "complete", "modified" or "partial"
+    #[sqlx(skip)]
+    pub status: String,
+}
@@ -0,0 +1,184 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+/// `scan_jobs.status` and `scan_runs.outcome` values.
+pub const SCAN_STATUS_RUNNING: &str = "running";
+pub const SCAN_STATUS_PAUSED: &str = "paused";
+/// The app stopped while the scan was running.
+pub const SCAN_STATUS_INTERRUPTED: &str = "interrupted";
+pub const SCAN_STATUS_COMPLETED: &str = "completed";
+pub const SCAN_STATUS_CANCELLED: &str = "cancelled";
+pub const SCAN_STATUS_FAILED: &str = "failed";
+
+/// A folder scan of one location, persisted so it can resume where it stopped.
+/// A job spans one or more runs; pausing or closing the app ends a run but not the job.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ScanJob {
+    pub id: i64,
+    pub location_id: i64,
+    pub status: String,
+    /// Path of the last folder, archive or file whose batch was written to the database.
+    pub checkpoint_path: Option<String>,
+    pub processed_items: i64,
+    pub total_items: i64,
+    pub assets_found: i64,
+    pub error_count: i64,
+    /// Time spent scanning across all runs.
+    pub active_ms: i64,
+    pub created_date: String,
+    pub updated_date: String,
+}
+
+impl ScanJob {
+    /// Whether the job stopped before finishing and can be picked up again.
+    pub fn is_resumable(&self) -> bool {
+        self.status == SCAN_STATUS_PAUSED || self.status == SCAN_STATUS_INTERRUPTED
+    }
+}
+
+/// One run of a scan job, kept as scan history.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ScanRun {
+    pub id: i64,
+    pub job_id: i64,
+    pub location_id: i64,
+    pub started_date: String,
+    pub finished_date: Option<String>,
+    pub duration_ms: Option<i64>,
+    /// `None` while the run is in progress, otherwise one of the status values.
+    pub outcome: Option<String>,
+    /// Items processed during this run only.
+    pub processed_items: i64,
+    pub assets_found: i64,
+    /// Errors raised during this run, listed by `get_scan_errors`.
+    pub error_count: i64,
+}
+
+/// What went wrong with a path during a scan.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum ScanErrorKind {
+    PermissionDenied,
+    /// The path cannot be stored as text; renaming it fixes the error.
+    PathNotUtf8,
+    /// Size or timestamps of the path could not be read.
+    MetadataUnavailable,
+    /// The folder was read but writing it to the database failed.
+    Database,
+    /// The asset was saved but its marketplace lookup failed.
+    Marketplace,
+    /// Anything else that kept the path from being read, such as a corrupt archive.
+    Unreadable,
+}
+
+/// A scan error of a single path (or of the whole location when `path` is `None`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanError {
+    pub kind: ScanErrorKind,
+    pub path: Option<String>,
+    pub message: String,
+}
+
+impl ScanError {
+    pub fn new(kind: ScanErrorKind, path: Option<&Path>, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            path: path.map(|p| p.to_string_lossy().into_owned()),
+            message: message.into(),
+        }
+    }
+
+    pub fn path_not_utf8(path: &Path) -> Self {
+        Self::new(ScanErrorKind::PathNotUtf8, Some(path), "Path is not valid UTF-8")
+    }
+
+    /// An I/O error reading `path`, reported as `kind` unless access was denied.
+    pub fn io(kind: ScanErrorKind, path: &Path, error: &io::Error) -> Self {
+        let kind = if error.kind() == io::ErrorKind::PermissionDenied { ScanErrorKind::PermissionDenied } else { kind };
+        Self::new(kind, Some(path), error.to_string())
+    }
+
+    /// Classify an error raised while processing `path`.
+    pub fn from_error(path: &Path, error: &anyhow::Error) -> Self {
+        if let Some(scan_error) = error.downcast_ref::<ScanError>() {
+            let mut scan_error = scan_error.clone();
+            scan_error.path.get_or_insert_with(|| path.to_string_lossy().into_owned());
+            return scan_error;
+        }
+        let kind = if error.chain().any(|cause| cause.is::<sqlx::Error>()) {
+            ScanErrorKind::Database
+        } else if error
+            .chain()
+            .filter_map(|cause| cause.downcast_ref::<io::Error>())
+            .any(|e| e.kind() == io::ErrorKind::PermissionDenied)
+        {
+            ScanErrorKind::PermissionDenied
+        } else {
+            ScanErrorKind::Unreadable
+        };
+        Self::new(kind, Some(path), format!("{:#}", error))
+    }
+}
+
+impl fmt::Display for ScanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.path {
+            Some(path) => write!(f, "{}: {}", path, self.message),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+impl std::error::Error for ScanError {}
+
+/// A scan error as stored for a run.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ScanErrorRecord {
+    pub id: i64,
+    pub run_id: i64,
+    pub job_id: i64,
+    pub location_id: i64,
+    pub path: Option<String>,
+    pub kind: ScanErrorKind,
+    pub message: String,
+    pub occurred_date: String,
+    /// Set once retrying the path succeeded.
+    pub resolved_date: Option<String>,
+}
+
+impl ScanErrorRecord {
+    /// Whether retrying can fix the error; non-UTF-8 paths have to be renamed and location-wide
+    /// errors need a new scan.
+    pub fn is_retryable(&self) -> bool {
+        self.path.is_some() && self.kind != ScanErrorKind::PathNotUtf8
+    }
+}
+
+/// Which scan errors `get_scan_errors` returns. Unset fields match everything.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScanErrorFilter {
+    pub location_id: Option<i64>,
+    pub run_id: Option<i64>,
+    pub kind: Option<ScanErrorKind>,
+    pub path_contains: Option<String>,
+    /// Also return errors whose path has since been retried successfully.
+    pub include_resolved: bool,
+    pub limit: Option<i64>,
+}
+
+/// Outcome of retrying the failed paths of a location.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScanRetryReport {
+    pub location_id: i64,
+    pub retried: usize,
+    pub resolved: usize,
+    /// Paths that failed again, with their new error.
+    pub failed: Vec<ScanError>,
+    /// Errors that retrying cannot fix.
+    pub skipped: usize,
+}
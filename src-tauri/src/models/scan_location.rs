@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
+use super::ScanError;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[derive(FromRow)]
 pub struct ScanLocation {
@@ -13,6 +15,98 @@ pub struct ScanLocation {
     pub file_extensions: Option<String>, // JSON string of extensions array
     pub created_date: String,
     pub description: Option<String>,
+    pub watch_enabled: bool,
+    pub location_kind: String, // "vault" (asset packs), "project" (an Unreal project) or "media" (loose files)
+    pub root_rules: Option<String>, // JSON of AssetRootRules; None keeps the scan_recursive behaviour
+    pub team_key: Option<String>, // Name of the location in the team library; None keeps it private
+}
+
+pub const LOCATION_KIND_VAULT: &str = "vault";
+pub const LOCATION_KIND_PROJECT: &str = "project";
+pub const LOCATION_KIND_MEDIA: &str = "media";
+
+impl ScanLocation {
+    pub fn is_project(&self) -> bool {
+        self.location_kind == LOCATION_KIND_PROJECT
+    }
+
+    /// Whether every supported media file is indexed as an asset, rather than asset folders.
+    pub fn is_media(&self) -> bool {
+        self.location_kind == LOCATION_KIND_MEDIA
+    }
+
+    /// Lower-case extensions from `file_extensions`, accepting a comma-separated list or a JSON array.
+    /// Empty when the location does not narrow them down.
+    pub fn extension_filter(&self) -> Vec<String> {
+        let Some(list) = self.file_extensions.as_deref() else {
+            return Vec::new();
+        };
+        let items: Vec<String> = serde_json::from_str(list)
+            .unwrap_or_else(|_| list.split(',').map(str::to_string).collect());
+        items
+            .iter()
+            .map(|item| item.trim().trim_start_matches('.').to_ascii_lowercase())
+            .filter(|item| !item.is_empty())
+            .collect()
+    }
+
+    /// The location's asset-root rules; empty when none are configured.
+    pub fn asset_root_rules(&self) -> anyhow::Result<AssetRootRules> {
+        match self.root_rules.as_deref() {
+            Some(json) if !json.trim().is_empty() => serde_json::from_str(json)
+                .map_err(|e| anyhow::anyhow!("Invalid asset-root rules for {}: {}", self.path, e)),
+            _ => Ok(AssetRootRules::default()),
+        }
+    }
+}
+
+/// A condition that makes a folder an asset root.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RootRule {
+    /// The folder directly contains a `.uproject` or `.uplugin` file.
+    UnrealDescriptor,
+    /// The folder directly contains a `Content` folder.
+    ContentFolder,
+    /// The folder sits exactly `depth` levels below the location root (1 = direct child).
+    Depth { depth: usize },
+    /// The folder's path relative to the location root matches a glob, e.g. `Packs/*`.
+    Glob { pattern: String },
+}
+
+/// Decides which folders of a vault location become assets.
+///
+/// Without `roots` the location keeps the `scan_recursive` behaviour. With rules, the scan
+/// walks down from the location root and the first folder matching any rule becomes an asset;
+/// nothing below an asset root becomes an asset of its own.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AssetRootRules {
+    pub roots: Vec<RootRule>,
+    /// If not empty, only folders matching one of these globs can become asset roots.
+    pub include: Vec<String>,
+    /// Files and folders matching one of these globs are skipped together with their contents.
+    pub exclude: Vec<String>,
+}
+
+/// A folder a rule set would index.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreviewRoot {
+    pub relative_path: String,
+    pub file_count: i64,
+    pub total_bytes: i64,
+    /// Whether the folder is already indexed as an asset.
+    pub indexed: bool,
+}
+
+/// What a scan of a location would index with a given rule set.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetRootPreview {
+    pub path: String,
+    pub roots: Vec<PreviewRoot>,
+    /// Relative paths of assets indexed under this path that the rules would no longer select.
+    pub not_selected: Vec<String>,
+    pub walk_errors: Vec<ScanError>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,5 +117,32 @@ pub struct ScanProgress {
     pub processed_items: u64,
     pub total_items: u64,
     pub completed_successfully: bool,
+    /// Why the scan failed as a whole. Errors of single paths are counted in `error_count`.
     pub error: Option<String>,
-} 
\ No newline at end of file
+    /// Errors recorded so far in this run, listed by `get_scan_errors`.
+    pub error_count: u64,
+}
+
+/// Outcome of re-pointing a scan location at a new root folder.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelocationReport {
+    pub location_id: i64,
+    pub old_path: String,
+    pub new_path: String,
+    /// Relative paths that were checked under the new root.
+    pub sampled: usize,
+    /// Sampled relative paths that do not exist under the new root.
+    pub missing: Vec<String>,
+    /// Whether the new root was saved and asset paths rewritten.
+    pub committed: bool,
+    pub assets_updated: u64,
+}
+
+/// Emitted as `library-changed` when the filesystem watcher updates assets of a location.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct LibraryChange {
+    pub location_id: i64,
+    pub added: Vec<i64>,
+    pub updated: Vec<i64>,
+    pub removed: Vec<i64>,
+}
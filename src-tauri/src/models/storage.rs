@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Files of one extension or top-level subfolder within an asset (or across the library).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(FromRow)]
+pub struct InventoryEntry {
+    pub group_key: String,
+    pub file_count: i64,
+    pub total_bytes: i64,
+}
+
+/// Where the bytes of a single asset go.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetSizeBreakdown {
+    pub asset_id: i64,
+    pub name: String,
+    pub total_files: i64,
+    pub total_bytes: i64,
+    /// Keyed by lower-case extension without the dot, `(none)` for files without one
+    pub by_extension: Vec<InventoryEntry>,
+    /// Keyed by top-level subfolder, `.` for files directly in the asset folder
+    pub by_folder: Vec<InventoryEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(FromRow)]
+pub struct LocationStorage {
+    pub location_id: i64,
+    pub name: String,
+    pub path: String,
+    pub asset_count: i64,
+    pub total_bytes: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(FromRow)]
+pub struct AssetStorage {
+    pub asset_id: i64,
+    pub name: String,
+    pub total_bytes: i64,
+}
+
+/// Library-wide disk usage for the storage dashboard.
+/// Assets nested inside another asset are left out so no file is counted twice.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageOverview {
+    pub total_files: i64,
+    pub total_bytes: i64,
+    pub by_extension: Vec<InventoryEntry>,
+    pub by_location: Vec<LocationStorage>,
+    pub largest_assets: Vec<AssetStorage>,
+}
@@ -251,7 +251,7 @@ impl OrbitalApiClient {
 
     pub fn extract_product_id_from_url(&self, url_str: &str) -> Option<String> {
         Url::parse(url_str).ok().and_then(|url| {
-            url.path_segments()?.last().map(String::from)
+            url.path_segments()?.next_back().map(String::from)
         })
     }
     pub async fn search_by_folder_name(&self, folder_name: &str) -> Result<Vec<OrbitalAsset>> {
@@ -285,7 +285,7 @@ impl OrbitalApiClient {
                 info!("📦 Fetched raw data from Orbital: {}", json_content);
                 match serde_json::from_str::<api_models::ApiResponse>(&json_content) {
                     Ok(api_response) => {
-                        let asset = self.map_api_response_to_orbital_asset(api_response);
+                        let asset = Self::map_api_response_to_orbital_asset(api_response);
                         info!("✅ Successfully parsed API response for: {:?}", asset.title);
                         info!("🔎 Found asset data: {:?}", asset);
                         Ok(Some(asset))
@@ -305,7 +305,7 @@ impl OrbitalApiClient {
         }
     }
     
-    fn map_api_response_to_orbital_asset(&self, api_response: api_models::ApiResponse) -> OrbitalAsset {
+    pub(crate) fn map_api_response_to_orbital_asset(api_response: api_models::ApiResponse) -> OrbitalAsset {
         let gallery_images: Vec<String> = api_response.media.images.clone().into_iter().collect();
 
         let rating = if api_response.review.count > 0 {
@@ -340,7 +340,7 @@ impl OrbitalApiClient {
             description: Some(api_response.description.long.clone()),
             technical_details: Some(api_response.description.technical.clone()),
             seller: Some(api_response.owner.name.clone()),
-            categories: vec![api_response.category.clone()],
+            categories: Self::categories_of(&api_response),
             supported_versions: vec![api_response.engine.min.clone(), api_response.engine.max.clone()],
             gallery_images,
             rating_average: rating.as_ref().map(|r| r.average_rating as f64),
@@ -354,6 +354,19 @@ impl OrbitalApiClient {
         }
     }
 
+    /// The product's category, split into its levels when it is a path such as
+    /// `environments/nature`, followed by the kinds of content the product embeds.
+    fn categories_of(api_response: &api_models::ApiResponse) -> Vec<String> {
+        let mut categories: Vec<String> = Vec::new();
+        let levels = api_response.category.split(['/', '>']).map(str::trim);
+        for category in levels.chain(api_response.computed.embedded_content.iter().map(|c| c.trim())) {
+            if !category.is_empty() && !categories.iter().any(|c| c.eq_ignore_ascii_case(category)) {
+                categories.push(category.to_string());
+            }
+        }
+        categories
+    }
+
     /// Generate multiple slug variations to try for better matching
     fn generate_slug_variations(&self, folder_name: &str) -> Vec<String> {
         let mut variations = std::collections::HashSet::new();
@@ -493,7 +506,7 @@ impl OrbitalApiClient {
     fn folder_name_to_product_slug(&self, name: &str) -> String {
         let name = name.to_lowercase();
         // Replace separators with spaces for consistent splitting
-        let name = name.replace('_', " ").replace('-', " ");
+        let name = name.replace(['_', '-'], " ");
         // Remove all non-alphanumeric characters, but keep spaces
         let re = regex::Regex::new(r"[^a-z0-9\s]").unwrap();
         let name = re.replace_all(&name, "");
@@ -1,7 +1,7 @@
 use anyhow::Result;
 use log::{info, warn};
 use urlencoding;
-use crate::database::DatabaseManager;
+use crate::database::AssetStore;
 use crate::models::asset::Asset;
 
 use crate::orbital::scraper::{fetch_product_page_html, parse_asset_details_from_html, find_first_product_link};
@@ -15,9 +15,11 @@ pub mod matching;
 pub mod models;
 pub mod api_models;
 pub mod scraper;
+pub mod source;
 
 pub use api::OrbitalApiClient;
 pub use auth::OrbitalAuth;
+pub use source::{InMemoryMarketplace, MarketplaceSource};
 // Note: OrbitalBrowserClient not needed for Orbital Market
 pub use matching::AssetMatcher;
 pub use models::*;
@@ -134,7 +136,7 @@ impl OrbitalManager {
     /// Test slug generation variations
     #[cfg(test)]
     pub fn test_slug_generation(&self, folder_name: &str) -> Vec<String> {
-        self.api_client.generate_slug_variations(folder_name)
+        self.api_client.test_slug_generation(folder_name)
     }
 
     /// Test browser automation access specifically
@@ -146,17 +148,11 @@ impl OrbitalManager {
     pub async fn test_token(&self, token: &str) -> Result<bool> {
         self.auth_manager.test_token(token).await
     }
-
-    /// This function is intended for testing purposes.
-    #[cfg(test)]
-    pub async fn test_slug_generation(&self, name: &str) -> Vec<String> {
-        self.api_client.generate_slug_variations(name)
-    }
 }
 
 pub async fn refresh_orbital_metadata_for_asset(
     asset: &Asset,
-    db_manager: &DatabaseManager,
+    store: &dyn AssetStore,
     client: Arc<api::OrbitalApiClient>,
 ) -> Result<()> {
     info!("Fetching Orbital data for asset: {}", asset.name);
@@ -209,7 +205,7 @@ pub async fn refresh_orbital_metadata_for_asset(
 
     if let Some(orbital_data) = orbital_asset {
         info!("✅ Successfully scraped data for: {}", asset.name);
-        if let Err(e) = db_manager.update_asset_with_orbital_details(asset.id.unwrap(), &orbital_data).await {
+        if let Err(e) = store.update_asset_with_orbital_details(asset.id.unwrap(), &orbital_data).await {
             warn!("Failed to update database for asset {}: {}", asset.id.unwrap(), e);
         }
     } else {
@@ -217,4 +213,46 @@ pub async fn refresh_orbital_metadata_for_asset(
     }
 
     Ok(())
-} 
\ No newline at end of file
+}
+
+/// Match an asset to the Orbital product at `url` with full confidence, replacing its metadata.
+/// Only orbital-market.com product URLs are accepted.
+pub async fn match_asset_to_url(
+    store: &dyn AssetStore,
+    client: &dyn MarketplaceSource,
+    asset_id: i64,
+    url: &str,
+) -> Result<OrbitalAsset> {
+    let parsed_url = url::Url::parse(url).map_err(|_| anyhow::anyhow!("Invalid URL format"))?;
+    if parsed_url.host_str() != Some("orbital-market.com") {
+        return Err(anyhow::anyhow!("URL must be from orbital-market.com"));
+    }
+
+    let product_id = client
+        .extract_product_id_from_url(url)
+        .ok_or_else(|| anyhow::anyhow!("Could not extract product ID from URL"))?;
+    let orbital_asset = client
+        .fetch_asset_details(&product_id)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to fetch asset details from Orbital: {}", e))?;
+
+    if !store.asset_exists(asset_id).await? {
+        return Err(anyhow::anyhow!("Asset with ID {} not found", asset_id));
+    }
+
+    store
+        .update_asset_with_orbital_details(asset_id, &orbital_asset)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to update asset with Orbital details: {}", e))?;
+    store
+        .update_asset_orbital_match_details(
+            asset_id,
+            orbital_asset.product_slug.clone(),
+            Some(1.0), // Manual matches get 100% confidence
+            Some("Manual".to_string()),
+        )
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to update asset with match details: {}", e))?;
+
+    Ok(orbital_asset)
+}
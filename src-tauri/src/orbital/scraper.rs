@@ -194,7 +194,7 @@ fn extract_rating_from_text(text: &str) -> Option<f64> {
         // Try to parse each word as a number
         if let Ok(rating) = word.parse::<f64>() {
             // Check if it's in a reasonable rating range (0.0 to 5.0)
-            if rating >= 0.0 && rating <= 5.0 {
+            if (0.0..=5.0).contains(&rating) {
                 return Some(rating);
             }
         }
@@ -0,0 +1,132 @@
+//! The marketplace local assets are matched against: Orbital Market in the app, a fixed
+//! catalogue in tests.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use super::api::OrbitalApiClient;
+use super::models::OrbitalAsset;
+
+#[async_trait]
+pub trait MarketplaceSource: Send + Sync {
+    /// Products that may be the asset stored in a folder of this name.
+    async fn search_by_folder_name(&self, folder_name: &str) -> Result<Vec<OrbitalAsset>>;
+
+    /// Full details of a product, by ID or slug.
+    async fn fetch_asset_details(&self, product_id: &str) -> Result<OrbitalAsset>;
+
+    /// The product ID in the URL of a product page.
+    fn extract_product_id_from_url(&self, url: &str) -> Option<String>;
+}
+
+#[async_trait]
+impl MarketplaceSource for OrbitalApiClient {
+    async fn search_by_folder_name(&self, folder_name: &str) -> Result<Vec<OrbitalAsset>> {
+        OrbitalApiClient::search_by_folder_name(self, folder_name).await
+    }
+
+    async fn fetch_asset_details(&self, product_id: &str) -> Result<OrbitalAsset> {
+        OrbitalApiClient::fetch_asset_details(self, product_id).await
+    }
+
+    fn extract_product_id_from_url(&self, url: &str) -> Option<String> {
+        OrbitalApiClient::extract_product_id_from_url(self, url)
+    }
+}
+
+/// Lets callers keep a handle on the source they hand to the scanner.
+#[async_trait]
+impl<T: MarketplaceSource + ?Sized> MarketplaceSource for Arc<T> {
+    async fn search_by_folder_name(&self, folder_name: &str) -> Result<Vec<OrbitalAsset>> {
+        (**self).search_by_folder_name(folder_name).await
+    }
+
+    async fn fetch_asset_details(&self, product_id: &str) -> Result<OrbitalAsset> {
+        (**self).fetch_asset_details(product_id).await
+    }
+
+    fn extract_product_id_from_url(&self, url: &str) -> Option<String> {
+        (**self).extract_product_id_from_url(url)
+    }
+}
+
+/// A fixed catalogue of products, searched by the words of their titles.
+#[derive(Debug, Default)]
+pub struct InMemoryMarketplace {
+    products: Mutex<Vec<OrbitalAsset>>,
+    offline: AtomicBool,
+    requests: AtomicUsize,
+}
+
+impl InMemoryMarketplace {
+    pub fn new(products: Vec<OrbitalAsset>) -> Self {
+        Self { products: Mutex::new(products), ..Default::default() }
+    }
+
+    pub fn add_product(&self, product: OrbitalAsset) {
+        self.products.lock().unwrap().push(product);
+    }
+
+    /// Fail every request, as if the marketplace could not be reached.
+    pub fn set_offline(&self, offline: bool) {
+        self.offline.store(offline, Ordering::Relaxed);
+    }
+
+    /// Searches and detail lookups made so far.
+    pub fn request_count(&self) -> usize {
+        self.requests.load(Ordering::Relaxed)
+    }
+
+    fn begin_request(&self) -> Result<()> {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        if self.offline.load(Ordering::Relaxed) {
+            return Err(anyhow::anyhow!("Marketplace is offline"));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl MarketplaceSource for InMemoryMarketplace {
+    async fn search_by_folder_name(&self, folder_name: &str) -> Result<Vec<OrbitalAsset>> {
+        self.begin_request()?;
+        let words = title_words(folder_name);
+        Ok(self
+            .products
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|product| {
+                let title = title_words(product.title.as_deref().unwrap_or_default());
+                words.iter().any(|word| title.contains(word))
+            })
+            .cloned()
+            .collect())
+    }
+
+    async fn fetch_asset_details(&self, product_id: &str) -> Result<OrbitalAsset> {
+        self.begin_request()?;
+        self.products
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|product| {
+                product.product_slug.as_deref() == Some(product_id) || product.id.as_deref() == Some(product_id)
+            })
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("No product {}", product_id))
+    }
+
+    fn extract_product_id_from_url(&self, url: &str) -> Option<String> {
+        url::Url::parse(url).ok()?.path_segments()?.rfind(|s| !s.is_empty()).map(String::from)
+    }
+}
+
+fn title_words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
@@ -0,0 +1,151 @@
+//! Fab, through the JSON its own listing pages load. Listings are read leniently since the
+//! endpoints are not a published API: a missing field leaves that value empty.
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use serde_json::Value;
+
+use super::{json_number, json_text, MetadataProvider, ProductMetadata, SearchQuery};
+
+pub const PROVIDER_ID: &str = "fab";
+
+const FAB_BASE_URL: &str = "https://www.fab.com";
+
+pub struct FabProvider {
+    client: reqwest::Client,
+}
+
+impl FabProvider {
+    pub fn new(client: reqwest::Client) -> Self {
+        Self { client }
+    }
+
+    async fn get_json(&self, url: &str) -> Result<Value> {
+        let response = self.client.get(url).header("Accept", "application/json").send().await?;
+        if !response.status().is_success() {
+            return Err(anyhow!("Fab returned {} for {}", response.status(), url));
+        }
+        Ok(response.json().await?)
+    }
+}
+
+#[async_trait]
+impl MetadataProvider for FabProvider {
+    fn id(&self) -> &'static str {
+        PROVIDER_ID
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<ProductMetadata>> {
+        let url = format!("{}/i/listings/search?q={}", FAB_BASE_URL, urlencoding::encode(&query.name));
+        let results = self.get_json(&url).await?;
+        Ok(results
+            .get("results")
+            .and_then(Value::as_array)
+            .map(|listings| listings.iter().filter_map(parse_listing).collect())
+            .unwrap_or_default())
+    }
+
+    async fn fetch_details(&self, reference: &str) -> Result<ProductMetadata> {
+        let uid = listing_uid(reference).ok_or_else(|| anyhow!("'{}' is not a Fab listing", reference))?;
+        let listing = self.get_json(&format!("{}/i/listings/{}", FAB_BASE_URL, uid)).await?;
+        parse_listing(&listing).ok_or_else(|| anyhow!("Fab listing {} has no ID", uid))
+    }
+
+    fn parse(&self, document: &str) -> Result<ProductMetadata> {
+        let listing: Value = serde_json::from_str(document)?;
+        parse_listing(&listing).ok_or_else(|| anyhow!("Not a Fab listing"))
+    }
+}
+
+/// The listing ID in a Fab listing URL, or the reference itself when it is already an ID.
+pub fn listing_uid(reference: &str) -> Option<String> {
+    let reference = reference.trim();
+    if !reference.contains('/') {
+        return (!reference.is_empty()).then(|| reference.to_string());
+    }
+    let url = url::Url::parse(reference).ok()?;
+    if !url.host_str()?.ends_with("fab.com") {
+        return None;
+    }
+    let mut segments = url.path_segments()?;
+    segments.find(|segment| *segment == "listings")?;
+    segments.next().filter(|uid| !uid.is_empty()).map(str::to_string)
+}
+
+fn parse_listing(listing: &Value) -> Option<ProductMetadata> {
+    let uid = json_text(listing.get("uid"))?;
+    let names = |key: &str| -> Vec<String> {
+        listing
+            .get(key)
+            .and_then(Value::as_array)
+            .map(|items| items.iter().filter_map(|item| json_text(item.get("name")).or_else(|| json_text(Some(item)))).collect())
+            .unwrap_or_default()
+    };
+    let media_urls = |key: &str| -> Vec<String> {
+        listing
+            .get(key)
+            .and_then(Value::as_array)
+            .map(|items| items.iter().filter_map(|item| json_text(item.get("mediaUrl"))).collect())
+            .unwrap_or_default()
+    };
+    let price = listing.get("startingPrice");
+    let ratings = listing.get("ratings");
+
+    let mut categories: Vec<String> = json_text(listing.pointer("/category/name")).into_iter().collect();
+    categories.extend(names("categories"));
+    categories.dedup();
+
+    Some(ProductMetadata {
+        title: json_text(listing.get("title")),
+        description: json_text(listing.get("description")),
+        technical_details: json_text(listing.get("technicalSpecs")),
+        seller: json_text(listing.pointer("/user/sellerName")).or_else(|| json_text(listing.pointer("/user/displayName"))),
+        price: price.and_then(|p| json_number(p.get("price"))),
+        currency: price.and_then(|p| json_text(p.get("currencyCode"))),
+        categories,
+        tags: names("tags"),
+        engine_versions: names("engineVersions"),
+        images: media_urls("medias"),
+        thumbnail_url: media_urls("thumbnails").into_iter().next(),
+        rating_average: ratings.and_then(|r| json_number(r.get("averageRating"))),
+        rating_count: ratings.and_then(|r| json_number(r.get("total"))).map(|count| count as i64),
+        release_date: json_text(listing.get("createdAt")),
+        last_modified: json_text(listing.get("lastUpdatedAt")),
+        source_url: Some(format!("{}/listings/{}", FAB_BASE_URL, uid)),
+        reference: uid,
+        links: Default::default(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_listing_json() {
+        let provider = FabProvider::new(reqwest::Client::new());
+        let listing = provider
+            .parse(
+                r#"{
+                    "uid": "3f1c-88aa",
+                    "title": "Modular Castle Kit",
+                    "user": { "sellerName": "Stone Works" },
+                    "category": { "name": "Environments" },
+                    "tags": [{ "name": "medieval" }, { "name": "castle" }],
+                    "thumbnails": [{ "mediaUrl": "https://media.fab.com/thumb.jpg" }],
+                    "startingPrice": { "price": "29.99", "currencyCode": "USD" },
+                    "ratings": { "averageRating": 4.6, "total": 12 }
+                }"#,
+            )
+            .unwrap();
+        assert_eq!(listing.reference, "3f1c-88aa");
+        assert_eq!(listing.seller.as_deref(), Some("Stone Works"));
+        assert_eq!(listing.tags, ["medieval", "castle"]);
+        assert_eq!(listing.price, Some(29.99));
+        assert_eq!(listing.rating_count, Some(12));
+        assert_eq!(listing.source_url.as_deref(), Some("https://www.fab.com/listings/3f1c-88aa"));
+
+        assert_eq!(listing_uid("https://www.fab.com/listings/3f1c-88aa").as_deref(), Some("3f1c-88aa"));
+        assert_eq!(listing_uid("https://example.com/listings/3f1c-88aa"), None);
+    }
+}
@@ -0,0 +1,57 @@
+//! A manifest the user keeps with the asset: `omnidex.json` in the asset's folder, or
+//! `<file>.omnidex.json` beside an asset that is a single file. It holds any of the metadata
+//! fields, and its `links` point the other providers at the right product, e.g. a `web` page.
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use std::path::{Path, PathBuf};
+
+use super::{MetadataProvider, ProductMetadata, SearchQuery};
+
+pub const PROVIDER_ID: &str = "manifest";
+
+pub const MANIFEST_FILE_NAME: &str = "omnidex.json";
+
+pub struct ManifestProvider;
+
+/// Where the manifest of the asset at `path` is kept.
+pub fn manifest_path(path: &Path) -> PathBuf {
+    if path.is_dir() {
+        return path.join(MANIFEST_FILE_NAME);
+    }
+    let file_name = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+    path.with_file_name(format!("{}.{}", file_name, MANIFEST_FILE_NAME))
+}
+
+#[async_trait]
+impl MetadataProvider for ManifestProvider {
+    fn id(&self) -> &'static str {
+        PROVIDER_ID
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<ProductMetadata>> {
+        let path = manifest_path(&query.path);
+        if !tokio::fs::try_exists(&path).await.unwrap_or(false) {
+            return Ok(Vec::new());
+        }
+        Ok(vec![self.fetch_details(&path.to_string_lossy()).await?])
+    }
+
+    async fn fetch_details(&self, reference: &str) -> Result<ProductMetadata> {
+        let document = tokio::fs::read_to_string(reference)
+            .await
+            .map_err(|e| anyhow!("Could not read manifest {}: {}", reference, e))?;
+        let mut metadata = self.parse(&document)?;
+        metadata.reference = reference.to_string();
+        Ok(metadata)
+    }
+
+    fn parse(&self, document: &str) -> Result<ProductMetadata> {
+        Ok(serde_json::from_str(document)?)
+    }
+
+    /// The manifest sits with the asset, so it always describes it.
+    fn confidence(&self, _query: &SearchQuery, _candidate: &ProductMetadata) -> f64 {
+        1.0
+    }
+}
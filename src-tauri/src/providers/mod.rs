@@ -0,0 +1,467 @@
+//! Metadata sources beyond the Orbital matching the scanner does: every provider can search
+//! for a product, fetch its details and parse a document it serves. Each provider's result is
+//! stored per asset with where it came from, and the results are merged field by field in the
+//! configured precedence order.
+
+pub mod fab;
+pub mod manifest;
+pub mod orbital;
+pub mod web;
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sqlx::FromRow;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use strsim::jaro_winkler;
+
+use crate::database::DatabaseManager;
+use crate::models::Asset;
+use crate::orbital::MarketplaceSource;
+
+pub use fab::FabProvider;
+pub use manifest::ManifestProvider;
+pub use orbital::OrbitalProvider;
+pub use web::WebPageProvider;
+
+/// Provider ids, in the default merge precedence: the user's own manifest first, then the
+/// marketplaces, then arbitrary pages.
+pub const PROVIDER_IDS: &[&str] = &[manifest::PROVIDER_ID, orbital::PROVIDER_ID, fab::PROVIDER_ID, web::PROVIDER_ID];
+
+/// Lowest name similarity at which a search result is taken as the asset's product.
+pub const MIN_SEARCH_CONFIDENCE: f64 = 0.85;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Product metadata in the same shape for every provider.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProductMetadata {
+    /// What the provider needs to fetch the product again: an ID, slug, URL or file path.
+    pub reference: String,
+    #[serde(alias = "name")]
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub technical_details: Option<String>,
+    pub seller: Option<String>,
+    pub price: Option<f64>,
+    pub currency: Option<String>,
+    pub categories: Vec<String>,
+    pub tags: Vec<String>,
+    /// Supported engine releases, e.g. `5.3`.
+    pub engine_versions: Vec<String>,
+    pub images: Vec<String>,
+    pub thumbnail_url: Option<String>,
+    pub rating_average: Option<f64>,
+    pub rating_count: Option<i64>,
+    pub release_date: Option<String>,
+    pub last_modified: Option<String>,
+    pub source_url: Option<String>,
+    /// References of the same product at other providers, by provider id.
+    pub links: BTreeMap<String, String>,
+}
+
+/// What to look for: the asset's name and where it is on disk.
+#[derive(Debug, Clone)]
+pub struct SearchQuery {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+impl SearchQuery {
+    pub fn for_asset(asset: &Asset) -> Self {
+        Self { name: asset.name.clone(), path: PathBuf::from(&asset.file_path) }
+    }
+}
+
+#[async_trait]
+pub trait MetadataProvider: Send + Sync {
+    /// Stable id stored with every result, e.g. `orbital`.
+    fn id(&self) -> &'static str;
+
+    /// Products that may be the asset; empty when the provider cannot search.
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<ProductMetadata>>;
+
+    /// Full details of the product a reference (ID, URL or path) points to.
+    async fn fetch_details(&self, reference: &str) -> Result<ProductMetadata>;
+
+    /// Read a product from a document this provider serves.
+    fn parse(&self, document: &str) -> Result<ProductMetadata>;
+
+    /// How likely `candidate` is the asset `query` describes, from 0 to 1.
+    fn confidence(&self, query: &SearchQuery, candidate: &ProductMetadata) -> f64 {
+        name_similarity(&query.name, candidate.title.as_deref().unwrap_or_default())
+    }
+}
+
+/// A provider's stored result for an asset.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderRecord {
+    pub asset_id: i64,
+    pub provider: String,
+    pub reference: String,
+    /// `manual` when the user linked the product, `search` when found by name, `linked` when
+    /// another provider's listing pointed to it, `local` for local files.
+    pub match_kind: String,
+    pub confidence: f64,
+    pub fetched_date: String,
+    pub metadata: ProductMetadata,
+}
+
+#[derive(FromRow)]
+struct RecordRow {
+    asset_id: i64,
+    provider: String,
+    reference: String,
+    match_kind: String,
+    confidence: f64,
+    fetched_date: String,
+    metadata: String,
+}
+
+impl RecordRow {
+    fn into_record(self) -> ProviderRecord {
+        let metadata = serde_json::from_str(&self.metadata).unwrap_or_else(|e| {
+            tracing::warn!("Unreadable {} metadata for asset {}: {}", self.provider, self.asset_id, e);
+            ProductMetadata::default()
+        });
+        ProviderRecord {
+            asset_id: self.asset_id,
+            provider: self.provider,
+            reference: self.reference,
+            match_kind: self.match_kind,
+            confidence: self.confidence,
+            fetched_date: self.fetched_date,
+            metadata,
+        }
+    }
+}
+
+/// The asset's metadata merged over its providers, with the provider each field came from.
+#[derive(Debug, Clone, Serialize)]
+pub struct MergedMetadata {
+    pub asset_id: i64,
+    pub metadata: ProductMetadata,
+    /// Provider of every field that has a value.
+    pub sources: BTreeMap<String, String>,
+    /// Providers with a result, in the order they were merged.
+    pub providers: Vec<String>,
+}
+
+/// What a refresh did with one provider.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderOutcome {
+    pub provider: String,
+    /// `fetched`, `not_found` or `failed`.
+    pub status: String,
+    pub reference: Option<String>,
+    pub error: Option<String>,
+}
+
+/// The providers the app uses, with Orbital going through `orbital`.
+pub fn default_providers(orbital: Arc<dyn MarketplaceSource>) -> Result<Vec<Box<dyn MetadataProvider>>> {
+    let client = http_client()?;
+    Ok(vec![
+        Box::new(ManifestProvider),
+        Box::new(OrbitalProvider::new(orbital)),
+        Box::new(FabProvider::new(client.clone())),
+        Box::new(WebPageProvider::new(client)),
+    ])
+}
+
+fn http_client() -> Result<reqwest::Client> {
+    Ok(reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .user_agent(concat!("Omnidex/", env!("CARGO_PKG_VERSION")))
+        .build()?)
+}
+
+/// Provider ids in merge order: `order` first, then the other known providers in their
+/// default order.
+pub fn precedence(order: &[String]) -> Vec<String> {
+    let mut ids: Vec<String> = Vec::new();
+    for id in order.iter().map(String::as_str).chain(PROVIDER_IDS.iter().copied()) {
+        if !ids.iter().any(|known| known == id) {
+            ids.push(id.to_string());
+        }
+    }
+    ids
+}
+
+/// Check a configured precedence order for unknown or repeated providers.
+pub fn validate_order(order: &[String]) -> Result<(), String> {
+    for (index, id) in order.iter().enumerate() {
+        if !PROVIDER_IDS.contains(&id.as_str()) {
+            return Err(format!("Unknown metadata provider '{}', expected one of {:?}", id, PROVIDER_IDS));
+        }
+        if order[..index].contains(id) {
+            return Err(format!("Metadata provider '{}' is listed twice", id));
+        }
+    }
+    Ok(())
+}
+
+fn name_similarity(a: &str, b: &str) -> f64 {
+    let clean = |s: &str| {
+        s.split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(str::to_lowercase)
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    jaro_winkler(&clean(a), &clean(b))
+}
+
+/// Every stored provider result of an asset, by provider id.
+pub async fn get_provider_records(db_manager: &DatabaseManager, asset_id: i64) -> Result<Vec<ProviderRecord>> {
+    let rows: Vec<RecordRow> = sqlx::query_as(
+        "SELECT asset_id, provider, reference, match_kind, confidence, fetched_date, metadata
+         FROM asset_provider_metadata WHERE asset_id = ? ORDER BY provider",
+    )
+    .bind(asset_id)
+    .fetch_all(&db_manager.pool)
+    .await?;
+    Ok(rows.into_iter().map(RecordRow::into_record).collect())
+}
+
+async fn store_record(
+    db_manager: &DatabaseManager,
+    asset_id: i64,
+    provider: &str,
+    match_kind: &str,
+    confidence: f64,
+    metadata: &ProductMetadata,
+) -> Result<()> {
+    sqlx::query(
+        "INSERT OR REPLACE INTO asset_provider_metadata
+         (asset_id, provider, reference, match_kind, confidence, fetched_date, metadata)
+         VALUES (?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(asset_id)
+    .bind(provider)
+    .bind(&metadata.reference)
+    .bind(match_kind)
+    .bind(confidence)
+    .bind(db_manager.timestamp())
+    .bind(serde_json::to_string(metadata)?)
+    .execute(&db_manager.pool)
+    .await?;
+    Ok(())
+}
+
+/// Link an asset to a product at one provider and store its details.
+pub async fn link_asset(
+    db_manager: &DatabaseManager,
+    provider: &dyn MetadataProvider,
+    asset_id: i64,
+    reference: &str,
+) -> Result<ProviderRecord> {
+    db_manager.get_asset_by_id(asset_id).await?;
+    let metadata = provider.fetch_details(reference.trim()).await?;
+    store_record(db_manager, asset_id, provider.id(), "manual", 1.0, &metadata).await?;
+    get_provider_records(db_manager, asset_id)
+        .await?
+        .into_iter()
+        .find(|record| record.provider == provider.id())
+        .ok_or_else(|| anyhow!("{} result for asset {} was not stored", provider.id(), asset_id))
+}
+
+/// Fetch the asset's product from every provider and store each result.
+///
+/// A provider fetches the product the user linked it to, else the one another provider's
+/// result links to, else the one it matched before (for Orbital, also the scanner's match),
+/// and only then searches by name. One provider failing does not stop the others.
+pub async fn refresh_asset(
+    db_manager: &DatabaseManager,
+    providers: &[Box<dyn MetadataProvider>],
+    asset_id: i64,
+) -> Result<Vec<ProviderOutcome>> {
+    let asset = db_manager.get_asset_by_id(asset_id).await?;
+    let query = SearchQuery::for_asset(&asset);
+    let mut records = get_provider_records(db_manager, asset_id).await?;
+
+    let mut outcomes = Vec::new();
+    for provider in providers {
+        let id = provider.id();
+        let stored = records.iter().find(|record| record.provider == id);
+        let linked = records.iter().find_map(|record| record.metadata.links.get(id).cloned());
+        // A manual link wins, then what another provider links to, then an earlier match.
+        let known = match (stored, linked) {
+            (Some(record), _) if record.match_kind == "manual" => {
+                Some((record.reference.clone(), record.match_kind.clone(), record.confidence))
+            }
+            (_, Some(reference)) => Some((reference, "linked".to_string(), 1.0)),
+            (Some(record), None) => Some((record.reference.clone(), record.match_kind.clone(), record.confidence)),
+            (None, None) => asset
+                .matched_orbital_product_slug
+                .clone()
+                .filter(|_| id == orbital::PROVIDER_ID)
+                .map(|slug| (slug, "search".to_string(), asset.orbital_match_confidence.unwrap_or(1.0))),
+        };
+
+        let result = match known {
+            Some((reference, match_kind, confidence)) => provider
+                .fetch_details(&reference)
+                .await
+                .map(|metadata| Some((metadata, match_kind, confidence))),
+            None => search_best(provider.as_ref(), &query).await,
+        };
+
+        let outcome = match result {
+            Ok(Some((metadata, match_kind, confidence))) => {
+                store_record(db_manager, asset_id, id, &match_kind, confidence, &metadata).await?;
+                ProviderOutcome { provider: id.to_string(), status: "fetched".to_string(), reference: Some(metadata.reference), error: None }
+            }
+            Ok(None) => ProviderOutcome { provider: id.to_string(), status: "not_found".to_string(), reference: None, error: None },
+            Err(e) => {
+                tracing::warn!("{} metadata for '{}' failed: {}", id, asset.name, e);
+                ProviderOutcome { provider: id.to_string(), status: "failed".to_string(), reference: None, error: Some(e.to_string()) }
+            }
+        };
+        outcomes.push(outcome);
+        // Later providers can follow links in what was just fetched.
+        records = get_provider_records(db_manager, asset_id).await?;
+    }
+    Ok(outcomes)
+}
+
+/// The most likely search result with its full details, if one is likely enough.
+async fn search_best(provider: &dyn MetadataProvider, query: &SearchQuery) -> Result<Option<(ProductMetadata, String, f64)>> {
+    let best = provider
+        .search(query)
+        .await?
+        .into_iter()
+        .map(|candidate| (provider.confidence(query, &candidate), candidate))
+        .filter(|(confidence, _)| *confidence >= MIN_SEARCH_CONFIDENCE)
+        .max_by(|a, b| a.0.total_cmp(&b.0));
+    let Some((confidence, candidate)) = best else {
+        return Ok(None);
+    };
+    let match_kind = if provider.id() == manifest::PROVIDER_ID { "local" } else { "search" };
+    let metadata = provider.fetch_details(&candidate.reference).await?;
+    Ok(Some((metadata, match_kind.to_string(), confidence)))
+}
+
+/// Merge the asset's provider results in `order`: each field takes the value of the first
+/// provider that has one. Without a stored Orbital result, the matched Orbital details on the
+/// asset stand in for it.
+pub async fn merged_metadata(db_manager: &DatabaseManager, asset_id: i64, order: &[String]) -> Result<MergedMetadata> {
+    let asset = db_manager.get_asset_by_id(asset_id).await?;
+    let mut by_provider: BTreeMap<String, ProductMetadata> = get_provider_records(db_manager, asset_id)
+        .await?
+        .into_iter()
+        .map(|record| (record.provider, record.metadata))
+        .collect();
+    if !by_provider.contains_key(orbital::PROVIDER_ID) {
+        if let Some(metadata) = orbital::metadata_from_asset(&asset) {
+            by_provider.insert(orbital::PROVIDER_ID.to_string(), metadata);
+        }
+    }
+
+    let ranked: Vec<(String, ProductMetadata)> = precedence(order)
+        .into_iter()
+        .filter_map(|id| by_provider.remove(&id).map(|metadata| (id, metadata)))
+        .collect();
+    let (metadata, sources) = merge(&ranked)?;
+    Ok(MergedMetadata {
+        asset_id,
+        metadata,
+        sources,
+        providers: ranked.into_iter().map(|(id, _)| id).collect(),
+    })
+}
+
+/// Merge results ranked by precedence, field by field.
+pub fn merge(ranked: &[(String, ProductMetadata)]) -> Result<(ProductMetadata, BTreeMap<String, String>)> {
+    let mut merged = Map::new();
+    let mut sources = BTreeMap::new();
+    for (provider, metadata) in ranked {
+        let Value::Object(fields) = serde_json::to_value(metadata)? else {
+            continue;
+        };
+        for (field, value) in fields {
+            if field == "reference" || field == "links" || is_empty(&value) || merged.contains_key(&field) {
+                continue;
+            }
+            sources.insert(field.clone(), provider.clone());
+            merged.insert(field, value);
+        }
+    }
+    let mut metadata: ProductMetadata = serde_json::from_value(Value::Object(merged))?;
+    metadata.links = ranked
+        .iter()
+        .filter(|(_, metadata)| !metadata.reference.is_empty())
+        .map(|(provider, metadata)| (provider.clone(), metadata.reference.clone()))
+        .collect();
+    Ok((metadata, sources))
+}
+
+fn is_empty(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::String(s) => s.trim().is_empty(),
+        Value::Array(items) => items.is_empty(),
+        Value::Object(fields) => fields.is_empty(),
+        _ => false,
+    }
+}
+
+/// The provider with `id` among `providers`.
+pub fn find_provider<'a>(providers: &'a [Box<dyn MetadataProvider>], id: &str) -> Result<&'a dyn MetadataProvider> {
+    providers
+        .iter()
+        .find(|provider| provider.id() == id)
+        .map(|provider| provider.as_ref())
+        .ok_or_else(|| anyhow!("Unknown metadata provider '{}', expected one of {:?}", id, PROVIDER_IDS))
+}
+
+/// Text of a JSON value that may be a string or a number.
+fn json_text(value: Option<&Value>) -> Option<String> {
+    match value? {
+        Value::String(s) if !s.trim().is_empty() => Some(s.trim().to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// A number from a JSON number or a numeric string such as `"19.99"`.
+fn json_number(value: Option<&Value>) -> Option<f64> {
+    match value? {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merges_fields_in_precedence_order() {
+        let manifest = ProductMetadata { reference: "omnidex.json".into(), title: Some("Castle Kit".into()), ..Default::default() };
+        let orbital = ProductMetadata {
+            reference: "castle-kit".into(),
+            title: Some("Modular Castle Kit".into()),
+            seller: Some("Stone Works".into()),
+            categories: vec!["Environments".into()],
+            ..Default::default()
+        };
+        let order = precedence(&["orbital".to_string()]);
+        assert_eq!(order, ["orbital", "manifest", "fab", "web"]);
+
+        let (merged, sources) = merge(&[("manifest".into(), manifest), ("orbital".into(), orbital)]).unwrap();
+        assert_eq!(merged.title.as_deref(), Some("Castle Kit"));
+        assert_eq!(merged.seller.as_deref(), Some("Stone Works"));
+        assert_eq!(sources["title"], "manifest");
+        assert_eq!(sources["categories"], "orbital");
+        assert_eq!(merged.links["orbital"], "castle-kit");
+
+        assert!(validate_order(&["fab".into(), "fab".into()]).is_err());
+        assert!(validate_order(&["gumroad".into()]).is_err());
+    }
+}
@@ -0,0 +1,114 @@
+//! Orbital Market, through the same marketplace source the scanner matches with.
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use std::sync::Arc;
+
+use super::{MetadataProvider, ProductMetadata, SearchQuery};
+use crate::models::Asset;
+use crate::orbital::{api_models, MarketplaceSource, OrbitalApiClient, OrbitalAsset};
+
+pub const PROVIDER_ID: &str = "orbital";
+
+pub struct OrbitalProvider {
+    source: Arc<dyn MarketplaceSource>,
+}
+
+impl OrbitalProvider {
+    pub fn new(source: Arc<dyn MarketplaceSource>) -> Self {
+        Self { source }
+    }
+}
+
+#[async_trait]
+impl MetadataProvider for OrbitalProvider {
+    fn id(&self) -> &'static str {
+        PROVIDER_ID
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<ProductMetadata>> {
+        let results = self.source.search_by_folder_name(&query.name).await?;
+        Ok(results.into_iter().map(metadata_from_orbital).collect())
+    }
+
+    async fn fetch_details(&self, reference: &str) -> Result<ProductMetadata> {
+        let product_id = if reference.contains("://") {
+            self.source
+                .extract_product_id_from_url(reference)
+                .ok_or_else(|| anyhow!("Could not extract product ID from URL"))?
+        } else {
+            reference.to_string()
+        };
+        Ok(metadata_from_orbital(self.source.fetch_asset_details(&product_id).await?))
+    }
+
+    fn parse(&self, document: &str) -> Result<ProductMetadata> {
+        let response: api_models::ApiResponse = serde_json::from_str(document)?;
+        Ok(metadata_from_orbital(OrbitalApiClient::map_api_response_to_orbital_asset(response)))
+    }
+}
+
+/// Orbital details in provider form. The listing's Fab ID, when the API gives one, links the
+/// product to the Fab provider.
+pub fn metadata_from_orbital(asset: OrbitalAsset) -> ProductMetadata {
+    let fab_id = asset
+        .raw_json
+        .as_ref()
+        .and_then(|raw| raw.pointer("/meta/fabId"))
+        .and_then(|id| id.as_str())
+        .filter(|id| !id.trim().is_empty());
+    let mut links = std::collections::BTreeMap::new();
+    if let Some(fab_id) = fab_id {
+        links.insert(super::fab::PROVIDER_ID.to_string(), fab_id.trim().to_string());
+    }
+
+    ProductMetadata {
+        reference: asset.product_slug.clone().or(asset.id.clone()).unwrap_or_default(),
+        title: asset.title,
+        description: asset.description,
+        technical_details: asset.technical_details,
+        seller: asset.seller,
+        currency: asset.price.map(|_| "USD".to_string()),
+        price: asset.price,
+        categories: asset.categories,
+        tags: Vec::new(),
+        engine_versions: asset.supported_versions.into_iter().filter(|v| !v.trim().is_empty()).collect(),
+        images: asset.gallery_images,
+        thumbnail_url: asset.thumbnail_url,
+        rating_average: asset.rating_average,
+        rating_count: asset.rating_count.map(i64::from),
+        release_date: asset.release_date,
+        last_modified: asset.last_modified.filter(|d| !d.is_empty()),
+        source_url: asset.source_url,
+        links,
+    }
+}
+
+/// The Orbital details stored on the asset by matching, if it was matched.
+pub fn metadata_from_asset(asset: &Asset) -> Option<ProductMetadata> {
+    let reference = asset.matched_orbital_product_slug.clone()?;
+    let list = |column: &Option<String>| -> Vec<String> {
+        column.as_deref().and_then(|json| serde_json::from_str(json).ok()).unwrap_or_default()
+    };
+    let raw_json = asset.orbital_raw_json.as_deref().and_then(|json| serde_json::from_str(json).ok());
+
+    Some(metadata_from_orbital(OrbitalAsset {
+        id: None,
+        product_slug: Some(reference),
+        title: asset.orbital_title.clone(),
+        description: asset.orbital_description.clone(),
+        technical_details: asset.orbital_technical_details.clone(),
+        seller: asset.orbital_seller_name.clone(),
+        categories: list(&asset.orbital_categories),
+        supported_versions: list(&asset.orbital_supported_versions),
+        gallery_images: list(&asset.orbital_gallery_images),
+        rating_average: asset.orbital_rating_average,
+        rating_count: asset.orbital_rating_count,
+        price: asset.orbital_price,
+        release_date: asset.orbital_release_date.clone(),
+        last_modified: asset.orbital_last_modified.clone(),
+        raw_json,
+        source_url: asset.orbital_source_url.clone(),
+        thumbnail_url: asset.orbital_thumbnail_url.clone(),
+    }))
+}
@@ -0,0 +1,196 @@
+//! Any product page: the schema.org `Product` in its JSON-LD, or its Open Graph tags when it
+//! has none. Pages cannot be searched, so this provider only fetches pages it is linked to.
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use scraper::{Html, Selector};
+use serde_json::Value;
+
+use super::{json_number, json_text, MetadataProvider, ProductMetadata, SearchQuery};
+
+pub const PROVIDER_ID: &str = "web";
+
+pub struct WebPageProvider {
+    client: reqwest::Client,
+}
+
+impl WebPageProvider {
+    pub fn new(client: reqwest::Client) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl MetadataProvider for WebPageProvider {
+    fn id(&self) -> &'static str {
+        PROVIDER_ID
+    }
+
+    async fn search(&self, _query: &SearchQuery) -> Result<Vec<ProductMetadata>> {
+        Ok(Vec::new())
+    }
+
+    async fn fetch_details(&self, reference: &str) -> Result<ProductMetadata> {
+        let url = url::Url::parse(reference.trim()).map_err(|_| anyhow!("'{}' is not a URL", reference))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(anyhow!("'{}' is not a web page", reference));
+        }
+        let response = self.client.get(url.clone()).send().await?;
+        if !response.status().is_success() {
+            return Err(anyhow!("{} returned {}", url, response.status()));
+        }
+        let mut metadata = self.parse(&response.text().await?)?;
+        metadata.reference = url.to_string();
+        metadata.source_url.get_or_insert_with(|| url.to_string());
+        Ok(metadata)
+    }
+
+    fn parse(&self, document: &str) -> Result<ProductMetadata> {
+        let html = Html::parse_document(document);
+        product_from_json_ld(&html)
+            .or_else(|| product_from_open_graph(&html))
+            .ok_or_else(|| anyhow!("The page describes no product"))
+    }
+}
+
+fn selector(css: &str) -> Selector {
+    Selector::parse(css).expect("valid selector")
+}
+
+/// The first `Product` node in the page's JSON-LD blocks, including nodes in `@graph`.
+fn product_from_json_ld(html: &Html) -> Option<ProductMetadata> {
+    let scripts = selector(r#"script[type="application/ld+json"]"#);
+    html.select(&scripts)
+        .filter_map(|script| serde_json::from_str::<Value>(&script.text().collect::<String>()).ok())
+        .flat_map(|block| match block {
+            Value::Array(nodes) => nodes,
+            Value::Object(ref node) if node.contains_key("@graph") => {
+                node["@graph"].as_array().cloned().unwrap_or_default()
+            }
+            node => vec![node],
+        })
+        .find(is_product)
+        .map(|node| product_from_node(&node))
+}
+
+fn is_product(node: &Value) -> bool {
+    match node.get("@type") {
+        Some(Value::String(kind)) => kind == "Product",
+        Some(Value::Array(kinds)) => kinds.iter().any(|kind| kind == "Product"),
+        _ => false,
+    }
+}
+
+fn product_from_node(node: &Value) -> ProductMetadata {
+    // `image`, `category` and `offers` may each be one value or a list.
+    let list = |value: Option<&Value>| -> Vec<Value> {
+        match value {
+            Some(Value::Array(items)) => items.clone(),
+            Some(item) => vec![item.clone()],
+            None => Vec::new(),
+        }
+    };
+    let images: Vec<String> = list(node.get("image"))
+        .iter()
+        .filter_map(|image| json_text(Some(image)).or_else(|| json_text(image.get("url"))))
+        .collect();
+    let offer = list(node.get("offers")).into_iter().next();
+    let seller = node
+        .get("brand")
+        .or_else(|| node.get("manufacturer"))
+        .or_else(|| offer.as_ref().and_then(|offer| offer.get("seller")))
+        .and_then(|party| json_text(party.get("name")).or_else(|| json_text(Some(party))));
+    let tags = match node.get("keywords") {
+        Some(Value::String(keywords)) => {
+            keywords.split(',').map(str::trim).filter(|k| !k.is_empty()).map(str::to_string).collect()
+        }
+        keywords => list(keywords).iter().filter_map(|k| json_text(Some(k))).collect(),
+    };
+    let rating = node.get("aggregateRating");
+
+    ProductMetadata {
+        reference: json_text(node.get("url")).unwrap_or_default(),
+        title: json_text(node.get("name")),
+        description: json_text(node.get("description")),
+        seller,
+        price: offer.as_ref().and_then(|offer| json_number(offer.get("price")).or_else(|| json_number(offer.get("lowPrice")))),
+        currency: offer.as_ref().and_then(|offer| json_text(offer.get("priceCurrency"))),
+        categories: list(node.get("category")).iter().filter_map(|c| json_text(Some(c))).collect(),
+        tags,
+        thumbnail_url: images.first().cloned(),
+        images,
+        rating_average: rating.and_then(|r| json_number(r.get("ratingValue"))),
+        rating_count: rating
+            .and_then(|r| json_number(r.get("ratingCount")).or_else(|| json_number(r.get("reviewCount"))))
+            .map(|count| count as i64),
+        release_date: json_text(node.get("releaseDate")).or_else(|| json_text(node.get("datePublished"))),
+        last_modified: json_text(node.get("dateModified")),
+        source_url: json_text(node.get("url")),
+        ..Default::default()
+    }
+}
+
+/// A product from the page's `og:` meta tags, if it has at least a title.
+fn product_from_open_graph(html: &Html) -> Option<ProductMetadata> {
+    let meta = |property: &str| -> Option<String> {
+        let tag = selector(&format!(r#"meta[property="{}"]"#, property));
+        html.select(&tag)
+            .next()
+            .and_then(|element| element.value().attr("content"))
+            .map(str::trim)
+            .filter(|content| !content.is_empty())
+            .map(str::to_string)
+    };
+    let image = meta("og:image");
+    Some(ProductMetadata {
+        title: Some(meta("og:title")?),
+        description: meta("og:description"),
+        seller: meta("og:site_name"),
+        price: meta("product:price:amount").and_then(|price| price.parse().ok()),
+        currency: meta("product:price:currency"),
+        images: image.iter().cloned().collect(),
+        thumbnail_url: image,
+        reference: meta("og:url").unwrap_or_default(),
+        source_url: meta("og:url"),
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_json_ld_product_and_open_graph_fallback() {
+        let provider = WebPageProvider::new(reqwest::Client::new());
+        let page = r#"<html><head>
+            <script type="application/ld+json">{"@type": "Organization", "name": "Stone Works"}</script>
+            <script type="application/ld+json">{"@graph": [{
+                "@type": "Product",
+                "name": "Modular Castle Kit",
+                "brand": {"@type": "Brand", "name": "Stone Works"},
+                "image": ["https://example.com/1.jpg", "https://example.com/2.jpg"],
+                "keywords": "medieval, castle",
+                "offers": {"@type": "Offer", "price": "29.99", "priceCurrency": "EUR"},
+                "aggregateRating": {"ratingValue": "4.5", "reviewCount": 8}
+            }]}</script>
+        </head></html>"#;
+        let product = provider.parse(page).unwrap();
+        assert_eq!(product.title.as_deref(), Some("Modular Castle Kit"));
+        assert_eq!(product.seller.as_deref(), Some("Stone Works"));
+        assert_eq!(product.price, Some(29.99));
+        assert_eq!(product.currency.as_deref(), Some("EUR"));
+        assert_eq!(product.tags, ["medieval", "castle"]);
+        assert_eq!(product.thumbnail_url.as_deref(), Some("https://example.com/1.jpg"));
+        assert_eq!(product.rating_count, Some(8));
+
+        let page = r#"<html><head>
+            <meta property="og:title" content="Castle Kit">
+            <meta property="og:site_name" content="Stone Works Store">
+        </head></html>"#;
+        let product = provider.parse(page).unwrap();
+        assert_eq!(product.title.as_deref(), Some("Castle Kit"));
+        assert_eq!(product.seller.as_deref(), Some("Stone Works Store"));
+        assert!(provider.parse("<html></html>").is_err());
+    }
+}
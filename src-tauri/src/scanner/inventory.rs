@@ -0,0 +1,337 @@
+//! Per-asset file inventory, gathered in a single walk over a scan location.
+
+use jwalk::{DirEntry, WalkDir};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Component, Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::roots::RootSelector;
+use crate::archive::is_archive;
+use crate::models::{ScanError, ScanErrorKind};
+use crate::media::is_indexed_media;
+use crate::database::relative_asset_path;
+
+/// Inventory group for files without an extension.
+pub const NO_EXTENSION_KEY: &str = "(none)";
+
+/// Inventory group for files that sit directly in the asset folder rather than a subfolder.
+pub const ROOT_FOLDER_KEY: &str = ".";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InventoryTotals {
+    pub file_count: i64,
+    pub total_bytes: i64,
+}
+
+impl InventoryTotals {
+    fn add(&mut self, size: i64) {
+        self.file_count += 1;
+        self.total_bytes += size;
+    }
+}
+
+/// File counts and bytes of one asset folder, by extension and by top-level subfolder.
+#[derive(Debug, Clone, Default)]
+pub struct FolderInventory {
+    pub totals: InventoryTotals,
+    pub by_extension: BTreeMap<String, InventoryTotals>,
+    pub by_folder: BTreeMap<String, InventoryTotals>,
+    /// Sum of a digest of every file's relative path, size and modification time, so it does
+    /// not depend on the order the files were counted in.
+    content_digest: u128,
+}
+
+impl FolderInventory {
+    /// Walk a single asset folder below `location_root`, skipping what `selector` excludes.
+    /// Used when one asset changes outside of a full scan.
+    pub fn collect(folder: &Path, location_root: &Path, selector: &RootSelector) -> Self {
+        let mut inventory = Self::default();
+        let walk_root = location_root.to_path_buf();
+        let filter = selector.clone();
+        let walker = WalkDir::new(folder).process_read_dir(move |_, _, _, children| {
+            children.retain(|child| match child {
+                Ok(entry) => !filter.is_excluded(&relative_asset_path(&walk_root, &entry.path())),
+                Err(_) => true,
+            });
+        });
+        for entry in walker {
+            match entry {
+                Ok(entry) if entry.file_type().is_file() => {
+                    let path = entry.path();
+                    let (size, modified) = file_metadata(&entry);
+                    if let Ok(relative) = path.strip_prefix(folder) {
+                        inventory.add_file(relative, size, modified);
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Error walking {} for inventory: {}", folder.display(), e),
+            }
+        }
+        inventory
+    }
+
+    /// Count a file, given its path relative to the asset folder and, when known, when it was
+    /// last modified.
+    pub fn add_file(&mut self, relative: &Path, size: i64, modified: Option<SystemTime>) {
+        self.totals.add(size);
+
+        let mut hasher = Sha256::new();
+        hasher.update(relative.to_string_lossy().replace('\\', "/").as_bytes());
+        hasher.update([0]);
+        hasher.update(size.to_le_bytes());
+        // Whole seconds, since copy tools and some file systems keep no finer times.
+        if let Some(seconds) = modified.and_then(|m| m.duration_since(UNIX_EPOCH).ok()).map(|d| d.as_secs()) {
+            hasher.update(seconds.to_le_bytes());
+        }
+        let digest = hasher.finalize();
+        let mut prefix = [0u8; 16];
+        prefix.copy_from_slice(&digest[..16]);
+        self.content_digest = self.content_digest.wrapping_add(u128::from_le_bytes(prefix));
+
+        let extension = relative
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_else(|| NO_EXTENSION_KEY.to_string());
+        self.by_extension.entry(extension).or_default().add(size);
+
+        let mut components = relative.components().filter_map(|c| match c {
+            Component::Normal(part) => Some(part),
+            _ => None,
+        });
+        let first = components.next();
+        let folder = match (first, components.next()) {
+            (Some(dir), Some(_)) => dir.to_string_lossy().into_owned(),
+            _ => ROOT_FOLDER_KEY.to_string(),
+        };
+        self.by_folder.entry(folder).or_default().add(size);
+    }
+
+    /// Fingerprint of the folder's files: equal for two folders holding the same files by
+    /// relative path, size and modification time, wherever they are. It is built from file
+    /// metadata only, not a hash of the contents, so an edit that keeps a file's size and
+    /// modification time goes unnoticed. `None` for an empty folder.
+    pub fn fingerprint(&self) -> Option<String> {
+        (self.totals.file_count > 0).then(|| format!("{:032x}", self.content_digest))
+    }
+}
+
+/// Result of walking a scan location once.
+#[derive(Debug, Default)]
+pub struct LocationInventory {
+    /// Asset folders in walk order.
+    pub folders: Vec<PathBuf>,
+    pub inventories: HashMap<PathBuf, FolderInventory>,
+    /// Archive files indexed as assets of their own, in walk order.
+    pub archives: Vec<PathBuf>,
+    /// Media files indexed as assets of their own, in walk order (media locations only).
+    pub files: Vec<PathBuf>,
+    /// Entries that could not be read during the walk.
+    pub errors: Vec<ScanError>,
+}
+
+/// Walk `root` once, collecting the asset folders chosen by `selector` and the inventory of each,
+/// plus the archives to index as assets.
+///
+/// A file counts towards every asset folder above it, so in the plain recursive mode, where
+/// every folder is an asset, nested assets each get their own totals. Excluded files and
+/// folders are not visited at all.
+pub fn inventory_location(root: &Path, selector: &RootSelector) -> LocationInventory {
+    let mut result = LocationInventory::default();
+
+    let walk_root = root.to_path_buf();
+    let filter = selector.clone();
+    let walker = WalkDir::new(root).sort(true).process_read_dir(move |_, _, _, children| {
+        children.retain(|child| match child {
+            Ok(entry) => !filter.is_excluded(&relative_asset_path(&walk_root, &entry.path())),
+            Err(_) => true,
+        });
+    });
+
+    // The walk is depth-first, so the most recent root contains everything until the walk leaves it.
+    let mut current_root: Option<PathBuf> = None;
+    for entry in walker {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                result.errors.push(walk_error(root, e));
+                continue;
+            }
+        };
+        let path = entry.path();
+        let Ok(relative) = path.strip_prefix(root) else {
+            continue;
+        };
+        let depth = relative.components().count();
+        if depth == 0 {
+            continue;
+        }
+
+        if entry.file_type().is_dir() {
+            let inside_root = current_root.as_ref().is_some_and(|r| path.starts_with(r));
+            if inside_root && !selector.allows_nesting() {
+                continue;
+            }
+            if selector.is_root(&path, &relative_asset_path(root, &path), depth) {
+                result.inventories.insert(path.clone(), FolderInventory::default());
+                result.folders.push(path.clone());
+                current_root = Some(path);
+            }
+            continue;
+        }
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        if is_archive(&path) {
+            let inside_root = current_root.as_ref().is_some_and(|r| path.starts_with(r));
+            if selector.is_archive_root(&relative_asset_path(root, &path), depth, inside_root) {
+                result.archives.push(path.clone());
+            }
+        }
+
+        let (size, modified) = file_metadata(&entry);
+        let mut owner = root.to_path_buf();
+        for component in relative.components().take(depth - 1) {
+            owner.push(component);
+            if let (Some(inventory), Ok(within)) = (result.inventories.get_mut(&owner), path.strip_prefix(&owner)) {
+                inventory.add_file(within, size, modified);
+            }
+        }
+    }
+
+    result
+}
+
+/// Walk a media location, collecting every supported media file that passes the include and
+/// exclude globs and, when `allowed` is not empty, has one of its extensions.
+pub fn media_files(root: &Path, selector: &RootSelector, allowed: &[String]) -> LocationInventory {
+    let mut result = LocationInventory::default();
+
+    let walk_root = root.to_path_buf();
+    let filter = selector.clone();
+    let walker = WalkDir::new(root).sort(true).process_read_dir(move |_, _, _, children| {
+        children.retain(|child| match child {
+            Ok(entry) => !filter.is_excluded(&relative_asset_path(&walk_root, &entry.path())),
+            Err(_) => true,
+        });
+    });
+
+    for entry in walker {
+        match entry {
+            Ok(entry) if entry.file_type().is_file() => {
+                let path = entry.path();
+                if is_indexed_media(&path, allowed) && selector.is_included(&relative_asset_path(root, &path)) {
+                    result.files.push(path);
+                }
+            }
+            Ok(_) => {}
+            Err(e) => result.errors.push(walk_error(root, e)),
+        }
+    }
+
+    result
+}
+
+fn walk_error(root: &Path, error: jwalk::Error) -> ScanError {
+    let path = error.path().unwrap_or(root).to_path_buf();
+    match error.io_error() {
+        Some(io_error) => ScanError::io(ScanErrorKind::Unreadable, &path, io_error),
+        None => ScanError::new(ScanErrorKind::Unreadable, Some(&path), error.to_string()),
+    }
+}
+
+fn file_metadata(entry: &DirEntry<((), ())>) -> (i64, Option<SystemTime>) {
+    match entry.metadata() {
+        Ok(metadata) => (metadata.len() as i64, metadata.modified().ok()),
+        Err(e) => {
+            tracing::warn!("Couldn't get metadata for file {}: {}", entry.path().display(), e);
+            (0, None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{AssetRootRules, RootRule};
+
+    #[test]
+    fn test_single_walk_attributes_files_to_asset_folders() {
+        let root = std::env::temp_dir().join(format!("omnidex-inventory-{}", std::process::id()));
+        let forest = root.join("Forest");
+        std::fs::create_dir_all(forest.join("Content").join("Meshes")).unwrap();
+        std::fs::write(forest.join("Content").join("Meshes").join("SM_Tree.uasset"), vec![0u8; 100]).unwrap();
+        std::fs::write(forest.join("Content").join("T_Bark.PNG"), vec![0u8; 40]).unwrap();
+        std::fs::write(forest.join("README"), vec![0u8; 5]).unwrap();
+        std::fs::write(root.join("loose.txt"), b"x").unwrap();
+
+        let flat = inventory_location(&root, &RootSelector::new(&AssetRootRules::default(), false).unwrap());
+        let recursive = inventory_location(&root, &RootSelector::new(&AssetRootRules::default(), true).unwrap());
+        std::fs::remove_dir_all(&root).ok();
+
+        assert_eq!(flat.folders, vec![forest.clone()]);
+        let inventory = &flat.inventories[&forest];
+        assert_eq!(inventory.totals, InventoryTotals { file_count: 3, total_bytes: 145 });
+        assert_eq!(inventory.by_extension["uasset"].total_bytes, 100);
+        assert_eq!(inventory.by_extension["png"].file_count, 1);
+        assert_eq!(inventory.by_extension[NO_EXTENSION_KEY].total_bytes, 5);
+        assert_eq!(inventory.by_folder["Content"].total_bytes, 140);
+        assert_eq!(inventory.by_folder[ROOT_FOLDER_KEY].total_bytes, 5);
+
+        assert_eq!(recursive.folders.len(), 3);
+        assert_eq!(recursive.inventories[&forest].totals.total_bytes, 145);
+        let content = &recursive.inventories[&forest.join("Content")];
+        assert_eq!(content.totals.total_bytes, 140);
+        assert_eq!(content.by_folder["Meshes"].total_bytes, 100);
+        assert_eq!(recursive.inventories[&forest.join("Content").join("Meshes")].totals.file_count, 1);
+    }
+
+    #[test]
+    fn test_rules_pick_outermost_roots_and_skip_excluded() {
+        let root = std::env::temp_dir().join(format!("omnidex-roots-{}", std::process::id()));
+        let forest = root.join("Environments").join("Forest");
+        std::fs::create_dir_all(forest.join("Content").join("Meshes")).unwrap();
+        std::fs::write(forest.join("Content").join("Meshes").join("SM_Tree.uasset"), vec![0u8; 100]).unwrap();
+        std::fs::create_dir_all(forest.join("Saved")).unwrap();
+        std::fs::write(forest.join("Saved").join("cache.bin"), vec![0u8; 50]).unwrap();
+        std::fs::create_dir_all(root.join("Tools").join("Cleaner")).unwrap();
+        std::fs::write(root.join("Tools").join("Cleaner").join("Cleaner.uplugin"), b"{}").unwrap();
+        std::fs::create_dir_all(root.join("Loose")).unwrap();
+
+        let rules = AssetRootRules {
+            roots: vec![RootRule::UnrealDescriptor, RootRule::ContentFolder],
+            include: Vec::new(),
+            exclude: vec!["**/Saved".to_string()],
+        };
+        let result = inventory_location(&root, &RootSelector::new(&rules, true).unwrap());
+        std::fs::remove_dir_all(&root).ok();
+
+        assert_eq!(result.folders, vec![forest.clone(), root.join("Tools").join("Cleaner")]);
+        assert_eq!(result.inventories[&forest].totals, InventoryTotals { file_count: 1, total_bytes: 100 });
+    }
+
+    #[test]
+    fn test_fingerprint_depends_on_files_not_order() {
+        let exported = Some(UNIX_EPOCH + std::time::Duration::from_secs(1_700_000_000));
+        let reexported = Some(UNIX_EPOCH + std::time::Duration::from_secs(1_700_086_400));
+        let mut forest = FolderInventory::default();
+        forest.add_file(Path::new("Content/SM_Tree.uasset"), 100, exported);
+        forest.add_file(Path::new("README"), 5, exported);
+        let mut copy = FolderInventory::default();
+        copy.add_file(Path::new("README"), 5, exported);
+        copy.add_file(Path::new("Content/SM_Tree.uasset"), 100, exported);
+        let mut edited = FolderInventory::default();
+        edited.add_file(Path::new("Content/SM_Tree.uasset"), 101, exported);
+        edited.add_file(Path::new("README"), 5, exported);
+        let mut touched = FolderInventory::default();
+        touched.add_file(Path::new("Content/SM_Tree.uasset"), 100, reexported);
+        touched.add_file(Path::new("README"), 5, exported);
+
+        assert!(forest.fingerprint().is_some());
+        assert_eq!(forest.fingerprint(), copy.fingerprint());
+        assert_ne!(forest.fingerprint(), edited.fingerprint());
+        assert_ne!(forest.fingerprint(), touched.fingerprint());
+        assert_eq!(FolderInventory::default().fingerprint(), None);
+    }
+}
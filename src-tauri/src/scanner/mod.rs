@@ -1,27 +1,61 @@
-use crate::models::{Asset, ScanLocation, ScanProgress};
+use crate::models::{
+    Asset, ScanError, ScanErrorKind, ScanJob, ScanLocation, ScanProgress, ScanRetryReport, SCAN_STATUS_CANCELLED, SCAN_STATUS_COMPLETED, SCAN_STATUS_FAILED,
+    SCAN_STATUS_PAUSED,
+};
 use anyhow::Result;
-use jwalk::WalkDir;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::{Instant};
-use tauri::{AppHandle, Emitter};
-use std::collections::hash_map::DefaultHasher;
-use std::hash::{Hash, Hasher};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::collections::HashMap;
-use crate::database::DatabaseManager;
+use tokio::task::JoinSet;
+use crate::archive::{self, ArchiveFormat};
+use crate::categories::{Categorization, CategoryEngine, CategorySignals};
+use crate::database::{relative_asset_path, DatabaseManager};
+use crate::media;
 use strsim::{jaro_winkler, levenshtein};
 use crate::orbital::models::OrbitalAsset;
-use crate::orbital::api::OrbitalApiClient;
+use crate::orbital::MarketplaceSource;
 use chrono;
 
+pub mod inventory;
+pub mod progress;
+pub mod project;
+pub mod roots;
 pub mod utils;
+pub mod watcher;
+
+use inventory::FolderInventory;
+use progress::{NoProgress, ProgressSink};
+use roots::RootSelector;
+use utils::hash_file_contents;
+use watcher::FolderChange;
 
 // Global scanner manager
 static SCANNER_MANAGER: Mutex<Option<ScannerManager>> = Mutex::new(None);
 
+/// Flags a running scan watches between batches.
+#[derive(Clone, Default)]
+pub struct ScanControl {
+    pub cancel: Arc<AtomicBool>,
+    /// Stop after the current batch but keep the job resumable.
+    pub pause: Arc<AtomicBool>,
+}
+
+impl ScanControl {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
 pub struct ScannerManager {
-    active_scanners: HashMap<i64, Arc<AtomicBool>>,
+    active_scanners: HashMap<i64, ScanControl>,
+}
+
+impl Default for ScannerManager {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl ScannerManager {
@@ -35,13 +69,26 @@ impl ScannerManager {
         self.active_scanners.contains_key(&location_id)
     }
 
-    pub fn register_scanner(&mut self, location_id: i64, cancel_token: Arc<AtomicBool>) {
-        self.active_scanners.insert(location_id, cancel_token);
+    pub fn active_scan_count(&self) -> usize {
+        self.active_scanners.len()
+    }
+
+    pub fn register_scanner(&mut self, location_id: i64, control: ScanControl) {
+        self.active_scanners.insert(location_id, control);
     }
 
     pub fn cancel_scan(&mut self, location_id: i64) -> bool {
-        if let Some(cancel_token) = self.active_scanners.get(&location_id) {
-            cancel_token.store(true, Ordering::Relaxed);
+        if let Some(control) = self.active_scanners.get(&location_id) {
+            control.cancel.store(true, Ordering::Relaxed);
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn pause_scan(&mut self, location_id: i64) -> bool {
+        if let Some(control) = self.active_scanners.get(&location_id) {
+            control.pause.store(true, Ordering::Relaxed);
             true
         } else {
             false
@@ -54,8 +101,8 @@ impl ScannerManager {
 
     pub fn cancel_all_scans(&mut self) -> usize {
         let cancelled_count = self.active_scanners.len();
-        for (_, cancel_token) in &self.active_scanners {
-            cancel_token.store(true, Ordering::Relaxed);
+        for control in self.active_scanners.values() {
+            control.cancel.store(true, Ordering::Relaxed);
         }
         cancelled_count
     }
@@ -102,6 +149,7 @@ pub struct MatchResult {
     pub fab_asset_url: Option<String>, // Optional: URL to the FAB asset page
 }
 
+#[derive(Clone, Default)]
 pub struct AssetMatcher;
 
 impl AssetMatcher {
@@ -138,10 +186,8 @@ impl AssetMatcher {
         let words1: Vec<&str> = clean_name1.split_whitespace().collect();
         let words2: Vec<&str> = clean_name2.split_whitespace().collect();
         let prefix_len = words1.len().min(words2.len()).min(COMMON_PREFIX_WORDS);
-        if prefix_len > 0 {
-            if words1[..prefix_len] == words2[..prefix_len] {
-                combined_score += COMMON_PREFIX_BONUS;
-            }
+        if prefix_len > 0 && words1[..prefix_len] == words2[..prefix_len] {
+            combined_score += COMMON_PREFIX_BONUS;
         }
         // Ensure score does not exceed 1.0 due to bonus
         combined_score = combined_score.min(1.0);
@@ -162,23 +208,103 @@ impl AssetMatcher {
     }
 }
 
+/// Folders written to the database per transaction during a scan.
+const SCAN_BATCH_SIZE: usize = 64;
+
+/// Folders read (and Orbital lookups made) at the same time unless configured otherwise.
+pub const DEFAULT_SCAN_CONCURRENCY: usize = 4;
+
+/// A folder read from disk, ready to be written to the database.
+#[derive(Debug, Clone)]
+pub struct ScannedFolder {
+    pub asset: Asset,
+    pub inventory: FolderInventory,
+    /// Categories of the asset; the primary one is `asset.asset_type`. Recorded for newly inserted assets.
+    pub categories: Categorization,
+}
+
+/// What a single run of a scan job did, for the scan history.
+#[derive(Debug, Default)]
+struct ScanRunTally {
+    run_id: i64,
+    processed_items: i64,
+    assets_found: i64,
+    error_count: i64,
+}
+
+/// Something a scan turns into an asset row.
+enum ScanTarget {
+    Folder(PathBuf, FolderInventory),
+    Archive(PathBuf),
+    MediaFile(PathBuf),
+}
+
+impl ScanTarget {
+    fn path(&self) -> &Path {
+        match self {
+            Self::Folder(path, _) | Self::Archive(path) | Self::MediaFile(path) => path,
+        }
+    }
+}
+
+#[derive(Clone)]
 pub struct AssetScanner {
     db_manager: DatabaseManager,
     cancel_token: Arc<AtomicBool>,
+    pause_token: Arc<AtomicBool>,
+    resume: bool,
     asset_matcher: AssetMatcher,
-    fab_api_client: OrbitalApiClient,
+    marketplace: Arc<dyn MarketplaceSource>,
+    concurrency: usize,
+    categories: CategoryEngine,
+    progress: Arc<dyn ProgressSink>,
 }
 
 impl AssetScanner {
-    pub fn new(db_manager: DatabaseManager, fab_api_client: OrbitalApiClient, cancel_token: Arc<AtomicBool>) -> Self {
+    pub fn new(db_manager: DatabaseManager, marketplace: impl MarketplaceSource + 'static, cancel_token: Arc<AtomicBool>) -> Self {
         Self {
             db_manager,
             cancel_token,
+            pause_token: Arc::new(AtomicBool::new(false)),
+            resume: false,
             asset_matcher: AssetMatcher::new(),
-            fab_api_client,
+            marketplace: Arc::new(marketplace),
+            concurrency: DEFAULT_SCAN_CONCURRENCY,
+            categories: CategoryEngine::default(),
+            progress: Arc::new(NoProgress),
         }
     }
 
+    /// Process up to `concurrency` folders at a time.
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Categorize new assets with `categories` instead of the built-in taxonomy.
+    pub fn with_categories(mut self, categories: CategoryEngine) -> Self {
+        self.categories = categories;
+        self
+    }
+
+    /// Stop after the current batch, keeping the scan job resumable, once `pause_token` is set.
+    pub fn with_pause_token(mut self, pause_token: Arc<AtomicBool>) -> Self {
+        self.pause_token = pause_token;
+        self
+    }
+
+    /// Continue the location's paused or interrupted scan job instead of starting a new one.
+    pub fn with_resume(mut self, resume: bool) -> Self {
+        self.resume = resume;
+        self
+    }
+
+    /// Report progress and errors of scans to `progress`; by default they are only logged.
+    pub fn with_progress(mut self, progress: Arc<dyn ProgressSink>) -> Self {
+        self.progress = progress;
+        self
+    }
+
     pub fn get_cancel_token(&self) -> Arc<AtomicBool> {
         self.cancel_token.clone()
     }
@@ -187,6 +313,11 @@ impl AssetScanner {
         self.cancel_token.store(true, Ordering::Relaxed);
     }
 
+    /// Whether the scan was cancelled or paused and should stop at the next batch.
+    fn stop_requested(&self) -> bool {
+        self.cancel_token.load(Ordering::Relaxed) || self.pause_token.load(Ordering::Relaxed)
+    }
+
     pub fn match_local_asset_to_fab_candidates(
         &self,
         local_asset_name: &str,
@@ -223,13 +354,103 @@ impl AssetScanner {
         None
     }
 
-    pub async fn scan_directory_and_process_folders(
-        &self, 
-        scan_location: &ScanLocation, 
-        app_handle_for_emit: &AppHandle
+    /// Scan a location according to its kind: asset folders for vaults, packages for projects.
+    /// The scan runs as a persisted job, and every run of it is recorded in the scan history.
+    pub async fn scan_location(&self, scan_location: &ScanLocation) -> Result<()> {
+        let location_id = scan_location.id.unwrap_or(0);
+        let mut job = self.db_manager.begin_scan_job(location_id, self.resume).await?;
+        let run_id = self.db_manager.start_scan_run(&job).await?;
+        let start_time = Instant::now();
+        let mut tally = ScanRunTally { run_id, ..Default::default() };
+
+        let result = if scan_location.is_project() {
+            // Project indexing reuses unchanged hashes, so a resumed job simply indexes again.
+            match self.index_project_location(scan_location).await {
+                Ok(summary) => {
+                    tally.processed_items = summary.packages as i64;
+                    tally.assets_found = summary.assets as i64;
+                    job.processed_items = tally.processed_items;
+                    job.total_items = tally.processed_items;
+                    job.assets_found = tally.assets_found;
+                    self.report_errors(&mut job, &mut tally, summary.errors).await;
+                    Ok(())
+                }
+                Err(e) => Err(e),
+            }
+        } else {
+            self.scan_directory_and_process_folders(scan_location, &mut job, &mut tally)
+                .await
+                .map(|_| ())
+        };
+
+        let outcome = match &result {
+            Ok(()) => SCAN_STATUS_COMPLETED,
+            Err(_) if self.cancel_token.load(Ordering::Relaxed) => SCAN_STATUS_CANCELLED,
+            Err(_) if self.pause_token.load(Ordering::Relaxed) => SCAN_STATUS_PAUSED,
+            Err(e) => {
+                let error = ScanError {
+                    path: None,
+                    ..ScanError::from_error(Path::new(&scan_location.path), e)
+                };
+                let failed_progress = ScanProgress {
+                    location_id,
+                    status: "Error".to_string(),
+                    total_items: job.total_items as u64,
+                    processed_items: job.processed_items as u64,
+                    current_path: "".to_string(),
+                    error: Some(error.message.clone()),
+                    error_count: tally.error_count as u64 + 1,
+                    completed_successfully: false,
+                };
+                self.report_errors(&mut job, &mut tally, vec![error]).await;
+                self.progress.scan_progress(&failed_progress);
+                SCAN_STATUS_FAILED
+            }
+        };
+        let elapsed_ms = start_time.elapsed().as_millis() as i64;
+        job.active_ms += elapsed_ms;
+        if let Err(e) = self.db_manager.finish_scan_job(&job, outcome).await {
+            tracing::error!("Failed to save scan job {}: {}", job.id, e);
+        }
+        if let Err(e) = self
+            .db_manager
+            .finish_scan_run(run_id, outcome, elapsed_ms, tally.processed_items, tally.assets_found, tally.error_count)
+            .await
+        {
+            tracing::error!("Failed to record scan run {}: {}", run_id, e);
+        }
+        result
+    }
+
+    /// Store errors of the current run, report each to the progress sink and count them.
+    async fn report_errors(&self, job: &mut ScanJob, tally: &mut ScanRunTally, errors: Vec<ScanError>) {
+        if errors.is_empty() {
+            return;
+        }
+        for error in &errors {
+            tracing::warn!("Scan error ({:?}) {}", error.kind, error);
+        }
+        job.error_count += errors.len() as i64;
+        tally.error_count += errors.len() as i64;
+        match self.db_manager.record_scan_errors(job, tally.run_id, &errors).await {
+            Ok(records) => {
+                for record in &records {
+                    self.progress.scan_error(record);
+                }
+            }
+            Err(e) => tracing::error!("Failed to record {} scan errors: {}", errors.len(), e),
+        }
+    }
+
+    /// Read every asset folder, archive and media file of a location and write them in batches,
+    /// checkpointing `job` after each batch. A resumed job skips the targets up to its checkpoint.
+    async fn scan_directory_and_process_folders(
+        &self,
+        scan_location: &ScanLocation,
+        job: &mut ScanJob,
+        tally: &mut ScanRunTally,
     ) -> Result<Vec<Asset>> {
         let mut assets = Vec::new();
-        let mut errors: Vec<String> = Vec::new();
         let path = Path::new(&scan_location.path);
         let start_time = Instant::now();
 
@@ -239,157 +460,604 @@ impl AssetScanner {
 
         tracing::info!("Starting folder scan of location: {}", scan_location.path);
 
-        let walker = if scan_location.scan_recursive {
-            WalkDir::new(path)
-        } else {
-            WalkDir::new(path).max_depth(1)
+        let selector = RootSelector::for_location(scan_location)?;
+        let location_inventory = {
+            let root = path.to_path_buf();
+            let is_media = scan_location.is_media();
+            let extensions = scan_location.extension_filter();
+            tokio::task::spawn_blocking(move || {
+                if is_media {
+                    inventory::media_files(&root, &selector, &extensions)
+                } else {
+                    inventory::inventory_location(&root, &selector)
+                }
+            })
+            .await?
         };
 
-        let mut folders_processed = 0i64;
-        let total_folders = self.count_folders(path, scan_location.scan_recursive)?;
-        tracing::info!("Found {} folders to process in {}", total_folders, scan_location.path);
+        let total_folders = (location_inventory.folders.len()
+            + location_inventory.archives.len()
+            + location_inventory.files.len()) as i64;
+        tracing::info!(
+            "Found {} folders, {} archives and {} media files to process in {}",
+            location_inventory.folders.len(),
+            location_inventory.archives.len(),
+            location_inventory.files.len(),
+            scan_location.path
+        );
+
+        let scan_location_id = scan_location.id.unwrap_or(0);
+        let mut inventories = location_inventory.inventories;
+        let mut targets: Vec<ScanTarget> = location_inventory
+            .folders
+            .into_iter()
+            .map(move |folder| {
+                let inventory = inventories.remove(&folder).unwrap_or_default();
+                ScanTarget::Folder(folder, inventory)
+            })
+            .chain(location_inventory.archives.into_iter().map(ScanTarget::Archive))
+            .chain(location_inventory.files.into_iter().map(ScanTarget::MediaFile))
+            .collect();
+
+        // The inventory is sorted, so everything up to the checkpoint was written by an earlier run.
+        let resume_at = job.checkpoint_path.as_deref().and_then(|checkpoint| {
+            targets.iter().position(|target| target.path().to_string_lossy() == checkpoint)
+        });
+        match resume_at {
+            Some(index) => {
+                targets.drain(..=index);
+                tracing::info!("Resuming scan of {} after {}", scan_location.path, job.checkpoint_path.as_deref().unwrap_or_default());
+            }
+            None => {
+                if let Some(checkpoint) = job.checkpoint_path.take() {
+                    tracing::info!("Checkpoint {} is no longer in {}, scanning from the start", checkpoint, scan_location.path);
+                }
+                job.processed_items = 0;
+                job.assets_found = 0;
+            }
+        }
+        let mut folders_processed = job.processed_items;
+        job.total_items = total_folders;
 
         let initial_progress = ScanProgress {
-            location_id: scan_location.id.unwrap_or(0),
+            location_id: scan_location_id,
             status: "Initializing Scan".to_string(),
             total_items: total_folders as u64,
-            processed_items: 0,
+            processed_items: folders_processed as u64,
             current_path: scan_location.path.clone(),
             error: None,
+            error_count: 0,
             completed_successfully: false,
         };
-        if let Err(e) = app_handle_for_emit.emit("scan-progress", &initial_progress) {
-            tracing::error!("Failed to emit initial scan progress: {}", e);
-        }
+        self.progress.scan_progress(&initial_progress);
+
+        self.report_errors(job, tally, location_inventory.errors).await;
 
-        for entry_result in walker {
-            if self.cancel_token.load(Ordering::Relaxed) {
-                tracing::info!("Scan cancelled for location: {}", scan_location.path);
-                let cancel_progress = ScanProgress {
-                    location_id: scan_location.id.unwrap_or(0),
-                    status: "Cancelled".to_string(),
+        let mut targets = targets.into_iter().peekable();
+        while targets.peek().is_some() {
+            if self.stop_requested() {
+                let cancelled = self.cancel_token.load(Ordering::Relaxed);
+                tracing::info!(
+                    "Scan {} for location: {}",
+                    if cancelled { "cancelled" } else { "paused" },
+                    scan_location.path
+                );
+                let stop_progress = ScanProgress {
+                    location_id: scan_location_id,
+                    status: if cancelled { "Cancelled" } else { "Paused" }.to_string(),
                     total_items: total_folders as u64,
                     processed_items: folders_processed as u64,
                     current_path: "".to_string(),
                     error: None,
+                    error_count: tally.error_count as u64,
                     completed_successfully: false,
                 };
-                let _ = app_handle_for_emit.emit("scan-progress", &cancel_progress);
-                return Err(anyhow::anyhow!("Scan cancelled by user for location: {}", scan_location.path));
+                self.progress.scan_progress(&stop_progress);
+                if cancelled {
+                    return Err(anyhow::anyhow!("Scan cancelled by user for location: {}", scan_location.path));
+                }
+                return Err(anyhow::anyhow!("Scan paused by user for location: {}", scan_location.path));
             }
 
-            match entry_result {
-                Ok(entry) => {
-                    if entry.file_type().is_dir() && entry.path() != path {
-                        let current_folder_path = entry.path();
-                        match self.process_folder_and_save_asset(&current_folder_path, scan_location.id.unwrap_or(0)).await {
-                            Ok(Some(asset)) => {
-                                assets.push(asset);
-                            }
-                            Ok(None) => {
-                                tracing::info!("Folder {} processed but not saved as new asset.", current_folder_path.display());
-                            }
-                            Err(e) => {
-                                let error_msg = format!("Failed to process folder {}: {}", current_folder_path.display(), e);
-                                tracing::warn!("{}", error_msg);
-                                errors.push(error_msg);
-                            }
-                        }
-                        folders_processed += 1;
-                        
-                        let progress = ScanProgress {
-                            location_id: scan_location.id.unwrap_or(0),
-                            status: "Scanning".to_string(),
-                            total_items: total_folders as u64,
-                            processed_items: folders_processed as u64,
-                            current_path: current_folder_path.to_string_lossy().into_owned(),
-                            error: None,
-                            completed_successfully: false,
-                        };
-                        if let Err(e) = app_handle_for_emit.emit("scan-progress", &progress) {
-                            tracing::error!("Failed to emit scan progress update: {}", e);
+            let jobs: Vec<ScanTarget> = targets.by_ref().take(SCAN_BATCH_SIZE).collect();
+            let batch_len = jobs.len();
+            let last_path = jobs.last().map(|job| job.path().to_string_lossy().into_owned()).unwrap_or_default();
+            let (scanned, mut batch_errors) = self.read_folders(jobs, scan_location_id, path).await;
+
+            match self.db_manager.save_scanned_folders(&scanned).await {
+                Ok(results) => {
+                    let mut saved = Vec::with_capacity(results.len());
+                    for (folder, result) in scanned.iter().zip(results) {
+                        match result {
+                            Ok(asset) => saved.push(asset),
+                            Err(e) => batch_errors.push(ScanError::new(
+                                ScanErrorKind::Database,
+                                Some(Path::new(&folder.asset.file_path)),
+                                format!("Failed to save folder: {:#}", e),
+                            )),
                         }
                     }
+                    let (enriched, lookup_errors) = self.enrich_saved_assets(saved).await;
+                    job.assets_found += enriched.len() as i64;
+                    tally.assets_found += enriched.len() as i64;
+                    assets.extend(enriched);
+                    batch_errors.extend(lookup_errors);
                 }
                 Err(e) => {
-                    let error_msg = format!("Error walking directory entry in {}: {}", scan_location.path, e);
-                    tracing::warn!("{}", error_msg);
-                    errors.push(error_msg.clone());
-                    let error_progress = ScanProgress {
-                        location_id: scan_location.id.unwrap_or(0),
-                        status: "Error".to_string(),
-                        total_items: total_folders as u64,
-                        processed_items: folders_processed as u64,
-                        current_path: scan_location.path.clone(),
-                        error: Some(error_msg),
-                        completed_successfully: false,
-                    };
-                    if let Err(e_emit) = app_handle_for_emit.emit("scan-progress", &error_progress) {
-                        tracing::error!("Failed to emit error scan progress: {}", e_emit);
-                    }
+                    // The batch transaction itself failed, so none of its folders were saved.
+                    let message = format!("Failed to save folder: {}", e);
+                    batch_errors.extend(scanned.iter().map(|folder| {
+                        ScanError::new(ScanErrorKind::Database, Some(Path::new(&folder.asset.file_path)), message.clone())
+                    }));
                 }
             }
+            folders_processed += batch_len as i64;
+            tally.processed_items += batch_len as i64;
+
+            job.processed_items = folders_processed;
+            job.checkpoint_path = Some(last_path.clone());
+            self.report_errors(job, tally, batch_errors).await;
+            if let Err(e) = self.db_manager.checkpoint_scan_job(job).await {
+                tracing::warn!("Failed to checkpoint scan of {}: {}", scan_location.path, e);
+            }
+
+            let progress = ScanProgress {
+                location_id: scan_location_id,
+                status: "Scanning".to_string(),
+                total_items: total_folders as u64,
+                processed_items: folders_processed as u64,
+                current_path: last_path,
+                error: None,
+                error_count: tally.error_count as u64,
+                completed_successfully: false,
+            };
+            self.progress.scan_progress(&progress);
         }
 
         let final_progress = ScanProgress {
-            location_id: scan_location.id.unwrap_or(0),
+            location_id: scan_location_id,
             status: "Completed".to_string(),
             total_items: total_folders as u64,
             processed_items: folders_processed as u64,
             current_path: "".to_string(),
-            error: if errors.is_empty() { None } else { Some(errors.join("; ")) },
-            completed_successfully: errors.is_empty(),
+            error: None,
+            error_count: tally.error_count as u64,
+            completed_successfully: tally.error_count == 0,
         };
-        if let Err(e) = app_handle_for_emit.emit("scan-progress", &final_progress) {
-            tracing::error!("Failed to emit final scan progress: {}", e);
-        }
+        self.progress.scan_progress(&final_progress);
         
         let elapsed = start_time.elapsed();
         tracing::info!("Scan of {} completed in {:?}. Processed {}/{} folders. Found {} assets. Errors: {}", 
-                      scan_location.path, elapsed, folders_processed, total_folders, assets.len(), errors.len());
+                      scan_location.path, elapsed, folders_processed, total_folders, assets.len(), tally.error_count);
 
         Ok(assets)
     }
 
-    async fn process_folder_and_save_asset(&self, folder_path: &Path, scan_location_id: i64) -> Result<Option<Asset>> {
-        if self.cancel_token.load(Ordering::Relaxed) {
-            return Ok(None);
+    /// Bring the asset row for a single watched folder (or archive) in line with what is on disk:
+    /// new folders go through the regular insert path, existing ones get their size and
+    /// modification time refreshed, and vanished folders are removed together with any nested assets.
+    pub async fn sync_watched_folder(
+        &self,
+        folder_path: &Path,
+        scan_location_id: i64,
+        location_root: &Path,
+        selector: &RootSelector,
+    ) -> Result<FolderChange> {
+        let folder_path_str = folder_path.to_string_lossy().into_owned();
+
+        if !folder_path.exists() {
+            let removed = self.db_manager.delete_assets_under_path(&folder_path_str).await?;
+            return Ok(if removed.is_empty() { FolderChange::Unchanged } else { FolderChange::Removed(removed) });
         }
 
-        let folder_path_str = folder_path.to_str().unwrap().to_string();
-        
-        // Check if asset already exists
-        if let Some(mut existing_asset) = self.db_manager.get_asset_by_path(&folder_path_str).await? {
-            // If asset exists, check if it needs metadata refresh
-            if existing_asset.orbital_description.is_none() || existing_asset.orbital_title.is_none() {
-                tracing::info!("Asset {} found, attempting to refresh metadata.", existing_asset.name);
-                if let Err(e) = self.fetch_and_update_orbital_data(&mut existing_asset).await {
-                    tracing::warn!("Failed to refresh metadata for asset {}: {}", existing_asset.name, e);
+        let is_archive = folder_path.is_file() && archive::is_archive(folder_path);
+        if !folder_path.is_dir() && !is_archive {
+            return Ok(FolderChange::Unchanged);
+        }
+
+        let scanned = {
+            let folder = folder_path.to_path_buf();
+            let root = location_root.to_path_buf();
+            let selector = selector.clone();
+            let categories = self.categories.clone();
+            tokio::task::spawn_blocking(move || {
+                if is_archive {
+                    return Self::read_archive(&folder, scan_location_id, &root, &categories);
+                }
+                let inventory = FolderInventory::collect(&folder, &root, &selector);
+                Self::read_folder(&folder, scan_location_id, &root, inventory, &categories)
+            })
+            .await??
+        };
+
+        let Some((mut asset, inserted)) = self.db_manager.save_scanned_folders(&[scanned]).await?.pop().transpose()? else {
+            return Ok(FolderChange::Unchanged);
+        };
+        let Some(asset_id) = asset.id else {
+            return Ok(FolderChange::Unchanged);
+        };
+        if !inserted {
+            return Ok(FolderChange::Updated(asset_id));
+        }
+
+        if let Err(e) = self.fetch_and_update_orbital_data(&mut asset).await {
+            tracing::warn!("Failed to fetch Orbital metadata for new asset {} (id {}): {}", asset.name, asset_id, e);
+        }
+        Ok(FolderChange::Added(asset_id))
+    }
+
+    /// The media-location counterpart of [`Self::sync_watched_folder`]: index a new or changed
+    /// media file, or remove what was indexed at a path that no longer exists.
+    pub async fn sync_watched_file(&self, file_path: &Path, scan_location_id: i64, location_root: &Path) -> Result<FolderChange> {
+        if !file_path.exists() {
+            let removed = self.db_manager.delete_assets_under_path(&file_path.to_string_lossy()).await?;
+            return Ok(if removed.is_empty() { FolderChange::Unchanged } else { FolderChange::Removed(removed) });
+        }
+        if !file_path.is_file() {
+            return Ok(FolderChange::Unchanged);
+        }
+
+        let scanned = {
+            let file = file_path.to_path_buf();
+            let root = location_root.to_path_buf();
+            let categories = self.categories.clone();
+            tokio::task::spawn_blocking(move || Self::read_media_file(&file, scan_location_id, &root, &categories))
+                .await??
+        };
+        Ok(match self.db_manager.save_scanned_folders(&[scanned]).await?.pop().transpose()? {
+            Some((Asset { id: Some(asset_id), .. }, true)) => FolderChange::Added(asset_id),
+            Some((Asset { id: Some(asset_id), .. }, false)) => FolderChange::Updated(asset_id),
+            _ => FolderChange::Unchanged,
+        })
+    }
+
+    /// Sync one asset path of a location with what is on disk, as a media file or as a folder or archive.
+    pub async fn sync_location_path(
+        &self,
+        location: &ScanLocation,
+        path: &Path,
+        location_root: &Path,
+        selector: &RootSelector,
+    ) -> Result<FolderChange> {
+        let location_id = location.id.unwrap_or(0);
+        if location.is_media() {
+            self.sync_watched_file(path, location_id, location_root).await
+        } else {
+            self.sync_watched_folder(path, location_id, location_root, selector).await
+        }
+    }
+
+    /// Retry every path of a location whose last scan attempt failed. Paths that now succeed get
+    /// their errors resolved; those that fail again get their error updated.
+    pub async fn retry_failed_paths(&self, location: &ScanLocation) -> Result<ScanRetryReport> {
+        let location_id = location
+            .id
+            .ok_or_else(|| anyhow::anyhow!("Scan location has no id: {}", location.path))?;
+        let root = PathBuf::from(&location.path);
+        let selector = RootSelector::for_location(location)?;
+        let mut report = ScanRetryReport {
+            location_id,
+            ..Default::default()
+        };
+
+        for record in self.db_manager.get_unresolved_scan_errors(location_id).await? {
+            // Errors of the location root itself need a full scan.
+            let Some(path) = record.path.as_deref().filter(|path| record.is_retryable() && Path::new(path) != root) else {
+                report.skipped += 1;
+                continue;
+            };
+            if self.stop_requested() {
+                break;
+            }
+            report.retried += 1;
+            match self.retry_path(location, Path::new(path), record.kind, &root, &selector).await {
+                Ok(()) => {
+                    self.db_manager.resolve_scan_errors(location_id, path, record.kind).await?;
+                    report.resolved += 1;
+                }
+                Err(error) => {
+                    self.db_manager.update_scan_error(record.id, &error).await?;
+                    report.failed.push(error);
                 }
             }
-            return Ok(Some(existing_asset));
         }
+        tracing::info!(
+            "Retried {} failed path(s) of {}: {} resolved, {} still failing",
+            report.retried,
+            location.path,
+            report.resolved,
+            report.failed.len()
+        );
+        Ok(report)
+    }
 
-        let folder_name = folder_path.file_name().unwrap().to_str().unwrap().to_string();
-        let created_date_system_time = folder_path.metadata()?.created()?;
-        let modified_date_system_time = folder_path.metadata()?.modified()?;
+    async fn retry_path(
+        &self,
+        location: &ScanLocation,
+        path: &Path,
+        kind: ScanErrorKind,
+        location_root: &Path,
+        selector: &RootSelector,
+    ) -> std::result::Result<(), ScanError> {
+        if kind == ScanErrorKind::Marketplace {
+            let asset = self
+                .db_manager
+                .get_asset_by_path(&path.to_string_lossy())
+                .await
+                .map_err(|e| ScanError::from_error(path, &e))?;
+            // An asset removed since the scan has nothing left to look up.
+            let Some(mut asset) = asset else {
+                return Ok(());
+            };
+            return self
+                .fetch_and_update_orbital_data(&mut asset)
+                .await
+                .map_err(|e| ScanError::new(ScanErrorKind::Marketplace, Some(path), format!("{:#}", e)));
+        }
 
-        let created_date = chrono::DateTime::<chrono::Utc>::from(created_date_system_time).to_rfc3339();
-        let modified_date = chrono::DateTime::<chrono::Utc>::from(modified_date_system_time).to_rfc3339();
+        // Project packages are indexed with the whole project; once readable, the next scan picks them up.
+        if location.is_project() {
+            let package = path.to_path_buf();
+            return tokio::task::spawn_blocking(move || hash_file_contents(&package))
+                .await
+                .map_err(|e| ScanError::new(ScanErrorKind::Unreadable, Some(path), e.to_string()))?
+                .map(|_| ())
+                .map_err(|e| ScanError::io(ScanErrorKind::Unreadable, path, &e));
+        }
+
+        for asset_path in watcher::asset_paths_for_change(location, location_root, selector, path) {
+            self.sync_location_path(location, &asset_path, location_root, selector)
+                .await
+                .map_err(|e| ScanError::from_error(&asset_path, &e))?;
+        }
+        Ok(())
+    }
+
+    /// Read a batch of folders and archives on the blocking pool, split across `self.concurrency` workers.
+    /// Returns them in their original order plus an error for each that failed.
+    async fn read_folders(
+        &self,
+        jobs: Vec<ScanTarget>,
+        scan_location_id: i64,
+        location_root: &Path,
+    ) -> (Vec<ScannedFolder>, Vec<ScanError>) {
+        let chunk_size = jobs.len().div_ceil(self.concurrency).max(1);
+        let mut jobs = jobs.into_iter().enumerate().peekable();
+        let mut workers = JoinSet::new();
+
+        while jobs.peek().is_some() {
+            let chunk: Vec<_> = jobs.by_ref().take(chunk_size).collect();
+            let root = location_root.to_path_buf();
+            let cancel_token = self.cancel_token.clone();
+            let categories = self.categories.clone();
+            workers.spawn_blocking(move || {
+                let mut results = Vec::with_capacity(chunk.len());
+                for (index, job) in chunk {
+                    if cancel_token.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    let result = match job {
+                        ScanTarget::Folder(folder, inventory) => {
+                            Self::read_folder(&folder, scan_location_id, &root, inventory, &categories)
+                                .map_err(|e| ScanError::from_error(&folder, &e))
+                        }
+                        ScanTarget::Archive(path) => Self::read_archive(&path, scan_location_id, &root, &categories)
+                            .map_err(|e| ScanError::from_error(&path, &e)),
+                        ScanTarget::MediaFile(path) => Self::read_media_file(&path, scan_location_id, &root, &categories)
+                            .map_err(|e| ScanError::from_error(&path, &e)),
+                    };
+                    results.push((index, result));
+                }
+                results
+            });
+        }
+
+        let mut results = Vec::new();
+        while let Some(joined) = workers.join_next().await {
+            match joined {
+                Ok(chunk) => results.extend(chunk),
+                Err(e) => tracing::error!("Folder worker failed: {}", e),
+            }
+        }
+        results.sort_by_key(|(index, _)| *index);
+
+        let mut scanned = Vec::with_capacity(results.len());
+        let mut errors = Vec::new();
+        for (_, result) in results {
+            match result {
+                Ok(folder) => scanned.push(folder),
+                Err(e) => errors.push(e),
+            }
+        }
+        (scanned, errors)
+    }
+
+    /// Look up marketplace matches for `assets` the way a scan does for new folders.
+    /// Returns the assets with whatever metadata was found, plus an error per failed lookup.
+    pub async fn match_assets(&self, assets: Vec<Asset>) -> (Vec<Asset>, Vec<ScanError>) {
+        self.enrich_saved_assets(assets.into_iter().map(|asset| (asset, true)).collect()).await
+    }
+
+    /// Fetch Orbital metadata for newly inserted assets and for known ones that still lack it,
+    /// running up to `self.concurrency` lookups at a time. Returns every asset in its original order,
+    /// plus an error for each lookup that failed.
+    /// Loose media files are not marketplace products, so they are never looked up.
+    async fn enrich_saved_assets(&self, saved: Vec<(Asset, bool)>) -> (Vec<Asset>, Vec<ScanError>) {
+        let mut lookups = JoinSet::new();
+        let mut assets = Vec::with_capacity(saved.len());
+        let mut errors = Vec::new();
+
+        for (index, (mut asset, inserted)) in saved.into_iter().enumerate() {
+            let needs_metadata = inserted || asset.orbital_description.is_none() || asset.orbital_title.is_none();
+            let is_media_file = media::media_kind(Path::new(&asset.file_path)).is_some();
+            if !needs_metadata || is_media_file || self.cancel_token.load(Ordering::Relaxed) {
+                assets.push((index, asset));
+                continue;
+            }
+
+            while lookups.len() >= self.concurrency {
+                if let Some(done) = lookups.join_next().await {
+                    match done {
+                        Ok((index, asset, error)) => {
+                            assets.push((index, asset));
+                            errors.extend(error);
+                        }
+                        Err(e) => tracing::error!("Orbital lookup task failed: {}", e),
+                    }
+                }
+            }
+
+            let scanner = self.clone();
+            lookups.spawn(async move {
+                let error = scanner.fetch_and_update_orbital_data(&mut asset).await.err().map(|e| {
+                    ScanError::new(ScanErrorKind::Marketplace, Some(Path::new(&asset.file_path)), format!("{:#}", e))
+                });
+                (index, asset, error)
+            });
+        }
+
+        while let Some(done) = lookups.join_next().await {
+            match done {
+                Ok((index, asset, error)) => {
+                    assets.push((index, asset));
+                    errors.extend(error);
+                }
+                Err(e) => tracing::error!("Orbital lookup task failed: {}", e),
+            }
+        }
+        assets.sort_by_key(|(index, _)| *index);
+        (assets.into_iter().map(|(_, asset)| asset).collect(), errors)
+    }
+
+    /// Build the asset row for a folder from what is on disk. Runs on the blocking pool.
+    fn read_folder(
+        folder_path: &Path,
+        scan_location_id: i64,
+        location_root: &Path,
+        inventory: FolderInventory,
+        categories: &CategoryEngine,
+    ) -> Result<ScannedFolder> {
+        let folder_name = folder_path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .ok_or_else(|| anyhow::anyhow!("Folder has no name: {}", folder_path.display()))?;
+        let categorization = categories.assign(&CategorySignals::for_folder(folder_path));
+        let mut asset = Self::new_asset(
+            folder_name,
+            folder_path,
+            scan_location_id,
+            location_root,
+            categorization.primary.category.clone(),
+            inventory.totals.total_bytes,
+        )?;
+        asset.file_hash = inventory.fingerprint();
+
+        Ok(ScannedFolder { asset, inventory, categories: categorization })
+    }
+
+    /// Build the asset row for an archive from its directory, without extracting it.
+    /// The inventory and size describe the unpacked contents. Runs on the blocking pool.
+    fn read_archive(
+        archive_path: &Path,
+        scan_location_id: i64,
+        location_root: &Path,
+        categories: &CategoryEngine,
+    ) -> Result<ScannedFolder> {
+        let format = ArchiveFormat::from_path(archive_path)
+            .ok_or_else(|| anyhow::anyhow!("Not an archive: {}", archive_path.display()))?;
+        let name = archive_path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .ok_or_else(|| anyhow::anyhow!("Archive has no name: {}", archive_path.display()))?;
+
+        let entries = archive::list_entries(archive_path)?;
+        let archive_size = archive_path
+            .metadata()
+            .map_err(|e| ScanError::io(ScanErrorKind::MetadataUnavailable, archive_path, &e))?
+            .len();
+        let summary = archive::summarize(format, archive_size, &entries);
+        let inventory = archive::inventory(&entries);
+        let metadata_json = archive::read_metadata_json(archive_path, &entries);
+        let categorization = categories.assign(&CategorySignals::for_archive(archive_path, metadata_json.as_deref()));
+
+        let mut asset = Self::new_asset(
+            name,
+            archive_path,
+            scan_location_id,
+            location_root,
+            categorization.primary.category.clone(),
+            summary.uncompressed_size as i64,
+        )?;
+        asset.metadata = Some(serde_json::json!({ "archive": summary }).to_string());
+        asset.file_hash = inventory.fingerprint();
+
+        Ok(ScannedFolder { asset, inventory, categories: categorization })
+    }
+
+    /// Build the asset row for a single media file from its header. Runs on the blocking pool.
+    /// A file whose header cannot be read is still indexed, just without the media details.
+    fn read_media_file(
+        file_path: &Path,
+        scan_location_id: i64,
+        location_root: &Path,
+        categories: &CategoryEngine,
+    ) -> Result<ScannedFolder> {
+        let name = file_path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .ok_or_else(|| anyhow::anyhow!("File has no name: {}", file_path.display()))?;
+        let metadata = file_path
+            .metadata()
+            .map_err(|e| ScanError::io(ScanErrorKind::MetadataUnavailable, file_path, &e))?;
+        let size = metadata.len() as i64;
+
+        let mut inventory = FolderInventory::default();
+        if let Some(file_name) = file_path.file_name() {
+            inventory.add_file(Path::new(file_name), size, metadata.modified().ok());
+        }
+
+        let categorization = categories.assign(&CategorySignals::for_media_file(file_path));
+        let mut asset = Self::new_asset(
+            name,
+            file_path,
+            scan_location_id,
+            location_root,
+            categorization.primary.category.clone(),
+            size,
+        )?;
+        asset.file_hash = inventory.fingerprint();
+        match media::read_metadata(file_path) {
+            Ok(details) => asset.metadata = Some(serde_json::json!({ "media": details }).to_string()),
+            Err(e) => tracing::warn!("Couldn't read media header of {}: {}", file_path.display(), e),
+        }
+
+        Ok(ScannedFolder { asset, inventory, categories: categorization })
+    }
+
+    fn new_asset(
+        name: String,
+        path: &Path,
+        scan_location_id: i64,
+        location_root: &Path,
+        asset_type: String,
+        file_size: i64,
+    ) -> Result<Asset> {
+        // Paths are stored as text, and a lossy conversion would point at a file that does not exist.
+        let file_path = path.to_str().ok_or_else(|| ScanError::path_not_utf8(path))?.to_string();
+        let metadata_error = |e: std::io::Error| ScanError::io(ScanErrorKind::MetadataUnavailable, path, &e);
+        let metadata = path.metadata().map_err(metadata_error)?;
+        let created_date = chrono::DateTime::<chrono::Utc>::from(metadata.created().map_err(metadata_error)?).to_rfc3339();
+        let modified_date = chrono::DateTime::<chrono::Utc>::from(metadata.modified().map_err(metadata_error)?).to_rfc3339();
 
-        let folder_size = self.calculate_folder_size(folder_path).await.unwrap_or(0);
-        let asset_type = self.determine_asset_type_from_folder(folder_path);
-        
-        // This is a simplified hash for demonstration. A more robust solution might be needed.
-        let file_hash = self.calculate_folder_hash(&folder_name);
         let first_indexed_timestamp = chrono::Utc::now().to_rfc3339();
 
-        let new_asset = Asset {
+        Ok(Asset {
             id: None,
-            name: folder_name.clone(),
-            file_path: folder_path_str,
+            name,
+            file_path,
+            relative_path: Some(relative_asset_path(location_root, path)),
             asset_type,
-            file_size: folder_size,
+            file_size,
             created_date,
             modified_date,
             first_indexed_timestamp: Some(first_indexed_timestamp),
@@ -399,7 +1067,7 @@ impl AssetScanner {
             scan_location_id,
             is_favorite: false,
             last_accessed: None,
-            file_hash: Some(file_hash),
+            file_hash: None, // Set from the inventory by the caller
             metadata: None, // Can be extended to store more data
             orbital_title: None,
             orbital_description: None,
@@ -422,93 +1090,15 @@ impl AssetScanner {
             orbital_match_type: None,
             notes: None,
             orbital_manual_overrides: None,
-        };
-        
-        match self.db_manager.insert_asset(&new_asset).await {
-            Ok(asset_id) => {
-                let mut saved_asset = new_asset;
-                saved_asset.id = Some(asset_id);
-
-                // Immediately attempt to enrich the new asset with Orbital marketplace data
-                if let Err(e) = self.fetch_and_update_orbital_data(&mut saved_asset).await {
-                    tracing::warn!(
-                        "Failed to fetch Orbital metadata for new asset {} (id {}): {}",
-                        saved_asset.name,
-                        asset_id,
-                        e
-                    );
-                }
-
-                Ok(Some(saved_asset))
-            },
-            Err(e) => {
-                tracing::error!("Failed to insert asset for folder {}: {}", folder_name, e);
-                Err(e.into())
-            }
-        }
-    }
-
-    #[tracing::instrument(skip(self, folder_path), fields(path = %folder_path.display()))]
-    async fn calculate_folder_size(&self, folder_path: &Path) -> Result<i64> {
-        let mut total_size = 0;
-        for entry in WalkDir::new(folder_path) {
-            match entry {
-                Ok(entry) => {
-                    if entry.file_type().is_file() {
-                        match entry.metadata() {
-                            Ok(metadata) => {
-                                total_size += metadata.len();
-                            }
-                            Err(e) => {
-                                tracing::warn!("Couldn't get metadata for file {}: {}", entry.path().display(), e);
-                            }
-                        }
-                    }
-                }
-                Err(e) => {
-                    tracing::error!("Error walking directory for size calculation {}: {}", folder_path.display(), e);
-                }
-            }
-        }
-        Ok(total_size as i64)
-    }
-
-    fn calculate_folder_hash(&self, folder_name: &str) -> String {
-        let mut hasher = DefaultHasher::new();
-        folder_name.hash(&mut hasher);
-        format!("{:x}", hasher.finish())
-    }
-
-    fn determine_asset_type_from_folder(&self, folder_path: &Path) -> String {
-        utils::determine_asset_type_from_path(folder_path)
-    }
-
-    fn count_folders(&self, path: &Path, recursive: bool) -> Result<i64> {
-        if !path.is_dir() {
-            return Ok(0);
-        }
-
-        let walker = if recursive {
-            WalkDir::new(path)
-        } else {
-            WalkDir::new(path).max_depth(1)
-        };
-
-        let mut count = 0i64;
-        for entry in walker {
-            if let Ok(entry) = entry {
-                if entry.file_type().is_dir() && entry.path() != path {
-                    count += 1;
-                }
-            }
-        }
-        Ok(count)
+            user_modified_date: None,
+            packages_indexed_date: None,
+        })
     }
 
     async fn fetch_and_update_orbital_data(&self, asset: &mut Asset) -> Result<()> {
         tracing::info!("Fetching Orbital data for asset: {}", asset.name);
         
-        let fab_candidates = self.fab_api_client.search_by_folder_name(&asset.name).await?;
+        let fab_candidates = self.marketplace.search_by_folder_name(&asset.name).await?;
         
         if let Some((best_match, _, _)) = self.match_local_asset_to_fab_candidates(&asset.name, &fab_candidates) {
             tracing::info!("Found best match for {}: {:?}", asset.name, best_match.title);
@@ -0,0 +1,71 @@
+//! Where a scan reports its progress: app events in the desktop app, the terminal in the CLI.
+
+use crate::models::{ScanErrorRecord, ScanProgress};
+use std::sync::Mutex;
+#[cfg(feature = "desktop")]
+use tauri::{AppHandle, Emitter};
+
+/// Receives the progress updates and errors of a running scan.
+pub trait ProgressSink: Send + Sync {
+    fn scan_progress(&self, progress: &ScanProgress);
+
+    fn scan_error(&self, error: &ScanErrorRecord);
+}
+
+/// Drops every update, for scans nobody is watching.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoProgress;
+
+impl ProgressSink for NoProgress {
+    fn scan_progress(&self, _progress: &ScanProgress) {}
+
+    fn scan_error(&self, _error: &ScanErrorRecord) {}
+}
+
+/// Keeps every update, for tests and for callers that report once the scan is over.
+#[derive(Debug, Default)]
+pub struct ProgressLog {
+    progress: Mutex<Vec<ScanProgress>>,
+    errors: Mutex<Vec<ScanErrorRecord>>,
+}
+
+impl ProgressLog {
+    pub fn progress(&self) -> Vec<ScanProgress> {
+        self.progress.lock().unwrap().clone()
+    }
+
+    pub fn errors(&self) -> Vec<ScanErrorRecord> {
+        self.errors.lock().unwrap().clone()
+    }
+
+    /// The status of every progress update, in order.
+    pub fn statuses(&self) -> Vec<String> {
+        self.progress.lock().unwrap().iter().map(|p| p.status.clone()).collect()
+    }
+}
+
+impl ProgressSink for ProgressLog {
+    fn scan_progress(&self, progress: &ScanProgress) {
+        self.progress.lock().unwrap().push(progress.clone());
+    }
+
+    fn scan_error(&self, error: &ScanErrorRecord) {
+        self.errors.lock().unwrap().push(error.clone());
+    }
+}
+
+/// Forwards updates to the frontend as `scan-progress` and `scan-error` events.
+#[cfg(feature = "desktop")]
+impl ProgressSink for AppHandle {
+    fn scan_progress(&self, progress: &ScanProgress) {
+        if let Err(e) = self.emit("scan-progress", progress) {
+            tracing::error!("Failed to emit scan progress update: {}", e);
+        }
+    }
+
+    fn scan_error(&self, error: &ScanErrorRecord) {
+        if let Err(e) = self.emit("scan-error", error) {
+            tracing::error!("Failed to emit scan error: {}", e);
+        }
+    }
+}
@@ -0,0 +1,357 @@
+//! Indexing Unreal project locations and cross-referencing them with the library.
+
+use crate::database::resolve_asset_path;
+use crate::models::{ScanError, ScanErrorKind, ScanLocation, ScanProgress};
+use crate::scanner::utils::hash_file_contents;
+use crate::unreal::index::{self, ProjectPackage};
+use crate::unreal::{find_uproject, UnrealProject};
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+
+use super::AssetScanner;
+
+/// Marker stored in `asset_project_usage.import_mode` for packs found by indexing a project.
+pub const DETECTED_IMPORT_MODE: &str = "detected";
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ProjectIndexSummary {
+    pub location_id: i64,
+    pub packages: usize,
+    pub matched: usize,
+    pub assets: usize,
+    /// Packages that could not be read; they are left out of the index.
+    pub errors: Vec<ScanError>,
+}
+
+/// How a project package relates to the library.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PackageMatch {
+    /// Same package name and same content as in the pack.
+    Identical,
+    /// Same package name, different content.
+    Modified,
+    /// Same content under a different package name.
+    Moved,
+}
+
+impl PackageMatch {
+    fn as_str(&self) -> &'static str {
+        match self {
+            PackageMatch::Identical => "identical",
+            PackageMatch::Modified => "modified",
+            PackageMatch::Moved => "moved",
+        }
+    }
+}
+
+struct LibraryPackageRow {
+    asset_id: i64,
+    relative_path: String,
+    asset_path: String,
+    content_hash: Option<String>,
+}
+
+impl AssetScanner {
+    /// Walk a project's content, read package headers and hashes, and match every package
+    /// against the packages shipped in library assets.
+    pub async fn index_project_location(
+        &self,
+        location: &ScanLocation,
+    ) -> Result<ProjectIndexSummary> {
+        let location_id = location.id.unwrap_or(0);
+        let root = PathBuf::from(&location.path);
+        let uproject_path = find_uproject(&root)
+            .ok_or_else(|| anyhow::anyhow!("No .uproject file found in {}", location.path))?;
+        let project = UnrealProject::open(&uproject_path)?;
+        tracing::info!("Indexing Unreal project {} at {}", project.name, location.path);
+
+        let emit = |status: &str, processed: u64, total: u64, current_path: &str| {
+            let progress = ScanProgress {
+                location_id,
+                status: status.to_string(),
+                current_path: current_path.to_string(),
+                processed_items: processed,
+                total_items: total,
+                completed_successfully: false,
+                error: None,
+                error_count: 0,
+            };
+            self.progress.scan_progress(&progress);
+        };
+        emit("Initializing Scan", 0, 0, &location.path);
+
+        // Hashes of unchanged files are reused from the previous index.
+        let previous: HashMap<String, (i64, String, String)> = sqlx::query_as::<_, (String, i64, String, String)>(
+            "SELECT relative_path, file_size, modified_date, content_hash FROM project_packages WHERE location_id = ?",
+        )
+        .bind(location_id)
+        .fetch_all(&self.db_manager.pool)
+        .await?
+        .into_iter()
+        .map(|(path, size, modified, hash)| (path, (size, modified, hash)))
+        .collect();
+
+        let cancel_token = self.cancel_token.clone();
+        let pause_token = self.pause_token.clone();
+        let progress_sink = self.progress.clone();
+        let project_root = root.clone();
+        let (packages, errors) = tokio::task::spawn_blocking(move || -> Result<(Vec<ProjectPackage>, Vec<ScanError>)> {
+            let found = index::list_packages(&project_root, &index::project_content_roots(&project_root));
+            let total = found.len() as u64;
+            let mut packages = Vec::with_capacity(found.len());
+            let mut errors = Vec::new();
+
+            for (i, package) in found.into_iter().enumerate() {
+                if cancel_token.load(Ordering::Relaxed) || pause_token.load(Ordering::Relaxed) {
+                    return Err(anyhow::anyhow!("Scan stopped by user"));
+                }
+                let content_hash = match previous.get(&package.relative_path) {
+                    Some((size, modified, hash)) if *size == package.file_size && *modified == package.modified_date => {
+                        hash.clone()
+                    }
+                    _ => match hash_file_contents(&package.path) {
+                        Ok(hash) => hash,
+                        Err(e) => {
+                            errors.push(ScanError::io(ScanErrorKind::Unreadable, &package.path, &e));
+                            continue;
+                        }
+                    },
+                };
+                if i % 50 == 0 {
+                    progress_sink.scan_progress(&ScanProgress {
+                        location_id,
+                        status: "Scanning".to_string(),
+                        current_path: package.relative_path.clone(),
+                        processed_items: i as u64,
+                        total_items: total,
+                        completed_successfully: false,
+                        error: None,
+                        error_count: 0,
+                    });
+                }
+                packages.push(index::inspect_package(package, content_hash));
+            }
+            Ok((packages, errors))
+        })
+        .await??;
+
+        emit("Indexing library packages", 0, packages.len() as u64, &location.path);
+        self.refresh_library_packages().await?;
+
+        let matches = self.match_project_packages(&packages).await?;
+
+        // Replace the project's package index and its detected usages in one go.
+        let mut tx = self.db_manager.pool.begin().await?;
+        sqlx::query("DELETE FROM project_packages WHERE location_id = ?")
+            .bind(location_id)
+            .execute(&mut *tx)
+            .await?;
+        for (package, matched) in packages.iter().zip(&matches) {
+            let header = package.header.as_ref();
+            sqlx::query(
+                r#"
+                INSERT INTO project_packages (
+                    location_id, relative_path, package_name, file_size, modified_date, content_hash,
+                    file_version_ue4, file_version_ue5, package_flags, matched_asset_id, match_kind
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(location_id)
+            .bind(&package.found.relative_path)
+            .bind(&package.found.package_name)
+            .bind(package.found.file_size)
+            .bind(&package.found.modified_date)
+            .bind(&package.content_hash)
+            .bind(header.map(|h| h.file_version_ue4))
+            .bind(header.and_then(|h| h.file_version_ue5))
+            .bind(header.map(|h| h.package_flags as i64))
+            .bind(matched.map(|(asset_id, _)| asset_id))
+            .bind(matched.map(|(_, kind)| kind.as_str()))
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        let project_path = project.uproject_path.to_string_lossy().into_owned();
+        sqlx::query("DELETE FROM asset_project_usage WHERE project_path = ? AND import_mode = ?")
+            .bind(&project_path)
+            .bind(DETECTED_IMPORT_MODE)
+            .execute(&mut *tx)
+            .await?;
+        let matched_assets: HashSet<i64> = matches.iter().flatten().map(|(asset_id, _)| *asset_id).collect();
+        let now = self.db_manager.timestamp();
+        for asset_id in &matched_assets {
+            // Imports done through Omnidex keep their recorded mode.
+            sqlx::query(
+                r#"
+                INSERT OR IGNORE INTO asset_project_usage (asset_id, project_path, project_name, import_mode, imported_date)
+                VALUES (?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(asset_id)
+            .bind(&project_path)
+            .bind(&project.name)
+            .bind(DETECTED_IMPORT_MODE)
+            .bind(&now)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        let summary = ProjectIndexSummary {
+            location_id,
+            packages: packages.len(),
+            matched: matches.iter().filter(|m| m.is_some()).count(),
+            assets: matched_assets.len(),
+            errors,
+        };
+
+        let final_progress = ScanProgress {
+            location_id,
+            status: "Completed".to_string(),
+            current_path: String::new(),
+            processed_items: summary.packages as u64,
+            total_items: summary.packages as u64,
+            completed_successfully: true,
+            error: None,
+            error_count: summary.errors.len() as u64,
+        };
+        self.progress.scan_progress(&final_progress);
+        tracing::info!(
+            "Indexed project {}: {} packages, {} from {} library assets",
+            project.name,
+            summary.packages,
+            summary.matched,
+            summary.assets
+        );
+        Ok(summary)
+    }
+
+    /// Rebuild `asset_packages` for vault assets that changed since they were last listed.
+    async fn refresh_library_packages(&self) -> Result<()> {
+        let stale: Vec<(i64, String)> = sqlx::query_as(
+            r#"
+            SELECT a.id, a.file_path FROM assets a
+            JOIN scan_locations l ON l.id = a.scan_location_id
+            WHERE l.location_kind = 'vault'
+              AND (a.packages_indexed_date IS NULL OR a.packages_indexed_date < a.modified_date)
+            "#,
+        )
+        .fetch_all(&self.db_manager.pool)
+        .await?;
+
+        for (asset_id, file_path) in stale {
+            if self.stop_requested() {
+                return Err(anyhow::anyhow!("Scan stopped by user"));
+            }
+            let asset_dir = PathBuf::from(&file_path);
+            let packages = tokio::task::spawn_blocking(move || index::library_packages(&asset_dir)).await?;
+
+            let mut tx = self.db_manager.pool.begin().await?;
+            sqlx::query("DELETE FROM asset_packages WHERE asset_id = ?")
+                .bind(asset_id)
+                .execute(&mut *tx)
+                .await?;
+            for package in &packages {
+                sqlx::query(
+                    "INSERT INTO asset_packages (asset_id, relative_path, package_name, file_size) VALUES (?, ?, ?, ?)",
+                )
+                .bind(asset_id)
+                .bind(&package.relative_path)
+                .bind(&package.package_name)
+                .bind(package.file_size)
+                .execute(&mut *tx)
+                .await?;
+            }
+            sqlx::query("UPDATE assets SET packages_indexed_date = ? WHERE id = ?")
+                .bind(self.db_manager.timestamp())
+                .bind(asset_id)
+                .execute(&mut *tx)
+                .await?;
+            tx.commit().await?;
+        }
+        Ok(())
+    }
+
+    /// Pair every project package with a library asset: by package name first (identical or
+    /// modified depending on the hash), then by content hash alone (moved).
+    async fn match_project_packages(&self, packages: &[ProjectPackage]) -> Result<Vec<Option<(i64, PackageMatch)>>> {
+        let project_names: HashSet<&str> = packages.iter().map(|p| p.found.package_name.as_str()).collect();
+
+        let mut rows: Vec<(String, LibraryPackageRow)> = sqlx::query_as::<_, (i64, String, String, Option<String>, String)>(
+            r#"
+            SELECT ap.asset_id, ap.relative_path, ap.package_name, ap.content_hash, a.file_path
+            FROM asset_packages ap JOIN assets a ON a.id = ap.asset_id
+            "#,
+        )
+        .fetch_all(&self.db_manager.pool)
+        .await?
+        .into_iter()
+        .map(|(asset_id, relative_path, package_name, content_hash, asset_path)| {
+            (package_name, LibraryPackageRow { asset_id, relative_path, asset_path, content_hash })
+        })
+        .collect();
+
+        // Library packages are hashed lazily, only for packs that share at least one package
+        // name with the project. That also catches packages moved to another folder after import.
+        let candidate_assets: HashSet<i64> = rows
+            .iter()
+            .filter(|(name, _)| project_names.contains(name.as_str()))
+            .map(|(_, row)| row.asset_id)
+            .collect();
+        for (_, row) in rows
+            .iter_mut()
+            .filter(|(_, row)| row.content_hash.is_none() && candidate_assets.contains(&row.asset_id))
+        {
+            if self.stop_requested() {
+                return Err(anyhow::anyhow!("Scan stopped by user"));
+            }
+            let path = resolve_asset_path(Path::new(&row.asset_path), &row.relative_path);
+            let hash = match tokio::task::spawn_blocking(move || hash_file_contents(&path)).await? {
+                Ok(hash) => hash,
+                Err(e) => {
+                    tracing::warn!("Failed to hash library package {}: {}", row.relative_path, e);
+                    continue;
+                }
+            };
+            sqlx::query("UPDATE asset_packages SET content_hash = ? WHERE asset_id = ? AND relative_path = ?")
+                .bind(&hash)
+                .bind(row.asset_id)
+                .bind(&row.relative_path)
+                .execute(&self.db_manager.pool)
+                .await?;
+            row.content_hash = Some(hash);
+        }
+
+        let mut by_name: HashMap<&str, Vec<&LibraryPackageRow>> = HashMap::new();
+        let mut by_hash: HashMap<&str, i64> = HashMap::new();
+        for (package_name, row) in &rows {
+            if let Some(hash) = row.content_hash.as_deref() {
+                by_hash.entry(hash).or_insert(row.asset_id);
+            }
+            if project_names.contains(package_name.as_str()) {
+                by_name.entry(package_name.as_str()).or_default().push(row);
+            }
+        }
+
+        Ok(packages
+            .iter()
+            .map(|package| {
+                if let Some(candidates) = by_name.get(package.found.package_name.as_str()) {
+                    let identical = candidates
+                        .iter()
+                        .find(|row| row.content_hash.as_deref() == Some(package.content_hash.as_str()));
+                    return Some(match identical {
+                        Some(row) => (row.asset_id, PackageMatch::Identical),
+                        None => (candidates[0].asset_id, PackageMatch::Modified),
+                    });
+                }
+                by_hash
+                    .get(package.content_hash.as_str())
+                    .map(|asset_id| (*asset_id, PackageMatch::Moved))
+            })
+            .collect())
+    }
+}
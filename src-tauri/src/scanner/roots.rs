@@ -0,0 +1,172 @@
+//! Deciding which folders of a vault location are asset roots.
+
+use crate::database::relative_asset_path;
+use crate::models::{AssetRootRules, RootRule, ScanLocation};
+use anyhow::{anyhow, Result};
+use globset::{Glob, GlobBuilder, GlobMatcher, GlobSet, GlobSetBuilder};
+use std::path::Path;
+
+/// Compiled form of a location's [`AssetRootRules`].
+#[derive(Debug, Clone)]
+pub struct RootSelector {
+    recursive: bool,
+    roots: Vec<CompiledRule>,
+    include: Option<GlobSet>,
+    exclude: Option<GlobSet>,
+}
+
+#[derive(Debug, Clone)]
+enum CompiledRule {
+    UnrealDescriptor,
+    ContentFolder,
+    Depth(usize),
+    Glob(GlobMatcher),
+}
+
+/// Globs match paths relative to the location root with `/` separators. `*` stays within
+/// one folder, `**` crosses folders, and case is ignored like on the file systems vaults live on.
+fn compile_glob(pattern: &str) -> Result<Glob> {
+    GlobBuilder::new(pattern.trim().trim_matches('/'))
+        .literal_separator(true)
+        .case_insensitive(true)
+        .build()
+        .map_err(|e| anyhow!("Invalid glob '{}': {}", pattern, e))
+}
+
+fn compile_set(patterns: &[String]) -> Result<Option<GlobSet>> {
+    if patterns.is_empty() {
+        return Ok(None);
+    }
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        builder.add(compile_glob(pattern)?);
+    }
+    Ok(Some(builder.build()?))
+}
+
+impl RootSelector {
+    pub fn new(rules: &AssetRootRules, recursive: bool) -> Result<Self> {
+        let roots = rules
+            .roots
+            .iter()
+            .map(|rule| {
+                Ok(match rule {
+                    RootRule::UnrealDescriptor => CompiledRule::UnrealDescriptor,
+                    RootRule::ContentFolder => CompiledRule::ContentFolder,
+                    RootRule::Depth { depth } if *depth == 0 => {
+                        return Err(anyhow!("Root depth must be at least 1"));
+                    }
+                    RootRule::Depth { depth } => CompiledRule::Depth(*depth),
+                    RootRule::Glob { pattern } => CompiledRule::Glob(compile_glob(pattern)?.compile_matcher()),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            recursive,
+            roots,
+            include: compile_set(&rules.include)?,
+            exclude: compile_set(&rules.exclude)?,
+        })
+    }
+
+    pub fn for_location(location: &ScanLocation) -> Result<Self> {
+        Self::new(&location.asset_root_rules()?, location.scan_recursive)
+    }
+
+    /// Whether root rules are configured, as opposed to the plain `scan_recursive` behaviour.
+    pub fn has_rules(&self) -> bool {
+        !self.roots.is_empty()
+    }
+
+    /// Whether folders below an asset root can be asset roots themselves. Only the plain
+    /// recursive mode does this, where every folder is an asset.
+    pub fn allows_nesting(&self) -> bool {
+        !self.has_rules() && self.recursive
+    }
+
+    /// Whether a file or folder (relative to the location root) is skipped entirely.
+    pub fn is_excluded(&self, relative: &str) -> bool {
+        self.exclude.as_ref().is_some_and(|set| set.is_match(relative))
+    }
+
+    /// Whether `relative` or any folder above it is excluded, for paths reported individually
+    /// (e.g. by the watcher) rather than reached by walking down from the root.
+    pub fn is_under_excluded(&self, relative: &str) -> bool {
+        let Some(set) = &self.exclude else {
+            return false;
+        };
+        relative
+            .match_indices('/')
+            .map(|(index, _)| &relative[..index])
+            .chain(std::iter::once(relative))
+            .any(|prefix| set.is_match(prefix))
+    }
+
+    /// Whether a path relative to the location root passes the include globs, if there are any.
+    pub fn is_included(&self, relative: &str) -> bool {
+        self.include.as_ref().map_or(true, |set| set.is_match(relative))
+    }
+
+    /// Whether `dir`, `depth` levels below the location root, is an asset root.
+    /// `relative` is its path relative to the root with `/` separators.
+    pub fn is_root(&self, dir: &Path, relative: &str, depth: usize) -> bool {
+        if !self.is_included(relative) {
+            return false;
+        }
+        if !self.has_rules() {
+            return self.recursive || depth == 1;
+        }
+        self.roots.iter().any(|rule| match rule {
+            CompiledRule::UnrealDescriptor => contains_unreal_descriptor(dir),
+            CompiledRule::ContentFolder => dir.join("Content").is_dir(),
+            CompiledRule::Depth(root_depth) => depth == *root_depth,
+            CompiledRule::Glob(matcher) => matcher.is_match(relative),
+        })
+    }
+
+    /// Whether an archive file, `depth` levels below the location root, is indexed as an asset.
+    /// Without rules archives are picked up where folders would be; with rules, any archive
+    /// outside an asset root is one, since it is a pack that has not been unpacked yet.
+    pub fn is_archive_root(&self, relative: &str, depth: usize, inside_root: bool) -> bool {
+        if !self.is_included(relative) {
+            return false;
+        }
+        if !self.has_rules() {
+            return self.recursive || depth == 1;
+        }
+        !inside_root
+    }
+
+    /// The asset root containing `path` (a file or folder below `root`), found by walking
+    /// down from the location root. Only meaningful when rules are configured.
+    pub fn root_containing(&self, root: &Path, path: &Path) -> Option<std::path::PathBuf> {
+        let relative = path.strip_prefix(root).ok()?;
+        let mut current = root.to_path_buf();
+        for (index, component) in relative.components().enumerate() {
+            current.push(component);
+            let current_relative = relative_asset_path(root, &current);
+            if self.is_excluded(&current_relative) || !current.is_dir() {
+                return None;
+            }
+            if self.is_root(&current, &current_relative, index + 1) {
+                return Some(current);
+            }
+        }
+        None
+    }
+}
+
+fn contains_unreal_descriptor(dir: &Path) -> bool {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return false;
+    };
+    entries.flatten().any(|entry| {
+        let path = entry.path();
+        path.is_file()
+            && path
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| e.eq_ignore_ascii_case("uproject") || e.eq_ignore_ascii_case("uplugin"))
+    })
+}
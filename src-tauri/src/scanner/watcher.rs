@@ -0,0 +1,315 @@
+use crate::archive::is_archive;
+use crate::database::relative_asset_path;
+use crate::media::is_indexed_media;
+use crate::models::ScanLocation;
+use notify_debouncer_mini::notify::RecommendedWatcher;
+use notify_debouncer_mini::Debouncer;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use super::roots::RootSelector;
+
+// Watchers report to the frontend, so starting them needs the desktop app.
+#[cfg(feature = "desktop")]
+use {
+    super::AssetScanner,
+    crate::categories::CategoryEngine,
+    crate::database::DatabaseManager,
+    crate::models::LibraryChange,
+    crate::orbital::api::OrbitalApiClient,
+    anyhow::Result,
+    notify_debouncer_mini::notify::RecursiveMode,
+    notify_debouncer_mini::{new_debouncer, DebounceEventResult},
+    std::collections::BTreeSet,
+    std::sync::atomic::AtomicBool,
+    std::sync::Arc,
+    std::time::Duration,
+    tauri::{AppHandle, Emitter},
+};
+
+/// How long a path has to stay quiet before its changes are processed.
+/// Unzipping a large pack produces thousands of events, so this is generous.
+#[cfg(feature = "desktop")]
+const WATCH_DEBOUNCE: Duration = Duration::from_secs(3);
+
+// Global watcher manager
+static WATCHER_MANAGER: Mutex<Option<WatcherManager>> = Mutex::new(None);
+
+/// Outcome of re-syncing one asset folder after a filesystem change.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FolderChange {
+    Added(i64),
+    Updated(i64),
+    Removed(Vec<i64>),
+    Unchanged,
+}
+
+pub struct WatcherManager {
+    watchers: HashMap<i64, Debouncer<RecommendedWatcher>>,
+}
+
+impl Default for WatcherManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WatcherManager {
+    pub fn new() -> Self {
+        Self {
+            watchers: HashMap::new(),
+        }
+    }
+
+    pub fn is_watching(&self, location_id: i64) -> bool {
+        self.watchers.contains_key(&location_id)
+    }
+
+    pub fn register_watcher(&mut self, location_id: i64, debouncer: Debouncer<RecommendedWatcher>) {
+        // Replacing an existing entry drops the old debouncer, which stops it.
+        self.watchers.insert(location_id, debouncer);
+    }
+
+    pub fn stop_watching(&mut self, location_id: i64) -> bool {
+        self.watchers.remove(&location_id).is_some()
+    }
+
+    pub fn stop_all(&mut self) -> usize {
+        let stopped_count = self.watchers.len();
+        self.watchers.clear();
+        stopped_count
+    }
+}
+
+pub fn get_watcher_manager() -> &'static Mutex<Option<WatcherManager>> {
+    &WATCHER_MANAGER
+}
+
+pub fn init_watcher_manager() {
+    let mut manager = WATCHER_MANAGER.lock().unwrap();
+    if manager.is_none() {
+        *manager = Some(WatcherManager::new());
+    }
+}
+
+/// Map a changed path to the asset folder(s) whose row may need to change.
+///
+/// Without recursive scanning every direct child of the location root is an asset, so any
+/// change deep inside a pack collapses onto that top-level folder. With recursive scanning
+/// every directory is its own asset, so every directory containing the path is refreshed and
+/// the path itself is checked in case it was a directory or archive that appeared or disappeared.
+pub fn asset_folders_for_change(root: &Path, changed: &Path, recursive: bool) -> Vec<PathBuf> {
+    let Ok(relative) = changed.strip_prefix(root) else {
+        return Vec::new();
+    };
+    let Some(first_component) = relative.components().next() else {
+        return Vec::new();
+    };
+
+    if !recursive {
+        return vec![root.join(first_component)];
+    }
+
+    // Outermost first; a folder's size and file count include everything below it.
+    let mut folders: Vec<PathBuf> = changed
+        .ancestors()
+        .skip(1)
+        .take_while(|ancestor| *ancestor != root && ancestor.starts_with(root))
+        .map(Path::to_path_buf)
+        .collect();
+    folders.reverse();
+    if changed.is_dir() || !changed.exists() || is_archive(changed) {
+        folders.push(changed.to_path_buf());
+    }
+    folders
+}
+
+/// Map a changed path to the asset root containing it when root rules are configured.
+///
+/// A path that no longer exists may have been an asset root itself, so it is returned as is
+/// and syncing it removes whatever was indexed there. Archives outside any root are assets of their own.
+pub fn asset_roots_for_change(root: &Path, changed: &Path, selector: &RootSelector) -> Vec<PathBuf> {
+    if changed == root || !changed.starts_with(root) {
+        return Vec::new();
+    }
+    match selector.root_containing(root, changed) {
+        Some(folder) => vec![folder],
+        None if !changed.exists() || is_archive(changed) => vec![changed.to_path_buf()],
+        None => Vec::new(),
+    }
+}
+
+/// Map a changed path in a media location to the file asset it affects. A path that no longer
+/// exists may have been a folder of media files, so it is returned to remove what was below it.
+pub fn media_files_for_change(root: &Path, changed: &Path, extensions: &[String]) -> Vec<PathBuf> {
+    if changed == root || !changed.starts_with(root) {
+        return Vec::new();
+    }
+    if is_indexed_media(changed, extensions) || !changed.exists() {
+        vec![changed.to_path_buf()]
+    } else {
+        Vec::new()
+    }
+}
+
+/// Map a changed path to the assets of `location` it affects, picking them the way a scan would.
+pub fn asset_paths_for_change(location: &ScanLocation, root: &Path, selector: &RootSelector, changed: &Path) -> Vec<PathBuf> {
+    let relative = relative_asset_path(root, changed);
+    if selector.is_under_excluded(&relative) {
+        return Vec::new();
+    }
+    if location.is_media() {
+        if !selector.is_included(&relative) {
+            return Vec::new();
+        }
+        media_files_for_change(root, changed, &location.extension_filter())
+    } else if selector.has_rules() {
+        asset_roots_for_change(root, changed, selector)
+    } else {
+        asset_folders_for_change(root, changed, location.scan_recursive)
+    }
+}
+
+/// Start watching a scan location. Any previous watcher for the same location is replaced.
+#[cfg(feature = "desktop")]
+pub fn start_watching_location(
+    location: &ScanLocation,
+    db_manager: DatabaseManager,
+    app_handle: AppHandle,
+) -> Result<()> {
+    let location_id = location
+        .id
+        .ok_or_else(|| anyhow::anyhow!("Cannot watch a scan location without an id"))?;
+    let root = PathBuf::from(&location.path);
+    if !root.is_dir() {
+        return Err(anyhow::anyhow!("Scan location does not exist: {}", location.path));
+    }
+
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<DebounceEventResult>();
+    let mut debouncer = new_debouncer(WATCH_DEBOUNCE, move |result: DebounceEventResult| {
+        let _ = tx.send(result);
+    })?;
+    // Always watch recursively: even when only top-level folders are assets,
+    // a change deep inside one still affects its size.
+    debouncer.watcher().watch(&root, RecursiveMode::Recursive)?;
+
+    let watched_location = location.clone();
+    let selector = RootSelector::for_location(location)?;
+    let orbital_client = OrbitalApiClient::new()?;
+    let scanner = AssetScanner::new(db_manager, orbital_client, Arc::new(AtomicBool::new(false)));
+
+    // The task ends on its own once the debouncer is dropped and the channel closes.
+    tauri::async_runtime::spawn(async move {
+        while let Some(result) = rx.recv().await {
+            let events = match result {
+                Ok(events) => events,
+                Err(e) => {
+                    tracing::warn!("Filesystem watcher error for location {}: {}", location_id, e);
+                    continue;
+                }
+            };
+
+            let folders: BTreeSet<PathBuf> = events
+                .iter()
+                .flat_map(|event| asset_paths_for_change(&watched_location, &root, &selector, &event.path))
+                .collect();
+            if folders.is_empty() {
+                continue;
+            }
+
+            tracing::info!("Watcher picked up changes in {} asset folder(s) of location {}", folders.len(), location_id);
+            let mut change = LibraryChange {
+                location_id,
+                ..Default::default()
+            };
+
+            // Pick up taxonomy changes made since the watcher started.
+            let scanner = scanner.clone().with_categories(CategoryEngine::configured(&app_handle));
+            for folder in folders {
+                match scanner.sync_location_path(&watched_location, &folder, &root, &selector).await {
+                    Ok(FolderChange::Added(id)) => change.added.push(id),
+                    Ok(FolderChange::Updated(id)) => change.updated.push(id),
+                    Ok(FolderChange::Removed(ids)) => change.removed.extend(ids),
+                    Ok(FolderChange::Unchanged) => {}
+                    Err(e) => tracing::warn!("Failed to sync watched folder {}: {}", folder.display(), e),
+                }
+            }
+
+            if change.added.is_empty() && change.updated.is_empty() && change.removed.is_empty() {
+                continue;
+            }
+            if let Err(e) = app_handle.emit("library-changed", &change) {
+                tracing::error!("Failed to emit library-changed event: {}", e);
+            }
+        }
+        tracing::info!("Filesystem watcher stopped for location {}", location_id);
+    });
+
+    let mut manager_guard = WATCHER_MANAGER.lock().unwrap();
+    match manager_guard.as_mut() {
+        Some(manager) => {
+            manager.register_watcher(location_id, debouncer);
+            tracing::info!("Watching scan location {} ({})", location_id, location.path);
+            Ok(())
+        }
+        None => Err(anyhow::anyhow!("WatcherManager is not initialized.")),
+    }
+}
+
+/// Start watchers for every active location that has watching turned on.
+#[cfg(feature = "desktop")]
+pub async fn start_configured_watchers(db_manager: DatabaseManager, app_handle: AppHandle) {
+    let locations = match db_manager.get_watched_scan_locations().await {
+        Ok(locations) => locations,
+        Err(e) => {
+            tracing::error!("Failed to load watched scan locations: {}", e);
+            return;
+        }
+    };
+
+    for location in locations {
+        if let Err(e) = start_watching_location(&location, db_manager.clone(), app_handle.clone()) {
+            tracing::warn!("Failed to start watcher for {}: {}", location.path, e);
+        }
+    }
+}
+
+pub fn stop_watching_location(location_id: i64) -> bool {
+    match WATCHER_MANAGER.lock().unwrap().as_mut() {
+        Some(manager) => manager.stop_watching(location_id),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_changes_collapse_to_top_level_folder_when_not_recursive() {
+        let root = Path::new("/vault");
+        let changed = Path::new("/vault/ForestPack/Content/Meshes/SM_Tree.uasset");
+        assert_eq!(
+            asset_folders_for_change(root, changed, false),
+            vec![PathBuf::from("/vault/ForestPack")]
+        );
+        assert!(asset_folders_for_change(root, Path::new("/elsewhere/file.txt"), false).is_empty());
+        assert!(asset_folders_for_change(root, root, false).is_empty());
+    }
+
+    #[test]
+    fn test_recursive_changes_refresh_containing_folders() {
+        let root = Path::new("/vault");
+        // A vanished path may have been a directory, so it is checked as well.
+        let folders = asset_folders_for_change(root, Path::new("/vault/ForestPack/Content/gone"), true);
+        assert_eq!(
+            folders,
+            vec![
+                PathBuf::from("/vault/ForestPack"),
+                PathBuf::from("/vault/ForestPack/Content"),
+                PathBuf::from("/vault/ForestPack/Content/gone"),
+            ]
+        );
+    }
+}
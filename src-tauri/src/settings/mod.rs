@@ -0,0 +1,304 @@
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use serde_json::{Map, Value};
+use tokio::sync::{watch, Mutex};
+
+use crate::database::DatabaseManager;
+use crate::models::{AppSetting, AppSettings};
+
+/// Version of the layout of the settings stored in `app_settings`.
+/// Bump it when a key is renamed or changes meaning, and add a step to `migrate_settings`.
+pub const SETTINGS_SCHEMA_VERSION: i64 = 1;
+const SCHEMA_VERSION_KEY: &str = "settings_schema_version";
+
+/// Typed access to the persisted application settings.
+///
+/// The current value is cached in a `watch` channel: subsystems that need to react to
+/// changes at runtime (scanner, Orbital client, backups) call `subscribe()` and get
+/// notified whenever a command updates or resets a setting.
+pub struct SettingsService {
+    db_manager: DatabaseManager,
+    sender: watch::Sender<AppSettings>,
+    /// Held for every write, so changing one setting cannot overwrite a concurrent change to another.
+    write_lock: Mutex<()>,
+}
+
+impl SettingsService {
+    /// Migrate the stored settings to the current schema and load them.
+    pub async fn load(db_manager: DatabaseManager) -> Result<Self> {
+        migrate_settings(&db_manager).await?;
+        let settings = read_settings(&db_manager).await?;
+        let (sender, _) = watch::channel(settings);
+        Ok(Self { db_manager, sender, write_lock: Mutex::new(()) })
+    }
+
+    pub fn current(&self) -> AppSettings {
+        self.sender.borrow().clone()
+    }
+
+    pub fn subscribe(&self) -> watch::Receiver<AppSettings> {
+        self.sender.subscribe()
+    }
+
+    /// Re-read the settings from the database, e.g. after it was restored from a backup.
+    pub async fn reload(&self) -> Result<AppSettings> {
+        let _guard = self.write_lock.lock().await;
+        migrate_settings(&self.db_manager).await?;
+        let settings = read_settings(&self.db_manager).await?;
+        self.sender.send_replace(settings.clone());
+        Ok(settings)
+    }
+
+    /// Validate and persist a complete settings object, then notify subscribers.
+    pub async fn update(&self, settings: AppSettings) -> Result<AppSettings> {
+        let _guard = self.write_lock.lock().await;
+        self.store(settings).await
+    }
+
+    /// Change a single setting by its key (e.g. `"thumbnail_size"`).
+    pub async fn set(&self, key: &str, value: Value) -> Result<AppSettings> {
+        let _guard = self.write_lock.lock().await;
+        self.store_field(key, value).await
+    }
+
+    /// Restore one setting, or all of them when `key` is `None`, to its default.
+    pub async fn reset(&self, key: Option<&str>) -> Result<AppSettings> {
+        let _guard = self.write_lock.lock().await;
+        let defaults = AppSettings::default();
+        match key {
+            None => self.store(defaults).await,
+            Some(key) => {
+                let default_value = settings_to_fields(&defaults)?
+                    .remove(key)
+                    .ok_or_else(|| anyhow!("Unknown setting '{}'", key))?;
+                self.store_field(key, default_value).await
+            }
+        }
+    }
+
+    /// Callers hold `write_lock`.
+    async fn store(&self, settings: AppSettings) -> Result<AppSettings> {
+        settings.validate().map_err(|e| anyhow!(e))?;
+        write_settings(&self.db_manager, &settings).await?;
+        self.sender.send_replace(settings.clone());
+        Ok(settings)
+    }
+
+    /// Callers hold `write_lock`, so the settings read here are still current when stored.
+    async fn store_field(&self, key: &str, value: Value) -> Result<AppSettings> {
+        let mut fields = settings_to_fields(&self.current())?;
+        if !fields.contains_key(key) {
+            return Err(anyhow!("Unknown setting '{}'", key));
+        }
+        fields.insert(key.to_string(), value);
+
+        let settings: AppSettings = serde_json::from_value(Value::Object(fields))
+            .map_err(|e| anyhow!("Invalid value for setting '{}': {}", key, e))?;
+        self.store(settings).await
+    }
+}
+
+fn settings_to_fields(settings: &AppSettings) -> Result<Map<String, Value>> {
+    match serde_json::to_value(settings)? {
+        Value::Object(fields) => Ok(fields),
+        _ => Err(anyhow!("Settings did not serialize to an object")),
+    }
+}
+
+/// Convert a JSON value into the `(value, setting_type)` pair stored in `app_settings`.
+fn encode_value(value: &Value) -> (String, &'static str) {
+    match value {
+        Value::String(s) => (s.clone(), "string"),
+        Value::Bool(b) => (b.to_string(), "boolean"),
+        Value::Number(n) => (n.to_string(), "number"),
+        other => (other.to_string(), "json"),
+    }
+}
+
+fn decode_value(value: &str, setting_type: &str) -> Option<Value> {
+    match setting_type {
+        "string" => Some(Value::String(value.to_string())),
+        "boolean" => value.parse::<bool>().ok().map(Value::Bool),
+        "number" | "json" => serde_json::from_str(value).ok(),
+        _ => None,
+    }
+}
+
+fn setting_description(key: &str) -> Option<&'static str> {
+    match key {
+        "theme" => Some("UI colour theme"),
+        "auto_scan" => Some("Rescan all active locations periodically"),
+        "scan_interval_minutes" => Some("Minutes between automatic scans"),
+        "thumbnail_size" => Some("Size of thumbnails in the asset grid"),
+        "show_hidden_files" => Some("Include hidden folders when scanning"),
+        "default_view" => Some("Initial layout of the asset browser"),
+        "backup_enabled" => Some("Create periodic database backups"),
+        "backup_location" => Some("Folder backups are written to"),
+        "backup_interval_hours" => Some("Hours between automatic backups"),
+        "backup_keep_count" => Some("Number of backups to keep"),
+        "backup_max_age_days" => Some("Days after which backups are deleted"),
+        "scan_concurrency" => Some("Folders processed in parallel during a scan"),
+        "category_taxonomy" => Some("Custom category taxonomy used to categorize assets"),
+        "api_enabled" => Some("Serve the library to local tools over HTTP"),
+        "api_port" => Some("Port of the local HTTP API"),
+        "api_token" => Some("Token local tools send to use the HTTP API"),
+        "team_library_path" => Some("Shared team library file on a network share"),
+        "team_user_name" => Some("Name recorded with your edits in the team library"),
+        "team_sync_interval_minutes" => Some("Minutes between automatic team library syncs (0 = manual)"),
+        "engine_version" => Some("Unreal Engine version assets are checked against"),
+        "metadata_provider_order" => Some("Metadata providers in merge precedence, highest first"),
+        _ => None,
+    }
+}
+
+async fn read_settings(db_manager: &DatabaseManager) -> Result<AppSettings> {
+    let rows = sqlx::query_as::<_, AppSetting>("SELECT * FROM app_settings")
+        .fetch_all(&db_manager.pool)
+        .await?;
+
+    let defaults = AppSettings::default();
+    let mut fields = settings_to_fields(&defaults)?;
+    for row in rows {
+        if !fields.contains_key(&row.key) {
+            continue;
+        }
+        match decode_value(&row.value, &row.setting_type) {
+            Some(value) => {
+                fields.insert(row.key, value);
+            }
+            None => tracing::warn!("Ignoring unreadable stored value for setting '{}'", row.key),
+        }
+    }
+
+    let settings = match serde_json::from_value::<AppSettings>(Value::Object(fields)) {
+        Ok(settings) => settings,
+        Err(e) => {
+            tracing::warn!("Stored settings could not be read ({}), using defaults", e);
+            return Ok(defaults);
+        }
+    };
+    if let Err(e) = settings.validate() {
+        tracing::warn!("Stored settings are invalid ({}), using defaults", e);
+        return Ok(defaults);
+    }
+    Ok(settings)
+}
+
+async fn write_settings(db_manager: &DatabaseManager, settings: &AppSettings) -> Result<()> {
+    let mut tx = db_manager.pool.begin().await?;
+    let now = Utc::now().to_rfc3339();
+
+    for (key, value) in settings_to_fields(settings)? {
+        let (stored_value, setting_type) = encode_value(&value);
+        upsert_setting(&mut tx, &key, &stored_value, setting_type, setting_description(&key), &now).await?;
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+async fn upsert_setting(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    key: &str,
+    value: &str,
+    setting_type: &str,
+    description: Option<&str>,
+    now: &str,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO app_settings (key, value, setting_type, description, created_date, modified_date)
+        VALUES (?, ?, ?, ?, ?, ?)
+        ON CONFLICT(key) DO UPDATE SET
+            value = excluded.value,
+            setting_type = excluded.setting_type,
+            description = excluded.description,
+            modified_date = excluded.modified_date
+        "#,
+    )
+    .bind(key)
+    .bind(value)
+    .bind(setting_type)
+    .bind(description)
+    .bind(now)
+    .bind(now)
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
+
+/// Bring the stored settings up to `SETTINGS_SCHEMA_VERSION`.
+async fn migrate_settings(db_manager: &DatabaseManager) -> Result<()> {
+    let stored_version: i64 = sqlx::query_scalar::<_, String>("SELECT value FROM app_settings WHERE key = ?")
+        .bind(SCHEMA_VERSION_KEY)
+        .fetch_optional(&db_manager.pool)
+        .await?
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+
+    if stored_version == SETTINGS_SCHEMA_VERSION {
+        return Ok(());
+    }
+    if stored_version > SETTINGS_SCHEMA_VERSION {
+        tracing::warn!(
+            "Settings were written by a newer version (schema {}, this build understands {}); unknown keys are ignored",
+            stored_version,
+            SETTINGS_SCHEMA_VERSION
+        );
+        return Ok(());
+    }
+
+    // Version 0 -> 1: settings were never persisted. Write every key so the table is complete.
+    let settings = read_settings(db_manager).await?;
+    write_settings(db_manager, &settings).await?;
+
+    let mut tx = db_manager.pool.begin().await?;
+    upsert_setting(
+        &mut tx,
+        SCHEMA_VERSION_KEY,
+        &SETTINGS_SCHEMA_VERSION.to_string(),
+        "number",
+        Some("Layout version of the stored settings"),
+        &Utc::now().to_rfc3339(),
+    )
+    .await?;
+    tx.commit().await?;
+
+    tracing::info!("🔄 Migrated settings from schema {} to {}", stored_version, SETTINGS_SCHEMA_VERSION);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_concurrent_changes_to_different_settings_are_all_kept() {
+        let db = DatabaseManager::open_in_memory().await.unwrap();
+        let service = Arc::new(SettingsService::load(db.clone()).await.unwrap());
+
+        let changes = [
+            ("scan_interval_minutes", Value::from(45)),
+            ("backup_keep_count", Value::from(3)),
+            ("engine_version", Value::from("5.3")),
+            ("auto_scan", Value::from(true)),
+        ];
+        let tasks: Vec<_> = changes
+            .iter()
+            .cloned()
+            .map(|(key, value)| {
+                let service = service.clone();
+                tokio::spawn(async move { service.set(key, value).await.map(|_| ()) })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap().unwrap();
+        }
+
+        let stored = settings_to_fields(&read_settings(&db).await.unwrap()).unwrap();
+        for (key, value) in changes {
+            assert_eq!(stored[key], value, "{}", key);
+        }
+    }
+}
@@ -0,0 +1,722 @@
+//! Shared team library: several users' libraries over the same vault, kept in step through a
+//! shared SQLite file.
+//!
+//! A scan location takes part once it is given a team key; members link their own location
+//! (whatever its path on their machine) to the same key, and its assets are paired by their
+//! path below the location. Scan results, tags, notes, descriptions, manual overrides, match
+//! decisions and collections are shared. Favorites are not: they never leave the local library.
+//!
+//! Each shared asset has a revision that goes up whenever its shared fields change. A sync
+//! pushes local edits made since the last sync, pulls changes other members made, and when
+//! both sides changed merges them field by field, keeping the newer edit where they disagree.
+//! As with library imports, a pulled empty value never clears a filled one.
+
+pub mod store;
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use sqlx::{FromRow, Sqlite, SqliteConnection, Transaction};
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+use std::time::Duration;
+use tokio::sync::watch;
+
+use crate::database::{relative_asset_path, resolve_asset_path, DatabaseManager};
+use crate::models::{AppSettings, ScanLocation};
+use crate::transfer::{imported_wins, merge_value, non_empty, split_tags, MergeStrategy};
+use store::TeamStore;
+
+/// Which side of a conflict was kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictWinner {
+    Local,
+    Team,
+}
+
+/// An asset both this user and another member edited since this user's last sync.
+#[derive(Debug, Clone, Serialize)]
+pub struct TeamConflict {
+    pub asset_id: i64,
+    pub name: String,
+    /// Fields both sides changed to different values.
+    pub fields: Vec<String>,
+    pub kept: ConflictWinner,
+    /// The member whose edit was on the team side.
+    pub other_user: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TeamSyncReport {
+    pub user: String,
+    pub locations: usize,
+    /// Assets first added to the team library by this sync.
+    pub shared: usize,
+    /// Assets whose local edits were sent to the team library.
+    pub pushed: usize,
+    /// Assets updated with other members' edits.
+    pub pulled: usize,
+    /// Assets other members scanned that were added to this library.
+    pub added_locally: usize,
+    /// Team assets whose folder is not reachable from this machine.
+    pub missing_locally: usize,
+    pub conflicts: Vec<TeamConflict>,
+    pub collections_created: Vec<String>,
+    pub memberships_added: usize,
+    pub synced_at: String,
+}
+
+/// The shared fields of an asset, normalized so both sides compare equal when they agree.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct SharedFields {
+    tags: Vec<String>,
+    notes: Option<String>,
+    description: Option<String>,
+    manual_overrides: Option<String>,
+    orbital_match: Option<(String, Option<String>, Option<f64>)>,
+}
+
+impl SharedFields {
+    fn new(
+        tags: Option<&str>,
+        notes: Option<String>,
+        description: Option<String>,
+        manual_overrides: Option<String>,
+        slug: Option<String>,
+        match_type: Option<String>,
+        match_confidence: Option<f64>,
+    ) -> Self {
+        Self {
+            tags: split_tags(tags),
+            notes: non_empty(notes),
+            description: non_empty(description),
+            manual_overrides: non_empty(manual_overrides),
+            orbital_match: non_empty(slug).map(|slug| (slug, match_type, match_confidence)),
+        }
+    }
+
+    fn tags_column(&self) -> Option<String> {
+        (!self.tags.is_empty()).then(|| self.tags.join(","))
+    }
+
+    fn match_columns(&self) -> (Option<&str>, Option<&str>, Option<f64>) {
+        match &self.orbital_match {
+            Some((slug, match_type, confidence)) => (Some(slug.as_str()), match_type.as_deref(), *confidence),
+            None => (None, None, None),
+        }
+    }
+
+    /// Merge two edited versions. An empty side never clears a filled one; otherwise the winner's value is kept.
+    fn merge(&self, team: &SharedFields, take_team: bool) -> (SharedFields, Vec<String>) {
+        let merged = SharedFields {
+            tags: merge_value(&self.tags, &team.tags, |t| t.is_empty(), take_team),
+            notes: merge_value(&self.notes, &team.notes, Option::is_none, take_team),
+            description: merge_value(&self.description, &team.description, Option::is_none, take_team),
+            manual_overrides: merge_value(&self.manual_overrides, &team.manual_overrides, Option::is_none, take_team),
+            orbital_match: merge_value(&self.orbital_match, &team.orbital_match, Option::is_none, take_team),
+        };
+
+        let both_set = |local_empty: bool, team_empty: bool, differ: bool| !local_empty && !team_empty && differ;
+        let mut conflicting = Vec::new();
+        if both_set(self.tags.is_empty(), team.tags.is_empty(), self.tags != team.tags) {
+            conflicting.push("tags".to_string());
+        }
+        if both_set(self.notes.is_none(), team.notes.is_none(), self.notes != team.notes) {
+            conflicting.push("notes".to_string());
+        }
+        if both_set(self.description.is_none(), team.description.is_none(), self.description != team.description) {
+            conflicting.push("description".to_string());
+        }
+        if both_set(self.manual_overrides.is_none(), team.manual_overrides.is_none(), self.manual_overrides != team.manual_overrides) {
+            conflicting.push("manual_overrides".to_string());
+        }
+        if both_set(self.orbital_match.is_none(), team.orbital_match.is_none(), self.orbital_match != team.orbital_match) {
+            conflicting.push("orbital_match".to_string());
+        }
+        (merged, conflicting)
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+struct LocalTeamAsset {
+    id: i64,
+    name: String,
+    file_path: String,
+    relative_path: Option<String>,
+    asset_type: String,
+    file_size: i64,
+    file_hash: Option<String>,
+    tags: Option<String>,
+    notes: Option<String>,
+    description: Option<String>,
+    orbital_manual_overrides: Option<String>,
+    matched_orbital_product_slug: Option<String>,
+    orbital_match_type: Option<String>,
+    orbital_match_confidence: Option<f64>,
+    user_modified_date: Option<String>,
+    team_revision: Option<i64>,
+    team_synced_date: Option<String>,
+}
+
+impl LocalTeamAsset {
+    fn fields(&self) -> SharedFields {
+        SharedFields::new(
+            self.tags.as_deref(),
+            self.notes.clone(),
+            self.description.clone(),
+            self.orbital_manual_overrides.clone(),
+            self.matched_orbital_product_slug.clone(),
+            self.orbital_match_type.clone(),
+            self.orbital_match_confidence,
+        )
+    }
+
+    /// Whether the shared fields were edited since the last sync.
+    fn edited_since_sync(&self) -> bool {
+        match (&self.user_modified_date, &self.team_synced_date) {
+            (Some(_), None) => true,
+            (Some(modified), Some(synced)) => is_later(modified, synced),
+            (None, _) => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+struct TeamAsset {
+    id: i64,
+    relative_path: String,
+    name: String,
+    asset_type: String,
+    file_size: i64,
+    file_hash: Option<String>,
+    tags: Option<String>,
+    notes: Option<String>,
+    description: Option<String>,
+    manual_overrides: Option<String>,
+    orbital_slug: Option<String>,
+    match_type: Option<String>,
+    match_confidence: Option<f64>,
+    revision: i64,
+    modified_by: String,
+    modified_date: String,
+}
+
+impl TeamAsset {
+    fn fields(&self) -> SharedFields {
+        SharedFields::new(
+            self.tags.as_deref(),
+            self.notes.clone(),
+            self.description.clone(),
+            self.manual_overrides.clone(),
+            self.orbital_slug.clone(),
+            self.match_type.clone(),
+            self.match_confidence,
+        )
+    }
+}
+
+const LOCAL_TEAM_ASSET_COLUMNS: &str = "id, name, file_path, relative_path, asset_type, file_size, file_hash, tags, notes, \
+     description, orbital_manual_overrides, matched_orbital_product_slug, orbital_match_type, orbital_match_confidence, \
+     user_modified_date, team_revision, team_synced_date";
+
+fn is_later(a: &str, b: &str) -> bool {
+    let parse = |d: &str| chrono::DateTime::parse_from_rfc3339(d).ok();
+    match (parse(a), parse(b)) {
+        (Some(a), Some(b)) => a > b,
+        _ => a > b,
+    }
+}
+
+/// The name this user's edits are recorded under.
+pub fn team_user_name(settings: &AppSettings) -> String {
+    settings
+        .team_user_name
+        .clone()
+        .or_else(|| std::env::var("USERNAME").ok())
+        .or_else(|| std::env::var("USER").ok())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Sync every shared scan location with the team library at `team_path`.
+pub async fn sync_team_library(db_manager: &DatabaseManager, team_path: &Path, user: &str) -> Result<TeamSyncReport> {
+    let locations = db_manager.get_team_scan_locations().await?;
+    if locations.is_empty() {
+        return Err(anyhow!("No scan location is shared with the team library"));
+    }
+
+    let store = TeamStore::open(team_path).await?;
+    let result = sync_locations(db_manager, &store, &locations, user).await;
+    store.close().await;
+    let report = result?;
+
+    tracing::info!(
+        "Team sync as {}: {} shared, {} pushed, {} pulled, {} added, {} conflicts",
+        user,
+        report.shared,
+        report.pushed,
+        report.pulled,
+        report.added_locally,
+        report.conflicts.len()
+    );
+    Ok(report)
+}
+
+async fn sync_locations(
+    db_manager: &DatabaseManager,
+    store: &TeamStore,
+    locations: &[ScanLocation],
+    user: &str,
+) -> Result<TeamSyncReport> {
+    let now = db_manager.timestamp();
+    let mut report = TeamSyncReport {
+        user: user.to_string(),
+        locations: locations.len(),
+        synced_at: now.clone(),
+        ..Default::default()
+    };
+
+    let mut team = store.begin().await?;
+    let mut local = db_manager.pool.begin().await?;
+
+    // Local asset id -> team asset id, for the collections below.
+    let mut paired = HashMap::new();
+    for location in locations {
+        sync_location(&mut local, &mut team, location, user, &now, &mut report, &mut paired).await?;
+    }
+    sync_collections(&mut local, &mut team, user, &now, &paired, &mut report).await?;
+
+    sqlx::query("INSERT INTO team_members (user_name, last_sync) VALUES (?, ?) ON CONFLICT(user_name) DO UPDATE SET last_sync = excluded.last_sync")
+        .bind(user)
+        .bind(&now)
+        .execute(&mut *team)
+        .await?;
+
+    // The team side goes first: if the local commit then fails, the next sync finds the
+    // team already holding our edits instead of pulling older values over them.
+    team.commit().await?;
+    local.commit().await?;
+    Ok(report)
+}
+
+async fn sync_location(
+    local: &mut Transaction<'static, Sqlite>,
+    team: &mut Transaction<'static, Sqlite>,
+    location: &ScanLocation,
+    user: &str,
+    now: &str,
+    report: &mut TeamSyncReport,
+    paired: &mut HashMap<i64, i64>,
+) -> Result<()> {
+    let Some(location_id) = location.id else {
+        return Ok(());
+    };
+    let location_key = location.team_key.as_deref().unwrap_or_default();
+    let root = Path::new(&location.path);
+
+    let mut team_assets: HashMap<String, TeamAsset> =
+        sqlx::query_as::<_, TeamAsset>("SELECT * FROM team_assets WHERE location_key = ?")
+            .bind(location_key)
+            .fetch_all(&mut **team)
+            .await?
+            .into_iter()
+            .map(|asset| (asset.relative_path.clone(), asset))
+            .collect();
+
+    let local_assets: Vec<LocalTeamAsset> = sqlx::query_as(&format!(
+        "SELECT {} FROM assets WHERE scan_location_id = ? ORDER BY id",
+        LOCAL_TEAM_ASSET_COLUMNS
+    ))
+    .bind(location_id)
+    .fetch_all(&mut **local)
+    .await?;
+
+    for asset in &local_assets {
+        let relative = asset
+            .relative_path
+            .clone()
+            .unwrap_or_else(|| relative_asset_path(root, Path::new(&asset.file_path)));
+        let local_fields = asset.fields();
+
+        let Some(shared) = team_assets.remove(&relative) else {
+            let modified = asset.user_modified_date.clone().unwrap_or_else(|| now.to_string());
+            let team_id = insert_team_asset(team, location_key, &relative, asset, &local_fields, user, &modified).await?;
+            mark_synced(local, asset.id, 1, now).await?;
+            paired.insert(asset.id, team_id);
+            report.shared += 1;
+            continue;
+        };
+        paired.insert(asset.id, shared.id);
+
+        update_team_scan_result(team, &shared, asset).await?;
+        let team_fields = shared.fields();
+        let mut revision = shared.revision;
+        if local_fields != team_fields {
+            let edited_here = asset.edited_since_sync();
+            let edited_there = shared.revision > asset.team_revision.unwrap_or(0);
+            let merged = match (edited_here, edited_there) {
+                // Nobody else touched it since our last sync, so our edit stands as it is, cleared fields included.
+                (true, false) => local_fields.clone(),
+                // Theirs wins, but values only we have (e.g. a match the scanner made) are kept and shared.
+                (false, _) => local_fields.merge(&team_fields, true).0,
+                (true, true) => {
+                    let take_team = imported_wins(
+                        MergeStrategy::Newest,
+                        asset.user_modified_date.as_deref(),
+                        Some(&shared.modified_date),
+                    );
+                    let (merged, fields) = local_fields.merge(&team_fields, take_team);
+                    if !fields.is_empty() {
+                        report.conflicts.push(TeamConflict {
+                            asset_id: asset.id,
+                            name: asset.name.clone(),
+                            fields,
+                            kept: if take_team { ConflictWinner::Team } else { ConflictWinner::Local },
+                            other_user: shared.modified_by.clone(),
+                        });
+                    }
+                    merged
+                }
+            };
+            if merged != team_fields {
+                revision += 1;
+                write_team_fields(team, shared.id, &merged, revision, user, now).await?;
+                report.pushed += 1;
+            }
+            if merged != local_fields {
+                let modified = if merged == team_fields { shared.modified_date.as_str() } else { now };
+                write_local_fields(local, asset.id, &merged, modified).await?;
+                report.pulled += 1;
+            }
+        }
+        mark_synced(local, asset.id, revision, now).await?;
+    }
+
+    // What is left was scanned by other members only.
+    for shared in team_assets.into_values() {
+        let path = resolve_asset_path(root, &shared.relative_path);
+        if !path.exists() {
+            report.missing_locally += 1;
+            continue;
+        }
+        if let Some(asset_id) = insert_local_asset(local, location_id, &path, &shared, now).await? {
+            paired.insert(asset_id, shared.id);
+            report.added_locally += 1;
+        }
+    }
+    Ok(())
+}
+
+async fn insert_team_asset(
+    team: &mut SqliteConnection,
+    location_key: &str,
+    relative: &str,
+    asset: &LocalTeamAsset,
+    fields: &SharedFields,
+    user: &str,
+    modified: &str,
+) -> Result<i64> {
+    let (slug, match_type, confidence) = fields.match_columns();
+    let id = sqlx::query_scalar(
+        "INSERT INTO team_assets (location_key, relative_path, name, asset_type, file_size, file_hash, tags, notes,
+            description, manual_overrides, orbital_slug, match_type, match_confidence, revision, modified_by, modified_date)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, 1, ?, ?) RETURNING id",
+    )
+    .bind(location_key)
+    .bind(relative)
+    .bind(&asset.name)
+    .bind(&asset.asset_type)
+    .bind(asset.file_size)
+    .bind(&asset.file_hash)
+    .bind(fields.tags_column())
+    .bind(&fields.notes)
+    .bind(&fields.description)
+    .bind(&fields.manual_overrides)
+    .bind(slug)
+    .bind(match_type)
+    .bind(confidence)
+    .bind(user)
+    .bind(modified)
+    .fetch_one(&mut *team)
+    .await?;
+    Ok(id)
+}
+
+/// Keep the scanned facts current. They come from the files, so they are not revisioned.
+async fn update_team_scan_result(team: &mut SqliteConnection, shared: &TeamAsset, asset: &LocalTeamAsset) -> Result<()> {
+    if shared.name == asset.name
+        && shared.asset_type == asset.asset_type
+        && shared.file_size == asset.file_size
+        && shared.file_hash == asset.file_hash
+    {
+        return Ok(());
+    }
+    sqlx::query("UPDATE team_assets SET name = ?, asset_type = ?, file_size = ?, file_hash = ? WHERE id = ?")
+        .bind(&asset.name)
+        .bind(&asset.asset_type)
+        .bind(asset.file_size)
+        .bind(&asset.file_hash)
+        .bind(shared.id)
+        .execute(&mut *team)
+        .await?;
+    Ok(())
+}
+
+async fn write_team_fields(
+    team: &mut SqliteConnection,
+    team_id: i64,
+    fields: &SharedFields,
+    revision: i64,
+    user: &str,
+    now: &str,
+) -> Result<()> {
+    let (slug, match_type, confidence) = fields.match_columns();
+    sqlx::query(
+        "UPDATE team_assets SET tags = ?, notes = ?, description = ?, manual_overrides = ?, orbital_slug = ?,
+            match_type = ?, match_confidence = ?, revision = ?, modified_by = ?, modified_date = ?
+         WHERE id = ?",
+    )
+    .bind(fields.tags_column())
+    .bind(&fields.notes)
+    .bind(&fields.description)
+    .bind(&fields.manual_overrides)
+    .bind(slug)
+    .bind(match_type)
+    .bind(confidence)
+    .bind(revision)
+    .bind(user)
+    .bind(now)
+    .bind(team_id)
+    .execute(&mut *team)
+    .await?;
+    Ok(())
+}
+
+async fn write_local_fields(local: &mut SqliteConnection, asset_id: i64, fields: &SharedFields, modified: &str) -> Result<()> {
+    let (slug, match_type, confidence) = fields.match_columns();
+    sqlx::query(
+        "UPDATE assets SET tags = ?, notes = ?, description = ?, orbital_manual_overrides = ?,
+            matched_orbital_product_slug = ?, orbital_match_type = ?, orbital_match_confidence = ?,
+            user_modified_date = ?
+         WHERE id = ?",
+    )
+    .bind(fields.tags_column())
+    .bind(&fields.notes)
+    .bind(&fields.description)
+    .bind(&fields.manual_overrides)
+    .bind(slug)
+    .bind(match_type)
+    .bind(confidence)
+    .bind(modified)
+    .bind(asset_id)
+    .execute(&mut *local)
+    .await?;
+    Ok(())
+}
+
+async fn mark_synced(local: &mut SqliteConnection, asset_id: i64, revision: i64, now: &str) -> Result<()> {
+    sqlx::query("UPDATE assets SET team_revision = ?, team_synced_date = ? WHERE id = ?")
+        .bind(revision)
+        .bind(now)
+        .bind(asset_id)
+        .execute(&mut *local)
+        .await?;
+    Ok(())
+}
+
+/// Add an asset another member scanned. The next scan of the location fills in the rest.
+async fn insert_local_asset(
+    local: &mut SqliteConnection,
+    location_id: i64,
+    path: &Path,
+    shared: &TeamAsset,
+    now: &str,
+) -> Result<Option<i64>> {
+    let fields = shared.fields();
+    let (slug, match_type, confidence) = fields.match_columns();
+    let id = sqlx::query_scalar(
+        "INSERT INTO assets (name, file_path, relative_path, asset_type, file_size, created_date, modified_date,
+            first_indexed_timestamp, tags, description, scan_location_id, file_hash, matched_orbital_product_slug,
+            orbital_match_type, orbital_match_confidence, notes, orbital_manual_overrides, user_modified_date,
+            team_revision, team_synced_date)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+         ON CONFLICT(file_path) DO NOTHING
+         RETURNING id",
+    )
+    .bind(&shared.name)
+    .bind(path.to_string_lossy())
+    .bind(&shared.relative_path)
+    .bind(&shared.asset_type)
+    .bind(shared.file_size)
+    .bind(now)
+    .bind(now)
+    .bind(now)
+    .bind(fields.tags_column())
+    .bind(&fields.description)
+    .bind(location_id)
+    .bind(&shared.file_hash)
+    .bind(slug)
+    .bind(match_type)
+    .bind(confidence)
+    .bind(&fields.notes)
+    .bind(&fields.manual_overrides)
+    .bind(&shared.modified_date)
+    .bind(shared.revision)
+    .bind(now)
+    .fetch_optional(&mut *local)
+    .await?;
+    Ok(id)
+}
+
+/// Collections are matched by name and memberships only ever added, on both sides.
+async fn sync_collections(
+    local: &mut Transaction<'static, Sqlite>,
+    team: &mut Transaction<'static, Sqlite>,
+    user: &str,
+    now: &str,
+    paired: &HashMap<i64, i64>,
+    report: &mut TeamSyncReport,
+) -> Result<()> {
+    let team_to_local: HashMap<i64, i64> = paired.iter().map(|(local_id, team_id)| (*team_id, *local_id)).collect();
+
+    let local_collections: Vec<(i64, String, Option<String>)> =
+        sqlx::query_as("SELECT id, name, description FROM collections")
+            .fetch_all(&mut **local)
+            .await?;
+    let mut local_ids: HashMap<String, i64> = local_collections.iter().map(|(id, name, _)| (name.clone(), *id)).collect();
+    let local_members: BTreeSet<(i64, i64)> = sqlx::query_as::<_, (i64, i64)>("SELECT collection_id, asset_id FROM collection_assets")
+        .fetch_all(&mut **local)
+        .await?
+        .into_iter()
+        .collect();
+
+    let team_collections: HashMap<String, Option<String>> =
+        sqlx::query_as::<_, (String, Option<String>)>("SELECT name, description FROM team_collections")
+            .fetch_all(&mut **team)
+            .await?
+            .into_iter()
+            .collect();
+    let team_members: BTreeSet<(String, i64)> =
+        sqlx::query_as::<_, (String, i64)>("SELECT collection_name, team_asset_id FROM team_collection_assets")
+            .fetch_all(&mut **team)
+            .await?
+            .into_iter()
+            .collect();
+
+    // Push: shared assets in local collections.
+    let local_names: HashMap<i64, (&String, &Option<String>)> =
+        local_collections.iter().map(|(id, name, description)| (*id, (name, description))).collect();
+    for (collection_id, asset_id) in &local_members {
+        let (Some(team_asset_id), Some((name, description))) = (paired.get(asset_id), local_names.get(collection_id)) else {
+            continue;
+        };
+        if !team_collections.contains_key(*name) {
+            sqlx::query("INSERT OR IGNORE INTO team_collections (name, description, created_by, created_date) VALUES (?, ?, ?, ?)")
+                .bind(name)
+                .bind(description)
+                .bind(user)
+                .bind(now)
+                .execute(&mut **team)
+                .await?;
+        }
+        if !team_members.contains(&((*name).clone(), *team_asset_id)) {
+            sqlx::query("INSERT OR IGNORE INTO team_collection_assets (collection_name, team_asset_id, added_by, added_date) VALUES (?, ?, ?, ?)")
+                .bind(name)
+                .bind(team_asset_id)
+                .bind(user)
+                .bind(now)
+                .execute(&mut **team)
+                .await?;
+            report.memberships_added += 1;
+        }
+    }
+
+    // Pull: team memberships of assets this library has.
+    for (name, team_asset_id) in &team_members {
+        let Some(asset_id) = team_to_local.get(team_asset_id) else {
+            continue;
+        };
+        let collection_id = match local_ids.get(name) {
+            Some(id) => *id,
+            None => {
+                let id: i64 = sqlx::query_scalar(
+                    "INSERT INTO collections (name, description, created_date, modified_date) VALUES (?, ?, ?, ?) RETURNING id",
+                )
+                .bind(name)
+                .bind(team_collections.get(name).cloned().flatten())
+                .bind(now)
+                .bind(now)
+                .fetch_one(&mut **local)
+                .await?;
+                local_ids.insert(name.clone(), id);
+                report.collections_created.push(name.clone());
+                id
+            }
+        };
+        if !local_members.contains(&(collection_id, *asset_id)) {
+            sqlx::query("INSERT OR IGNORE INTO collection_assets (collection_id, asset_id, added_date) VALUES (?, ?, ?)")
+                .bind(collection_id)
+                .bind(asset_id)
+                .bind(now)
+                .execute(&mut **local)
+                .await?;
+            report.memberships_added += 1;
+        }
+    }
+    Ok(())
+}
+
+/// Sync with the team library every `team_sync_interval_minutes` while one is configured.
+pub async fn run_team_sync_scheduler(db_manager: DatabaseManager, mut settings_rx: watch::Receiver<AppSettings>) {
+    loop {
+        let settings = settings_rx.borrow_and_update().clone();
+        let Some(team_path) = settings.team_library_path.clone().filter(|_| settings.team_sync_interval_minutes > 0) else {
+            if settings_rx.changed().await.is_err() {
+                break;
+            }
+            continue;
+        };
+
+        match sync_team_library(&db_manager, Path::new(&team_path), &team_user_name(&settings)).await {
+            Ok(_) => {}
+            Err(e) => tracing::warn!("Scheduled team sync failed: {}", e),
+        }
+
+        let interval = Duration::from_secs(settings.team_sync_interval_minutes as u64 * 60);
+        tokio::select! {
+            changed = settings_rx.changed() => {
+                if changed.is_err() {
+                    break;
+                }
+            }
+            _ = tokio::time::sleep(interval) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conflicting_fields_keep_the_winner_and_fill_gaps() {
+        let local = SharedFields {
+            notes: Some("Uses Lumen".to_string()),
+            tags: vec!["castle".to_string()],
+            ..Default::default()
+        };
+        let team = SharedFields {
+            notes: Some("Needs UE 5.3".to_string()),
+            description: Some("Modular castle".to_string()),
+            ..Default::default()
+        };
+
+        let (merged, conflicts) = local.merge(&team, true);
+        assert_eq!(merged.notes.as_deref(), Some("Needs UE 5.3"));
+        assert_eq!(merged.tags, vec!["castle".to_string()]);
+        assert_eq!(merged.description.as_deref(), Some("Modular castle"));
+        assert_eq!(conflicts, vec!["notes".to_string()]);
+
+        let (merged, _) = local.merge(&team, false);
+        assert_eq!(merged.notes.as_deref(), Some("Uses Lumen"));
+    }
+}
@@ -0,0 +1,161 @@
+//! The shared team library file and its schema.
+//!
+//! The file usually lives on a network share that several machines open at once, so it is
+//! opened the conservative way: a rollback journal instead of WAL (WAL needs shared memory,
+//! which network file systems do not provide), full fsyncs, and one connection that is only
+//! held for the length of a sync. Every sync runs in a `BEGIN IMMEDIATE` transaction, which
+//! takes the write lock up front; a second machine syncing at the same time waits for it.
+
+use anyhow::{anyhow, Result};
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions, SqliteSynchronous};
+use sqlx::{Pool, Sqlite, SqliteConnection, Transaction};
+use std::path::Path;
+use std::time::Duration;
+
+/// Version of the team library schema. Files written by a newer version are refused.
+pub const TEAM_SCHEMA_VERSION: i64 = 1;
+
+/// How long to wait for another machine's sync to release the file.
+const LOCK_TIMEOUT: Duration = Duration::from_secs(60);
+
+pub struct TeamStore {
+    pool: Pool<Sqlite>,
+}
+
+impl TeamStore {
+    /// Open the team library at `path`, creating it if it does not exist yet.
+    pub async fn open(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            if !parent.is_dir() {
+                return Err(anyhow!("Team library folder {} is not reachable", parent.display()));
+            }
+        }
+
+        let options = SqliteConnectOptions::new()
+            .filename(path)
+            .create_if_missing(true)
+            .journal_mode(SqliteJournalMode::Delete)
+            .synchronous(SqliteSynchronous::Full)
+            .foreign_keys(true)
+            .busy_timeout(LOCK_TIMEOUT);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .map_err(|e| anyhow!("Failed to open team library {}: {}", path.display(), e))?;
+        Ok(Self { pool })
+    }
+
+    /// Lock the team library for writing and make sure its schema is current.
+    pub async fn begin(&self) -> Result<Transaction<'static, Sqlite>> {
+        let mut tx = self
+            .pool
+            .begin_with("BEGIN IMMEDIATE")
+            .await
+            .map_err(|e| anyhow!("Failed to lock the team library: {}", e))?;
+        create_team_tables(&mut tx).await?;
+        Ok(tx)
+    }
+
+    /// Release the file, so the share does not see it held open between syncs.
+    pub async fn close(self) {
+        self.pool.close().await;
+    }
+}
+
+async fn create_team_tables(conn: &mut SqliteConnection) -> Result<()> {
+    sqlx::query("CREATE TABLE IF NOT EXISTS team_meta (key TEXT PRIMARY KEY, value TEXT NOT NULL)")
+        .execute(&mut *conn)
+        .await?;
+
+    let version: Option<String> = sqlx::query_scalar("SELECT value FROM team_meta WHERE key = 'schema_version'")
+        .fetch_optional(&mut *conn)
+        .await?;
+    match version.map(|v| v.parse::<i64>()) {
+        Some(Ok(version)) if version > TEAM_SCHEMA_VERSION => {
+            return Err(anyhow!(
+                "The team library was written by a newer version of Omnidex (schema {}, this version supports {})",
+                version,
+                TEAM_SCHEMA_VERSION
+            ));
+        }
+        Some(Ok(_)) => return Ok(()),
+        Some(Err(_)) => return Err(anyhow!("The team library has an unreadable schema version")),
+        None => {}
+    }
+
+    // One row per asset folder, identified by the team key of its location and its path below it.
+    // `revision` counts changes to the shared fields, so a member can tell what changed since their last sync.
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS team_assets (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            location_key TEXT NOT NULL,
+            relative_path TEXT NOT NULL,
+            name TEXT NOT NULL,
+            asset_type TEXT NOT NULL,
+            file_size INTEGER NOT NULL,
+            file_hash TEXT,
+            tags TEXT,
+            notes TEXT,
+            description TEXT,
+            manual_overrides TEXT,
+            orbital_slug TEXT,
+            match_type TEXT,
+            match_confidence REAL,
+            revision INTEGER NOT NULL,
+            modified_by TEXT NOT NULL,
+            modified_date TEXT NOT NULL,
+            UNIQUE (location_key, relative_path)
+        )
+        "#,
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS team_collections (
+            name TEXT PRIMARY KEY,
+            description TEXT,
+            created_by TEXT NOT NULL,
+            created_date TEXT NOT NULL
+        )
+        "#,
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS team_collection_assets (
+            collection_name TEXT NOT NULL,
+            team_asset_id INTEGER NOT NULL,
+            added_by TEXT NOT NULL,
+            added_date TEXT NOT NULL,
+            PRIMARY KEY (collection_name, team_asset_id),
+            FOREIGN KEY (collection_name) REFERENCES team_collections(name) ON DELETE CASCADE,
+            FOREIGN KEY (team_asset_id) REFERENCES team_assets(id) ON DELETE CASCADE
+        )
+        "#,
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS team_members (
+            user_name TEXT PRIMARY KEY,
+            last_sync TEXT NOT NULL
+        )
+        "#,
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query("INSERT INTO team_meta (key, value) VALUES ('schema_version', ?)")
+        .bind(TEAM_SCHEMA_VERSION.to_string())
+        .execute(&mut *conn)
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,111 @@
+//! Spreadsheet-friendly flavour of the library export: one row per asset.
+//!
+//! List values (tags, collections) are joined with `;` and manual overrides are kept as a JSON
+//! string. Collection descriptions do not survive a CSV round trip.
+
+use super::{ExportedAsset, LibraryExport, EXPORT_FORMAT, EXPORT_VERSION};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+const LIST_SEPARATOR: char = ';';
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CsvRow {
+    name: String,
+    relative_path: String,
+    // Missing from CSV files written before locations were exported.
+    #[serde(default)]
+    location: Option<String>,
+    file_hash: Option<String>,
+    orbital_slug: Option<String>,
+    match_type: Option<String>,
+    match_confidence: Option<f64>,
+    tags: String,
+    notes: Option<String>,
+    description: Option<String>,
+    is_favorite: bool,
+    manual_overrides: Option<String>,
+    collections: String,
+    user_modified_date: Option<String>,
+}
+
+fn join_list(values: &[String]) -> String {
+    values.join(&LIST_SEPARATOR.to_string())
+}
+
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(LIST_SEPARATOR)
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+pub fn write_export(export: &LibraryExport, path: &Path) -> Result<()> {
+    let mut writer = csv::Writer::from_path(path).with_context(|| format!("Failed to create {}", path.display()))?;
+
+    for asset in &export.assets {
+        writer.serialize(CsvRow {
+            name: asset.name.clone(),
+            relative_path: asset.relative_path.clone(),
+            location: asset.location.clone(),
+            file_hash: asset.file_hash.clone(),
+            orbital_slug: asset.orbital_slug.clone(),
+            match_type: asset.match_type.clone(),
+            match_confidence: asset.match_confidence,
+            tags: join_list(&asset.tags),
+            notes: asset.notes.clone(),
+            description: asset.description.clone(),
+            is_favorite: asset.is_favorite,
+            manual_overrides: asset.manual_overrides.as_ref().map(|v| v.to_string()),
+            collections: join_list(&asset.collections),
+            user_modified_date: asset.user_modified_date.clone(),
+        })?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+pub fn read_export(path: &Path) -> Result<LibraryExport> {
+    let mut reader = csv::Reader::from_path(path).with_context(|| format!("Failed to open {}", path.display()))?;
+
+    let mut assets = Vec::new();
+    for (line, row) in reader.deserialize::<CsvRow>().enumerate() {
+        // Line numbers are 1-based and the header occupies the first line.
+        let row = row.with_context(|| format!("Invalid CSV row on line {}", line + 2))?;
+        let manual_overrides = match row.manual_overrides.as_deref().map(str::trim) {
+            Some(raw) if !raw.is_empty() => Some(
+                serde_json::from_str(raw)
+                    .with_context(|| format!("Invalid manual_overrides JSON on line {}", line + 2))?,
+            ),
+            _ => None,
+        };
+
+        assets.push(ExportedAsset {
+            name: row.name,
+            relative_path: row.relative_path,
+            location: row.location,
+            file_hash: row.file_hash,
+            orbital_slug: row.orbital_slug,
+            match_type: row.match_type,
+            match_confidence: row.match_confidence,
+            tags: split_list(&row.tags),
+            notes: row.notes,
+            description: row.description,
+            is_favorite: row.is_favorite,
+            manual_overrides,
+            collections: split_list(&row.collections),
+            user_modified_date: row.user_modified_date,
+        });
+    }
+
+    Ok(LibraryExport {
+        format: EXPORT_FORMAT.to_string(),
+        version: EXPORT_VERSION,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        assets,
+        collections: Vec::new(),
+    })
+}
@@ -0,0 +1,701 @@
+//! Portable export and import of the curated parts of the library.
+//!
+//! Only what a user has put into the library travels: tags, notes, descriptions, favorites,
+//! collections, manual overrides and Orbital match decisions. File sizes, thumbnails and the
+//! scraped Orbital details are rebuilt by scanning on the receiving machine.
+
+pub mod csv;
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Pool, Sqlite};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+
+use crate::database::relative_asset_path;
+
+/// Identifies an Omnidex library export.
+pub const EXPORT_FORMAT: &str = "omnidex-library";
+
+/// Bumped whenever the layout of [`LibraryExport`] changes incompatibly.
+pub const EXPORT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Json,
+    Csv,
+}
+
+impl ExportFormat {
+    /// Guess the format from a file extension, defaulting to JSON.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("csv") => ExportFormat::Csv,
+            _ => ExportFormat::Json,
+        }
+    }
+}
+
+/// Decides which side wins when both the local library and the import have a value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MergeStrategy {
+    /// The imported value wins.
+    Theirs,
+    /// The local value wins; imported values only fill gaps.
+    Ours,
+    /// Whichever side was edited last wins, by `user_modified_date`. Ties keep ours.
+    Newest,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LibraryExport {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: String,
+    pub assets: Vec<ExportedAsset>,
+    #[serde(default)]
+    pub collections: Vec<ExportedCollection>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExportedAsset {
+    pub name: String,
+    /// Path relative to the asset's scan location, always with `/` separators.
+    pub relative_path: String,
+    /// Name of the asset's scan location. Missing in exports written before it was recorded.
+    pub location: Option<String>,
+    pub file_hash: Option<String>,
+    pub orbital_slug: Option<String>,
+    pub match_type: Option<String>,
+    pub match_confidence: Option<f64>,
+    pub tags: Vec<String>,
+    pub notes: Option<String>,
+    pub description: Option<String>,
+    pub is_favorite: bool,
+    pub manual_overrides: Option<serde_json::Value>,
+    pub collections: Vec<String>,
+    pub user_modified_date: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportedCollection {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+/// How an imported record was paired with a local asset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchKey {
+    FileHash,
+    OrbitalSlug,
+    RelativePath,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AssetImportChange {
+    pub asset_id: i64,
+    pub name: String,
+    pub matched_by: MatchKey,
+    /// Names of the fields that the import changes.
+    pub fields: Vec<String>,
+    pub collections_added: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportReport {
+    pub dry_run: bool,
+    pub strategy: MergeStrategy,
+    pub total_records: usize,
+    pub matched: usize,
+    pub unchanged: usize,
+    pub changes: Vec<AssetImportChange>,
+    /// Relative paths of imported records with no local counterpart.
+    pub unmatched: Vec<String>,
+    /// Relative paths of imported records that matched more than one local asset.
+    pub ambiguous: Vec<String>,
+    pub collections_created: Vec<String>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+struct LocalAsset {
+    id: i64,
+    name: String,
+    file_path: String,
+    relative_path: Option<String>,
+    file_hash: Option<String>,
+    matched_orbital_product_slug: Option<String>,
+    orbital_match_type: Option<String>,
+    orbital_match_confidence: Option<f64>,
+    tags: Option<String>,
+    notes: Option<String>,
+    description: Option<String>,
+    is_favorite: bool,
+    orbital_manual_overrides: Option<String>,
+    user_modified_date: Option<String>,
+    location_name: Option<String>,
+}
+
+const LOCAL_ASSET_COLUMNS: &str = "a.id, a.name, a.file_path, a.relative_path, a.file_hash, \
+     a.matched_orbital_product_slug, a.orbital_match_type, a.orbital_match_confidence, a.tags, a.notes, \
+     a.description, a.is_favorite, a.orbital_manual_overrides, a.user_modified_date, sl.name AS location_name";
+
+/// Curated values of one asset, in the shape both sides of a merge are compared in.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct CuratedFields {
+    tags: Vec<String>,
+    notes: Option<String>,
+    description: Option<String>,
+    is_favorite: bool,
+    manual_overrides: Option<String>,
+    orbital_match: Option<(String, Option<String>, Option<f64>)>,
+}
+
+impl CuratedFields {
+    fn from_local(asset: &LocalAsset) -> Self {
+        Self {
+            tags: split_tags(asset.tags.as_deref()),
+            notes: non_empty(asset.notes.clone()),
+            description: non_empty(asset.description.clone()),
+            is_favorite: asset.is_favorite,
+            manual_overrides: non_empty(asset.orbital_manual_overrides.clone()),
+            orbital_match: non_empty(asset.matched_orbital_product_slug.clone())
+                .map(|slug| (slug, asset.orbital_match_type.clone(), asset.orbital_match_confidence)),
+        }
+    }
+
+    fn from_export(asset: &ExportedAsset) -> Self {
+        Self {
+            tags: asset.tags.clone(),
+            notes: non_empty(asset.notes.clone()),
+            description: non_empty(asset.description.clone()),
+            is_favorite: asset.is_favorite,
+            manual_overrides: asset
+                .manual_overrides
+                .as_ref()
+                .filter(|v| !v.is_null())
+                .map(|v| v.to_string()),
+            orbital_match: non_empty(asset.orbital_slug.clone())
+                .map(|slug| (slug, asset.match_type.clone(), asset.match_confidence)),
+        }
+    }
+}
+
+pub(crate) fn non_empty(value: Option<String>) -> Option<String> {
+    value.filter(|v| !v.trim().is_empty())
+}
+
+/// Tags are stored as a comma-separated list in the database.
+pub(crate) fn split_tags(tags: Option<&str>) -> Vec<String> {
+    tags.map(|t| {
+        t.split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect()
+    })
+    .unwrap_or_default()
+}
+
+async fn load_local_assets(pool: &Pool<Sqlite>) -> Result<Vec<(LocalAsset, String)>> {
+    let assets: Vec<LocalAsset> = sqlx::query_as(&format!(
+        "SELECT {} FROM assets a LEFT JOIN scan_locations sl ON sl.id = a.scan_location_id ORDER BY a.id",
+        LOCAL_ASSET_COLUMNS
+    ))
+    .fetch_all(pool)
+    .await?;
+
+    Ok(assets
+        .into_iter()
+        .map(|asset| {
+            let relative = asset
+                .relative_path
+                .clone()
+                .unwrap_or_else(|| relative_asset_path(Path::new(""), Path::new(&asset.file_path)));
+            (asset, relative)
+        })
+        .collect())
+}
+
+/// Collect everything that should travel with the library into an export document.
+pub async fn build_export(pool: &Pool<Sqlite>) -> Result<LibraryExport> {
+    let assets = load_local_assets(pool).await?;
+
+    let collections: Vec<(i64, String, Option<String>)> =
+        sqlx::query_as("SELECT id, name, description FROM collections ORDER BY name")
+            .fetch_all(pool)
+            .await?;
+    let collection_names: HashMap<i64, String> =
+        collections.iter().map(|(id, name, _)| (*id, name.clone())).collect();
+
+    let mut memberships: HashMap<i64, Vec<String>> = HashMap::new();
+    let rows: Vec<(i64, i64)> = sqlx::query_as("SELECT collection_id, asset_id FROM collection_assets")
+        .fetch_all(pool)
+        .await?;
+    for (collection_id, asset_id) in rows {
+        if let Some(name) = collection_names.get(&collection_id) {
+            memberships.entry(asset_id).or_default().push(name.clone());
+        }
+    }
+
+    let assets = assets
+        .into_iter()
+        .map(|(asset, relative_path)| {
+            let mut collections = memberships.remove(&asset.id).unwrap_or_default();
+            collections.sort();
+            let manual_overrides = asset
+                .orbital_manual_overrides
+                .as_deref()
+                .and_then(|raw| serde_json::from_str(raw).ok());
+            ExportedAsset {
+                name: asset.name,
+                relative_path,
+                location: asset.location_name,
+                file_hash: asset.file_hash,
+                orbital_slug: asset.matched_orbital_product_slug,
+                match_type: asset.orbital_match_type,
+                match_confidence: asset.orbital_match_confidence,
+                tags: split_tags(asset.tags.as_deref()),
+                notes: asset.notes,
+                description: asset.description,
+                is_favorite: asset.is_favorite,
+                manual_overrides,
+                collections,
+                user_modified_date: asset.user_modified_date,
+            }
+        })
+        .collect();
+
+    Ok(LibraryExport {
+        format: EXPORT_FORMAT.to_string(),
+        version: EXPORT_VERSION,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        assets,
+        collections: collections
+            .into_iter()
+            .map(|(_, name, description)| ExportedCollection { name, description })
+            .collect(),
+    })
+}
+
+/// Write an export of the library to `path`. Returns the number of assets written.
+pub async fn export_to_file(pool: &Pool<Sqlite>, path: &Path, format: ExportFormat) -> Result<usize> {
+    let export = build_export(pool).await?;
+    let count = export.assets.len();
+
+    match format {
+        ExportFormat::Json => {
+            let json = serde_json::to_string_pretty(&export)?;
+            std::fs::write(path, json).with_context(|| format!("Failed to write {}", path.display()))?;
+        }
+        ExportFormat::Csv => csv::write_export(&export, path)?,
+    }
+
+    tracing::info!("Exported {} assets to {}", count, path.display());
+    Ok(count)
+}
+
+/// Read and validate an export file written by [`export_to_file`].
+pub fn read_export(path: &Path, format: ExportFormat) -> Result<LibraryExport> {
+    let export = match format {
+        ExportFormat::Json => {
+            let contents =
+                std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+            serde_json::from_str::<LibraryExport>(&contents).context("Not a valid library export")?
+        }
+        ExportFormat::Csv => csv::read_export(path)?,
+    };
+
+    if export.format != EXPORT_FORMAT {
+        return Err(anyhow!("Unrecognised export format '{}'", export.format));
+    }
+    if export.version > EXPORT_VERSION {
+        return Err(anyhow!(
+            "Export version {} is newer than this version of Omnidex supports ({})",
+            export.version,
+            EXPORT_VERSION
+        ));
+    }
+    Ok(export)
+}
+
+/// Returns true when the imported side should win a conflict.
+pub(crate) fn imported_wins(strategy: MergeStrategy, local_modified: Option<&str>, imported_modified: Option<&str>) -> bool {
+    match strategy {
+        MergeStrategy::Theirs => true,
+        MergeStrategy::Ours => false,
+        MergeStrategy::Newest => {
+            let parse = |d: Option<&str>| d.and_then(|d| chrono::DateTime::parse_from_rfc3339(d).ok());
+            match (parse(local_modified), parse(imported_modified)) {
+                (Some(local), Some(imported)) => imported > local,
+                (None, Some(_)) => true,
+                _ => false,
+            }
+        }
+    }
+}
+
+/// Merge one field. An empty side never overwrites a filled one; conflicts go to the winner.
+pub(crate) fn merge_value<T: Clone + PartialEq>(local: &T, imported: &T, is_empty: impl Fn(&T) -> bool, take_imported: bool) -> T {
+    if is_empty(imported) || local == imported {
+        local.clone()
+    } else if is_empty(local) || take_imported {
+        imported.clone()
+    } else {
+        local.clone()
+    }
+}
+
+/// Compute the merged curated fields and the names of the fields that changed.
+fn merge_fields(local: &CuratedFields, imported: &CuratedFields, take_imported: bool) -> (CuratedFields, Vec<String>) {
+    let merged = CuratedFields {
+        tags: merge_value(&local.tags, &imported.tags, |t| t.is_empty(), take_imported),
+        notes: merge_value(&local.notes, &imported.notes, Option::is_none, take_imported),
+        description: merge_value(&local.description, &imported.description, Option::is_none, take_imported),
+        // A favorite flag is never "empty"; only the winner can change it.
+        is_favorite: if take_imported { imported.is_favorite } else { local.is_favorite },
+        manual_overrides: merge_value(&local.manual_overrides, &imported.manual_overrides, Option::is_none, take_imported),
+        orbital_match: merge_value(&local.orbital_match, &imported.orbital_match, Option::is_none, take_imported),
+    };
+
+    let mut changed = Vec::new();
+    if merged.tags != local.tags {
+        changed.push("tags".to_string());
+    }
+    if merged.notes != local.notes {
+        changed.push("notes".to_string());
+    }
+    if merged.description != local.description {
+        changed.push("description".to_string());
+    }
+    if merged.is_favorite != local.is_favorite {
+        changed.push("is_favorite".to_string());
+    }
+    if merged.manual_overrides != local.manual_overrides {
+        changed.push("manual_overrides".to_string());
+    }
+    if merged.orbital_match != local.orbital_match {
+        changed.push("orbital_match".to_string());
+    }
+    (merged, changed)
+}
+
+/// Lookup tables used to pair imported records with local assets.
+struct LocalIndex {
+    by_hash: HashMap<String, Vec<usize>>,
+    by_slug: HashMap<String, Vec<usize>>,
+    /// By scan location name and relative path.
+    by_location_path: HashMap<(String, String), Vec<usize>>,
+    /// By relative path alone, for records whose scan location is unknown here.
+    by_path: HashMap<String, Vec<usize>>,
+    locations: HashSet<String>,
+}
+
+enum Lookup {
+    Found(usize, MatchKey),
+    Ambiguous,
+    Missing,
+}
+
+impl LocalIndex {
+    fn build(assets: &[(LocalAsset, String)]) -> Self {
+        let mut index = Self {
+            by_hash: HashMap::new(),
+            by_slug: HashMap::new(),
+            by_location_path: HashMap::new(),
+            by_path: HashMap::new(),
+            locations: HashSet::new(),
+        };
+        for (i, (asset, relative)) in assets.iter().enumerate() {
+            if let Some(hash) = non_empty(asset.file_hash.clone()) {
+                index.by_hash.entry(hash).or_default().push(i);
+            }
+            if let Some(slug) = non_empty(asset.matched_orbital_product_slug.clone()) {
+                index.by_slug.entry(slug).or_default().push(i);
+            }
+            if let Some(location) = &asset.location_name {
+                index.locations.insert(location.clone());
+                index.by_location_path.entry((location.clone(), relative.clone())).or_default().push(i);
+            }
+            index.by_path.entry(relative.clone()).or_default().push(i);
+        }
+        index
+    }
+
+    /// Try the fingerprint, then Orbital slug, then relative path. Only a unique hit counts;
+    /// an ambiguous key falls through to the next one.
+    ///
+    /// A path is looked up in the record's scan location, so same-named folders in different
+    /// locations are told apart. Only records without a location, or from a location this
+    /// library does not have, are matched on the path alone.
+    fn find(&self, record: &ExportedAsset) -> Lookup {
+        let path_hits = match record.location.as_ref().filter(|l| self.locations.contains(*l)) {
+            Some(location) => self.by_location_path.get(&(location.clone(), record.relative_path.clone())),
+            None => self.by_path.get(record.relative_path.as_str()),
+        };
+        let keys = [
+            (MatchKey::FileHash, self.by_hash.get(record.file_hash.as_deref().unwrap_or_default())),
+            (MatchKey::OrbitalSlug, self.by_slug.get(record.orbital_slug.as_deref().unwrap_or_default())),
+            (MatchKey::RelativePath, path_hits),
+        ];
+
+        let mut ambiguous = false;
+        for (key, hits) in keys {
+            match hits.map(|h| h.as_slice()) {
+                Some([single]) => return Lookup::Found(*single, key),
+                Some([_, _, ..]) => ambiguous = true,
+                _ => {}
+            }
+        }
+        if ambiguous {
+            Lookup::Ambiguous
+        } else {
+            Lookup::Missing
+        }
+    }
+}
+
+struct PlannedUpdate {
+    asset_id: i64,
+    fields: CuratedFields,
+    user_modified_date: Option<String>,
+    write_fields: bool,
+    collections: Vec<String>,
+}
+
+/// Merge an export into the library. With `dry_run` nothing is written and the report
+/// describes what would happen; otherwise all changes are applied in a single transaction.
+pub async fn import_export(
+    pool: &Pool<Sqlite>,
+    export: &LibraryExport,
+    strategy: MergeStrategy,
+    dry_run: bool,
+) -> Result<ImportReport> {
+    let local_assets = load_local_assets(pool).await?;
+    let index = LocalIndex::build(&local_assets);
+
+    let local_collections: HashMap<String, i64> = sqlx::query_as::<_, (String, i64)>("SELECT name, id FROM collections")
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .collect();
+    let local_memberships: HashSet<(i64, i64)> =
+        sqlx::query_as::<_, (i64, i64)>("SELECT collection_id, asset_id FROM collection_assets")
+            .fetch_all(pool)
+            .await?
+            .into_iter()
+            .collect();
+
+    let mut report = ImportReport {
+        dry_run,
+        strategy,
+        total_records: export.assets.len(),
+        matched: 0,
+        unchanged: 0,
+        changes: Vec::new(),
+        unmatched: Vec::new(),
+        ambiguous: Vec::new(),
+        collections_created: Vec::new(),
+    };
+
+    // Collections described in the export, plus any referenced only by asset rows (CSV).
+    let mut descriptions: BTreeMap<String, Option<String>> = export
+        .collections
+        .iter()
+        .map(|c| (c.name.clone(), c.description.clone()))
+        .collect();
+    for record in &export.assets {
+        for name in &record.collections {
+            descriptions.entry(name.clone()).or_insert(None);
+        }
+    }
+
+    let mut planned = Vec::new();
+    let mut seen_assets = HashSet::new();
+    for record in &export.assets {
+        let (position, matched_by) = match index.find(record) {
+            Lookup::Found(position, key) => (position, key),
+            Lookup::Ambiguous => {
+                report.ambiguous.push(record.relative_path.clone());
+                continue;
+            }
+            Lookup::Missing => {
+                report.unmatched.push(record.relative_path.clone());
+                continue;
+            }
+        };
+        let local = &local_assets[position].0;
+        if !seen_assets.insert(local.id) {
+            // Two imported records resolved to the same asset; only the first is applied.
+            report.ambiguous.push(record.relative_path.clone());
+            continue;
+        }
+        report.matched += 1;
+
+        let take_imported = imported_wins(
+            strategy,
+            local.user_modified_date.as_deref(),
+            record.user_modified_date.as_deref(),
+        );
+        let (merged, fields) = merge_fields(
+            &CuratedFields::from_local(local),
+            &CuratedFields::from_export(record),
+            take_imported,
+        );
+
+        // Memberships are only ever added, never removed.
+        let collections_added: Vec<String> = record
+            .collections
+            .iter()
+            .filter(|name| match local_collections.get(*name) {
+                Some(collection_id) => !local_memberships.contains(&(*collection_id, local.id)),
+                None => true,
+            })
+            .cloned()
+            .collect();
+
+        if fields.is_empty() && collections_added.is_empty() {
+            report.unchanged += 1;
+            continue;
+        }
+
+        let user_modified_date = if take_imported && record.user_modified_date.is_some() {
+            record.user_modified_date.clone()
+        } else {
+            Some(chrono::Utc::now().to_rfc3339())
+        };
+        planned.push(PlannedUpdate {
+            asset_id: local.id,
+            fields: merged,
+            user_modified_date,
+            write_fields: !fields.is_empty(),
+            collections: collections_added.clone(),
+        });
+        report.changes.push(AssetImportChange {
+            asset_id: local.id,
+            name: local.name.clone(),
+            matched_by,
+            fields,
+            collections_added,
+        });
+    }
+
+    report.collections_created = descriptions
+        .keys()
+        .filter(|name| !local_collections.contains_key(*name))
+        .cloned()
+        .collect();
+
+    if dry_run {
+        return Ok(report);
+    }
+
+    let now = chrono::Utc::now().to_rfc3339();
+    let mut tx = pool.begin().await?;
+
+    let mut collection_ids = local_collections;
+    for name in &report.collections_created {
+        let description = descriptions.get(name).cloned().flatten();
+        let id: i64 = sqlx::query_scalar(
+            "INSERT INTO collections (name, description, created_date, modified_date) VALUES (?, ?, ?, ?) RETURNING id",
+        )
+        .bind(name)
+        .bind(description)
+        .bind(&now)
+        .bind(&now)
+        .fetch_one(&mut *tx)
+        .await?;
+        collection_ids.insert(name.clone(), id);
+    }
+
+    for update in &planned {
+        if update.write_fields {
+            let fields = &update.fields;
+            let (slug, match_type, confidence) = match &fields.orbital_match {
+                Some((slug, match_type, confidence)) => (Some(slug.clone()), match_type.clone(), *confidence),
+                None => (None, None, None),
+            };
+            let tags = if fields.tags.is_empty() { None } else { Some(fields.tags.join(",")) };
+
+            sqlx::query(
+                "UPDATE assets SET tags = ?, notes = ?, description = ?, is_favorite = ?, orbital_manual_overrides = ?,
+                    matched_orbital_product_slug = ?, orbital_match_type = ?, orbital_match_confidence = ?,
+                    user_modified_date = ?
+                 WHERE id = ?",
+            )
+            .bind(tags)
+            .bind(&fields.notes)
+            .bind(&fields.description)
+            .bind(fields.is_favorite)
+            .bind(&fields.manual_overrides)
+            .bind(slug)
+            .bind(match_type)
+            .bind(confidence)
+            .bind(&update.user_modified_date)
+            .bind(update.asset_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        for name in &update.collections {
+            let Some(collection_id) = collection_ids.get(name) else {
+                continue;
+            };
+            sqlx::query("INSERT OR IGNORE INTO collection_assets (collection_id, asset_id, added_date) VALUES (?, ?, ?)")
+                .bind(collection_id)
+                .bind(update.asset_id)
+                .bind(&now)
+                .execute(&mut *tx)
+                .await?;
+        }
+    }
+
+    tx.commit().await?;
+    tracing::info!(
+        "Imported library: {} matched, {} changed, {} unmatched",
+        report.matched,
+        report.changes.len(),
+        report.unmatched.len()
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_values_never_overwrite() {
+        let local = CuratedFields {
+            notes: Some("keep me".to_string()),
+            ..Default::default()
+        };
+        let imported = CuratedFields {
+            tags: vec!["forest".to_string()],
+            ..Default::default()
+        };
+
+        // Even when the import wins, its missing notes do not clear ours, and our
+        // missing tags are filled in regardless of strategy.
+        for take_imported in [true, false] {
+            let (merged, changed) = merge_fields(&local, &imported, take_imported);
+            assert_eq!(merged.notes.as_deref(), Some("keep me"));
+            assert_eq!(merged.tags, vec!["forest".to_string()]);
+            assert_eq!(changed, vec!["tags".to_string()]);
+        }
+    }
+
+    #[test]
+    fn test_newest_strategy_compares_edit_dates() {
+        let older = Some("2024-01-01T00:00:00+00:00");
+        let newer = Some("2024-06-01T00:00:00+00:00");
+        assert!(imported_wins(MergeStrategy::Newest, older, newer));
+        assert!(!imported_wins(MergeStrategy::Newest, newer, older));
+        assert!(!imported_wins(MergeStrategy::Newest, newer, newer));
+        assert!(imported_wins(MergeStrategy::Newest, None, newer));
+        assert!(imported_wins(MergeStrategy::Theirs, newer, older));
+        assert!(!imported_wins(MergeStrategy::Ours, older, newer));
+    }
+}
@@ -0,0 +1,354 @@
+//! Copying (or hard-linking) an indexed asset pack into an Unreal project.
+
+use super::{is_package_file, package_path, UnrealProject, GAME_MOUNT_POINT};
+use crate::database::DatabaseManager;
+use anyhow::{anyhow, Context, Result};
+use jwalk::WalkDir;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportMode {
+    Copy,
+    /// Hard links share storage with the vault. Only works when both live on the same volume.
+    HardLink,
+}
+
+impl ImportMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ImportMode::Copy => "copy",
+            ImportMode::HardLink => "hard_link",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportKind {
+    /// The pack's `Content/` tree is merged into the project's `Content/`.
+    Content,
+    /// The pack is a code or content plugin and goes to `Plugins/<Name>/`.
+    Plugin,
+}
+
+/// Where an asset pack's files come from and where they end up in a project.
+#[derive(Debug, Clone)]
+pub struct ImportSource {
+    pub kind: ImportKind,
+    pub source_root: PathBuf,
+    pub target_root: PathBuf,
+    /// Content folder on the target side, used to derive package paths.
+    pub target_content_root: PathBuf,
+    pub mount_point: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct PlannedFile {
+    pub source: PathBuf,
+    pub destination: PathBuf,
+    pub size: u64,
+    pub conflict: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectImportReport {
+    pub asset_id: i64,
+    pub project_name: String,
+    pub project_path: String,
+    pub kind: ImportKind,
+    pub mode: ImportMode,
+    pub target_dir: String,
+    pub dry_run: bool,
+    pub file_count: usize,
+    pub total_bytes: u64,
+    /// Package paths (or project-relative paths for non-package files) that already exist.
+    pub conflicts: Vec<String>,
+    /// Whether files were written. False for dry runs and for refused overwrites.
+    pub committed: bool,
+    pub files_written: usize,
+}
+
+/// Find the `.uplugin` descriptor of a plugin pack, either at its root or one level down
+/// (vault downloads usually wrap the plugin in a folder of the same name).
+pub(crate) fn find_plugin_descriptor(asset_dir: &Path) -> Option<PathBuf> {
+    let is_uplugin = |p: &Path| {
+        p.is_file()
+            && p.extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| e.eq_ignore_ascii_case("uplugin"))
+    };
+
+    WalkDir::new(asset_dir)
+        .max_depth(2)
+        .sort(true)
+        .into_iter()
+        .flatten()
+        .map(|entry| entry.path())
+        .find(|path| is_uplugin(path))
+}
+
+/// Content folder of an asset pack and the mount point its packages load under:
+/// `/Game` for regular packs, `/<PluginName>` for plugins.
+pub fn pack_content_root(asset_dir: &Path) -> Option<(PathBuf, String)> {
+    if let Some(descriptor) = find_plugin_descriptor(asset_dir) {
+        let plugin_root = descriptor.parent().unwrap_or(asset_dir);
+        let plugin_name = descriptor.file_stem()?.to_string_lossy().into_owned();
+        return Some((plugin_root.join("Content"), format!("/{}", plugin_name)));
+    }
+
+    let content_dir = asset_dir.join("Content");
+    content_dir
+        .is_dir()
+        .then(|| (content_dir, GAME_MOUNT_POINT.to_string()))
+}
+
+/// Work out how an asset folder maps onto a project.
+pub fn resolve_source(asset_dir: &Path, project: &UnrealProject) -> Result<ImportSource> {
+    if !asset_dir.is_dir() {
+        return Err(anyhow!("Asset folder does not exist: {}", asset_dir.display()));
+    }
+
+    if let Some(descriptor) = find_plugin_descriptor(asset_dir) {
+        let plugin_root = descriptor.parent().unwrap_or(asset_dir).to_path_buf();
+        let plugin_name = descriptor
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let target_root = project.plugins_dir().join(&plugin_name);
+        return Ok(ImportSource {
+            kind: ImportKind::Plugin,
+            source_root: plugin_root,
+            target_content_root: target_root.join("Content"),
+            target_root,
+            mount_point: format!("/{}", plugin_name),
+        });
+    }
+
+    let content_dir = asset_dir.join("Content");
+    if content_dir.is_dir() {
+        return Ok(ImportSource {
+            kind: ImportKind::Content,
+            source_root: content_dir,
+            target_root: project.content_dir(),
+            target_content_root: project.content_dir(),
+            mount_point: GAME_MOUNT_POINT.to_string(),
+        });
+    }
+
+    Err(anyhow!(
+        "{} has neither a Content folder nor a .uplugin file",
+        asset_dir.display()
+    ))
+}
+
+/// List every file that would be written and flag the ones that already exist.
+pub fn plan_import(source: &ImportSource) -> Result<Vec<PlannedFile>> {
+    let mut files = Vec::new();
+    for entry in WalkDir::new(&source.source_root).sort(true) {
+        let entry = entry?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let path = entry.path();
+        let relative = path.strip_prefix(&source.source_root)?;
+        let destination = source.target_root.join(relative);
+        files.push(PlannedFile {
+            size: entry.metadata().map(|m| m.len()).unwrap_or(0),
+            conflict: destination.exists(),
+            source: path,
+            destination,
+        });
+    }
+    Ok(files)
+}
+
+/// Human-readable name of a conflicting destination: its package path when it is a package.
+pub fn conflict_label(source: &ImportSource, project: &UnrealProject, destination: &Path) -> String {
+    if is_package_file(destination) {
+        if let Some(package) = package_path(&source.mount_point, &source.target_content_root, destination) {
+            return package;
+        }
+    }
+    destination
+        .strip_prefix(&project.root)
+        .unwrap_or(destination)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+/// Write the planned files. Existing destinations are replaced.
+pub fn execute_import(files: &[PlannedFile], mode: ImportMode) -> Result<usize> {
+    let mut written = 0;
+    for file in files {
+        if let Some(parent) = file.destination.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+
+        match mode {
+            ImportMode::Copy => copy_replacing(&file.source, &file.destination)?,
+            ImportMode::HardLink => {
+                if file.destination.exists() {
+                    std::fs::remove_file(&file.destination)
+                        .with_context(|| format!("Failed to replace {}", file.destination.display()))?;
+                }
+                std::fs::hard_link(&file.source, &file.destination).with_context(|| {
+                    format!(
+                        "Failed to hard-link {} (hard links need the vault and project on the same volume)",
+                        file.destination.display()
+                    )
+                })?;
+            }
+        }
+        written += 1;
+    }
+    Ok(written)
+}
+
+/// Copy `source` to a temporary file beside `destination` and rename it over the destination.
+/// Copying onto the destination itself would write through an earlier hard-link import into
+/// the vault's own file.
+fn copy_replacing(source: &Path, destination: &Path) -> Result<()> {
+    let mut temp_name = destination.file_name().unwrap_or_default().to_os_string();
+    temp_name.push(".omnidex-import");
+    let temp = destination.with_file_name(temp_name);
+
+    let copied = std::fs::copy(source, &temp)
+        .with_context(|| format!("Failed to copy {} to {}", source.display(), destination.display()))
+        .and_then(|_| {
+            std::fs::rename(&temp, destination)
+                .with_context(|| format!("Failed to replace {}", destination.display()))
+        });
+    if copied.is_err() {
+        let _ = std::fs::remove_file(&temp);
+    }
+    copied
+}
+
+/// Import an indexed asset into the project at `uproject_path`, refusing to overwrite conflicting
+/// files unless `overwrite` is set, and record the import in the asset's "used in" list.
+pub async fn import_asset(
+    db: &DatabaseManager,
+    asset_id: i64,
+    uproject_path: &Path,
+    mode: ImportMode,
+    dry_run: bool,
+    overwrite: bool,
+) -> Result<ProjectImportReport> {
+    let asset = db.get_asset_by_id(asset_id).await?;
+    let project = UnrealProject::open(uproject_path)?;
+
+    let asset_dir = PathBuf::from(&asset.file_path);
+    let blocking_project = project.clone();
+    let report = tokio::task::spawn_blocking(move || -> Result<ProjectImportReport> {
+        let project = blocking_project;
+        let source = resolve_source(&asset_dir, &project)?;
+        let files = plan_import(&source)?;
+
+        let conflicts: Vec<String> = files
+            .iter()
+            .filter(|f| f.conflict)
+            .map(|f| conflict_label(&source, &project, &f.destination))
+            .collect();
+
+        let mut report = ProjectImportReport {
+            asset_id,
+            project_name: project.name.clone(),
+            project_path: project.uproject_path.to_string_lossy().into_owned(),
+            kind: source.kind,
+            mode,
+            target_dir: source.target_root.to_string_lossy().into_owned(),
+            dry_run,
+            file_count: files.len(),
+            total_bytes: files.iter().map(|f| f.size).sum(),
+            conflicts,
+            committed: false,
+            files_written: 0,
+        };
+
+        if dry_run || (!report.conflicts.is_empty() && !overwrite) {
+            return Ok(report);
+        }
+
+        report.files_written = execute_import(&files, mode)?;
+        report.committed = true;
+        Ok(report)
+    })
+    .await
+    .map_err(|e| anyhow!("Import task failed: {}", e))?
+    .map_err(|e| anyhow!("Failed to import asset into project: {}", e))?;
+
+    if report.committed {
+        db.record_project_usage(asset_id, &project, mode.as_str())
+            .await
+            .map_err(|e| anyhow!("Imported, but failed to record project usage: {}", e))?;
+        info!(
+            "Imported {} into {} ({} files, {} overwritten)",
+            asset.name,
+            project.name,
+            report.files_written,
+            report.conflicts.len()
+        );
+    } else if !report.dry_run {
+        info!("Import of {} into {} refused: {} conflicts", asset.name, project.name, report.conflicts.len());
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn planned(source: &Path, destination: &Path) -> PlannedFile {
+        PlannedFile { source: source.to_path_buf(), destination: destination.to_path_buf(), size: 0, conflict: destination.exists() }
+    }
+
+    #[test]
+    fn test_copy_over_hard_linked_import_leaves_vault_file_intact() {
+        let root = std::env::temp_dir().join(format!("omnidex-import-{}", std::process::id()));
+        let vault_file = root.join("Vault").join("Content").join("SM_Rock.uasset");
+        let project_file = root.join("Project").join("Content").join("SM_Rock.uasset");
+        std::fs::create_dir_all(vault_file.parent().unwrap()).unwrap();
+        std::fs::write(&vault_file, b"vault").unwrap();
+
+        let linked = execute_import(&[planned(&vault_file, &project_file)], ImportMode::HardLink);
+        // A second copy import over the hard link, from an updated pack.
+        let updated = root.join("Updated.uasset");
+        std::fs::write(&updated, b"updated pack").unwrap();
+        let copied = execute_import(&[planned(&updated, &project_file)], ImportMode::Copy);
+        let vault = std::fs::read(&vault_file).unwrap();
+        let project = std::fs::read(&project_file).unwrap();
+        let leftovers = std::fs::read_dir(project_file.parent().unwrap()).unwrap().count();
+        std::fs::remove_dir_all(&root).ok();
+
+        assert_eq!(linked.unwrap(), 1);
+        assert_eq!(copied.unwrap(), 1);
+        assert_eq!(vault, b"vault");
+        assert_eq!(project, b"updated pack");
+        assert_eq!(leftovers, 1);
+    }
+
+    #[test]
+    fn test_hard_link_over_copied_import_replaces_destination() {
+        let root = std::env::temp_dir().join(format!("omnidex-import-link-{}", std::process::id()));
+        let vault_file = root.join("Vault").join("T_Moss.uasset");
+        let project_file = root.join("Project").join("T_Moss.uasset");
+        std::fs::create_dir_all(vault_file.parent().unwrap()).unwrap();
+        std::fs::write(&vault_file, b"moss").unwrap();
+
+        let copied = execute_import(&[planned(&vault_file, &project_file)], ImportMode::Copy);
+        let linked = execute_import(&[planned(&vault_file, &project_file)], ImportMode::HardLink);
+        std::fs::write(&project_file, b"edited").unwrap();
+        let vault = std::fs::read(&vault_file).unwrap();
+        std::fs::remove_dir_all(&root).ok();
+
+        assert_eq!(copied.unwrap(), 1);
+        assert_eq!(linked.unwrap(), 1);
+        // Hard links share the file, so an edit in the project shows in the vault.
+        assert_eq!(vault, b"edited");
+    }
+}
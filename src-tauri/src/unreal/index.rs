@@ -0,0 +1,108 @@
+//! Listing the packages of projects and asset packs so they can be cross-referenced.
+
+use super::import::pack_content_root;
+use super::package::{read_package_header, PackageHeader};
+use super::{is_package_file, package_path, GAME_MOUNT_POINT};
+use crate::database::relative_asset_path;
+use jwalk::WalkDir;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone)]
+pub struct FoundPackage {
+    pub path: PathBuf,
+    /// Relative to the scanned root, with `/` separators.
+    pub relative_path: String,
+    pub package_name: String,
+    pub file_size: i64,
+    pub modified_date: String,
+}
+
+/// A project package together with what could be read from it.
+#[derive(Debug, Clone)]
+pub struct ProjectPackage {
+    pub found: FoundPackage,
+    pub content_hash: String,
+    pub header: Option<PackageHeader>,
+}
+
+/// Content folders of a project with their mount points: `Content/` as `/Game` plus the
+/// `Content/` of every plugin under `Plugins/`.
+pub fn project_content_roots(project_root: &Path) -> Vec<(PathBuf, String)> {
+    let mut roots = vec![(project_root.join("Content"), GAME_MOUNT_POINT.to_string())];
+
+    let plugins_dir = project_root.join("Plugins");
+    if plugins_dir.is_dir() {
+        for entry in WalkDir::new(&plugins_dir).max_depth(3).sort(true).into_iter().flatten() {
+            let path = entry.path();
+            let is_descriptor = path
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| e.eq_ignore_ascii_case("uplugin"));
+            if !is_descriptor {
+                continue;
+            }
+            if let (Some(dir), Some(name)) = (path.parent(), path.file_stem()) {
+                roots.push((dir.join("Content"), format!("/{}", name.to_string_lossy())));
+            }
+        }
+    }
+    roots
+}
+
+/// Every package file below `content_roots`, with paths relative to `scan_root`.
+pub fn list_packages(scan_root: &Path, content_roots: &[(PathBuf, String)]) -> Vec<FoundPackage> {
+    let mut packages = Vec::new();
+    for (content_root, mount_point) in content_roots {
+        if !content_root.is_dir() {
+            continue;
+        }
+        for entry in WalkDir::new(content_root).sort(true).into_iter().flatten() {
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let path = entry.path();
+            if !is_package_file(&path) {
+                continue;
+            }
+            let Some(package_name) = package_path(mount_point, content_root, &path) else {
+                continue;
+            };
+            let metadata = entry.metadata().ok();
+            packages.push(FoundPackage {
+                relative_path: relative_asset_path(scan_root, &path),
+                package_name,
+                file_size: metadata.as_ref().map(|m| m.len() as i64).unwrap_or(0),
+                modified_date: metadata
+                    .and_then(|m| m.modified().ok())
+                    .map(|t| chrono::DateTime::<chrono::Utc>::from(t).to_rfc3339())
+                    .unwrap_or_default(),
+                path,
+            });
+        }
+    }
+    packages
+}
+
+/// Packages shipped in a library asset pack, relative to the asset folder.
+pub fn library_packages(asset_dir: &Path) -> Vec<FoundPackage> {
+    match pack_content_root(asset_dir) {
+        Some(root) => list_packages(asset_dir, &[root]),
+        None => Vec::new(),
+    }
+}
+
+/// Read the header of a project package. A package that cannot be parsed is still indexed.
+pub fn inspect_package(found: FoundPackage, content_hash: String) -> ProjectPackage {
+    let header = match read_package_header(&found.path) {
+        Ok(header) => Some(header),
+        Err(e) => {
+            tracing::debug!("Could not read package header of {}: {}", found.path.display(), e);
+            None
+        }
+    };
+    ProjectPackage {
+        found,
+        content_hash,
+        header,
+    }
+}
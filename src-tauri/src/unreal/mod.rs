@@ -0,0 +1,118 @@
+//! Helpers for working with Unreal Engine projects on disk.
+
+pub mod import;
+pub mod index;
+pub mod package;
+pub mod version;
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path, PathBuf};
+
+/// File extensions of Unreal packages.
+pub const PACKAGE_EXTENSIONS: &[&str] = &["uasset", "umap"];
+
+/// Mount point of a project's own `Content/` folder.
+pub const GAME_MOUNT_POINT: &str = "/Game";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnrealProject {
+    pub name: String,
+    pub uproject_path: PathBuf,
+    pub root: PathBuf,
+    pub engine_association: Option<String>,
+}
+
+impl UnrealProject {
+    /// Open a project from the path of its `.uproject` file.
+    pub fn open(uproject_path: &Path) -> Result<Self> {
+        let is_uproject = uproject_path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| e.eq_ignore_ascii_case("uproject"));
+        if !is_uproject || !uproject_path.is_file() {
+            return Err(anyhow!("Not an Unreal project file: {}", uproject_path.display()));
+        }
+
+        let contents = std::fs::read_to_string(uproject_path)
+            .with_context(|| format!("Failed to read {}", uproject_path.display()))?;
+        let descriptor: serde_json::Value = serde_json::from_str(&contents)
+            .with_context(|| format!("Invalid project file {}", uproject_path.display()))?;
+
+        let name = uproject_path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let root = uproject_path
+            .parent()
+            .map(Path::to_path_buf)
+            .ok_or_else(|| anyhow!("Project file has no parent folder"))?;
+
+        Ok(Self {
+            name,
+            uproject_path: uproject_path.to_path_buf(),
+            root,
+            engine_association: descriptor
+                .get("EngineAssociation")
+                .and_then(|v| v.as_str())
+                .map(str::to_string),
+        })
+    }
+
+    pub fn content_dir(&self) -> PathBuf {
+        self.root.join("Content")
+    }
+
+    pub fn plugins_dir(&self) -> PathBuf {
+        self.root.join("Plugins")
+    }
+}
+
+/// The `.uproject` file directly inside `dir`, if there is one.
+pub fn find_uproject(dir: &Path) -> Option<PathBuf> {
+    std::fs::read_dir(dir).ok()?.flatten().map(|entry| entry.path()).find(|path| {
+        path.is_file()
+            && path
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| e.eq_ignore_ascii_case("uproject"))
+    })
+}
+
+pub fn is_package_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| PACKAGE_EXTENSIONS.iter().any(|p| e.eq_ignore_ascii_case(p)))
+}
+
+/// Package path of `file` below the content folder `content_root` mounted at `mount_point`,
+/// e.g. `/Game/Forest/Meshes/SM_Tree`. Returns `None` for files outside `content_root`.
+pub fn package_path(mount_point: &str, content_root: &Path, file: &Path) -> Option<String> {
+    let relative = file.strip_prefix(content_root).ok()?.with_extension("");
+    let mut package = mount_point.trim_end_matches('/').to_string();
+    for component in relative.components() {
+        if let Component::Normal(part) = component {
+            package.push('/');
+            package.push_str(&part.to_string_lossy());
+        }
+    }
+    Some(package)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_package_path_strips_extension_and_mounts() {
+        let content = Path::new("Project").join("Content");
+        let file = content.join("Forest").join("Meshes").join("SM_Tree.uasset");
+        assert_eq!(
+            package_path(GAME_MOUNT_POINT, &content, &file).as_deref(),
+            Some("/Game/Forest/Meshes/SM_Tree")
+        );
+        assert_eq!(package_path("/Game", &content, Path::new("Elsewhere/SM_Tree.uasset")), None);
+        assert!(is_package_file(&file));
+        assert!(!is_package_file(Path::new("Forest/readme.txt")));
+    }
+}
@@ -0,0 +1,186 @@
+//! Minimal reader for the summary at the start of `.uasset` / `.umap` files.
+//!
+//! Only the leading fields of `FPackageFileSummary` are read: enough to recognise a real
+//! package, tell which engine saved it and, for UE5 packages, recover the package name.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+/// `PACKAGE_FILE_TAG`, stored little-endian at offset 0.
+const PACKAGE_FILE_TAG: u32 = 0x9E2A_83C1;
+
+/// First UE5 object version that stores a saved hash ahead of the header size.
+const UE5_PACKAGE_SAVED_HASH: i32 = 1016;
+
+/// Upper bound for strings in the summary; anything larger means we are misreading.
+const MAX_SUMMARY_STRING: i32 = 4096;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PackageHeader {
+    pub legacy_file_version: i32,
+    pub file_version_ue4: i32,
+    pub file_version_ue5: Option<i32>,
+    pub licensee_version: i32,
+    pub total_header_size: i32,
+    /// Package name recorded by UE5; older engines write `None` here.
+    pub package_name: Option<String>,
+    pub package_flags: u32,
+    pub name_count: i32,
+}
+
+struct SummaryReader<R: Read> {
+    inner: R,
+}
+
+impl<R: Read> SummaryReader<R> {
+    fn i32(&mut self) -> Result<i32> {
+        let mut buf = [0u8; 4];
+        self.inner.read_exact(&mut buf)?;
+        Ok(i32::from_le_bytes(buf))
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        Ok(self.i32()? as u32)
+    }
+
+    fn skip(&mut self, count: u64) -> Result<()> {
+        let skipped = std::io::copy(&mut (&mut self.inner).take(count), &mut std::io::sink())?;
+        if skipped != count {
+            return Err(anyhow!("Unexpected end of package summary"));
+        }
+        Ok(())
+    }
+
+    /// An `FString`: positive lengths are ANSI, negative lengths UTF-16; both include the terminator.
+    fn fstring(&mut self) -> Result<String> {
+        let length = self.i32()?;
+        if length == 0 {
+            return Ok(String::new());
+        }
+        if length.abs() > MAX_SUMMARY_STRING {
+            return Err(anyhow!("Implausible string length {} in package summary", length));
+        }
+
+        if length > 0 {
+            let mut buf = vec![0u8; length as usize];
+            self.inner.read_exact(&mut buf)?;
+            buf.pop();
+            Ok(String::from_utf8_lossy(&buf).into_owned())
+        } else {
+            let mut buf = vec![0u8; (-length as usize) * 2];
+            self.inner.read_exact(&mut buf)?;
+            let mut units: Vec<u16> = buf.chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]])).collect();
+            units.pop();
+            Ok(String::from_utf16_lossy(&units))
+        }
+    }
+
+    /// Skip the custom version container, whose layout depends on the legacy file version.
+    fn skip_custom_versions(&mut self, legacy_file_version: i32) -> Result<()> {
+        if legacy_file_version > -2 {
+            return Ok(());
+        }
+        let count = self.i32()?;
+        if !(0..=10_000).contains(&count) {
+            return Err(anyhow!("Implausible custom version count {}", count));
+        }
+        for _ in 0..count {
+            match legacy_file_version {
+                // Enum keyed: tag + version.
+                -2 => self.skip(8)?,
+                // Guid keyed with a friendly name.
+                -5..=-3 => {
+                    self.skip(20)?;
+                    self.fstring()?;
+                }
+                // Optimized: guid + version.
+                _ => self.skip(20)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn read_package_header(path: &Path) -> Result<PackageHeader> {
+    let mut reader = SummaryReader {
+        inner: BufReader::new(File::open(path)?),
+    };
+
+    if reader.u32()? != PACKAGE_FILE_TAG {
+        return Err(anyhow!("{} is not an Unreal package", path.display()));
+    }
+
+    let legacy_file_version = reader.i32()?;
+    if legacy_file_version >= 0 {
+        return Err(anyhow!("Unsupported package version {}", legacy_file_version));
+    }
+    if legacy_file_version != -4 {
+        // LegacyUE3Version
+        reader.i32()?;
+    }
+    let file_version_ue4 = reader.i32()?;
+    let file_version_ue5 = if legacy_file_version <= -8 { Some(reader.i32()?) } else { None };
+    let licensee_version = reader.i32()?;
+    reader.skip_custom_versions(legacy_file_version)?;
+
+    if file_version_ue5.is_some_and(|v| v >= UE5_PACKAGE_SAVED_HASH) {
+        // SavedHash (FIoHash)
+        reader.skip(20)?;
+    }
+    let total_header_size = reader.i32()?;
+    let package_name = Some(reader.fstring()?).filter(|name| name.starts_with('/'));
+    let package_flags = reader.u32()?;
+    let name_count = reader.i32()?;
+
+    Ok(PackageHeader {
+        legacy_file_version,
+        file_version_ue4,
+        file_version_ue5,
+        licensee_version,
+        total_header_size,
+        package_name,
+        package_flags,
+        name_count,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fstring(bytes: &mut Vec<u8>, value: &str) {
+        bytes.extend(((value.len() + 1) as i32).to_le_bytes());
+        bytes.extend(value.as_bytes());
+        bytes.push(0);
+    }
+
+    #[test]
+    fn test_reads_ue5_summary() {
+        let mut bytes = Vec::new();
+        bytes.extend(PACKAGE_FILE_TAG.to_le_bytes());
+        for value in [-8i32, 864, 522, 1012, 0] {
+            bytes.extend(value.to_le_bytes());
+        }
+        bytes.extend(1i32.to_le_bytes()); // one custom version
+        bytes.extend([0u8; 20]);
+        bytes.extend(2048i32.to_le_bytes());
+        fstring(&mut bytes, "/Game/Forest/SM_Tree");
+        bytes.extend(0x400u32.to_le_bytes());
+        bytes.extend(42i32.to_le_bytes());
+
+        let path = std::env::temp_dir().join(format!("omnidex-header-{}.uasset", std::process::id()));
+        std::fs::write(&path, &bytes).unwrap();
+        let header = read_package_header(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(header.file_version_ue4, 522);
+        assert_eq!(header.file_version_ue5, Some(1012));
+        assert_eq!(header.total_header_size, 2048);
+        assert_eq!(header.package_name.as_deref(), Some("/Game/Forest/SM_Tree"));
+        assert_eq!(header.package_flags, 0x400);
+        assert_eq!(header.name_count, 42);
+    }
+}
@@ -0,0 +1,121 @@
+//! Unreal Engine release numbers, as written in descriptors and marketplace listings or implied
+//! by the object versions in package headers.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use super::package::PackageHeader;
+
+/// An engine release such as 5.3. Patch releases share their minor version's packages, so they
+/// are not kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub struct EngineVersion {
+    pub major: u32,
+    pub minor: u32,
+}
+
+/// Lowest UE5 minor release writing each `file_version_ue5`, oldest first.
+const UE5_RELEASES: &[(i32, u32)] = &[(1000, 0), (1006, 1), (1009, 2), (1010, 3), (1011, 4), (1013, 5), (1015, 6)];
+
+/// Lowest UE4 minor release writing each `file_version_ue4`, oldest first.
+const UE4_RELEASES: &[(i32, u32)] = &[
+    (342, 0),
+    (352, 1),
+    (363, 2),
+    (382, 3),
+    (385, 4),
+    (401, 5),
+    (434, 7),
+    (451, 8),
+    (482, 9),
+    (498, 11),
+    (504, 12),
+    (505, 13),
+    (508, 14),
+    (510, 15),
+    (513, 16),
+    (514, 18),
+    (516, 19),
+    (517, 21),
+    (518, 24),
+    (519, 26),
+    (522, 27),
+];
+
+static VERSION_PATTERN: OnceLock<Regex> = OnceLock::new();
+
+impl EngineVersion {
+    pub const fn new(major: u32, minor: u32) -> Self {
+        Self { major, minor }
+    }
+
+    /// The first `major.minor` in `text`, e.g. from `5.3.2`, `UE_5.3` or `Unreal Engine 4.27`.
+    pub fn find(text: &str) -> Option<Self> {
+        let captures = VERSION_PATTERN
+            .get_or_init(|| Regex::new(r"(\d+)\.(\d+)").unwrap())
+            .captures(text)?;
+        let version = Self::new(captures[1].parse().ok()?, captures[2].parse().ok()?);
+        (4..=9).contains(&version.major).then_some(version)
+    }
+
+    /// The oldest engine that could have saved a package with this header.
+    pub fn of_package(header: &PackageHeader) -> Option<Self> {
+        let lowest = |releases: &[(i32, u32)], object_version: i32| {
+            releases.iter().rev().find(|(first, _)| object_version >= *first).map(|(_, minor)| *minor)
+        };
+        match header.file_version_ue5 {
+            Some(ue5) => lowest(UE5_RELEASES, ue5).map(|minor| Self::new(5, minor)),
+            None => lowest(UE4_RELEASES, header.file_version_ue4).map(|minor| Self::new(4, minor)),
+        }
+    }
+}
+
+impl fmt::Display for EngineVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+impl FromStr for EngineVersion {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::find(s).ok_or_else(|| format!("'{}' is not an Unreal Engine version", s))
+    }
+}
+
+impl From<EngineVersion> for String {
+    fn from(version: EngineVersion) -> Self {
+        version.to_string()
+    }
+}
+
+impl TryFrom<String> for EngineVersion {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_versions_from_text_and_headers() {
+        assert_eq!(EngineVersion::find("5.3.2"), Some(EngineVersion::new(5, 3)));
+        assert_eq!(EngineVersion::find("UE_4.27"), Some(EngineVersion::new(4, 27)));
+        assert_eq!(EngineVersion::find("{A1B2C3}"), None);
+        assert!(EngineVersion::new(4, 27) < EngineVersion::new(5, 0));
+
+        let ue4 = PackageHeader { file_version_ue4: 517, ..Default::default() };
+        assert_eq!(EngineVersion::of_package(&ue4), Some(EngineVersion::new(4, 21)));
+        let ue5 = PackageHeader { file_version_ue4: 522, file_version_ue5: Some(1010), ..Default::default() };
+        assert_eq!(EngineVersion::of_package(&ue5), Some(EngineVersion::new(5, 3)));
+    }
+}
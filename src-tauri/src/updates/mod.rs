@@ -0,0 +1,365 @@
+//! Which version of each matched pack is on disk, compared with its marketplace listing.
+//!
+//! The installed engine comes from the pack's `.uplugin` / `.uproject` descriptor, or else from
+//! the headers of its packages. The copy's date is the newest package modification, which is
+//! usually when it was downloaded.
+
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::path::{Path, PathBuf};
+
+use crate::database::DatabaseManager;
+use crate::models::AppSettings;
+use crate::unreal::import::find_plugin_descriptor;
+use crate::unreal::package::read_package_header;
+use crate::unreal::version::EngineVersion;
+use crate::unreal::{find_uproject, index, UnrealProject};
+
+/// Package headers read for a pack without an engine in its descriptor. Packs are saved by one
+/// engine release, so a sample is enough.
+const SAMPLED_PACKAGES: usize = 64;
+
+/// Where the installed engine version was read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VersionSource {
+    Descriptor,
+    Packages,
+    Unknown,
+}
+
+impl VersionSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VersionSource::Descriptor => "descriptor",
+            VersionSource::Packages => "packages",
+            VersionSource::Unknown => "unknown",
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        match value {
+            "descriptor" => VersionSource::Descriptor,
+            "packages" => VersionSource::Packages,
+            _ => VersionSource::Unknown,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct InstalledVersion {
+    pub engine_version: Option<EngineVersion>,
+    /// `VersionName` of the descriptor, e.g. `1.2`.
+    pub version_name: Option<String>,
+    pub source: VersionSource,
+    pub is_plugin: bool,
+    /// Newest package modification time (RFC 3339).
+    pub files_date: Option<String>,
+}
+
+/// What the marketplace lists for a product.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MarketplaceListing {
+    pub engine_min: Option<EngineVersion>,
+    pub engine_max: Option<EngineVersion>,
+    /// Latest of the release and last-modified dates.
+    pub updated: Option<NaiveDate>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateFlag {
+    /// The listing changed after the copy on disk was downloaded.
+    UpdateAvailable,
+    /// The copy cannot be used with the engine in use.
+    EngineUnsupported,
+    /// The listing supports a newer engine than the copy on disk.
+    NewerEngineSupport,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AssetUpdateStatus {
+    pub asset_id: i64,
+    pub name: String,
+    pub file_path: String,
+    pub product_slug: String,
+    pub installed_engine: Option<EngineVersion>,
+    pub installed_version_name: Option<String>,
+    pub version_source: VersionSource,
+    pub files_date: Option<String>,
+    pub marketplace_engine_min: Option<EngineVersion>,
+    pub marketplace_engine_max: Option<EngineVersion>,
+    pub marketplace_updated: Option<NaiveDate>,
+    pub flags: Vec<UpdateFlag>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OutdatedReport {
+    /// Engine the copies were checked against; `None` skips the engine support check.
+    pub engine_in_use: Option<EngineVersion>,
+    /// Matched assets compared with their listing.
+    pub checked: usize,
+    /// Assets with at least one flag, by name.
+    pub assets: Vec<AssetUpdateStatus>,
+}
+
+#[derive(FromRow)]
+struct StatusRow {
+    id: i64,
+    name: String,
+    file_path: String,
+    matched_orbital_product_slug: String,
+    orbital_supported_versions: Option<String>,
+    orbital_release_date: Option<String>,
+    orbital_last_modified: Option<String>,
+    engine_version: Option<String>,
+    version_name: Option<String>,
+    source: String,
+    is_plugin: bool,
+    files_date: Option<String>,
+}
+
+/// Read the engine and release of the pack in `asset_dir`.
+pub fn detect_installed_version(asset_dir: &Path) -> InstalledVersion {
+    let packages = index::library_packages(asset_dir);
+    let files_date = packages
+        .iter()
+        .map(|package| package.modified_date.as_str())
+        .filter(|date| !date.is_empty())
+        .max()
+        .map(str::to_string);
+
+    let plugin = find_plugin_descriptor(asset_dir);
+    let descriptor = plugin
+        .clone()
+        .or_else(|| find_uproject(asset_dir))
+        .and_then(|path| read_descriptor(&path));
+    let field = |key: &str| descriptor.as_ref()?.get(key)?.as_str();
+    let version_name = field("VersionName").map(str::to_string);
+
+    let (engine_version, source) = match field("EngineVersion").or(field("EngineAssociation")).and_then(EngineVersion::find) {
+        Some(version) => (Some(version), VersionSource::Descriptor),
+        None => {
+            let newest = packages
+                .iter()
+                .take(SAMPLED_PACKAGES)
+                .filter_map(|package| read_package_header(&package.path).ok())
+                .filter_map(|header| EngineVersion::of_package(&header))
+                .max();
+            (newest, if newest.is_some() { VersionSource::Packages } else { VersionSource::Unknown })
+        }
+    };
+
+    InstalledVersion {
+        engine_version,
+        version_name,
+        source,
+        is_plugin: plugin.is_some(),
+        files_date,
+    }
+}
+
+fn read_descriptor(path: &Path) -> Option<serde_json::Value> {
+    let contents = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(contents.trim_start_matches('\u{feff}')).ok()
+}
+
+impl MarketplaceListing {
+    /// The listing from the stored Orbital columns.
+    pub fn from_columns(supported_versions: Option<&str>, release_date: Option<&str>, last_modified: Option<&str>) -> Self {
+        let versions: Vec<EngineVersion> = supported_versions
+            .and_then(|json| serde_json::from_str::<Vec<String>>(json).ok())
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|version| EngineVersion::find(version))
+            .collect();
+        Self {
+            engine_min: versions.iter().min().copied(),
+            engine_max: versions.iter().max().copied(),
+            updated: [release_date, last_modified].into_iter().flatten().filter_map(parse_date).max(),
+        }
+    }
+}
+
+/// A date from the API (RFC 3339), a plain `YYYY-MM-DD` or a page (`Jan 5, 2024`).
+fn parse_date(text: &str) -> Option<NaiveDate> {
+    let text = text.trim();
+    if let Ok(date_time) = DateTime::parse_from_rfc3339(text) {
+        return Some(date_time.date_naive());
+    }
+    text.get(..10)
+        .and_then(|prefix| NaiveDate::parse_from_str(prefix, "%Y-%m-%d").ok())
+        .or_else(|| NaiveDate::parse_from_str(text, "%b %d, %Y").ok())
+}
+
+/// Compare the copy on disk with its listing and the engine in use.
+pub fn assess(installed: &InstalledVersion, listing: &MarketplaceListing, engine_in_use: Option<EngineVersion>) -> Vec<UpdateFlag> {
+    let mut flags = Vec::new();
+
+    let downloaded = installed.files_date.as_deref().and_then(parse_date);
+    if let (Some(updated), Some(downloaded)) = (listing.updated, downloaded) {
+        if updated > downloaded {
+            flags.push(UpdateFlag::UpdateAvailable);
+        }
+    }
+
+    if let (Some(installed_engine), Some(in_use)) = (installed.engine_version, engine_in_use) {
+        // Packages do not load in older engines, and plugin binaries only in the release they
+        // were built for.
+        let plugin_mismatch = installed.is_plugin && installed.source == VersionSource::Descriptor && installed_engine != in_use;
+        if installed_engine > in_use || plugin_mismatch {
+            flags.push(UpdateFlag::EngineUnsupported);
+        }
+    }
+
+    if let (Some(installed_engine), Some(listed)) = (installed.engine_version, listing.engine_max) {
+        if listed > installed_engine {
+            flags.push(UpdateFlag::NewerEngineSupport);
+        }
+    }
+    flags
+}
+
+/// Detect the installed version of matched vault assets not checked since they last changed.
+/// Returns how many were detected.
+pub async fn refresh_installed_versions(db_manager: &DatabaseManager) -> Result<usize> {
+    let stale: Vec<(i64, String)> = sqlx::query_as(
+        r#"
+        SELECT a.id, a.file_path FROM assets a
+        JOIN scan_locations l ON l.id = a.scan_location_id
+        LEFT JOIN asset_versions v ON v.asset_id = a.id
+        WHERE l.location_kind = 'vault' AND a.matched_orbital_product_slug IS NOT NULL
+          AND (v.asset_id IS NULL OR v.detected_date < a.modified_date)
+        "#,
+    )
+    .fetch_all(&db_manager.pool)
+    .await?;
+
+    for (asset_id, file_path) in &stale {
+        let asset_dir = PathBuf::from(file_path);
+        let installed = tokio::task::spawn_blocking(move || detect_installed_version(&asset_dir)).await?;
+        sqlx::query(
+            "INSERT OR REPLACE INTO asset_versions
+             (asset_id, engine_version, version_name, source, is_plugin, files_date, detected_date)
+             VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(asset_id)
+        .bind(installed.engine_version.map(|version| version.to_string()))
+        .bind(&installed.version_name)
+        .bind(installed.source.as_str())
+        .bind(installed.is_plugin)
+        .bind(&installed.files_date)
+        .bind(db_manager.timestamp())
+        .execute(&db_manager.pool)
+        .await?;
+    }
+    Ok(stale.len())
+}
+
+/// The engine the user works with: the `engine_version` setting, or else the newest engine of
+/// the indexed projects.
+pub async fn engine_in_use(db_manager: &DatabaseManager, settings: &AppSettings) -> Result<Option<EngineVersion>> {
+    if let Some(version) = settings.engine_version.as_deref() {
+        return Ok(EngineVersion::find(version));
+    }
+
+    let project_paths: Vec<String> =
+        sqlx::query_scalar("SELECT path FROM scan_locations WHERE location_kind = 'project' AND is_active = 1")
+            .fetch_all(&db_manager.pool)
+            .await?;
+    let newest = tokio::task::spawn_blocking(move || {
+        project_paths
+            .iter()
+            .filter_map(|path| find_uproject(Path::new(path)))
+            .filter_map(|uproject| UnrealProject::open(&uproject).ok())
+            .filter_map(|project| project.engine_association.as_deref().and_then(EngineVersion::find))
+            .max()
+    })
+    .await?;
+    Ok(newest)
+}
+
+/// Every matched asset whose copy is behind its listing or does not suit `engine_in_use`.
+pub async fn find_outdated_assets(db_manager: &DatabaseManager, engine_in_use: Option<EngineVersion>) -> Result<OutdatedReport> {
+    refresh_installed_versions(db_manager).await?;
+
+    let rows: Vec<StatusRow> = sqlx::query_as(
+        r#"
+        SELECT a.id, a.name, a.file_path, a.matched_orbital_product_slug, a.orbital_supported_versions,
+               a.orbital_release_date, a.orbital_last_modified,
+               v.engine_version, v.version_name, v.source, v.is_plugin, v.files_date
+        FROM assets a JOIN asset_versions v ON v.asset_id = a.id
+        WHERE a.matched_orbital_product_slug IS NOT NULL
+        ORDER BY a.name COLLATE NOCASE
+        "#,
+    )
+    .fetch_all(&db_manager.pool)
+    .await?;
+
+    let checked = rows.len();
+    let assets = rows
+        .into_iter()
+        .filter_map(|row| {
+            let installed = InstalledVersion {
+                engine_version: row.engine_version.as_deref().and_then(EngineVersion::find),
+                version_name: row.version_name,
+                source: VersionSource::parse(&row.source),
+                is_plugin: row.is_plugin,
+                files_date: row.files_date,
+            };
+            let listing = MarketplaceListing::from_columns(
+                row.orbital_supported_versions.as_deref(),
+                row.orbital_release_date.as_deref(),
+                row.orbital_last_modified.as_deref(),
+            );
+            let flags = assess(&installed, &listing, engine_in_use);
+            if flags.is_empty() {
+                return None;
+            }
+            Some(AssetUpdateStatus {
+                asset_id: row.id,
+                name: row.name,
+                file_path: row.file_path,
+                product_slug: row.matched_orbital_product_slug,
+                installed_engine: installed.engine_version,
+                installed_version_name: installed.version_name,
+                version_source: installed.source,
+                files_date: installed.files_date,
+                marketplace_engine_min: listing.engine_min,
+                marketplace_engine_max: listing.engine_max,
+                marketplace_updated: listing.updated,
+                flags,
+            })
+        })
+        .collect();
+
+    Ok(OutdatedReport { engine_in_use, checked, assets })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_copies_behind_their_listing() {
+        let installed = InstalledVersion {
+            engine_version: Some(EngineVersion::new(5, 1)),
+            version_name: None,
+            source: VersionSource::Packages,
+            is_plugin: false,
+            files_date: Some("2024-02-01T10:00:00+00:00".to_string()),
+        };
+        let listing = MarketplaceListing::from_columns(Some(r#"["5.0", "5.3"]"#), Some("2023-06-01T00:00:00.000Z"), Some(""));
+        assert_eq!(listing.engine_max, Some(EngineVersion::new(5, 3)));
+        assert_eq!(assess(&installed, &listing, Some(EngineVersion::new(5, 3))), [UpdateFlag::NewerEngineSupport]);
+        assert_eq!(assess(&installed, &listing, Some(EngineVersion::new(5, 0))), [UpdateFlag::EngineUnsupported, UpdateFlag::NewerEngineSupport]);
+
+        let updated = MarketplaceListing::from_columns(Some(r#"["5.0", "5.1"]"#), None, Some("Mar 4, 2024"));
+        assert_eq!(assess(&installed, &updated, None), [UpdateFlag::UpdateAvailable]);
+
+        let plugin = InstalledVersion { source: VersionSource::Descriptor, is_plugin: true, ..installed };
+        assert_eq!(assess(&plugin, &MarketplaceListing::default(), Some(EngineVersion::new(5, 2))), [UpdateFlag::EngineUnsupported]);
+    }
+}
@@ -0,0 +1,129 @@
+//! Requests against the local HTTP API, served from an in-memory library on a free port.
+
+mod common;
+
+use app_lib::api;
+use app_lib::database::DatabaseManager;
+use common::{scanned_library, test_root, write_files};
+use reqwest::{redirect, Client, StatusCode};
+use serde_json::{json, Value};
+
+const TOKEN: &str = "test-token-0123456789";
+
+struct TestApi {
+    base_url: String,
+    client: Client,
+    db: DatabaseManager,
+}
+
+impl TestApi {
+    async fn get(&self, path: &str) -> reqwest::Response {
+        self.client.get(format!("{}{}", self.base_url, path)).bearer_auth(TOKEN).send().await.unwrap()
+    }
+
+    async fn get_json(&self, path: &str) -> Value {
+        let response = self.get(path).await;
+        assert_eq!(response.status(), StatusCode::OK, "GET {}", path);
+        response.json().await.unwrap()
+    }
+}
+
+/// A library with one scanned asset pack, "Castle Kit", in a collection, and the API serving it.
+async fn start_api(test_name: &str) -> TestApi {
+    let root = test_root("api", test_name);
+    write_files(&root, &[("Library/Castle Kit/Content/Tower.uasset", "tower"), ("Game/Game.uproject", "{}")]);
+    let db = scanned_library(&root.join("Library")).await;
+    sqlx::query(
+        "INSERT INTO collections (name, created_date, modified_date) VALUES ('Medieval', '2024-01-01', '2024-01-01');
+         INSERT INTO collection_assets (collection_id, asset_id, added_date) SELECT 1, id, '2024-01-01' FROM assets",
+    )
+    .execute(&db.pool)
+    .await
+    .unwrap();
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let app = api::router(db.clone(), TOKEN);
+    tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let client = Client::builder().redirect(redirect::Policy::none()).build().unwrap();
+    TestApi { base_url, client, db }
+}
+
+#[tokio::test]
+async fn requests_need_the_token() {
+    let api = start_api("auth").await;
+    let url = format!("{}/api/v1/collections", api.base_url);
+
+    let anonymous = api.client.get(&url).send().await.unwrap();
+    assert_eq!(anonymous.status(), StatusCode::UNAUTHORIZED);
+    let wrong = api.client.get(&url).bearer_auth("test-token-0123456788").send().await.unwrap();
+    assert_eq!(wrong.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(api.get("/api/v1/collections").await.status(), StatusCode::OK);
+
+    let spec = api.client.get(format!("{}/api/openapi.json", api.base_url)).send().await.unwrap();
+    assert_eq!(spec.status(), StatusCode::OK);
+    assert_eq!(spec.json::<Value>().await.unwrap()["openapi"], "3.0.3");
+}
+
+#[tokio::test]
+async fn assets_can_be_searched_and_fetched() {
+    let api = start_api("assets").await;
+
+    let results = api.get_json("/api/v1/assets/search?q=castle&limit=5").await;
+    assert_eq!(results.as_array().unwrap().len(), 1);
+    assert_eq!(results[0]["name"], "Castle Kit");
+    assert!(api.get_json("/api/v1/assets/search?q=forest").await.as_array().unwrap().is_empty());
+
+    let id = results[0]["id"].as_i64().unwrap();
+    let details = api.get_json(&format!("/api/v1/assets/{}", id)).await;
+    assert_eq!(details["name"], "Castle Kit");
+    assert!(details["filePath"].as_str().unwrap().ends_with("Castle Kit"));
+    assert_eq!(api.get("/api/v1/assets/999").await.status(), StatusCode::NOT_FOUND);
+
+    let thumbnail = format!("/api/v1/assets/{}/thumbnail", id);
+    assert_eq!(api.get(&thumbnail).await.status(), StatusCode::NOT_FOUND);
+    sqlx::query("UPDATE assets SET orbital_thumbnail_url = 'https://example.com/castle.png'")
+        .execute(&api.db.pool)
+        .await
+        .unwrap();
+    let redirect = api.get(&thumbnail).await;
+    assert_eq!(redirect.status(), StatusCode::TEMPORARY_REDIRECT);
+    assert_eq!(redirect.headers()["location"], "https://example.com/castle.png");
+}
+
+#[tokio::test]
+async fn collections_list_their_assets() {
+    let api = start_api("collections").await;
+
+    let collections = api.get_json("/api/v1/collections").await;
+    assert_eq!(collections[0]["name"], "Medieval");
+    assert_eq!(collections[0]["asset_count"], 1);
+
+    let assets = api.get_json("/api/v1/collections/1/assets?sort=name_desc").await;
+    assert_eq!(assets[0]["name"], "Castle Kit");
+    assert!(api.get_json("/api/v1/collections/2/assets").await.as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn import_is_planned_and_performed() {
+    let api = start_api("import").await;
+    let asset = api.get_json("/api/v1/assets/search?q=castle").await;
+    let import_url = format!("{}/api/v1/assets/{}/import", api.base_url, asset[0]["id"]);
+    let details = api.get_json(&format!("/api/v1/assets/{}", asset[0]["id"])).await;
+    let library = std::path::Path::new(details["filePath"].as_str().unwrap()).parent().unwrap().to_path_buf();
+    let uproject = library.parent().unwrap().join("Game").join("Game.uproject");
+
+    let post = |body: Value| api.client.post(&import_url).bearer_auth(TOKEN).json(&body).send();
+    let dry_run: Value = post(json!({ "uproject_path": uproject, "dry_run": true })).await.unwrap().json().await.unwrap();
+    assert_eq!(dry_run["file_count"], 1);
+    assert_eq!(dry_run["committed"], false);
+    assert!(!uproject.with_file_name("Content").join("Tower.uasset").exists());
+
+    let report: Value = post(json!({ "uproject_path": uproject })).await.unwrap().json().await.unwrap();
+    assert_eq!(report["committed"], true);
+    assert!(uproject.with_file_name("Content").join("Tower.uasset").exists());
+
+    let invalid = post(json!({ "uproject_path": library })).await.unwrap();
+    assert_eq!(invalid.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
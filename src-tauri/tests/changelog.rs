@@ -0,0 +1,169 @@
+//! A laptop and a workstation library over their own copies of the same vault, kept in step
+//! through their change logs.
+
+mod common;
+
+use std::path::{Path, PathBuf};
+
+use app_lib::changelog;
+use app_lib::database::DatabaseManager;
+use app_lib::models::Asset;
+use common::{asset_folders, scan_vault, test_root};
+
+struct Library {
+    db: DatabaseManager,
+    path: PathBuf,
+}
+
+impl Library {
+    /// A scanned library in `<root>/<name>.db` over its own copy of a vault with `folders`,
+    /// so the other side can sync with the file directly.
+    async fn create(root: &Path, name: &str, folders: &[&str]) -> Self {
+        let vault = root.join(format!("{}-vault", name));
+        asset_folders(&vault, folders);
+
+        let path = root.join(format!("{}.db", name));
+        let db = DatabaseManager::open_file(&path).await.unwrap();
+        scan_vault(&db, &vault).await;
+        Self { db, path }
+    }
+
+    async fn run(&self, sql: &str) {
+        sqlx::raw_sql(sql).execute(&self.db.pool).await.unwrap();
+    }
+
+    async fn asset(&self, name: &str) -> Asset {
+        common::asset(&self.db, name).await
+    }
+
+    /// Collection names with their members, sorted.
+    async fn collections(&self) -> Vec<(String, String)> {
+        sqlx::query_as(
+            "SELECT c.name, COALESCE(a.name, '') FROM collections c
+             LEFT JOIN collection_assets ca ON ca.collection_id = c.id
+             LEFT JOIN assets a ON a.id = ca.asset_id
+             ORDER BY 1, 2",
+        )
+        .fetch_all(&self.db.pool)
+        .await
+        .unwrap()
+    }
+}
+
+#[tokio::test]
+async fn edits_reach_the_other_database_both_ways() {
+    let root = test_root("changelog", "database");
+    let laptop = Library::create(&root, "laptop", &["Castle Kit", "Forest Pack"]).await;
+    let workstation = Library::create(&root, "workstation", &["Castle Kit", "Forest Pack"]).await;
+
+    laptop
+        .run(
+            "UPDATE assets SET tags = 'medieval', is_favorite = 1 WHERE name = 'Castle Kit';
+             INSERT INTO collections (name, description, created_date, modified_date)
+             VALUES ('Level 3', 'Blockout', '2024-05-01', '2024-05-01');
+             INSERT INTO collection_assets (collection_id, asset_id, added_date)
+             SELECT 1, id, '2024-05-01' FROM assets WHERE name = 'Castle Kit';",
+        )
+        .await;
+    workstation.run("UPDATE assets SET notes = 'Needs UE 5.3' WHERE name = 'Forest Pack'").await;
+
+    let report = changelog::sync_changes(&laptop.db, &workstation.path).await.unwrap();
+    assert_eq!(report.received, 1);
+    assert_eq!(report.sent, 5);
+    assert_eq!(report.unmatched, 0);
+
+    for library in [&laptop, &workstation] {
+        let castle = library.asset("Castle Kit").await;
+        assert_eq!(castle.tags.as_deref(), Some("medieval"));
+        assert!(castle.is_favorite);
+        assert_eq!(library.asset("Forest Pack").await.notes.as_deref(), Some("Needs UE 5.3"));
+        assert_eq!(library.collections().await, [("Level 3".to_string(), "Castle Kit".to_string())]);
+    }
+
+    // Renaming and emptying the collection on the workstation is carried back as well.
+    workstation
+        .run(
+            "UPDATE collections SET name = 'Level 4';
+             DELETE FROM collection_assets;",
+        )
+        .await;
+    let report = changelog::sync_changes(&laptop.db, &workstation.path).await.unwrap();
+    assert_eq!((report.sent, report.unmatched), (0, 0));
+    assert_eq!(laptop.collections().await, [("Level 4".to_string(), String::new())]);
+
+    let report = changelog::sync_changes(&laptop.db, &workstation.path).await.unwrap();
+    assert_eq!((report.received, report.sent), (0, 0));
+}
+
+#[tokio::test]
+async fn concurrent_edits_converge_through_a_folder() {
+    let root = test_root("changelog", "folder");
+    let shared = root.join("Sync");
+    std::fs::create_dir_all(&shared).unwrap();
+    let laptop = Library::create(&root, "laptop", &["Desert Rocks"]).await;
+    let workstation = Library::create(&root, "workstation", &["Desert Rocks", "Snow Props"]).await;
+
+    laptop.run("UPDATE assets SET notes = 'From the laptop'").await;
+    workstation.run("UPDATE assets SET description = 'Rock set' WHERE name = 'Desert Rocks'").await;
+    workstation.run("UPDATE assets SET notes = 'From the workstation'").await;
+
+    changelog::sync_changes(&laptop.db, &shared).await.unwrap();
+    let report = changelog::sync_changes(&workstation.db, &shared).await.unwrap();
+    assert_eq!(report.peers.len(), 1);
+    // The workstation's notes were written at a later clock value, so they win on both sides.
+    assert_eq!(report.superseded, 1);
+    let report = changelog::sync_changes(&laptop.db, &shared).await.unwrap();
+    // The laptop has no "Snow Props" to apply the workstation's notes to.
+    assert_eq!(report.unmatched, 1);
+
+    for library in [&laptop, &workstation] {
+        let rocks = library.asset("Desert Rocks").await;
+        assert_eq!(rocks.notes.as_deref(), Some("From the workstation"));
+        assert_eq!(rocks.description.as_deref(), Some("Rock set"));
+    }
+
+    // A later edit on the laptop wins over the workstation's.
+    laptop.run("UPDATE assets SET notes = 'Checked on the laptop'").await;
+    changelog::sync_changes(&laptop.db, &shared).await.unwrap();
+    changelog::sync_changes(&workstation.db, &shared).await.unwrap();
+    assert_eq!(workstation.asset("Desert Rocks").await.notes.as_deref(), Some("Checked on the laptop"));
+    assert_eq!(workstation.asset("Snow Props").await.notes.as_deref(), Some("From the workstation"));
+}
+
+#[tokio::test]
+async fn a_copied_database_is_refused() {
+    let root = test_root("changelog", "copy");
+    let laptop = Library::create(&root, "laptop", &["Castle Kit"]).await;
+    let copy = root.join("copy.db");
+    std::fs::copy(&laptop.path, &copy).unwrap();
+
+    let error = changelog::sync_changes(&laptop.db, &copy).await.unwrap_err();
+    assert!(error.to_string().contains("replica id"), "{}", error);
+    assert!(changelog::sync_changes(&laptop.db, &root.join("missing.db")).await.is_err());
+}
+
+#[tokio::test]
+async fn curated_values_of_inserted_assets_are_synced() {
+    let root = test_root("changelog", "insert");
+    let laptop = Library::create(&root, "laptop", &["Castle Kit"]).await;
+    let workstation = Library::create(&root, "workstation", &["Castle Kit", "Snow Props"]).await;
+
+    // A row that arrives already curated, as one added from the team library does.
+    laptop
+        .run(
+            "INSERT INTO assets (name, file_path, relative_path, asset_type, file_size, created_date,
+                modified_date, scan_location_id, tags, notes, is_favorite)
+             SELECT 'Snow Props', path || '/Snow Props', 'Snow Props', 'Unknown', 0, '2024-05-01',
+                '2024-05-01', id, 'winter', 'For the ski level', 1
+             FROM scan_locations;",
+        )
+        .await;
+
+    let report = changelog::sync_changes(&laptop.db, &workstation.path).await.unwrap();
+    assert_eq!((report.sent, report.unmatched), (3, 0));
+
+    let snow = workstation.asset("Snow Props").await;
+    assert_eq!(snow.tags.as_deref(), Some("winter"));
+    assert_eq!(snow.notes.as_deref(), Some("For the ski level"));
+    assert!(snow.is_favorite);
+}
@@ -0,0 +1,82 @@
+//! Fixtures shared by the integration tests: vaults written to a temporary folder, and
+//! libraries scanned over them without a marketplace.
+
+// Each test file compiles this module on its own and uses only some of it.
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+
+use app_lib::database::DatabaseManager;
+use app_lib::models::{Asset, ScanLocation, LOCATION_KIND_VAULT};
+use app_lib::orbital::InMemoryMarketplace;
+use app_lib::scanner::AssetScanner;
+
+/// An empty folder for one test of `suite`, cleared of whatever an earlier run left there.
+pub fn test_root(suite: &str, test_name: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("omnidex-{}-{}-{}", suite, test_name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(&root).unwrap();
+    root
+}
+
+/// One asset folder per name in `vault`, each with a package holding the folder's name.
+pub fn asset_folders(vault: &Path, folders: &[&str]) {
+    for folder in folders {
+        std::fs::create_dir_all(vault.join(folder)).unwrap();
+        std::fs::write(vault.join(folder).join("Content.uasset"), folder.as_bytes()).unwrap();
+    }
+}
+
+/// Files below `dir`, given by their relative path and contents.
+pub fn write_files(dir: &Path, files: &[(&str, &str)]) {
+    for (file, contents) in files {
+        let path = dir.join(file);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+}
+
+/// Add `vault` to the library as a scan location named "Vault".
+pub async fn add_vault(db: &DatabaseManager, vault: &Path) -> ScanLocation {
+    db.create_scan_location("Vault", &vault.to_string_lossy(), false, None, None, LOCATION_KIND_VAULT)
+        .await
+        .unwrap()
+}
+
+/// Scan `location` with an empty marketplace.
+pub async fn scan(db: &DatabaseManager, location: &ScanLocation) {
+    AssetScanner::new(db.clone(), InMemoryMarketplace::default(), Default::default())
+        .scan_location(location)
+        .await
+        .unwrap();
+}
+
+/// Add `vault` to the library and scan it.
+pub async fn scan_vault(db: &DatabaseManager, vault: &Path) -> ScanLocation {
+    let location = add_vault(db, vault).await;
+    scan(db, &location).await;
+    location
+}
+
+/// A new in-memory library with `vault` scanned into it.
+pub async fn scanned_library(vault: &Path) -> DatabaseManager {
+    let db = DatabaseManager::open_in_memory().await.unwrap();
+    scan_vault(&db, vault).await;
+    db
+}
+
+pub async fn asset(db: &DatabaseManager, name: &str) -> Asset {
+    sqlx::query_as("SELECT * FROM assets WHERE name = ?")
+        .bind(name)
+        .fetch_one(&db.pool)
+        .await
+        .unwrap()
+}
+
+pub async fn asset_id(db: &DatabaseManager, name: &str) -> i64 {
+    sqlx::query_scalar("SELECT id FROM assets WHERE name = ?")
+        .bind(name)
+        .fetch_one(&db.pool)
+        .await
+        .unwrap()
+}
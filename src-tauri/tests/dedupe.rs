@@ -0,0 +1,306 @@
+//! Duplicate groups over a scanned vault, and resolving them.
+
+mod common;
+
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use app_lib::database::DatabaseManager;
+use app_lib::dedupe::{self, DuplicateKind, ResolutionAction, ResolutionRequest};
+use common::{asset_id, scanned_library, test_root, write_files};
+
+/// A scanned library over a vault where each folder holds the given files, all last modified
+/// at the same time, as copies made with their timestamps kept would be.
+async fn scanned_vault(root: &Path, folders: &[(&str, &[(&str, &str)])]) -> DatabaseManager {
+    let vault = root.join("vault");
+    let exported = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    for (folder, files) in folders {
+        write_files(&vault.join(folder), files);
+        for (file, _) in files.iter() {
+            let file = std::fs::File::options().write(true).open(vault.join(folder).join(file)).unwrap();
+            file.set_modified(exported).unwrap();
+        }
+    }
+    scanned_library(&vault).await
+}
+
+const CASTLE: &[(&str, &str)] = &[("Content/Wall.uasset", "wall"), ("Content/Tower.uasset", "tower")];
+
+#[tokio::test]
+async fn finds_identical_superset_and_versioned_assets() {
+    let root = test_root("dedupe", "find");
+    let db = scanned_vault(
+        &root,
+        &[
+            ("Castle Kit", CASTLE),
+            ("Castle Kit (copy)", CASTLE),
+            ("Rocks v1.0", &[("Content/Rock.uasset", "rock")]),
+            ("Rocks v2.0", &[("Content/Rock.uasset", "rock 2")]),
+            ("Forest Pack", &[("Content/Tree.uasset", "tree")]),
+        ],
+    )
+    .await;
+
+    sqlx::raw_sql(
+        "UPDATE assets SET matched_orbital_product_slug = 'rocks' WHERE name LIKE 'Rocks%';
+         INSERT INTO asset_packages (asset_id, relative_path, package_name, file_size)
+         SELECT id, 'Content/Tree.uasset', 'Tree', 4 FROM assets WHERE name IN ('Forest Pack', 'Rocks v2.0');
+         INSERT INTO asset_packages (asset_id, relative_path, package_name, file_size)
+         SELECT id, 'Content/Rock.uasset', 'Rock', 6 FROM assets WHERE name = 'Rocks v2.0';",
+    )
+    .execute(&db.pool)
+    .await
+    .unwrap();
+
+    let groups = dedupe::find_duplicate_groups(&db).await.unwrap();
+    assert_eq!(groups.len(), 3, "{:?}", groups);
+
+    let identical = groups.iter().find(|g| g.kind == DuplicateKind::Identical).unwrap();
+    assert_eq!(identical.keep_asset_id, asset_id(&db, "Castle Kit").await);
+    assert_eq!(identical.members.len(), 2);
+    assert_eq!(identical.reclaimable_bytes, 9);
+
+    let superset = groups.iter().find(|g| g.kind == DuplicateKind::Superset).unwrap();
+    assert_eq!(superset.keep_asset_id, asset_id(&db, "Rocks v2.0").await);
+    assert_eq!(superset.members[1].name, "Forest Pack");
+
+    let versions = groups.iter().find(|g| g.kind == DuplicateKind::Versions).unwrap();
+    assert_eq!(versions.key, "rocks");
+    let mut found: Vec<_> = versions.members.iter().filter_map(|m| m.version.clone()).collect();
+    found.sort();
+    assert_eq!(found, ["1.0", "2.0"]);
+}
+
+#[tokio::test]
+async fn removing_a_copy_hands_its_edits_to_the_kept_asset() {
+    let root = test_root("dedupe", "remove");
+    let db = scanned_vault(&root, &[("Castle Kit", CASTLE), ("Castle Kit (copy)", CASTLE)]).await;
+    let keep = asset_id(&db, "Castle Kit").await;
+    let copy = asset_id(&db, "Castle Kit (copy)").await;
+    sqlx::raw_sql(&format!(
+        "UPDATE assets SET tags = 'medieval' WHERE id = {keep};
+         UPDATE assets SET tags = 'medieval,modular', notes = 'Used in Level 3', is_favorite = 1 WHERE id = {copy};
+         INSERT INTO collections (name, created_date, modified_date) VALUES ('Level 3', '2024-05-01', '2024-05-01');
+         INSERT INTO collection_assets (collection_id, asset_id, added_date) VALUES (1, {copy}, '2024-05-01');
+         INSERT INTO asset_licenses (asset_id, asset_name, asset_path, owner, license_type, created_date, modified_date)
+         VALUES ({copy}, 'Castle Kit (copy)', '', 'Studio', 'professional', '2024-05-01', '2024-05-01');
+         INSERT INTO asset_project_usage (asset_id, project_path, project_name, import_mode, imported_date)
+         VALUES ({copy}, '/projects/Keep/Keep.uproject', 'Keep', 'copy', '2024-05-01');
+         DELETE FROM asset_categories WHERE asset_id = {copy};
+         INSERT INTO asset_categories (asset_id, category, confidence, rule, is_primary, assigned_date)
+         VALUES ({copy}, 'Environments', 1.0, 'manual', 1, '2024-05-01');"
+    ))
+    .execute(&db.pool)
+    .await
+    .unwrap();
+
+    let mut request = ResolutionRequest {
+        keep_asset_id: keep,
+        asset_ids: vec![copy],
+        action: ResolutionAction::Remove,
+        archive_dir: None,
+        dry_run: true,
+    };
+    let report = dedupe::resolve_duplicates(&db, &request).await.unwrap();
+    assert_eq!((report.resolved.len(), report.bytes_reclaimed), (1, 9));
+    assert!(root.join("vault/Castle Kit (copy)").exists());
+    assert!(dedupe::get_resolutions(&db).await.unwrap().is_empty());
+
+    request.dry_run = false;
+    let report = dedupe::resolve_duplicates(&db, &request).await.unwrap();
+    assert!(report.failed.is_empty(), "{:?}", report.failed);
+    assert!(!root.join("vault/Castle Kit (copy)").exists());
+
+    let kept = db.get_asset_by_id(keep).await.unwrap();
+    assert_eq!(kept.tags.as_deref(), Some("medieval,modular"));
+    assert_eq!(kept.notes.as_deref(), Some("Used in Level 3"));
+    assert!(kept.is_favorite);
+    let member: i64 = sqlx::query_scalar("SELECT asset_id FROM collection_assets").fetch_one(&db.pool).await.unwrap();
+    assert_eq!(member, keep);
+    let licensed: (i64, String) =
+        sqlx::query_as("SELECT asset_id, asset_name FROM asset_licenses").fetch_one(&db.pool).await.unwrap();
+    assert_eq!(licensed, (keep, "Castle Kit".to_string()));
+    let used_in: i64 = sqlx::query_scalar("SELECT asset_id FROM asset_project_usage").fetch_one(&db.pool).await.unwrap();
+    assert_eq!(used_in, keep);
+    let (category, rule): (String, String) =
+        sqlx::query_as("SELECT category, rule FROM asset_categories WHERE asset_id = ? AND is_primary = 1")
+            .bind(keep)
+            .fetch_one(&db.pool)
+            .await
+            .unwrap();
+    assert_eq!((category.as_str(), rule.as_str()), ("Environments", "manual"));
+
+    let resolutions = dedupe::get_resolutions(&db).await.unwrap();
+    assert_eq!(resolutions.len(), 1);
+    assert_eq!((resolutions[0].action.as_str(), resolutions[0].kept_asset_id), ("remove", Some(keep)));
+    assert!(dedupe::find_duplicate_groups(&db).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn archiving_moves_the_copy_aside() {
+    let root = test_root("dedupe", "archive");
+    let db = scanned_vault(&root, &[("Castle Kit", CASTLE), ("Castle Kit (copy)", CASTLE)]).await;
+    let archive = root.join("Archive");
+    let request = ResolutionRequest {
+        keep_asset_id: asset_id(&db, "Castle Kit").await,
+        asset_ids: vec![asset_id(&db, "Castle Kit (copy)").await],
+        action: ResolutionAction::Archive,
+        archive_dir: Some(archive.clone()),
+        dry_run: false,
+    };
+    let report = dedupe::resolve_duplicates(&db, &request).await.unwrap();
+    assert!(report.failed.is_empty(), "{:?}", report.failed);
+    assert!(archive.join("Castle Kit (copy)/Content/Wall.uasset").exists());
+    assert!(!root.join("vault/Castle Kit (copy)").exists());
+    assert_eq!(
+        report.resolved[0].archive_path.as_deref(),
+        Some(&*archive.join("Castle Kit (copy)").to_string_lossy())
+    );
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn hard_linking_keeps_both_paths_but_only_identical_files() {
+    use std::os::unix::fs::MetadataExt;
+
+    let root = test_root("dedupe", "link");
+    let db = scanned_vault(
+        &root,
+        &[
+            ("Castle Kit", CASTLE),
+            ("Castle Kit (copy)", CASTLE),
+            ("Castle Kit (edited)", &[("Content/Wall.uasset", "WALL"), ("Content/Tower.uasset", "tower")]),
+        ],
+    )
+    .await;
+    let keep = asset_id(&db, "Castle Kit").await;
+    let copy = asset_id(&db, "Castle Kit (copy)").await;
+    let edited = asset_id(&db, "Castle Kit (edited)").await;
+
+    let request = ResolutionRequest {
+        keep_asset_id: keep,
+        asset_ids: vec![copy, edited],
+        action: ResolutionAction::HardLink,
+        archive_dir: None,
+        dry_run: false,
+    };
+    let report = dedupe::resolve_duplicates(&db, &request).await.unwrap();
+    assert_eq!(report.resolved.len(), 1);
+    assert_eq!(report.failed.len(), 1);
+    assert_eq!(report.failed[0].asset_id, edited);
+
+    let inode = |folder: &str| std::fs::metadata(root.join("vault").join(folder).join("Content/Wall.uasset")).unwrap().ino();
+    assert_eq!(inode("Castle Kit"), inode("Castle Kit (copy)"));
+    assert_ne!(inode("Castle Kit"), inode("Castle Kit (edited)"));
+    assert_eq!(std::fs::read_to_string(root.join("vault/Castle Kit (edited)/Content/Wall.uasset")).unwrap(), "WALL");
+    // Linked copies stay in the library.
+    assert!(db.get_asset_by_id(copy).await.is_ok());
+}
+
+#[tokio::test]
+async fn removal_needs_a_verified_copy_of_the_kept_asset() {
+    let root = test_root("dedupe", "verify");
+    let db = scanned_vault(
+        &root,
+        &[
+            ("Castle Kit", CASTLE),
+            // Same files at the same sizes, so grouped as identical, but one was edited.
+            ("Castle Kit (edited)", &[("Content/Wall.uasset", "WALL"), ("Content/Tower.uasset", "tower")]),
+            ("Forest Pack", &[("Content/Tree.uasset", "tree")]),
+        ],
+    )
+    .await;
+    let keep = asset_id(&db, "Castle Kit").await;
+    let edited = asset_id(&db, "Castle Kit (edited)").await;
+    let unrelated = asset_id(&db, "Forest Pack").await;
+
+    for action in [ResolutionAction::Remove, ResolutionAction::Archive] {
+        let request = ResolutionRequest {
+            keep_asset_id: keep,
+            asset_ids: vec![edited, unrelated],
+            action,
+            archive_dir: Some(root.join("Archive")),
+            dry_run: false,
+        };
+        let report = dedupe::resolve_duplicates(&db, &request).await.unwrap();
+        assert!(report.resolved.is_empty(), "{:?}", report.resolved);
+        let failed: Vec<i64> = report.failed.iter().map(|f| f.asset_id).collect();
+        assert_eq!(failed, [edited, unrelated]);
+    }
+    assert!(root.join("vault/Castle Kit (edited)/Content/Wall.uasset").exists());
+    assert!(root.join("vault/Forest Pack").exists());
+    assert!(db.get_asset_by_id(unrelated).await.is_ok());
+}
+
+#[tokio::test]
+async fn removing_a_smaller_pack_needs_all_of_its_files_in_the_larger_one() {
+    let root = test_root("dedupe", "superset");
+    let db = scanned_vault(
+        &root,
+        &[
+            (
+                "Environment Set",
+                &[("Content/Tree.uasset", "tree"), ("Content/Rock.uasset", "rock"), ("Content/Sky.uasset", "sky")],
+            ),
+            // Its only package is in the larger pack, but its readme is not.
+            ("Forest Pack", &[("Content/Tree.uasset", "tree"), ("README.txt", "forest notes")]),
+            ("Rock Pack", &[("Content/Rock.uasset", "rock")]),
+        ],
+    )
+    .await;
+    sqlx::raw_sql(
+        "INSERT INTO asset_packages (asset_id, relative_path, package_name, file_size)
+         SELECT id, 'Content/Tree.uasset', 'Tree', 4 FROM assets WHERE name IN ('Environment Set', 'Forest Pack');
+         INSERT INTO asset_packages (asset_id, relative_path, package_name, file_size)
+         SELECT id, 'Content/Rock.uasset', 'Rock', 4 FROM assets WHERE name IN ('Environment Set', 'Rock Pack');
+         INSERT INTO asset_packages (asset_id, relative_path, package_name, file_size)
+         SELECT id, 'Content/Sky.uasset', 'Sky', 3 FROM assets WHERE name = 'Environment Set';",
+    )
+    .execute(&db.pool)
+    .await
+    .unwrap();
+    let forest = asset_id(&db, "Forest Pack").await;
+    let rocks = asset_id(&db, "Rock Pack").await;
+
+    let request = ResolutionRequest {
+        keep_asset_id: asset_id(&db, "Environment Set").await,
+        asset_ids: vec![forest, rocks],
+        action: ResolutionAction::Remove,
+        archive_dir: None,
+        dry_run: false,
+    };
+    let report = dedupe::resolve_duplicates(&db, &request).await.unwrap();
+    let failed: Vec<i64> = report.failed.iter().map(|f| f.asset_id).collect();
+    assert_eq!(failed, [forest]);
+    assert!(report.failed[0].error.contains("README.txt"), "{}", report.failed[0].error);
+    assert_eq!(report.resolved.len(), 1);
+    assert!(root.join("vault/Forest Pack/README.txt").exists());
+    assert!(!root.join("vault/Rock Pack").exists());
+}
+
+#[tokio::test]
+async fn other_versions_are_never_removed_or_linked() {
+    let root = test_root("dedupe", "versions");
+    let db = scanned_vault(
+        &root,
+        &[("Rocks v1.0", &[("Content/Rock.uasset", "rock")]), ("Rocks v2.0", &[("Content/Rock.uasset", "rock 2")])],
+    )
+    .await;
+    sqlx::query("UPDATE assets SET matched_orbital_product_slug = 'rocks'").execute(&db.pool).await.unwrap();
+    let old = asset_id(&db, "Rocks v1.0").await;
+
+    for action in [ResolutionAction::Remove, ResolutionAction::HardLink] {
+        let request = ResolutionRequest {
+            keep_asset_id: asset_id(&db, "Rocks v2.0").await,
+            asset_ids: vec![old],
+            action,
+            archive_dir: None,
+            dry_run: false,
+        };
+        let report = dedupe::resolve_duplicates(&db, &request).await.unwrap();
+        assert!(report.resolved.is_empty(), "{:?}", report.resolved);
+        assert_eq!(report.failed.len(), 1);
+    }
+    assert_eq!(std::fs::read_to_string(root.join("vault/Rocks v1.0/Content/Rock.uasset")).unwrap(), "rock");
+    assert!(db.get_asset_by_id(old).await.is_ok());
+}
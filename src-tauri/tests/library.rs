@@ -0,0 +1,347 @@
+//! End-to-end tests of the library core, run against an in-memory database, an in-memory
+//! marketplace and a manual clock instead of the desktop app.
+
+mod common;
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use app_lib::clock::ManualClock;
+use app_lib::database::{AssetStore, DatabaseManager};
+use app_lib::models::{Asset, ScanErrorKind, ScanLocation, LOCATION_KIND_VAULT};
+use app_lib::orbital::{self, InMemoryMarketplace, OrbitalAsset};
+use app_lib::scanner::progress::ProgressLog;
+use app_lib::scanner::AssetScanner;
+use app_lib::transfer::{self, ExportFormat, MergeStrategy};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use common::{add_vault, asset_folders, scan, test_root};
+
+fn start_time() -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339("2024-05-01T12:00:00Z").unwrap().with_timezone(&Utc)
+}
+
+async fn open_library(clock: &Arc<ManualClock>) -> DatabaseManager {
+    DatabaseManager::open_in_memory().await.unwrap().with_clock(clock.clone())
+}
+
+/// A fresh folder holding one asset folder per name, each with a package in it.
+fn asset_library(test_name: &str, folders: &[&str]) -> PathBuf {
+    let root = test_root("library", test_name);
+    asset_folders(&root, folders);
+    root
+}
+
+fn product(slug: &str, title: &str) -> OrbitalAsset {
+    OrbitalAsset {
+        id: Some(slug.to_string()),
+        product_slug: Some(slug.to_string()),
+        title: Some(title.to_string()),
+        source_url: Some(format!("https://orbital-market.com/product/{}", slug)),
+        ..Default::default()
+    }
+}
+
+async fn location_assets(db: &DatabaseManager, location: &ScanLocation) -> Vec<Asset> {
+    sqlx::query_as("SELECT * FROM assets WHERE scan_location_id = ? ORDER BY name")
+        .bind(location.id)
+        .fetch_all(&db.pool)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn scan_indexes_folders_and_matches_them_against_the_marketplace() {
+    let clock = Arc::new(ManualClock::new(start_time()));
+    let db = open_library(&clock).await;
+    let root = asset_library("scan", &["Castle Kit", "Forest Pack"]);
+    let location = add_vault(&db, &root).await;
+    let progress = Arc::new(ProgressLog::default());
+    let marketplace = InMemoryMarketplace::new(vec![product("forest-pack", "Forest Pack")]);
+
+    AssetScanner::new(db.clone(), marketplace, Default::default())
+        .with_progress(progress.clone())
+        .scan_location(&location)
+        .await
+        .unwrap();
+
+    let assets = location_assets(&db, &location).await;
+    let names: Vec<&str> = assets.iter().map(|a| a.name.as_str()).collect();
+    assert_eq!(names, ["Castle Kit", "Forest Pack"]);
+    assert_eq!(assets[0].matched_orbital_product_slug, None);
+    assert_eq!(assets[1].matched_orbital_product_slug.as_deref(), Some("forest-pack"));
+
+    let statuses = progress.statuses();
+    assert_eq!(statuses.first().map(String::as_str), Some("Initializing Scan"));
+    assert_eq!(statuses.last().map(String::as_str), Some("Completed"));
+    assert!(progress.progress().last().unwrap().completed_successfully);
+    assert!(progress.errors().is_empty());
+
+    let history = db.get_scan_history(location.id, 10).await.unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].outcome.as_deref(), Some("completed"));
+    assert_eq!(history[0].started_date, start_time().to_rfc3339());
+    assert_eq!(history[0].assets_found, 2);
+}
+
+#[tokio::test]
+async fn paused_scan_resumes_as_the_same_job() {
+    let clock = Arc::new(ManualClock::new(start_time()));
+    let db = open_library(&clock).await;
+    let root = asset_library("resume", &["Alpha", "Beta", "Gamma"]);
+    let location = add_vault(&db, &root).await;
+    let pause = Arc::new(AtomicBool::new(true));
+
+    let scanner = AssetScanner::new(db.clone(), InMemoryMarketplace::default(), Default::default())
+        .with_pause_token(pause.clone());
+    assert!(scanner.scan_location(&location).await.is_err());
+    let paused = db.get_resumable_scan_job(location.id.unwrap()).await.unwrap().unwrap();
+    assert_eq!(paused.status, "paused");
+
+    clock.advance(Duration::minutes(5));
+    pause.store(false, Ordering::Relaxed);
+    scanner.with_resume(true).scan_location(&location).await.unwrap();
+
+    assert_eq!(location_assets(&db, &location).await.len(), 3);
+    assert!(db.get_resumable_scan_jobs().await.unwrap().is_empty());
+    let history = db.get_scan_history(location.id, 10).await.unwrap();
+    let outcomes: Vec<_> = history.iter().map(|run| run.outcome.as_deref().unwrap()).collect();
+    assert_eq!(outcomes, ["completed", "paused"]);
+    assert_eq!(history[0].job_id, paused.id);
+    assert_eq!(history[0].started_date, (start_time() + Duration::minutes(5)).to_rfc3339());
+}
+
+#[tokio::test]
+async fn marketplace_failures_are_recorded_and_retried() {
+    let clock = Arc::new(ManualClock::new(start_time()));
+    let db = open_library(&clock).await;
+    let root = asset_library("retry", &["Desert Rocks"]);
+    let location = add_vault(&db, &root).await;
+    let progress = Arc::new(ProgressLog::default());
+    let marketplace = Arc::new(InMemoryMarketplace::new(vec![product("desert-rocks", "Desert Rocks")]));
+    marketplace.set_offline(true);
+
+    let scanner = AssetScanner::new(db.clone(), marketplace.clone(), Default::default())
+        .with_progress(progress.clone());
+    scanner.scan_location(&location).await.unwrap();
+
+    let errors = progress.errors();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].kind, ScanErrorKind::Marketplace);
+    assert!(!progress.progress().last().unwrap().completed_successfully);
+
+    marketplace.set_offline(false);
+    let report = scanner.retry_failed_paths(&location).await.unwrap();
+    assert_eq!((report.retried, report.resolved), (1, 1));
+    assert!(db.get_unresolved_scan_errors(location.id.unwrap()).await.unwrap().is_empty());
+    let assets = location_assets(&db, &location).await;
+    assert_eq!(assets[0].matched_orbital_product_slug.as_deref(), Some("desert-rocks"));
+}
+
+#[tokio::test]
+async fn a_folder_that_cannot_be_saved_does_not_fail_its_batch() {
+    let clock = Arc::new(ManualClock::new(start_time()));
+    let db = open_library(&clock).await;
+    let root = asset_library("save", &["Castle Kit", "Broken Pack", "Forest Pack"]);
+    let location = add_vault(&db, &root).await;
+    sqlx::query(
+        "CREATE TEMP TRIGGER reject_broken BEFORE INSERT ON assets WHEN NEW.name = 'Broken Pack'
+         BEGIN SELECT RAISE(ABORT, 'rejected'); END",
+    )
+    .execute(&db.pool)
+    .await
+    .unwrap();
+    let progress = Arc::new(ProgressLog::default());
+
+    AssetScanner::new(db.clone(), InMemoryMarketplace::default(), Default::default())
+        .with_progress(progress.clone())
+        .scan_location(&location)
+        .await
+        .unwrap();
+
+    let assets = location_assets(&db, &location).await;
+    let names: Vec<&str> = assets.iter().map(|a| a.name.as_str()).collect();
+    assert_eq!(names, ["Castle Kit", "Forest Pack"]);
+    let errors = progress.errors();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].kind, ScanErrorKind::Database);
+    assert!(errors[0].path.as_deref().unwrap().ends_with("Broken Pack"));
+}
+
+#[tokio::test]
+async fn manual_match_replaces_the_marketplace_data() {
+    let clock = Arc::new(ManualClock::new(start_time()));
+    let db = open_library(&clock).await;
+    let root = asset_library("manual-match", &["Old Castle"]);
+    let location = add_vault(&db, &root).await;
+    let marketplace = InMemoryMarketplace::new(vec![product("castle-kit", "Medieval Castle Kit")]);
+    scan(&db, &location).await;
+    let asset_id = location_assets(&db, &location).await[0].id.unwrap();
+
+    orbital::match_asset_to_url(&db, &marketplace, asset_id, "https://orbital-market.com/product/castle-kit")
+        .await
+        .unwrap();
+    assert!(orbital::match_asset_to_url(&db, &marketplace, asset_id, "https://example.com/product/castle-kit")
+        .await
+        .is_err());
+
+    let asset = db.get_asset_by_id(asset_id).await.unwrap();
+    assert_eq!(asset.matched_orbital_product_slug.as_deref(), Some("castle-kit"));
+    assert_eq!(asset.orbital_match_type.as_deref(), Some("Manual"));
+    assert_eq!(asset.orbital_title.as_deref(), Some("Medieval Castle Kit"));
+}
+
+/// A store holding one asset that records the match written to it.
+#[derive(Default)]
+struct RecordingStore {
+    matches: Mutex<Vec<(i64, Option<String>)>>,
+}
+
+#[async_trait]
+impl AssetStore for RecordingStore {
+    async fn asset_exists(&self, asset_id: i64) -> anyhow::Result<bool> {
+        Ok(asset_id == 1)
+    }
+
+    async fn update_asset_with_orbital_details(&self, _asset_id: i64, _orbital_data: &OrbitalAsset) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn update_asset_orbital_match_details(
+        &self,
+        asset_id: i64,
+        product_slug: Option<String>,
+        _confidence: Option<f64>,
+        _match_type_str: Option<String>,
+    ) -> anyhow::Result<()> {
+        self.matches.lock().unwrap().push((asset_id, product_slug));
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn manual_match_is_written_through_the_store() {
+    let store = RecordingStore::default();
+    let marketplace = InMemoryMarketplace::new(vec![product("castle-kit", "Medieval Castle Kit")]);
+    let url = "https://orbital-market.com/product/castle-kit";
+
+    orbital::match_asset_to_url(&store, &marketplace, 1, url).await.unwrap();
+    assert!(orbital::match_asset_to_url(&store, &marketplace, 2, url).await.is_err());
+
+    assert_eq!(*store.matches.lock().unwrap(), [(1, Some("castle-kit".to_string()))]);
+}
+
+#[tokio::test]
+async fn library_export_merges_into_another_library() {
+    let clock = Arc::new(ManualClock::new(start_time()));
+    let root = asset_library("transfer", &["Snow Props"]);
+    let export_path = root.with_extension("json");
+    let source = open_library(&clock).await;
+    let target = open_library(&clock).await;
+    for db in [&source, &target] {
+        let location = add_vault(db, &root).await;
+        scan(db, &location).await;
+    }
+    sqlx::query("UPDATE assets SET notes = 'Used in level 3', is_favorite = 1")
+        .execute(&source.pool)
+        .await
+        .unwrap();
+
+    assert_eq!(transfer::export_to_file(&source.pool, &export_path, ExportFormat::Json).await.unwrap(), 1);
+    let export = transfer::read_export(&export_path, ExportFormat::Json).unwrap();
+    let report = transfer::import_export(&target.pool, &export, MergeStrategy::Theirs, false).await.unwrap();
+    assert_eq!(report.matched, 1);
+
+    let asset: Asset = sqlx::query_as("SELECT * FROM assets").fetch_one(&target.pool).await.unwrap();
+    assert_eq!(asset.notes.as_deref(), Some("Used in level 3"));
+    assert!(asset.is_favorite);
+    let _ = std::fs::remove_file(export_path);
+}
+
+#[tokio::test]
+async fn library_import_tells_same_named_folders_in_different_locations_apart() {
+    let clock = Arc::new(ManualClock::new(start_time()));
+    let root = test_root("library", "transfer-locations");
+    asset_folders(&root.join("Studio"), &["Props"]);
+    asset_folders(&root.join("Personal"), &["Props"]);
+    let export_path = root.join("export.json");
+    let source = open_library(&clock).await;
+    let target = open_library(&clock).await;
+    for db in [&source, &target] {
+        for name in ["Studio", "Personal"] {
+            let path = root.join(name);
+            let location = db
+                .create_scan_location(name, &path.to_string_lossy(), false, None, None, LOCATION_KIND_VAULT)
+                .await
+                .unwrap();
+            scan(db, &location).await;
+        }
+    }
+    sqlx::raw_sql(
+        "UPDATE assets SET notes = 'Studio license' WHERE file_path LIKE '%Studio%';
+         UPDATE assets SET notes = 'Bought myself' WHERE file_path LIKE '%Personal%';",
+    )
+    .execute(&source.pool)
+    .await
+    .unwrap();
+    // Without fingerprints (an older library) only the path can pair the records up.
+    sqlx::query("UPDATE assets SET file_hash = NULL").execute(&target.pool).await.unwrap();
+
+    transfer::export_to_file(&source.pool, &export_path, ExportFormat::Json).await.unwrap();
+    let export = transfer::read_export(&export_path, ExportFormat::Json).unwrap();
+    let report = transfer::import_export(&target.pool, &export, MergeStrategy::Theirs, false).await.unwrap();
+    assert_eq!(report.matched, 2);
+    assert!(report.ambiguous.is_empty(), "{:?}", report.ambiguous);
+
+    let notes: Vec<(String, Option<String>)> = sqlx::query_as(
+        "SELECT sl.name, a.notes FROM assets a JOIN scan_locations sl ON sl.id = a.scan_location_id ORDER BY sl.name",
+    )
+    .fetch_all(&target.pool)
+    .await
+    .unwrap();
+    assert_eq!(
+        notes,
+        [
+            ("Personal".to_string(), Some("Bought myself".to_string())),
+            ("Studio".to_string(), Some("Studio license".to_string())),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn a_failed_settings_write_leaves_the_location_at_its_old_root() {
+    let clock = Arc::new(ManualClock::new(start_time()));
+    let db = open_library(&clock).await;
+    let root = asset_library("update-location", &["Castle Kit"]);
+    let location = add_vault(&db, &root).await;
+    scan(&db, &location).await;
+    let moved = root.with_file_name("update-location-moved");
+    let id = location.id.unwrap();
+
+    sqlx::query(
+        "CREATE TEMP TRIGGER reject_rename BEFORE UPDATE OF name ON scan_locations WHEN NEW.name = 'Renamed'
+         BEGIN SELECT RAISE(ABORT, 'rejected'); END",
+    )
+    .execute(&db.pool)
+    .await
+    .unwrap();
+    let moved_path = moved.to_string_lossy();
+    assert!(db
+        .update_scan_location(id, "Renamed", &moved_path, true, false, None, None)
+        .await
+        .is_err());
+
+    let unchanged = db.get_scan_location_by_id(id).await.unwrap().unwrap();
+    assert_eq!(unchanged.path, location.path);
+    let asset = &location_assets(&db, &location).await[0];
+    assert!(Path::new(&asset.file_path).starts_with(&root), "{}", asset.file_path);
+
+    let (updated, assets_updated) = db
+        .update_scan_location(id, "Castle Vault", &moved_path, true, false, None, None)
+        .await
+        .unwrap();
+    assert_eq!((updated.name.as_str(), updated.path.as_str(), assets_updated), ("Castle Vault", moved_path.as_ref(), 1));
+    let asset = &location_assets(&db, &location).await[0];
+    assert!(Path::new(&asset.file_path).starts_with(&moved), "{}", asset.file_path);
+}
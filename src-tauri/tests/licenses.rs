@@ -0,0 +1,128 @@
+//! License records over a scanned vault and the yearly license report.
+
+mod common;
+
+use app_lib::database::DatabaseManager;
+use app_lib::licenses::{self, LicenseInput};
+use common::{asset_folders, asset_id, scanned_library, test_root};
+
+async fn scanned_vault(test_name: &str, folders: &[&str]) -> DatabaseManager {
+    let vault = test_root("licenses", test_name);
+    asset_folders(&vault, folders);
+    scanned_library(&vault).await
+}
+
+fn license(owner: &str, date: &str, license_type: &str) -> LicenseInput {
+    LicenseInput {
+        owner: owner.to_string(),
+        purchase_date: Some(date.to_string()),
+        license_type: license_type.to_string(),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn reports_spend_sellers_and_missing_licenses() {
+    let db = scanned_vault("report", &["Castle Kit", "Forest Pack", "Rain FX", "Snow Props"]).await;
+    sqlx::raw_sql(
+        "UPDATE assets SET orbital_price = 49.99, orbital_seller_name = 'Stone Works' WHERE name = 'Castle Kit';
+         UPDATE assets SET orbital_price = 19.99, orbital_seller_name = 'Green Studio' WHERE name = 'Forest Pack';",
+    )
+    .execute(&db.pool)
+    .await
+    .unwrap();
+    let castle = asset_id(&db, "Castle Kit").await;
+    let forest = asset_id(&db, "Forest Pack").await;
+    let rain = asset_id(&db, "Rain FX").await;
+
+    // The listing fills in price and seller.
+    let listed = licenses::add_license(&db, castle, &license("Studio", "2024-03-01", "Professional")).await.unwrap();
+    assert_eq!((listed.price_paid, listed.price_from_marketplace), (Some(49.99), true));
+    assert_eq!(listed.seller.as_deref(), Some("Stone Works"));
+    assert_eq!(listed.license_type, "professional");
+
+    let extra_seats = LicenseInput {
+        price_paid: Some(60.0),
+        seats: Some(3),
+        order_reference: Some("INV-0042".to_string()),
+        ..license("Studio", "2024-09-15", "professional")
+    };
+    let entered = licenses::add_license(&db, castle, &extra_seats).await.unwrap();
+    assert!(!entered.price_from_marketplace);
+
+    // A price in another currency does not fall back to the dollar list price.
+    let euros = LicenseInput { currency: Some("eur".to_string()), ..license("Alex", "2024-05-20", "personal") };
+    let euros = licenses::add_license(&db, forest, &euros).await.unwrap();
+    assert_eq!((euros.price_paid, euros.currency.as_str()), (None, "EUR"));
+    licenses::add_license(&db, rain, &LicenseInput { price_paid: Some(15.0), ..license("Alex", "2023-11-02", "personal") })
+        .await
+        .unwrap();
+
+    let spend = licenses::spend_report(&db, Some(2024)).await.unwrap();
+    assert_eq!((spend.licenses, spend.seats, spend.without_price), (3, 5, 1));
+    assert_eq!(spend.totals.len(), 1);
+    assert!((spend.totals[0].total - 109.99).abs() < 1e-9);
+    assert!((spend.totals[0].from_marketplace - 49.99).abs() < 1e-9);
+    let all_time = licenses::spend_report(&db, None).await.unwrap();
+    assert!((all_time.totals[0].total - 124.99).abs() < 1e-9);
+
+    let sellers = licenses::licenses_by_seller(&db, Some(2024)).await.unwrap();
+    assert_eq!(sellers[0].seller.as_deref(), Some("Stone Works"));
+    assert_eq!((sellers[0].licenses, sellers[0].seats, sellers[0].assets), (2, 4, 1));
+    assert_eq!(sellers[1].seller.as_deref(), Some("Green Studio"));
+
+    let unlicensed = licenses::unlicensed_assets(&db).await.unwrap();
+    assert_eq!(unlicensed.iter().map(|a| a.name.as_str()).collect::<Vec<_>>(), ["Snow Props"]);
+
+    // Entering the price paid later replaces the list price.
+    let corrected = licenses::update_license(
+        &db,
+        listed.id,
+        &LicenseInput { price_paid: Some(39.99), ..license("Studio", "2024-03-01", "professional") },
+    )
+    .await
+    .unwrap();
+    assert_eq!((corrected.price_paid, corrected.price_from_marketplace), (Some(39.99), false));
+    licenses::delete_license(&db, euros.id).await.unwrap();
+    assert_eq!(licenses::license_report(&db, None).await.unwrap().unlicensed.len(), 2);
+}
+
+#[tokio::test]
+async fn rejects_invalid_licenses() {
+    let db = scanned_vault("invalid", &["Castle Kit"]).await;
+    let castle = asset_id(&db, "Castle Kit").await;
+
+    for invalid in [
+        license("", "2024-03-01", "personal"),
+        license("Studio", "2024-03-01", "enterprise"),
+        license("Studio", "03/01/2024", "personal"),
+        LicenseInput { seats: Some(0), ..license("Studio", "2024-03-01", "personal") },
+        LicenseInput { price_paid: Some(-5.0), ..license("Studio", "2024-03-01", "personal") },
+    ] {
+        assert!(licenses::add_license(&db, castle, &invalid).await.is_err());
+    }
+    assert!(licenses::add_license(&db, 9999, &license("Studio", "2024-03-01", "personal")).await.is_err());
+    assert!(licenses::delete_license(&db, 9999).await.is_err());
+    assert!(licenses::get_asset_licenses(&db, castle).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn licenses_outlive_their_asset() {
+    let db = scanned_vault("orphaned", &["Castle Kit", "Castle Kit 2"]).await;
+    let castle = asset_id(&db, "Castle Kit").await;
+    let license = licenses::add_license(&db, castle, &license("Studio", "2024-03-01", "professional")).await.unwrap();
+    assert_eq!(license.asset_id, Some(castle));
+
+    // Removed from the library, e.g. renamed on disk.
+    sqlx::query("DELETE FROM assets WHERE id = ?").bind(castle).execute(&db.pool).await.unwrap();
+    let orphaned = licenses::license_report(&db, Some(2024)).await.unwrap().orphaned;
+    assert_eq!(orphaned.len(), 1);
+    assert_eq!((orphaned[0].asset_id, orphaned[0].asset_name.as_str()), (None, "Castle Kit"));
+    assert!(orphaned[0].asset_path.ends_with("Castle Kit"));
+    assert_eq!(licenses::spend_report(&db, Some(2024)).await.unwrap().licenses, 1);
+
+    let renamed = asset_id(&db, "Castle Kit 2").await;
+    let attached = licenses::attach_license(&db, license.id, renamed).await.unwrap();
+    assert_eq!((attached.asset_id, attached.asset_name.as_str()), (Some(renamed), "Castle Kit 2"));
+    assert!(licenses::orphaned_licenses(&db).await.unwrap().is_empty());
+}
@@ -0,0 +1,105 @@
+//! Metadata providers over a scanned vault: per-provider results and merging by precedence.
+
+mod common;
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+
+use app_lib::database::DatabaseManager;
+use app_lib::providers::{self, ManifestProvider, MetadataProvider, ProductMetadata, SearchQuery};
+use common::{asset_id, scanned_library, test_root, write_files};
+
+/// A marketplace that knows one listing per reference and cannot search.
+struct StubMarketplace;
+
+#[async_trait]
+impl MetadataProvider for StubMarketplace {
+    fn id(&self) -> &'static str {
+        "fab"
+    }
+
+    async fn search(&self, _query: &SearchQuery) -> Result<Vec<ProductMetadata>> {
+        Ok(Vec::new())
+    }
+
+    async fn fetch_details(&self, reference: &str) -> Result<ProductMetadata> {
+        if reference == "missing" {
+            return Err(anyhow!("No listing {}", reference));
+        }
+        Ok(ProductMetadata {
+            reference: reference.to_string(),
+            title: Some(format!("Listing {}", reference)),
+            seller: Some("Fab Seller".to_string()),
+            tags: vec!["medieval".to_string()],
+            ..Default::default()
+        })
+    }
+
+    fn parse(&self, document: &str) -> Result<ProductMetadata> {
+        Ok(serde_json::from_str(document)?)
+    }
+}
+
+async fn scanned_vault(test_name: &str, manifest: &str) -> (DatabaseManager, i64) {
+    let vault = test_root("providers", test_name);
+    write_files(&vault, &[("Castle Kit/Content.uasset", "castle"), ("Castle Kit/omnidex.json", manifest)]);
+    let db = scanned_library(&vault).await;
+    let asset_id = asset_id(&db, "Castle Kit").await;
+    (db, asset_id)
+}
+
+#[tokio::test]
+async fn stores_results_per_provider_and_merges_by_precedence() {
+    let (db, asset_id) =
+        scanned_vault("merge", r#"{"name": "Castle Kit", "description": "Our copy", "links": {"fab": "uid-1"}}"#).await;
+    sqlx::query(
+        "UPDATE assets SET matched_orbital_product_slug = 'castle-kit', orbital_title = 'Modular Castle Kit',
+         orbital_seller_name = 'Stone Works', orbital_categories = '[\"Environments\"]' WHERE id = ?",
+    )
+    .bind(asset_id)
+    .execute(&db.pool)
+    .await
+    .unwrap();
+
+    let providers: Vec<Box<dyn MetadataProvider>> = vec![Box::new(ManifestProvider), Box::new(StubMarketplace)];
+    let outcomes = providers::refresh_asset(&db, &providers, asset_id).await.unwrap();
+    assert!(outcomes.iter().all(|outcome| outcome.status == "fetched"), "{:?}", outcomes);
+
+    let records = providers::get_provider_records(&db, asset_id).await.unwrap();
+    let fab = records.iter().find(|record| record.provider == "fab").unwrap();
+    assert_eq!((fab.reference.as_str(), fab.match_kind.as_str()), ("uid-1", "linked"));
+    let manifest = records.iter().find(|record| record.provider == "manifest").unwrap();
+    assert_eq!(manifest.match_kind, "local");
+
+    // Default order: manifest, orbital (from the scanner's match), fab.
+    let merged = providers::merged_metadata(&db, asset_id, &[]).await.unwrap();
+    assert_eq!(merged.metadata.title.as_deref(), Some("Castle Kit"));
+    assert_eq!(merged.metadata.seller.as_deref(), Some("Stone Works"));
+    assert_eq!(merged.metadata.tags, ["medieval"]);
+    assert_eq!(merged.sources["title"], "manifest");
+    assert_eq!(merged.sources["categories"], "orbital");
+    assert_eq!(merged.sources["tags"], "fab");
+    assert_eq!(merged.providers, ["manifest", "orbital", "fab"]);
+    assert_eq!(merged.metadata.links["orbital"], "castle-kit");
+
+    let merged = providers::merged_metadata(&db, asset_id, &["fab".to_string()]).await.unwrap();
+    assert_eq!(merged.metadata.title.as_deref(), Some("Listing uid-1"));
+    assert_eq!(merged.metadata.description.as_deref(), Some("Our copy"));
+    assert_eq!(merged.sources["seller"], "fab");
+}
+
+#[tokio::test]
+async fn manual_links_win_and_failures_are_reported() {
+    let (db, asset_id) = scanned_vault("manual", r#"{"name": "Castle Kit", "links": {"fab": "missing"}}"#).await;
+    let providers: Vec<Box<dyn MetadataProvider>> = vec![Box::new(ManifestProvider), Box::new(StubMarketplace)];
+
+    let outcomes = providers::refresh_asset(&db, &providers, asset_id).await.unwrap();
+    assert_eq!(outcomes[0].status, "fetched");
+    assert_eq!(outcomes[1].status, "failed");
+    assert!(providers::link_asset(&db, &StubMarketplace, asset_id, "missing").await.is_err());
+
+    let record = providers::link_asset(&db, &StubMarketplace, asset_id, "uid-2").await.unwrap();
+    assert_eq!((record.match_kind.as_str(), record.confidence), ("manual", 1.0));
+    let outcomes = providers::refresh_asset(&db, &providers, asset_id).await.unwrap();
+    assert_eq!(outcomes[1].reference.as_deref(), Some("uid-2"));
+}
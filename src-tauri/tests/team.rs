@@ -0,0 +1,138 @@
+//! Two members' libraries over the same vault, kept in step through a team library file.
+
+mod common;
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use app_lib::clock::ManualClock;
+use app_lib::database::DatabaseManager;
+use app_lib::models::Asset;
+use app_lib::team::{self, ConflictWinner};
+use chrono::{DateTime, Duration, Utc};
+use common::{add_vault, asset_folders, test_root};
+
+struct Member {
+    db: DatabaseManager,
+    clock: Arc<ManualClock>,
+    name: &'static str,
+}
+
+impl Member {
+    async fn join(name: &'static str, vault: &Path, scan: bool) -> Self {
+        let clock = Arc::new(ManualClock::new(start_time()));
+        let db = DatabaseManager::open_in_memory().await.unwrap().with_clock(clock.clone());
+        let location = add_vault(&db, vault).await;
+        if scan {
+            common::scan(&db, &location).await;
+        }
+        db.set_scan_location_team_key(location.id.unwrap(), Some("NAS Vault")).await.unwrap();
+        Self { db, clock, name }
+    }
+
+    async fn sync(&self, team_file: &Path) -> team::TeamSyncReport {
+        team::sync_team_library(&self.db, team_file, self.name).await.unwrap()
+    }
+
+    async fn asset(&self, name: &str) -> Asset {
+        common::asset(&self.db, name).await
+    }
+
+    /// Edit a column the way the app's commands do, stamping the edit time.
+    async fn edit(&self, name: &str, column: &str, value: &str) {
+        self.clock.advance(Duration::minutes(1));
+        sqlx::query(&format!("UPDATE assets SET {} = ?, user_modified_date = ? WHERE name = ?", column))
+            .bind(value)
+            .bind(self.db.timestamp())
+            .bind(name)
+            .execute(&self.db.pool)
+            .await
+            .unwrap();
+    }
+}
+
+fn start_time() -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339("2024-05-01T12:00:00Z").unwrap().with_timezone(&Utc)
+}
+
+/// A vault with one folder per name, and where the team library file goes.
+fn team_setup(test_name: &str, folders: &[&str]) -> (PathBuf, PathBuf) {
+    let root = test_root("team", test_name);
+    let vault = root.join("vault");
+    asset_folders(&vault, folders);
+    (vault, root.join("omnidex-team.db"))
+}
+
+#[tokio::test]
+async fn members_share_edits_but_not_favorites() {
+    let (vault, team_file) = team_setup("share", &["Castle Kit", "Forest Pack"]);
+    let alice = Member::join("alice", &vault, true).await;
+    alice.edit("Castle Kit", "tags", "medieval,modular").await;
+    alice.edit("Castle Kit", "is_favorite", "1").await;
+    sqlx::query(
+        "INSERT INTO collections (name, created_date, modified_date) VALUES ('Level 3', '2024-05-01', '2024-05-01');
+         INSERT INTO collection_assets (collection_id, asset_id, added_date)
+         SELECT 1, id, '2024-05-01' FROM assets WHERE name = 'Castle Kit'",
+    )
+    .execute(&alice.db.pool)
+    .await
+    .unwrap();
+
+    let report = alice.sync(&team_file).await;
+    assert_eq!(report.shared, 2);
+    assert_eq!(report.memberships_added, 1);
+
+    // Bob has not scanned the vault; the sync brings in what Alice found.
+    let bob = Member::join("bob", &vault, false).await;
+    let report = bob.sync(&team_file).await;
+    assert_eq!(report.added_locally, 2);
+    assert_eq!(report.collections_created, ["Level 3"]);
+
+    let castle = bob.asset("Castle Kit").await;
+    assert_eq!(castle.tags.as_deref(), Some("medieval,modular"));
+    assert!(!castle.is_favorite);
+    assert!(castle.file_path.starts_with(&*vault.to_string_lossy()));
+    let members: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM collection_assets").fetch_one(&bob.db.pool).await.unwrap();
+    assert_eq!(members, 1);
+
+    // Nothing changed, so a second sync is a no-op.
+    let report = alice.sync(&team_file).await;
+    assert_eq!((report.pushed, report.pulled, report.conflicts.len()), (0, 0, 0));
+}
+
+#[tokio::test]
+async fn conflicting_edits_keep_the_newest() {
+    let (vault, team_file) = team_setup("conflict", &["Desert Rocks"]);
+    let alice = Member::join("alice", &vault, true).await;
+    let bob = Member::join("bob", &vault, true).await;
+    alice.sync(&team_file).await;
+    bob.sync(&team_file).await;
+
+    alice.edit("Desert Rocks", "notes", "Needs UE 5.3").await;
+    bob.clock.advance(Duration::minutes(5));
+    bob.edit("Desert Rocks", "notes", "Works in 5.2 with Nanite off").await;
+    bob.edit("Desert Rocks", "description", "Rock set").await;
+
+    assert_eq!(alice.sync(&team_file).await.pushed, 1);
+    let report = bob.sync(&team_file).await;
+    assert_eq!(report.conflicts.len(), 1);
+    assert_eq!(report.conflicts[0].fields, ["notes"]);
+    assert_eq!(report.conflicts[0].kept, ConflictWinner::Local);
+    assert_eq!(report.conflicts[0].other_user, "alice");
+
+    assert_eq!(alice.sync(&team_file).await.pulled, 1);
+    for member in [&alice, &bob] {
+        let rocks = member.asset("Desert Rocks").await;
+        assert_eq!(rocks.notes.as_deref(), Some("Works in 5.2 with Nanite off"));
+        assert_eq!(rocks.description.as_deref(), Some("Rock set"));
+    }
+}
+
+#[tokio::test]
+async fn unreachable_team_library_is_an_error() {
+    let (vault, team_file) = team_setup("offline", &["Snow Props"]);
+    let alice = Member::join("alice", &vault, true).await;
+    let offline = team_file.parent().unwrap().join("unmounted").join("omnidex-team.db");
+    assert!(team::sync_team_library(&alice.db, &offline, "alice").await.is_err());
+    assert!(!offline.exists());
+}
@@ -0,0 +1,85 @@
+//! Installed versions of matched packs compared with their marketplace listing.
+
+mod common;
+
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use app_lib::unreal::version::EngineVersion;
+use app_lib::updates::{self, UpdateFlag, VersionSource};
+use common::{scanned_library, test_root};
+
+/// A package summary as saved by UE 5.3 (object version 1010).
+fn ue53_package() -> Vec<u8> {
+    let mut bytes = Vec::new();
+    bytes.extend(0x9E2A_83C1u32.to_le_bytes());
+    for value in [-8i32, 864, 522, 1010, 0, 0, 2048] {
+        bytes.extend(value.to_le_bytes());
+    }
+    bytes.extend(5i32.to_le_bytes());
+    bytes.extend(b"None\0");
+    bytes.extend(0u32.to_le_bytes());
+    bytes.extend(10i32.to_le_bytes());
+    bytes
+}
+
+/// Write `contents` to `path`, dated `days_ago` before now.
+fn write_file(path: &Path, contents: &[u8], days_ago: u64) {
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, contents).unwrap();
+    let file = std::fs::File::options().write(true).open(path).unwrap();
+    file.set_modified(SystemTime::now() - Duration::from_secs(days_ago * 86_400)).unwrap();
+}
+
+#[tokio::test]
+async fn flags_outdated_copies_and_engine_mismatches() {
+    let root = test_root("updates", "outdated");
+    let vault = root.join("vault");
+    write_file(&vault.join("Castle Kit/Content/SM_Wall.uasset"), &ue53_package(), 400);
+    write_file(
+        &vault.join("Rain FX/RainFX/RainFX.uplugin"),
+        br#"{ "VersionName": "1.2", "EngineVersion": "5.1.0" }"#,
+        30,
+    );
+    write_file(&vault.join("Rain FX/RainFX/Content/NS_Rain.uasset"), &ue53_package(), 30);
+    write_file(&vault.join("Forest Pack/Content/SM_Tree.uasset"), &ue53_package(), 30);
+
+    let db = scanned_library(&vault).await;
+
+    let two_months_ago = (chrono::Utc::now() - chrono::Duration::days(60)).to_rfc3339();
+    sqlx::query(
+        "UPDATE assets SET matched_orbital_product_slug = lower(replace(name, ' ', '-')),
+                orbital_supported_versions = '[\"5.1\", \"5.4\"]', orbital_last_modified = ?
+         WHERE name = 'Castle Kit';
+         UPDATE assets SET matched_orbital_product_slug = 'rain-fx', orbital_supported_versions = '[\"5.1\", \"5.3\"]'
+         WHERE name = 'Rain FX';
+         UPDATE assets SET matched_orbital_product_slug = 'forest-pack', orbital_supported_versions = '[\"5.0\", \"5.3\"]',
+                orbital_release_date = '2021-03-01T00:00:00.000Z'
+         WHERE name = 'Forest Pack';",
+    )
+    .bind(&two_months_ago)
+    .execute(&db.pool)
+    .await
+    .unwrap();
+
+    let report = updates::find_outdated_assets(&db, Some(EngineVersion::new(5, 3))).await.unwrap();
+    assert_eq!(report.checked, 3);
+    let names: Vec<&str> = report.assets.iter().map(|a| a.name.as_str()).collect();
+    assert_eq!(names, ["Castle Kit", "Rain FX"]);
+
+    let castle = &report.assets[0];
+    assert_eq!(castle.installed_engine, Some(EngineVersion::new(5, 3)));
+    assert_eq!(castle.version_source, VersionSource::Packages);
+    assert_eq!(castle.flags, [UpdateFlag::UpdateAvailable, UpdateFlag::NewerEngineSupport]);
+
+    // The plugin's descriptor wins over its packages, and its binaries are built for 5.1.
+    let rain = &report.assets[1];
+    assert_eq!(rain.installed_engine, Some(EngineVersion::new(5, 1)));
+    assert_eq!(rain.installed_version_name.as_deref(), Some("1.2"));
+    assert_eq!(rain.flags, [UpdateFlag::EngineUnsupported, UpdateFlag::NewerEngineSupport]);
+
+    // Against an older engine the 5.3 content cannot be opened either.
+    let report = updates::find_outdated_assets(&db, Some(EngineVersion::new(5, 2))).await.unwrap();
+    let forest = report.assets.iter().find(|a| a.name == "Forest Pack").unwrap();
+    assert_eq!(forest.flags, [UpdateFlag::EngineUnsupported]);
+}
@@ -1 +0,0 @@
-use strsim::{jaro_winkler}; 
\ No newline at end of file
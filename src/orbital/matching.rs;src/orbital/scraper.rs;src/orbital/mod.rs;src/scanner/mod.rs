@@ -1,8 +0,0 @@
-use strsim::{jaro_winkler};
-
-use anyhow::{Result};
-
-
-
-use std::time::{Instant};
-
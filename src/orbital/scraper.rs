@@ -1 +0,0 @@
-use anyhow::{Result}; 
\ No newline at end of file
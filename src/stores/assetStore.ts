@@ -2,7 +2,7 @@ import { invoke } from '@tauri-apps/api/core'
 import { listen } from '@tauri-apps/api/event'
 import { defineStore } from 'pinia'
 import { computed, ref } from 'vue'
-import type { Asset, AssetFilter, AssetStats, ScanLocation, ScanProgress } from '@/types'
+import type { ArchiveExtraction, Asset, AssetFilter, AssetStats, ScanLocation, ScanProgress } from '@/types'
 
 export const useAssetStore = defineStore('assets', () => {
   // State
@@ -235,6 +235,22 @@ export const useAssetStore = defineStore('assets', () => {
     }
   }
   
+  async function extractArchive(assetId: number, locationId: number, folderName?: string): Promise<ArchiveExtraction | null> {
+    error.value = null;
+    try {
+      return await invoke<ArchiveExtraction>('extract_archive_asset', { assetId, locationId, folderName });
+    } catch (err: unknown) {
+      let message = 'Failed to extract archive';
+      if (err instanceof Error) {
+        message = err.message;
+      } else if (typeof err === 'string') {
+        message = err;
+      }
+      error.value = message;
+      return null;
+    }
+  }
+
   function toggleAssetSelection(assetId: number) {
     const index = selectedAssets.value.indexOf(assetId)
     if (index > -1) {
@@ -287,6 +303,7 @@ export const useAssetStore = defineStore('assets', () => {
     fetchScanLocations,
     toggleFavorite,
     updateAssetDescription,
+    extractArchive,
     toggleAssetSelection,
     clearSelection,
     clearError,
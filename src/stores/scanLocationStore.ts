@@ -1,4 +1,4 @@
-import type { ScanLocation, ScanProgress } from '@/types'
+import type { AssetRootPreview, AssetRootRules, LocationKind, RelocationReport, ScanLocation, ScanProgress } from '@/types'
 import { invoke } from '@tauri-apps/api/core'
 import { listen, type UnlistenFn } from '@tauri-apps/api/event'
 import { defineStore } from 'pinia'
@@ -36,6 +36,7 @@ export const useScanLocationStore = defineStore('scanLocations', () => {
     scanRecursive: boolean,
     fileExtensions?: string,
     description?: string,
+    locationKind: LocationKind = 'vault',
   ) {
     loading.value = true
     error.value = null
@@ -46,6 +47,7 @@ export const useScanLocationStore = defineStore('scanLocations', () => {
         scanRecursive: scanRecursive,
         fileExtensions,
         description,
+        locationKind,
       };
       const result = await invoke<ScanLocation>('create_scan_location', args)
       await fetchScanLocations()
@@ -124,6 +126,99 @@ export const useScanLocationStore = defineStore('scanLocations', () => {
     }
   }
 
+  async function updateScanWatching(id: number, watchEnabled: boolean) {
+    loading.value = true
+    error.value = null
+
+    try {
+      const result = await invoke<ScanLocation>('set_scan_location_watching', {
+        locationId: id,
+        watchEnabled
+      })
+
+      // Update local state
+      const index = scanLocations.value.findIndex(loc => loc.id === id)
+      if (index > -1) {
+        scanLocations.value[index] = result
+      }
+
+      return result
+    } catch (err) {
+      error.value = err as string
+      console.error('Failed to update scan watch setting:', err)
+      throw err
+    } finally {
+      loading.value = false
+    }
+  }
+
+  async function updateRootRules(id: number, rules: AssetRootRules | null) {
+    loading.value = true
+    error.value = null
+
+    try {
+      const result = await invoke<ScanLocation>('set_scan_location_root_rules', {
+        locationId: id,
+        rules
+      })
+
+      const index = scanLocations.value.findIndex(loc => loc.id === id)
+      if (index > -1) {
+        scanLocations.value[index] = result
+      }
+
+      return result
+    } catch (err) {
+      error.value = err as string
+      console.error('Failed to update asset-root rules:', err)
+      throw err
+    } finally {
+      loading.value = false
+    }
+  }
+
+  async function previewAssetRoots(path: string, scanRecursive: boolean, rules: AssetRootRules | null) {
+    try {
+      return await invoke<AssetRootPreview>('preview_asset_roots', {
+        path,
+        scanRecursive,
+        rules
+      })
+    } catch (err) {
+      error.value = err as string
+      console.error('Failed to preview asset roots:', err)
+      throw err
+    }
+  }
+
+  async function relocateScanLocation(id: number, newPath: string, force = false) {
+    loading.value = true
+    error.value = null
+
+    try {
+      const report = await invoke<RelocationReport>('relocate_scan_location', {
+        locationId: id,
+        newPath,
+        force
+      })
+
+      if (report.committed) {
+        const location = scanLocations.value.find(loc => loc.id === id)
+        if (location) {
+          location.path = report.new_path
+        }
+      }
+
+      return report
+    } catch (err) {
+      error.value = err as string
+      console.error('Failed to relocate scan location:', err)
+      throw err
+    } finally {
+      loading.value = false
+    }
+  }
+
   async function clearAssetsFromScanLocation(locationId: number) {
     loading.value = true
     error.value = null
@@ -220,7 +315,7 @@ export const useScanLocationStore = defineStore('scanLocations', () => {
             ErrorHandler.showInfoToast('Scan started', 'Scanning Assets');
           }
           scanProgress.value = event.payload;
-        } else if (status === 'Completed' || status === 'Error' || status === 'Cancelled') {
+        } else if (status === 'Completed' || status === 'Error' || status === 'Cancelled' || status === 'Paused') {
           if (isScanning.value) isScanning.value = false;
           scanProgress.value = event.payload; // Keep final progress
           if (status === 'Completed') {
@@ -248,6 +343,8 @@ export const useScanLocationStore = defineStore('scanLocations', () => {
             );
           } else if (status === 'Cancelled') {
             ErrorHandler.showInfoToast('Scan was cancelled', 'Scan Cancelled');
+          } else if (status === 'Paused') {
+            ErrorHandler.showInfoToast('Scan was paused and can be resumed later', 'Scan Paused');
           }
         } else {
           // For 'Initializing' or other states, just update progress
@@ -309,6 +406,10 @@ export const useScanLocationStore = defineStore('scanLocations', () => {
     createScanLocation,
     updateScanLocation,
     updateScanRecursive,
+    updateScanWatching,
+    updateRootRules,
+    previewAssetRoots,
+    relocateScanLocation,
     clearAssetsFromScanLocation,
     deleteScanLocation,
     startScan,
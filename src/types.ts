@@ -68,12 +68,18 @@ export interface LogEntry {
   message: string;
 }
 
+export type LocationKind = 'vault' | 'project' | 'media';
+
 export interface ScanLocation {
   id: number;
   name: string;
   path: string;
   is_active: boolean;
   scan_recursive?: boolean;
+  watch_enabled?: boolean;
+  location_kind?: LocationKind;
+  root_rules?: string | null;
+  team_key?: string | null;
   file_extensions?: string;
   last_scanned?: string;
   asset_count?: number;
@@ -81,17 +87,266 @@ export interface ScanLocation {
   created_date?: string;
 }
 
+export type RootRule =
+  | { kind: 'unreal_descriptor' }
+  | { kind: 'content_folder' }
+  | { kind: 'depth'; depth: number }
+  | { kind: 'glob'; pattern: string };
+
+export interface AssetRootRules {
+  roots: RootRule[];
+  include: string[];
+  exclude: string[];
+}
+
+export interface AssetRootPreview {
+  path: string;
+  roots: {
+    relative_path: string;
+    file_count: number;
+    total_bytes: number;
+    indexed: boolean;
+  }[];
+  not_selected: string[];
+  walk_errors: ScanError[];
+}
+
+export interface RelocationReport {
+  location_id: number;
+  old_path: string;
+  new_path: string;
+  sampled: number;
+  missing: string[];
+  committed: boolean;
+  assets_updated: number;
+}
+
+export type ProjectImportMode = 'copy' | 'hard_link';
+
+export interface ProjectImportReport {
+  asset_id: number;
+  project_name: string;
+  project_path: string;
+  kind: 'content' | 'plugin';
+  mode: ProjectImportMode;
+  target_dir: string;
+  dry_run: boolean;
+  file_count: number;
+  total_bytes: number;
+  conflicts: string[];
+  committed: boolean;
+  files_written: number;
+}
+
+export interface AssetProjectUsage {
+  asset_id: number;
+  project_name: string;
+  project_path: string;
+  import_mode: ProjectImportMode | 'detected';
+  imported_date: string;
+}
+
+export interface ProjectAssetMatch {
+  asset_id: number;
+  name: string;
+  library_packages: number;
+  identical: number;
+  modified: number;
+  moved: number;
+  status: 'complete' | 'modified' | 'partial';
+}
+
+export interface InventoryEntry {
+  group_key: string;
+  file_count: number;
+  total_bytes: number;
+}
+
+export interface AssetSizeBreakdown {
+  asset_id: number;
+  name: string;
+  total_files: number;
+  total_bytes: number;
+  by_extension: InventoryEntry[];
+  by_folder: InventoryEntry[];
+}
+
+export interface StorageOverview {
+  total_files: number;
+  total_bytes: number;
+  by_extension: InventoryEntry[];
+  by_location: {
+    location_id: number;
+    name: string;
+    path: string;
+    asset_count: number;
+    total_bytes: number;
+  }[];
+  largest_assets: {
+    asset_id: number;
+    name: string;
+    total_bytes: number;
+  }[];
+}
+
+export interface ArchiveSummary {
+  format: 'zip' | '7z' | 'rar';
+  compressed_size: number;
+  uncompressed_size: number;
+  file_count: number;
+  wrapper_folder: string | null;
+  has_uproject: boolean;
+  has_uplugin: boolean;
+  package_count: number;
+}
+
+export interface MediaMetadata {
+  kind: 'image' | 'audio' | 'mesh';
+  format: string;
+  width?: number;
+  height?: number;
+  bit_depth?: number;
+  channels?: number;
+  duration_seconds?: number;
+  sample_rate?: number;
+  vertex_count?: number;
+  mesh_count?: number;
+  material_count?: number;
+}
+
+export interface ArchiveExtraction {
+  asset_id: number;
+  location_id: number;
+  destination: string;
+  files_extracted: number;
+  extracted_asset_id: number | null;
+}
+
+export interface LibraryChange {
+  location_id: number;
+  added: number[];
+  updated: number[];
+  removed: number[];
+}
+
+export interface Collection {
+  id: number;
+  name: string;
+  description?: string;
+  created_date: string;
+  modified_date: string;
+  asset_count: number;
+}
+
+export type ExportFormat = 'json' | 'csv';
+export type MergeStrategy = 'theirs' | 'ours' | 'newest';
+
+export interface AssetImportChange {
+  asset_id: number;
+  name: string;
+  matched_by: 'file_hash' | 'orbital_slug' | 'relative_path';
+  fields: string[];
+  collections_added: string[];
+}
+
+export interface ImportReport {
+  dry_run: boolean;
+  strategy: MergeStrategy;
+  total_records: number;
+  matched: number;
+  unchanged: number;
+  changes: AssetImportChange[];
+  unmatched: string[];
+  ambiguous: string[];
+  collections_created: string[];
+}
+
 export interface ScanProgress {
   locationId?: number;
   total_items: number;
   processed_items: number;
   current_path: string;
+  /** Why the scan failed as a whole (status `Error`). */
   error?: string;
-  status?: 'Scanning' | 'Completed' | 'Error' | 'Cancelled';
+  status?: 'Initializing Scan' | 'Scanning' | 'Completed' | 'Error' | 'Cancelled' | 'Paused';
+  /** Errors of single paths recorded so far; see `get_scan_errors`. */
   error_count?: number;
   completed_successfully?: boolean;
 }
 
+export type ScanJobStatus = 'running' | 'paused' | 'interrupted' | 'completed' | 'cancelled' | 'failed';
+
+/** A persisted folder scan that can resume from its checkpoint. */
+export interface ScanJob {
+  id: number;
+  location_id: number;
+  status: ScanJobStatus;
+  checkpoint_path: string | null;
+  processed_items: number;
+  total_items: number;
+  assets_found: number;
+  error_count: number;
+  active_ms: number;
+  created_date: string;
+  updated_date: string;
+}
+
+/** One run of a scan job, as listed in the scan history. */
+export interface ScanRun {
+  id: number;
+  job_id: number;
+  location_id: number;
+  started_date: string;
+  finished_date: string | null;
+  duration_ms: number | null;
+  outcome: ScanJobStatus | null;
+  processed_items: number;
+  assets_found: number;
+  error_count: number;
+}
+
+export type ScanErrorKind =
+  | 'permission_denied'
+  | 'path_not_utf8'
+  | 'metadata_unavailable'
+  | 'database'
+  | 'marketplace'
+  | 'unreadable';
+
+/** An error of a single path during a scan, or of the whole location when `path` is null. */
+export interface ScanError {
+  kind: ScanErrorKind;
+  path: string | null;
+  message: string;
+}
+
+/** A scan error as stored per scan run, also sent as the `scan-error` event. */
+export interface ScanErrorRecord extends ScanError {
+  id: number;
+  run_id: number;
+  job_id: number;
+  location_id: number;
+  occurred_date: string;
+  resolved_date: string | null;
+}
+
+export interface ScanErrorFilter {
+  location_id?: number;
+  run_id?: number;
+  kind?: ScanErrorKind;
+  path_contains?: string;
+  include_resolved?: boolean;
+  limit?: number;
+}
+
+export interface ScanRetryReport {
+  location_id: number;
+  retried: number;
+  resolved: number;
+  failed: ScanError[];
+  skipped: number;
+}
+
 export interface AssetStats {
   total_assets: number;
   total_size_gb: number;
@@ -121,4 +376,40 @@ export interface GalleryVideoMedia {
 post: string;
 }
 
-export type GalleryItem = GalleryImageMedia | GalleryVideoMedia; 
\ No newline at end of file
+export type GalleryItem = GalleryImageMedia | GalleryVideoMedia; 
+export type CategorySignalSource =
+  | 'folder_name'
+  | 'parent_folder'
+  | 'descriptor'
+  | 'package_class'
+  | 'orbital_category'
+  | 'file_extension';
+
+export interface CategoryRule {
+  source: CategorySignalSource;
+  pattern: string;
+  confidence: number;
+}
+
+export interface CategoryDefinition {
+  id: string;
+  parent: string | null;
+  aliases: string[];
+  rules: CategoryRule[];
+}
+
+export interface CategoryTaxonomy {
+  categories: CategoryDefinition[];
+  fallback: string;
+}
+
+export interface CategoryAssignment {
+  category: string;
+  confidence: number;
+  rule: string;
+}
+
+export interface Categorization {
+  primary: CategoryAssignment;
+  secondary: CategoryAssignment[];
+}